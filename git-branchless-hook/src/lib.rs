@@ -26,6 +26,7 @@ use eyre::Context;
 use git_branchless_invoke::CommandContext;
 use git_branchless_opts::{HookArgs, HookSubcommand};
 use itertools::Itertools;
+use lib::core::config::env_vars::should_disable_hooks;
 use lib::core::config::{
     Hint, get_advance_auto, get_hint_enabled, get_hint_string, get_restack_preserve_timestamps,
     print_hint_suppression_notice,
@@ -47,6 +48,7 @@ use lib::core::formatting::{Glyphs, Pluralize};
 use lib::core::gc::{gc, mark_commit_reachable};
 use lib::git::{
     CategorizedReferenceName, GitRunInfo, MaybeZeroOid, NonZeroOid, ReferenceName, Repo,
+    find_added_conflict_markers,
 };
 
 use lib::core::effects::Effects;
@@ -79,7 +81,7 @@ fn hook_post_checkout(
     let repo = Repo::from_current_dir()?;
     let conn = repo.get_db_conn()?;
     let event_log_db = EventLogDb::new(&conn)?;
-    let event_tx_id = event_log_db.make_transaction_id(now, "hook-post-checkout")?;
+    let event_tx_id = event_log_db.make_hook_transaction_id(&repo, now, "hook-post-checkout")?;
     event_log_db.add_events(vec![Event::RefUpdateEvent {
         timestamp: timestamp.as_secs_f64(),
         event_tx_id,
@@ -160,7 +162,7 @@ fn hook_post_commit_common(
         .duration_since(SystemTime::UNIX_EPOCH)?
         .as_secs_f64();
 
-    let event_tx_id = event_log_db.make_transaction_id(now, hook_name)?;
+    let event_tx_id = event_log_db.make_hook_transaction_id(&repo, now, hook_name)?;
     event_log_db.add_events(vec![Event::CommitEvent {
         timestamp,
         event_tx_id,
@@ -172,6 +174,28 @@ fn hook_post_commit_common(
         glyphs.render(commit.friendly_describe(&glyphs)?)?,
     )?;
 
+    if get_hint_enabled(&repo, Hint::ConflictMarkers)? {
+        if let Some(diff) = repo.get_patch_for_commit(effects, &commit)? {
+            let paths = find_added_conflict_markers(&diff)?;
+            if !paths.is_empty() {
+                writeln!(
+                    effects.get_output_stream(),
+                    "{}: this commit adds what look like unresolved conflict markers in {}:",
+                    glyphs.render(get_hint_string())?,
+                    Pluralize {
+                        determiner: None,
+                        amount: paths.len(),
+                        unit: ("file", "files"),
+                    },
+                )?;
+                for path in paths {
+                    writeln!(effects.get_output_stream(), "- {}", path.display())?;
+                }
+                print_hint_suppression_notice(effects, Hint::ConflictMarkers)?;
+            }
+        }
+    }
+
     let head_commit_set = CommitSet::from(commit_oid);
     let parents = dag.query_parents(head_commit_set.clone())?;
     let children = dag.query_children(parents)?;
@@ -184,6 +208,8 @@ fn hook_post_commit_common(
             force_rewrite_public_commits: false,
             dump_rebase_constraints: false,
             dump_rebase_plan: false,
+            explain: false,
+            keep_empty: false,
             detect_duplicate_commits_via_patch_id: true,
         };
 
@@ -225,6 +251,10 @@ fn hook_post_commit_common(
                     force_on_disk: false,
                     dry_run: false,
                     resolve_merge_conflicts: false,
+                    merge_file_favor: None,
+                    reset_author: false,
+                    timing: false,
+                    exec_on_conflict: None,
                     check_out_commit_options: Default::default(),
                 };
                 let result = execute_rebase_plan(
@@ -624,7 +654,7 @@ fn hook_reference_transaction(effects: &Effects, transaction_state: &str) -> eyr
     let repo = Repo::from_current_dir()?;
     let conn = repo.get_db_conn()?;
     let event_log_db = EventLogDb::new(&conn)?;
-    let event_tx_id = event_log_db.make_transaction_id(now, "reference-transaction")?;
+    let event_tx_id = event_log_db.make_hook_transaction_id(&repo, now, "reference-transaction")?;
 
     // Use the parent repo for packed-refs resolution: packed-refs lives in the
     // main git dir, not in the worktree-specific git dir.
@@ -737,6 +767,10 @@ pub fn command_main(ctx: CommandContext, args: HookArgs) -> EyreExitOr<()> {
     } = ctx;
     let HookArgs { subcommand } = args;
 
+    if should_disable_hooks() {
+        return Ok(Ok(()));
+    }
+
     match subcommand {
         HookSubcommand::DetectEmptyCommit { old_commit_oid } => {
             let old_commit_oid: NonZeroOid = old_commit_oid.parse()?;
@@ -352,3 +352,40 @@ fn test_hook_reference_transaction_pack_refs_in_bare_worktree() -> eyre::Result<
 
     Ok(())
 }
+
+#[test]
+fn test_hook_post_commit_conflict_markers() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+
+    let contents = "<<<<<<< HEAD\nfoo\n=======\nbar\n>>>>>>> other\n";
+    git.write_file_txt("conflict1", contents)?;
+    git.run(&["add", "."])?;
+    {
+        // Disabled by default.
+        let (_stdout, stderr) = git.run(&["commit", "-m", "add conflict1"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 1 update: ref HEAD
+        branchless: processed commit: 8307c62 add conflict1
+        "###);
+    }
+
+    git.run(&["config", "branchless.hint.conflictMarkers", "true"])?;
+    git.write_file_txt("conflict2", contents)?;
+    git.write_file_txt("clean", "=======\n")?;
+    git.run(&["add", "."])?;
+    {
+        let (_stdout, stderr) = git.run(&["commit", "-m", "add conflict2"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 1 update: ref HEAD
+        branchless: processed commit: 226f0c0 add conflict2
+        hint: this commit adds what look like unresolved conflict markers in 1 file:
+        - conflict2.txt
+        hint: disable this hint by running: git config --global branchless.hint.conflictMarkers false
+        "###);
+    }
+
+    Ok(())
+}
@@ -22,52 +22,55 @@ use lib::util::EyreExitOr;
 use path_slash::PathExt;
 use tracing::{instrument, warn};
 
-use git_branchless_opts::{InitArgs, InstallManPagesArgs, write_man_pages};
+use git_branchless_opts::{InitArgs, InstallHooksArgs, InstallManPagesArgs, write_man_pages};
 use lib::core::config::{
     get_default_branch_name, get_default_hooks_dir, get_main_worktree_hooks_dir,
 };
 use lib::core::dag::Dag;
 use lib::core::effects::Effects;
 use lib::core::eventlog::{EventLogDb, EventReplayer};
+use lib::core::formatting::Pluralize;
 use lib::core::repo_ext::RepoExt;
 use lib::git::{BranchType, Config, ConfigRead, ConfigWrite, GitRunInfo, GitVersion, Repo};
 
-/// The contents of all Git hooks to install.
+/// The contents of all Git hooks to install. Each hook passes along the PID of
+/// the Git process which invoked it, so that all the hooks invoked by a single
+/// Git command can share an event transaction.
 pub const ALL_HOOKS: &[(&str, &str)] = &[
     (
         "post-applypatch",
         r#"
-git branchless hook post-applypatch "$@"
+BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-applypatch "$@"
 "#,
     ),
     (
         "post-checkout",
         r#"
-git branchless hook post-checkout "$@"
+BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-checkout "$@"
 "#,
     ),
     (
         "post-commit",
         r#"
-git branchless hook post-commit "$@"
+BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-commit "$@"
 "#,
     ),
     (
         "post-merge",
         r#"
-git branchless hook post-merge "$@"
+BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-merge "$@"
 "#,
     ),
     (
         "post-rewrite",
         r#"
-git branchless hook post-rewrite "$@"
+BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-rewrite "$@"
 "#,
     ),
     (
         "pre-auto-gc",
         r#"
-git branchless hook pre-auto-gc "$@"
+BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook pre-auto-gc "$@"
 "#,
     ),
     (
@@ -75,7 +78,7 @@ git branchless hook pre-auto-gc "$@"
         r#"
 # Avoid canceling the reference transaction in the case that `branchless` fails
 # for whatever reason.
-git branchless hook reference-transaction "$@" || (
+BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook reference-transaction "$@" || (
 echo 'branchless: Failed to process reference transaction!'
 echo 'branchless: Some events (e.g. branch updates) may have been lost.'
 echo 'branchless: This is a bug. Please report it.'
@@ -173,6 +176,39 @@ fn update_between_lines(lines: &str, updated_lines: &str) -> String {
     new_lines
 }
 
+/// Get the lines of a hook which weren't installed by git-branchless, i.e.
+/// those outside of the update markers, excluding the shebang line. An
+/// unterminated update marker is assumed to extend to the end of the hook.
+fn get_foreign_lines(lines: &str) -> String {
+    let mut foreign_lines = String::new();
+    let mut is_ignoring_lines = false;
+    for (i, line) in lines.lines().enumerate() {
+        if i == 0 && line.starts_with("#!") {
+            continue;
+        } else if line == UPDATE_MARKER_START {
+            is_ignoring_lines = true;
+        } else if line == UPDATE_MARKER_END {
+            is_ignoring_lines = false;
+        } else if !is_ignoring_lines {
+            foreign_lines.push_str(line);
+            foreign_lines.push('\n');
+        }
+    }
+    if foreign_lines.trim().is_empty() {
+        String::new()
+    } else {
+        foreign_lines
+    }
+}
+
+fn render_script_contents(contents: &str) -> String {
+    if should_use_separate_command_binary("hook") {
+        contents.replace("branchless hook", "branchless-hook")
+    } else {
+        contents.to_string()
+    }
+}
+
 #[instrument]
 fn write_script(path: &Path, contents: &str) -> eyre::Result<()> {
     let script_dir = path
@@ -180,11 +216,7 @@ fn write_script(path: &Path, contents: &str) -> eyre::Result<()> {
         .ok_or_else(|| eyre::eyre!("No parent for dir {:?}", path))?;
     std::fs::create_dir_all(script_dir).wrap_err("Creating script dir")?;
 
-    let contents = if should_use_separate_command_binary("hook") {
-        contents.replace("branchless hook", "branchless-hook")
-    } else {
-        contents.to_string()
-    };
+    let contents = render_script_contents(contents);
     std::fs::write(path, contents).wrap_err("Writing script contents")?;
 
     // Setting hook file as executable only supported on Unix systems.
@@ -230,6 +262,52 @@ fn update_hook_contents(hook: &Hook, hook_contents: &str) -> eyre::Result<()> {
     Ok(())
 }
 
+/// Rewrite the hook from scratch, rather than updating the git-branchless
+/// section in place. Any foreign content in a regular hook is kept, but moved
+/// after the git-branchless section, so that it can't prevent the
+/// git-branchless hook from running (e.g. by calling `exit`). If the hook's
+/// contents change, the original hook is backed up first.
+///
+/// Returns the path to the backup, if one was made.
+#[instrument]
+fn force_update_hook_contents(hook: &Hook, hook_contents: &str) -> eyre::Result<Option<PathBuf>> {
+    let (hook_path, existing_contents, new_contents) = match hook {
+        Hook::RegularHook { path } => {
+            let existing_contents = match std::fs::read_to_string(path) {
+                Ok(lines) => Some(lines),
+                Err(ref err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                Err(other) => return Err(eyre::eyre!(other)),
+            };
+            let mut new_contents = format!("{SHEBANG}\n");
+            append_hook(&mut new_contents, hook_contents);
+            if let Some(existing_contents) = &existing_contents {
+                new_contents.push_str(&get_foreign_lines(existing_contents));
+            }
+            (path, existing_contents, new_contents)
+        }
+
+        // The multi-hook script is owned entirely by git-branchless, so
+        // there's nothing to preserve.
+        Hook::MultiHook { path } => (path, None, format!("{SHEBANG}\n{hook_contents}")),
+    };
+
+    let backup_path = match existing_contents {
+        Some(existing_contents) if existing_contents != render_script_contents(&new_contents) => {
+            let mut backup_path = hook_path.clone().into_os_string();
+            backup_path.push(".branchless-backup");
+            let backup_path = PathBuf::from(backup_path);
+            std::fs::write(&backup_path, existing_contents)
+                .wrap_err_with(|| format!("Backing up hook to {backup_path:?}"))?;
+            Some(backup_path)
+        }
+        Some(_) | None => None,
+    };
+
+    write_script(hook_path, &new_contents).wrap_err("Writing hook script")?;
+
+    Ok(backup_path)
+}
+
 #[instrument]
 fn install_hook(
     repo: &Repo,
@@ -274,6 +352,100 @@ The Git hooks above may have been installed to an unexpected global location.",
     Ok(())
 }
 
+/// Determine whether the git-branchless section of the given hook is present
+/// and up-to-date.
+#[instrument]
+fn is_hook_installed(hook: &Hook, hook_contents: &str) -> eyre::Result<bool> {
+    let path = match hook {
+        Hook::RegularHook { path } | Hook::MultiHook { path } => path,
+    };
+    let existing_contents = match std::fs::read_to_string(path) {
+        Ok(lines) => lines,
+        Err(ref err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(other) => return Err(eyre::eyre!(other)),
+    };
+    let installed_contents = match hook {
+        Hook::RegularHook { path: _ } => existing_contents
+            .lines()
+            .skip_while(|line| *line != UPDATE_MARKER_START)
+            .skip(1)
+            .take_while(|line| *line != UPDATE_MARKER_END)
+            .join("\n"),
+        Hook::MultiHook { path: _ } => existing_contents
+            .strip_prefix(SHEBANG)
+            .unwrap_or(&existing_contents)
+            .to_string(),
+    };
+    Ok(installed_contents.trim() == render_script_contents(hook_contents).trim())
+}
+
+/// Unconditionally rewrite all of the git-branchless hooks, backing up any
+/// hooks whose contents change.
+#[instrument]
+fn force_install_hooks(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+) -> eyre::Result<()> {
+    writeln!(
+        effects.get_output_stream(),
+        "Reinstalling hooks: {}",
+        ALL_HOOKS
+            .iter()
+            .map(|(hook_type, _hook_script)| hook_type)
+            .join(", ")
+    )?;
+    let hooks_dir = get_main_worktree_hooks_dir(git_run_info, repo, None)?;
+    for (hook_type, hook_script) in ALL_HOOKS {
+        let hook = determine_hook_path(repo, &hooks_dir, hook_type)?;
+        if let Some(backup_path) = force_update_hook_contents(&hook, hook_script)? {
+            writeln!(
+                effects.get_output_stream(),
+                "Backed up existing {hook_type} hook to: {}",
+                backup_path.to_string_lossy()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the git-branchless section from the hook. If the hook has no other
+/// content, then the hook is deleted entirely; otherwise, the other content is
+/// kept, so that any hooks which were chained with the git-branchless hook keep
+/// running.
+#[instrument]
+fn remove_hook_contents(hook: &Hook) -> eyre::Result<()> {
+    let path = match hook {
+        Hook::RegularHook { path } => {
+            let lines = match std::fs::read_to_string(path) {
+                Ok(lines) => lines,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+                Err(other) => return Err(eyre::eyre!(other)),
+            };
+            let foreign_lines = get_foreign_lines(&lines);
+            if !foreign_lines.is_empty() {
+                let shebang = match lines.lines().next() {
+                    Some(line) if line.starts_with("#!") => line,
+                    Some(_) | None => SHEBANG,
+                };
+                std::fs::write(path, format!("{shebang}\n{foreign_lines}"))
+                    .wrap_err("Writing hook script")?;
+                return Ok(());
+            }
+            path
+        }
+
+        // The multi-hook script is owned entirely by git-branchless.
+        Hook::MultiHook { path } => path,
+    };
+
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).wrap_err_with(|| format!("Removing hook: {path:?}")),
+    }
+}
+
 #[instrument]
 fn uninstall_hooks(effects: &Effects, git_run_info: &GitRunInfo, repo: &Repo) -> eyre::Result<()> {
     writeln!(
@@ -286,15 +458,8 @@ fn uninstall_hooks(effects: &Effects, git_run_info: &GitRunInfo, repo: &Repo) ->
     )?;
     let hooks_dir = get_main_worktree_hooks_dir(git_run_info, repo, None)?;
     for (hook_type, _hook_script) in ALL_HOOKS {
-        install_hook(
-            repo,
-            &hooks_dir,
-            hook_type,
-            r#"
-# This hook has been uninstalled.
-# Run `git branchless init` to reinstall.
-"#,
-        )?;
+        let hook = determine_hook_path(repo, &hooks_dir, hook_type)?;
+        remove_hook_contents(&hook)?;
     }
     Ok(())
 }
@@ -469,9 +634,18 @@ fn set_configs(
     repo: &Repo,
     config: &mut Config,
     main_branch_name: Option<&str>,
+    already_initialized: bool,
 ) -> eyre::Result<()> {
     let main_branch_name = match main_branch_name {
-        Some(main_branch_name) => main_branch_name.to_string(),
+        Some(main_branch_name) => Some(main_branch_name.to_string()),
+
+        // Keep the main branch which was already configured, rather than
+        // re-detecting it and possibly overwriting the user's choice.
+        None if already_initialized
+            && !config.get_all("branchless.core.mainBranch")?.is_empty() =>
+        {
+            None
+        }
 
         None => match detect_main_branch_name(repo)? {
             Some(main_branch_name) => {
@@ -484,7 +658,7 @@ fn set_configs(
                     effects.get_output_stream(),
                     "If this is incorrect, run: git branchless init --main-branch <branch>"
                 )?;
-                main_branch_name
+                Some(main_branch_name)
             }
 
             None => {
@@ -512,13 +686,15 @@ fn set_configs(
                 r#in.read_line(&mut input)?;
                 match input.trim() {
                     "" => eyre::bail!("No main branch name provided"),
-                    main_branch_name => main_branch_name.to_string(),
+                    main_branch_name => Some(main_branch_name.to_string()),
                 }
             }
         },
     };
 
-    config.set("branchless.core.mainBranch", main_branch_name)?;
+    if let Some(main_branch_name) = main_branch_name {
+        config.set("branchless.core.mainBranch", main_branch_name)?;
+    }
     config.set("advice.detachedHead", false)?;
     config.set("log.excludeDecoration", "refs/branchless/*")?;
 
@@ -599,22 +775,72 @@ fn delete_isolated_config(
     Ok(())
 }
 
+/// Determine whether `git-branchless` has already been set up in the given
+/// repo, i.e. its config file is present, its event log can be opened, and all
+/// of its hooks are installed.
+#[instrument]
+fn is_already_initialized(git_run_info: &GitRunInfo, repo: &Repo) -> eyre::Result<bool> {
+    let branchless_dir = repo.get_branchless_dir()?;
+    if !repo.get_config_path()?.exists() || !branchless_dir.join("db.sqlite3").exists() {
+        return Ok(false);
+    }
+
+    match repo.get_db_conn() {
+        Ok(conn) => {
+            if let Err(err) = EventLogDb::new(&conn) {
+                warn!(?err, "Could not open existing event log");
+                return Ok(false);
+            }
+        }
+        Err(err) => {
+            warn!(?err, "Could not open existing database");
+            return Ok(false);
+        }
+    }
+
+    let hooks_dir = get_main_worktree_hooks_dir(git_run_info, repo, None)?;
+    for (hook_type, hook_script) in ALL_HOOKS {
+        let hook = determine_hook_path(repo, &hooks_dir, hook_type)?;
+        if !is_hook_installed(&hook, hook_script)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
 /// Initialize `git-branchless` in the current repo.
 #[instrument]
 fn command_init(
     effects: &Effects,
     git_run_info: &GitRunInfo,
     main_branch_name: Option<&str>,
+    force: bool,
 ) -> EyreExitOr<()> {
     let mut in_ = BufReader::new(stdin());
     let repo = Repo::from_current_dir()?;
     let mut repo = repo.open_worktree_parent_repo()?.unwrap_or(repo);
 
+    // Re-running `init` repairs the configuration, hooks, and aliases, but
+    // skips re-detecting the main branch and re-syncing the commit graph,
+    // unless `--force` is passed. Explicitly passing a main branch name is how
+    // the user reconfigures an existing installation, so don't skip anything
+    // in that case.
+    let already_initialized =
+        !force && main_branch_name.is_none() && is_already_initialized(git_run_info, &repo)?;
+
     let default_config = Config::open_default()?;
     let readonly_config = repo.get_readonly_config()?;
     let mut config = create_isolated_config(effects, &repo, readonly_config.into_config())?;
 
-    set_configs(&mut in_, effects, &repo, &mut config, main_branch_name)?;
+    set_configs(
+        &mut in_,
+        effects,
+        &repo,
+        &mut config,
+        main_branch_name,
+        already_initialized,
+    )?;
     install_hooks(effects, git_run_info, &repo)?;
     install_aliases(
         effects,
@@ -625,6 +851,19 @@ fn command_init(
     )?;
     install_man_pages(effects, &repo, &mut config)?;
 
+    if already_initialized {
+        writeln!(
+            effects.get_output_stream(),
+            "git-branchless is already initialized."
+        )?;
+        writeln!(
+            effects.get_output_stream(),
+            "To reinitialize it, run: {}",
+            console::style("git branchless init --force").bold()
+        )?;
+        return Ok(Ok(()));
+    }
+
     let conn = repo.get_db_conn()?;
     let event_log_db = EventLogDb::new(&conn)?;
     // If the main branch hasn't been born yet, then we may fail to generate a
@@ -658,13 +897,77 @@ fn command_init(
     Ok(Ok(()))
 }
 
+/// Remove any `branchless.*` settings from the repository's own configuration
+/// file. (Settings made by `git branchless init` are stored in the isolated
+/// config file instead; these would have been set by the user.)
+#[instrument]
+fn delete_branchless_config_keys(effects: &Effects, repo: &Repo) -> eyre::Result<()> {
+    let config_path = repo.get_path().join("config");
+    let mut config = Config::open(&config_path)?;
+    let keys = config
+        .list(r"^branchless\.")?
+        .into_iter()
+        .map(|(key, _value)| key)
+        .unique()
+        .collect_vec();
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(
+        effects.get_output_stream(),
+        "Removing {} from: {}",
+        Pluralize {
+            determiner: None,
+            amount: keys.len(),
+            unit: ("branchless config key", "branchless config keys"),
+        },
+        config_path.to_string_lossy()
+    )?;
+    for key in keys {
+        config.remove_multivar(&key, ".*")?;
+    }
+    Ok(())
+}
+
+/// Delete the data stored by git-branchless, i.e. the `.git/branchless`
+/// directory (containing the event log, the commit graph index, and cached
+/// test results) and the references under `refs/branchless/`.
+#[instrument]
+fn purge_branchless_data(effects: &Effects, repo: &Repo) -> eyre::Result<()> {
+    for mut reference in repo.get_all_references()? {
+        if reference
+            .get_name()?
+            .as_str()
+            .starts_with("refs/branchless/")
+        {
+            reference.delete()?;
+        }
+    }
+
+    let branchless_dir = repo.get_branchless_dir()?;
+    writeln!(
+        effects.get_output_stream(),
+        "Removing git-branchless data: {}",
+        branchless_dir.to_string_lossy()
+    )?;
+    std::fs::remove_dir_all(&branchless_dir)
+        .wrap_err_with(|| format!("Removing git-branchless data at {branchless_dir:?}"))?;
+    Ok(())
+}
+
 /// Uninstall `git-branchless` in the current repo.
 #[instrument]
-fn command_uninstall(effects: &Effects, git_run_info: &GitRunInfo) -> EyreExitOr<()> {
+fn command_uninstall(effects: &Effects, git_run_info: &GitRunInfo, purge: bool) -> EyreExitOr<()> {
     let repo = Repo::from_current_dir()?;
+    let repo = repo.open_worktree_parent_repo()?.unwrap_or(repo);
     let readonly_config = repo.get_readonly_config().wrap_err("Getting repo config")?;
     delete_isolated_config(effects, &repo, readonly_config.into_config())?;
+    delete_branchless_config_keys(effects, &repo)?;
     uninstall_hooks(effects, git_run_info, &repo)?;
+    if purge {
+        purge_branchless_data(effects, &repo)?;
+    }
     Ok(Ok(()))
 }
 
@@ -678,16 +981,39 @@ pub fn command_main(ctx: CommandContext, args: InitArgs) -> EyreExitOr<()> {
     match args {
         InitArgs {
             uninstall: false,
+            purge: _,
             main_branch_name,
-        } => command_init(&effects, &git_run_info, main_branch_name.as_deref()),
+            force,
+        } => command_init(&effects, &git_run_info, main_branch_name.as_deref(), force),
 
         InitArgs {
             uninstall: true,
+            purge,
             main_branch_name: _,
-        } => command_uninstall(&effects, &git_run_info),
+            force: _,
+        } => command_uninstall(&effects, &git_run_info, purge),
     }
 }
 
+/// Install the hooks for `git-branchless` in the current repo, without
+/// changing any configuration.
+#[instrument]
+pub fn command_install_hooks(ctx: CommandContext, args: InstallHooksArgs) -> EyreExitOr<()> {
+    let CommandContext {
+        effects,
+        git_run_info,
+    } = ctx;
+    let InstallHooksArgs { force } = args;
+    let repo = Repo::from_current_dir()?;
+    let repo = repo.open_worktree_parent_repo()?.unwrap_or(repo);
+    if force {
+        force_install_hooks(&effects, &git_run_info, &repo)?;
+    } else {
+        install_hooks(&effects, &git_run_info, &repo)?;
+    }
+    Ok(Ok(()))
+}
+
 /// Install the man-pages for `git-branchless` to the provided path.
 #[instrument]
 pub fn command_install_man_pages(ctx: CommandContext, args: InstallManPagesArgs) -> EyreExitOr<()> {
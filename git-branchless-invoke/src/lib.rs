@@ -25,9 +25,10 @@ use cursive_core::theme::BaseColor;
 use cursive_core::utils::markup::StyledString;
 use eyre::Context;
 use git_branchless_opts::{ColorSetting, GlobalArgs};
-use lib::core::config::env_vars::{get_git_exec_path, get_path_to_git};
+use lib::core::config::env_vars::{self, get_git_exec_path, get_path_to_git};
+use lib::core::config::{THEME_CONFIG_KEY, get_theme};
 use lib::core::effects::Effects;
-use lib::core::formatting::Glyphs;
+use lib::core::formatting::{Glyphs, Theme};
 use lib::git::GitRunInfo;
 use lib::git::{Repo, RepoError};
 use lib::util::{ExitCode, EyreExitOr};
@@ -51,7 +52,7 @@ pub struct CommandContext {
 
 #[must_use = "This function returns a guard object to flush traces. Dropping it immediately is probably incorrect. Make sure that the returned value lives until tracing has finished."]
 #[instrument]
-fn install_tracing(effects: Effects) -> eyre::Result<impl Drop> {
+fn install_tracing(effects: Effects, trace_path: Option<PathBuf>) -> eyre::Result<impl Drop> {
     let env_filter = EnvFilter::builder()
         .with_default_directive(LevelFilter::WARN.into())
         .parse(std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_else(|_|
@@ -80,8 +81,21 @@ fn install_tracing(effects: Effects) -> eyre::Result<impl Drop> {
             Ok(_) | Err(_) => false,
         };
 
-        let filename = match std::env::var("RUST_PROFILE") {
-            Ok(value) if value == "1" || value == "true" => {
+        let trace_path = trace_path.or_else(|| match std::env::var_os(env_vars::TRACE) {
+            Some(value) if !value.is_empty() => Some(PathBuf::from(value)),
+            Some(_) | None => None,
+        });
+        let filename = match (trace_path, std::env::var("RUST_PROFILE")) {
+            (Some(trace_path), _) => {
+                if nesting_level == 0 {
+                    Some(trace_path)
+                } else {
+                    let mut filename = trace_path.into_os_string();
+                    filename.push(format!("-{nesting_level}"));
+                    Some(PathBuf::from(filename))
+                }
+            }
+            (None, Ok(value)) if value == "1" || value == "true" => {
                 let filename = format!(
                     "trace-{}.json-{}",
                     SystemTime::now()
@@ -89,10 +103,12 @@ fn install_tracing(effects: Effects) -> eyre::Result<impl Drop> {
                         .as_secs(),
                     nesting_level,
                 );
-                Some(filename)
+                Some(PathBuf::from(filename))
+            }
+            (None, Ok(value)) if !value.is_empty() => {
+                Some(PathBuf::from(format!("{value}-{nesting_level}")))
             }
-            Ok(value) if !value.is_empty() => Some(format!("{value}-{nesting_level}")),
-            Ok(_) | Err(_) => None,
+            (None, Ok(_) | Err(_)) => None,
         };
 
         match filename {
@@ -179,9 +195,14 @@ pub fn do_main_and_drop_locals<T: Parser>(
     let GlobalArgs {
         working_directory,
         color,
+        trace,
     } = GlobalArgs::from_arg_matches(&matches)
         .map_err(|err| eyre::eyre!("Could not parse global arguments: {err}"))?;
 
+    // Resolve the trace path before changing directories, so that it's
+    // relative to the directory the command was invoked from.
+    let trace = trace.map(std::path::absolute).transpose()?;
+
     if let Some(working_directory) = working_directory {
         std::env::set_current_dir(&working_directory).wrap_err_with(|| {
             format!(
@@ -211,9 +232,13 @@ pub fn do_main_and_drop_locals<T: Parser>(
         Some(ColorSetting::Never) => Glyphs::text(),
         Some(ColorSetting::Auto) | None => Glyphs::detect(),
     };
-    let effects = Effects::new(color);
+    let theme = match Repo::from_current_dir() {
+        Ok(repo) => get_theme(&repo)?,
+        Err(_) => Ok(Theme::Default),
+    };
+    let effects = Effects::new(color.with_theme(theme.clone().unwrap_or(Theme::Default)));
 
-    let _tracing_guard = install_tracing(effects.clone());
+    let _tracing_guard = install_tracing(effects.clone(), trace);
     install_libgit2_tracing();
 
     if let Some(ExitCode(exit_code)) = check_unsupported_config_options(&effects)? {
@@ -221,6 +246,29 @@ pub fn do_main_and_drop_locals<T: Parser>(
         return Ok(exit_code);
     }
 
+    // Hooks don't render any themed output, and failing them would cause
+    // events to be lost, so they ignore an invalid theme.
+    let is_hook = T::command()
+        .ignore_errors(true)
+        .get_matches_from(&args)
+        .subcommand_name()
+        == Some("hook");
+    match theme {
+        Err(theme_name) if !is_hook => {
+            writeln!(
+                effects.get_error_stream(),
+                "Unknown theme '{theme_name}' set in {THEME_CONFIG_KEY}. Valid themes are: {}",
+                Theme::ALL
+                    .iter()
+                    .map(|theme| theme.name())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )?;
+            return Ok(1);
+        }
+        Ok(_) | Err(_) => {}
+    }
+
     let ctx = CommandContext {
         effects,
         git_run_info,
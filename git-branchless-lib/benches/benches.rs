@@ -60,6 +60,8 @@ fn bench_rebase_plan(c: &mut Criterion) {
             force_rewrite_public_commits: true,
             dump_rebase_constraints: false,
             dump_rebase_plan: false,
+            explain: false,
+            keep_empty: false,
             detect_duplicate_commits_via_patch_id: true,
         };
         let permissions = RebasePlanPermissions::verify_rewrite_set(
@@ -98,7 +100,7 @@ fn bench_cherry_pick_fast(c: &mut Criterion) {
 
         b.iter(|| {
             let mut index = repo
-                .cherry_pick_commit(&head_commit, &target_commit, 0)
+                .cherry_pick_commit(&head_commit, &target_commit, 0, None)
                 .unwrap();
             let tree_oid = repo.write_index_to_tree(&mut index).unwrap();
             repo.find_tree(tree_oid).unwrap().unwrap()
@@ -116,6 +118,8 @@ fn bench_cherry_pick_fast(c: &mut Criterion) {
                 &target_commit,
                 &CherryPickFastOptions {
                     reuse_parent_tree_if_possible: false,
+                    merge_file_favor: None,
+                    use_rerere: false,
                 },
             )
             .unwrap();
@@ -39,6 +39,8 @@ fn main() -> eyre::Result<()> {
             &parent_commit,
             &CherryPickFastOptions {
                 reuse_parent_tree_if_possible: false,
+                merge_file_favor: None,
+                use_rerere: false,
             },
         )?;
 
@@ -0,0 +1,215 @@
+//! Render commits according to user-supplied format strings, in the style of
+//! `git log --format`.
+//!
+//! The following placeholders are supported:
+//!
+//! - `%H`: the full commit OID.
+//! - `%h`: the abbreviated commit OID.
+//! - `%s`: the commit summary (first line of the message).
+//! - `%an`: the author name.
+//! - `%ae`: the author email.
+//! - `%d`: the branches pointing to the commit, like ` (HEAD -> main, foo)`.
+//! - `%n`: a newline.
+//! - `%%`: a literal `%`.
+
+use thiserror::Error;
+
+use crate::git::Commit;
+
+/// A value which can be substituted into a commit format string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommitFormatPlaceholder {
+    /// `%H`
+    Oid,
+
+    /// `%h`
+    ShortOid,
+
+    /// `%s`
+    Summary,
+
+    /// `%an`
+    AuthorName,
+
+    /// `%ae`
+    AuthorEmail,
+
+    /// `%d`
+    Decorations,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CommitFormatToken {
+    Literal(String),
+    Placeholder(CommitFormatPlaceholder),
+}
+
+/// An error parsing a commit format string.
+#[derive(Debug, Error)]
+pub enum CommitFormatError {
+    /// The format string contained a placeholder which isn't supported.
+    #[error("unknown placeholder '%{placeholder}' in format string")]
+    UnknownPlaceholder {
+        /// The text of the placeholder, without the leading `%`.
+        placeholder: String,
+    },
+
+    /// The format string ended in the middle of a placeholder.
+    #[error("incomplete placeholder '%{placeholder}' at end of format string")]
+    IncompletePlaceholder {
+        /// The text of the placeholder, without the leading `%`.
+        placeholder: String,
+    },
+}
+
+/// A parsed commit format string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitFormat {
+    tokens: Vec<CommitFormatToken>,
+}
+
+impl CommitFormat {
+    /// Parse the given format string. Returns an error if it contains any
+    /// unknown placeholders.
+    pub fn parse(format: &str) -> Result<Self, CommitFormatError> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+
+            let placeholder = match chars.next() {
+                Some('%') => {
+                    literal.push('%');
+                    continue;
+                }
+                Some('n') => {
+                    literal.push('\n');
+                    continue;
+                }
+                Some('H') => CommitFormatPlaceholder::Oid,
+                Some('h') => CommitFormatPlaceholder::ShortOid,
+                Some('s') => CommitFormatPlaceholder::Summary,
+                Some('d') => CommitFormatPlaceholder::Decorations,
+                Some('a') => match chars.next() {
+                    Some('n') => CommitFormatPlaceholder::AuthorName,
+                    Some('e') => CommitFormatPlaceholder::AuthorEmail,
+                    Some(c) => {
+                        return Err(CommitFormatError::UnknownPlaceholder {
+                            placeholder: format!("a{c}"),
+                        });
+                    }
+                    None => {
+                        return Err(CommitFormatError::IncompletePlaceholder {
+                            placeholder: "a".to_string(),
+                        });
+                    }
+                },
+                Some(c) => {
+                    return Err(CommitFormatError::UnknownPlaceholder {
+                        placeholder: c.to_string(),
+                    });
+                }
+                None => {
+                    return Err(CommitFormatError::IncompletePlaceholder {
+                        placeholder: String::new(),
+                    });
+                }
+            };
+            if !literal.is_empty() {
+                tokens.push(CommitFormatToken::Literal(std::mem::take(&mut literal)));
+            }
+            tokens.push(CommitFormatToken::Placeholder(placeholder));
+        }
+        if !literal.is_empty() {
+            tokens.push(CommitFormatToken::Literal(literal));
+        }
+        Ok(Self { tokens })
+    }
+
+    /// Render the given commit. `decorations` are the names to render for the
+    /// `%d` placeholder, in order.
+    pub fn render(&self, commit: &Commit, decorations: &[String]) -> eyre::Result<String> {
+        let mut result = String::new();
+        for token in self.tokens.iter() {
+            match token {
+                CommitFormatToken::Literal(literal) => result.push_str(literal),
+                CommitFormatToken::Placeholder(placeholder) => match placeholder {
+                    CommitFormatPlaceholder::Oid => result.push_str(&commit.get_oid().to_string()),
+                    CommitFormatPlaceholder::ShortOid => result.push_str(&commit.get_short_oid()?),
+                    CommitFormatPlaceholder::Summary => {
+                        result.push_str(&String::from_utf8_lossy(&commit.get_summary()?))
+                    }
+                    CommitFormatPlaceholder::AuthorName => {
+                        result.push_str(commit.get_author().get_name().unwrap_or_default())
+                    }
+                    CommitFormatPlaceholder::AuthorEmail => {
+                        result.push_str(commit.get_author().get_email().unwrap_or_default())
+                    }
+                    CommitFormatPlaceholder::Decorations => {
+                        if !decorations.is_empty() {
+                            result.push_str(&format!(" ({})", decorations.join(", ")));
+                        }
+                    }
+                },
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commit_format() {
+        insta::assert_debug_snapshot!(CommitFormat::parse("%h %s%d%%%n"), @r###"
+        Ok(
+            CommitFormat {
+                tokens: [
+                    Placeholder(
+                        ShortOid,
+                    ),
+                    Literal(
+                        " ",
+                    ),
+                    Placeholder(
+                        Summary,
+                    ),
+                    Placeholder(
+                        Decorations,
+                    ),
+                    Literal(
+                        "%\n",
+                    ),
+                ],
+            },
+        )
+        "###);
+        insta::assert_debug_snapshot!(CommitFormat::parse("%x"), @r###"
+        Err(
+            UnknownPlaceholder {
+                placeholder: "x",
+            },
+        )
+        "###);
+        insta::assert_debug_snapshot!(CommitFormat::parse("%ax"), @r###"
+        Err(
+            UnknownPlaceholder {
+                placeholder: "ax",
+            },
+        )
+        "###);
+        insta::assert_debug_snapshot!(CommitFormat::parse("foo %"), @r###"
+        Err(
+            IncompletePlaceholder {
+                placeholder: "",
+            },
+        )
+        "###);
+    }
+}
@@ -2,15 +2,16 @@
 
 use std::ffi::OsString;
 use std::fmt::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use cursive::theme::{BaseColor, Effect, Style};
 use cursive::utils::markup::StyledString;
 use eyre::Context;
 use tracing::{instrument, warn};
 
-use crate::core::formatting::StyledStringBuilder;
-use crate::git::{ConfigRead, GitRunInfo, GitRunOpts, Repo};
+use crate::core::formatting::{StyledStringBuilder, Theme};
+use crate::git::{ConfigRead, ConfigValue, GitRunInfo, GitRunOpts, NonZeroOid, Repo};
 
 use super::effects::Effects;
 use super::eventlog::EventTransactionId;
@@ -61,30 +62,40 @@ pub fn get_main_worktree_hooks_dir(
     Ok(hooks_path)
 }
 
-/// Get the configured name of the main branch.
+/// Get the configured names of the main branches.
 ///
 /// The following config values are resolved, in order. The first valid value is returned.
 /// - branchless.core.mainBranch
 /// - (deprecated) branchless.mainBranch
 /// - init.defaultBranch
 /// - finally, default to "master"
+///
+/// `branchless.core.mainBranch` may be set multiple times in the same config
+/// file (e.g. with `git config --add`) for repositories with several trunks, in
+/// which case all of the values are returned, in the order they were set.
 #[instrument]
-pub fn get_main_branch_name(repo: &Repo) -> eyre::Result<String> {
+pub fn get_main_branch_names(repo: &Repo) -> eyre::Result<Vec<String>> {
     let config = repo.get_readonly_config()?;
 
-    if let Some(branch_name) = config.get("branchless.core.mainBranch")? {
-        return Ok(branch_name);
+    let mut branch_names: Vec<String> = Vec::new();
+    for branch_name in config.get_all("branchless.core.mainBranch")? {
+        if !branch_names.contains(&branch_name) {
+            branch_names.push(branch_name);
+        }
+    }
+    if !branch_names.is_empty() {
+        return Ok(branch_names);
     }
 
     if let Some(branch_name) = config.get("branchless.mainBranch")? {
-        return Ok(branch_name);
+        return Ok(vec![branch_name]);
     }
 
     if let Some(branch_name) = get_default_branch_name(repo)? {
-        return Ok(branch_name);
+        return Ok(vec![branch_name]);
     }
 
-    Ok("master".to_string())
+    Ok(vec!["master".to_string()])
 }
 
 /// If `true`, switch to the branch associated with a target commit instead of
@@ -97,6 +108,21 @@ pub fn get_auto_switch_branches(repo: &Repo) -> eyre::Result<bool> {
         .get_or("branchless.navigation.autoSwitchBranches", true)
 }
 
+/// Config key for `get_theme`.
+pub const THEME_CONFIG_KEY: &str = "branchless.theme";
+
+/// The theme used to render output. If the configured name doesn't refer to
+/// a known theme, it's returned as the error value.
+#[instrument]
+pub fn get_theme(repo: &Repo) -> eyre::Result<Result<Theme, String>> {
+    let theme_name: Option<String> = repo.get_readonly_config()?.get(THEME_CONFIG_KEY)?;
+    let theme = match theme_name {
+        None => Ok(Theme::Default),
+        Some(theme_name) => Theme::from_name(&theme_name).ok_or(theme_name),
+    };
+    Ok(theme)
+}
+
 /// The default smartlog revset to render. This will be used when running `git
 /// smartlog` with no arguments, and also when the smartlog is rendered
 /// automatically as part of some commands like `git next`/`git prev`.
@@ -104,10 +130,149 @@ pub fn get_auto_switch_branches(repo: &Repo) -> eyre::Result<bool> {
 pub fn get_smartlog_default_revset(repo: &Repo) -> eyre::Result<String> {
     repo.get_readonly_config()?
         .get_or_else("branchless.smartlog.defaultRevset", || {
-            "((draft() | branches() | @) % main()) | branches() | @".to_string()
+            SMARTLOG_DEFAULT_REVSET.to_string()
         })
 }
 
+/// The revset used by `get_smartlog_default_revset` if none is configured.
+pub const SMARTLOG_DEFAULT_REVSET: &str = "((draft() | branches() | @) % main()) | branches() | @";
+
+/// Whether or not the smartlog should mark draft commits which appear to be
+/// duplicates of other rendered commits, based on their patch IDs.
+#[instrument]
+pub fn get_smartlog_detect_duplicates(repo: &Repo) -> eyre::Result<bool> {
+    repo.get_readonly_config()?
+        .get_or("branchless.smartlog.detectDuplicates", false)
+}
+
+/// Whether or not the smartlog should render stash entries as nodes attached
+/// to the commits they were created on.
+#[instrument]
+pub fn get_smartlog_show_stashes(repo: &Repo) -> eyre::Result<bool> {
+    repo.get_readonly_config()?
+        .get_or("branchless.smartlog.showStashes", false)
+}
+
+/// The glyph used to render obsolete commits in the smartlog, in place of the
+/// default for the current set of glyphs (such as `x`).
+#[instrument]
+pub fn get_smartlog_obsolete_marker(repo: &Repo) -> eyre::Result<Option<String>> {
+    repo.get_readonly_config()?
+        .get("branchless.smartlog.obsoleteMarker")
+}
+
+/// The phrase used to refer to the commit that an obsolete commit was rewritten
+/// as, such as in `(rewritten as 047b7ad7)`.
+#[instrument]
+pub fn get_smartlog_rewritten_as_text(repo: &Repo) -> eyre::Result<String> {
+    repo.get_readonly_config()?
+        .get_or_else("branchless.smartlog.rewrittenAsText", || {
+            "rewritten as".to_string()
+        })
+}
+
+/// Config key for `get_smartlog_max_commits`.
+pub const SMARTLOG_MAX_COMMITS_CONFIG_KEY: &str = "branchless.smartlog.maxCommits";
+
+/// The maximum number of commits to render in the smartlog. If more commits
+/// would be rendered, only the most recent ones are shown. A value of `0` (the
+/// default) means that there is no limit.
+#[instrument]
+pub fn get_smartlog_max_commits(repo: &Repo) -> eyre::Result<Option<usize>> {
+    let max_commits: i32 = repo
+        .get_readonly_config()?
+        .get_or(SMARTLOG_MAX_COMMITS_CONFIG_KEY, 0)?;
+    match usize::try_from(max_commits) {
+        Ok(0) | Err(_) => Ok(None),
+        Ok(max_commits) => Ok(Some(max_commits)),
+    }
+}
+
+/// The order in which sibling commits are laid out in the smartlog.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SmartlogChildOrder {
+    /// Order siblings by their committer date, oldest first.
+    CommitterDate,
+
+    /// Order siblings by the name of the branch pointing to them, with
+    /// unbranched siblings last (ordered by committer date).
+    AlphabeticalBranch,
+
+    /// Order siblings according to the topological ordering of the commit
+    /// graph.
+    Topological,
+}
+
+/// Config key for `get_smartlog_child_order`.
+pub const SMARTLOG_CHILD_ORDER_CONFIG_KEY: &str = "branchless.smartlog.childOrder";
+
+/// The order in which sibling commits should be laid out in the smartlog.
+/// Unrecognized values fall back to ordering by committer date.
+#[instrument]
+pub fn get_smartlog_child_order(repo: &Repo) -> eyre::Result<SmartlogChildOrder> {
+    let child_order: Option<String> = repo
+        .get_readonly_config()?
+        .get(SMARTLOG_CHILD_ORDER_CONFIG_KEY)?;
+    let child_order = match child_order {
+        None => SmartlogChildOrder::CommitterDate,
+        Some(child_order) => match child_order.to_lowercase().as_str() {
+            "committerdate" => SmartlogChildOrder::CommitterDate,
+            "alphabeticalbranch" => SmartlogChildOrder::AlphabeticalBranch,
+            "topological" => SmartlogChildOrder::Topological,
+            _ => {
+                warn!(
+                    ?child_order,
+                    "Invalid value for config value {SMARTLOG_CHILD_ORDER_CONFIG_KEY}"
+                );
+                SmartlogChildOrder::CommitterDate
+            }
+        },
+    };
+    Ok(child_order)
+}
+
+/// How commands which operate relative to `HEAD` by default (such as
+/// `advance`, `move` and `restack`) behave when `HEAD` is at a public commit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PublicHeadBehavior {
+    /// Run the command as usual.
+    Allow,
+
+    /// Print a message and exit successfully without doing anything.
+    Skip,
+
+    /// Refuse to run the command unless `-f`/`--force-rewrite` is passed.
+    RequireForce,
+}
+
+/// Config key for `get_public_head_behavior`.
+pub const PUBLIC_HEAD_BEHAVIOR_CONFIG_KEY: &str = "branchless.core.publicHeadBehavior";
+
+/// How commands should behave when `HEAD` is at a public commit. Unrecognized
+/// values fall back to running the command as usual.
+#[instrument]
+pub fn get_public_head_behavior(repo: &Repo) -> eyre::Result<PublicHeadBehavior> {
+    let behavior: Option<String> = repo
+        .get_readonly_config()?
+        .get(PUBLIC_HEAD_BEHAVIOR_CONFIG_KEY)?;
+    let behavior = match behavior {
+        None => PublicHeadBehavior::Allow,
+        Some(behavior) => match behavior.to_lowercase().as_str() {
+            "allow" => PublicHeadBehavior::Allow,
+            "skip" => PublicHeadBehavior::Skip,
+            "requireforce" => PublicHeadBehavior::RequireForce,
+            _ => {
+                warn!(
+                    ?behavior,
+                    "Invalid value for config value {PUBLIC_HEAD_BEHAVIOR_CONFIG_KEY}"
+                );
+                PublicHeadBehavior::Allow
+            }
+        },
+    };
+    Ok(behavior)
+}
+
 /// Get the default comment character.
 #[instrument]
 pub fn get_comment_char(repo: &Repo) -> eyre::Result<char> {
@@ -215,23 +380,142 @@ pub fn get_advance_auto(repo: &Repo) -> eyre::Result<bool> {
         .get_or("branchless.advance.auto", false)
 }
 
-/// Get the list of branch name patterns to ignore (exclude from branchless tracking).
-/// Supports exact names and glob patterns (e.g. `release/*`).
+/// If `true`, ask for confirmation before hiding commits which aren't
+/// reachable from any remaining branch or `HEAD`.
+#[instrument]
+pub fn get_hide_confirm_unique(repo: &Repo) -> eyre::Result<bool> {
+    repo.get_readonly_config()?
+        .get_or("branchless.hide.confirmUnique", false)
+}
+
+/// A pattern from `branchless.core.ignoreBranches` describing which branches
+/// to ignore.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IgnoreBranchPattern {
+    /// Ignore branches whose names match this pattern. Supports exact names
+    /// and `*` as a glob wildcard.
+    Name(String),
+
+    /// Ignore branches whose tip commit has a committer date older than this
+    /// duration. Written as `older-than:<duration>`, such as
+    /// `older-than:90d`. Main branches are never ignored by age.
+    OlderThan(Duration),
+}
+
+impl IgnoreBranchPattern {
+    fn parse(value: &str) -> Option<Self> {
+        match value.strip_prefix("older-than:") {
+            Some(duration) => parse_duration(duration).map(Self::OlderThan),
+            None => Some(Self::Name(value.to_owned())),
+        }
+    }
+}
+
+/// Parse a duration such as `30s`, `15m`, `12h`, `90d`, or `2w`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let unit_index = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(unit_index);
+    let amount: u64 = amount.parse().ok()?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount.checked_mul(unit_secs)?))
+}
+
+/// Get the list of patterns for branches to ignore (exclude from branchless
+/// tracking). Supports exact names, glob patterns (e.g. `release/*`), and
+/// `older-than:<duration>` to ignore branches whose tip is older than the
+/// given duration (e.g. `older-than:90d`). Entries with an invalid duration
+/// are skipped with a warning.
 ///
 /// Set with: `git config --add branchless.core.ignoreBranches "pattern"`
 #[instrument]
-pub fn get_ignore_branches(repo: &Repo) -> eyre::Result<Vec<String>> {
+pub fn get_ignore_branches(repo: &Repo) -> eyre::Result<Vec<IgnoreBranchPattern>> {
     let config = repo.get_readonly_config()?;
     let entries = config.list("branchless.core.ignorebranches")?;
-    Ok(entries.into_iter().map(|(_, value)| value).collect())
+    Ok(entries
+        .into_iter()
+        .filter_map(|(_, value)| {
+            let pattern = IgnoreBranchPattern::parse(&value);
+            if pattern.is_none() {
+                warn!(
+                    ?value,
+                    "Invalid duration in branchless.core.ignoreBranches, ignoring pattern"
+                );
+            }
+            pattern
+        })
+        .collect())
 }
 
-/// Check if a branch name matches any of the ignore patterns.
-/// Patterns support `*` as a glob wildcard matching any sequence of characters.
-pub fn is_branch_ignored(branch_name: &str, ignore_patterns: &[String]) -> bool {
-    ignore_patterns
-        .iter()
-        .any(|pattern| glob_match(pattern, branch_name))
+/// Check if a branch matches any of the ignore patterns.
+///
+/// The tip commit of the branch is only looked up if there is an
+/// `older-than:` pattern. If the tip commit can't be found (for example,
+/// because it was garbage-collected), the age-based patterns don't match.
+pub fn is_branch_ignored(
+    repo: &Repo,
+    branch_name: &str,
+    branch_oid: Option<NonZeroOid>,
+    ignore_patterns: &[IgnoreBranchPattern],
+) -> eyre::Result<bool> {
+    for pattern in ignore_patterns {
+        let is_match = match pattern {
+            IgnoreBranchPattern::Name(pattern) => glob_match(pattern, branch_name),
+            IgnoreBranchPattern::OlderThan(_)
+                if get_main_branch_names(repo)?
+                    .iter()
+                    .any(|main_branch_name| main_branch_name == branch_name) =>
+            {
+                false
+            }
+            IgnoreBranchPattern::OlderThan(duration) => {
+                let commit = match branch_oid {
+                    Some(branch_oid) => repo.find_commit(branch_oid)?,
+                    None => None,
+                };
+                match commit {
+                    Some(commit) => {
+                        let commit_time = commit.get_committer().get_time().to_system_time()?;
+                        match SystemTime::now().duration_since(commit_time) {
+                            Ok(age) => age > *duration,
+                            Err(_) => false,
+                        }
+                    }
+                    None => false,
+                }
+            }
+        };
+        if is_match {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Get the pattern for tags which should be treated as public, if any. Commits
+/// reachable from a matching tag are considered public, in the same way as
+/// commits reachable from the main branch. Patterns support `*` as a glob
+/// wildcard, as with `branchless.core.ignoreBranches`.
+///
+/// Set with: `git config branchless.core.publicTags "v*"`
+#[instrument]
+pub fn get_public_tags(repo: &Repo) -> eyre::Result<Option<String>> {
+    let config = repo.get_readonly_config()?;
+    let pattern: Option<String> = config.get("branchless.core.publicTags")?;
+    Ok(pattern)
+}
+
+/// Check if a tag name (without the `refs/tags/` prefix) matches the public
+/// tags pattern.
+pub fn is_tag_public(tag_name: &str, public_tags_pattern: &str) -> bool {
+    glob_match(public_tags_pattern, tag_name)
 }
 
 /// Simple glob matching: `*` matches any sequence of characters.
@@ -275,6 +559,81 @@ pub fn get_restack_preserve_timestamps(repo: &Repo) -> eyre::Result<bool> {
         .get_or("branchless.restack.preserveTimestamps", false)
 }
 
+/// If `true`, rewritten commits have their committer date set to their author
+/// date, in the manner of `git rebase --committer-date-is-author-date`. This
+/// makes the OIDs of rewritten commits independent of when the rewrite happened.
+#[instrument]
+pub fn get_rebase_committer_date_is_author_date(repo: &Repo) -> eyre::Result<bool> {
+    repo.get_readonly_config()?
+        .get_or("branchless.rebase.committerDateIsAuthorDate", false)
+}
+
+/// If `true`, uncommitted changes in the working copy are stashed before a
+/// rebase and re-applied once it has concluded, in the manner of `git rebase
+/// --autostash`.
+#[instrument]
+pub fn get_rebase_auto_stash(repo: &Repo) -> eyre::Result<bool> {
+    repo.get_readonly_config()?
+        .get_or("branchless.rebase.autoStash", false)
+}
+
+/// If `true`, merge conflicts encountered during an in-memory rebase are
+/// resolved using the resolutions recorded by `git rerere`, if any, rather than
+/// aborting the rebase. On-disk rebases use `git rebase`, which consults the
+/// rerere cache itself when `rerere.enabled` is set.
+#[instrument]
+pub fn get_rebase_use_rerere(repo: &Repo) -> eyre::Result<bool> {
+    repo.get_readonly_config()?
+        .get_or("branchless.rebase.useRerere", false)
+}
+
+/// Get the list of path patterns for files which are regenerated rather than
+/// edited by hand, such as lockfiles. During an in-memory rebase, merge
+/// conflicts confined to these paths are resolved in favor of the commit being
+/// applied, rather than aborting the rebase.
+///
+/// Set with: `git config --add branchless.rebase.regeneratePaths "Cargo.lock"`
+#[instrument]
+pub fn get_rebase_regenerate_paths(repo: &Repo) -> eyre::Result<Vec<String>> {
+    let config = repo.get_readonly_config()?;
+    let entries = config.list("branchless.rebase.regeneratepaths")?;
+    Ok(entries.into_iter().map(|(_, value)| value).collect())
+}
+
+/// Check if a path matches any of the regenerated path patterns. As with
+/// `.gitignore`, a pattern without a slash matches the file name at any depth,
+/// a pattern with a leading slash is anchored to the root of the repository,
+/// and a pattern with a trailing slash matches everything under a directory.
+pub fn is_path_regenerated(path: &Path, regenerate_patterns: &[String]) -> bool {
+    let path = path.to_string_lossy();
+    regenerate_patterns.iter().any(|pattern| {
+        let (pattern, is_dir_pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (pattern, true),
+            None => (pattern.as_str(), false),
+        };
+        let is_anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+        let mut candidates = Vec::new();
+        let components = path.split('/').collect::<Vec<_>>();
+        if is_dir_pattern {
+            // Consider each ancestor directory of the path.
+            for i in 1..components.len() {
+                candidates.push((components[..i].join("/"), components[i - 1]));
+            }
+        } else {
+            candidates.push((path.to_string(), components[components.len() - 1]));
+        }
+        candidates.into_iter().any(|(full_path, name)| {
+            if is_anchored {
+                glob_match(pattern, &full_path)
+            } else {
+                glob_match(pattern, name)
+            }
+        })
+    })
+}
+
 /// If `true`, when advancing to a "next" commit, prompt interactively to
 /// if there is ambiguity in which commit to advance to.
 #[instrument]
@@ -297,6 +656,22 @@ pub fn get_commit_descriptors_differential_revision(repo: &Repo) -> eyre::Result
         .get_or("branchless.commitDescriptors.differentialRevision", true)
 }
 
+/// If `true`, show the cached status of the code review associated with each
+/// commit's branches in the smartlog.
+#[instrument]
+pub fn get_commit_descriptors_forge_status(repo: &Repo) -> eyre::Result<bool> {
+    repo.get_readonly_config()?
+        .get_or("branchless.commitDescriptors.forgeStatus", true)
+}
+
+/// If `true`, show the cached result of the most recent `git test run`
+/// command for each commit in the smartlog.
+#[instrument]
+pub fn get_commit_descriptors_test_status(repo: &Repo) -> eyre::Result<bool> {
+    repo.get_readonly_config()?
+        .get_or("branchless.commitDescriptors.testStatus", false)
+}
+
 /// If `true`, show the age of each commit in the smartlog.
 #[instrument]
 pub fn get_commit_descriptors_relative_time(repo: &Repo) -> eyre::Result<bool> {
@@ -321,6 +696,10 @@ pub enum Hint {
     /// Suggest running `git test clean` in order to clean cached test results.
     CleanCachedTestResults,
 
+    /// Warn when a newly-created commit adds lines which look like unresolved
+    /// merge conflict markers. Disabled by default.
+    ConflictMarkers,
+
     /// Suggest omitting arguments when they would default to `HEAD`.
     MoveImplicitHeadArgument,
 
@@ -340,18 +719,49 @@ impl Hint {
             Hint::AdvanceChildCommits => "branchless.hint.advanceChildCommits",
             Hint::AddSkippedFiles => "branchless.hint.addSkippedFiles",
             Hint::CleanCachedTestResults => "branchless.hint.cleanCachedTestResults",
+            Hint::ConflictMarkers => "branchless.hint.conflictMarkers",
             Hint::MoveImplicitHeadArgument => "branchless.hint.moveImplicitHeadArgument",
             Hint::RestackWarnAbandoned => "branchless.hint.restackWarnAbandoned",
             Hint::SmartlogFixAbandoned => "branchless.hint.smartlogFixAbandoned",
             Hint::TestShowVerbose => "branchless.hint.testShowVerbose",
         }
     }
+
+    fn is_enabled_by_default(&self) -> bool {
+        match self {
+            Hint::ConflictMarkers => false,
+            Hint::AdvanceChildCommits
+            | Hint::AddSkippedFiles
+            | Hint::CleanCachedTestResults
+            | Hint::MoveImplicitHeadArgument
+            | Hint::RestackWarnAbandoned
+            | Hint::SmartlogFixAbandoned
+            | Hint::TestShowVerbose => true,
+        }
+    }
 }
 
 /// Determine if a given hint is enabled.
 pub fn get_hint_enabled(repo: &Repo, hint: Hint) -> eyre::Result<bool> {
     repo.get_readonly_config()?
-        .get_or(hint.get_config_key(), true)
+        .get_or(hint.get_config_key(), hint.is_enabled_by_default())
+}
+
+/// Get the minimum amount of time which must elapse between two displays of
+/// the abandoned-commit warning emitted after a rewrite. A value of `0` means
+/// that the warning is always displayed.
+#[instrument]
+pub fn get_hint_abandoned_throttle(repo: &Repo) -> eyre::Result<Duration> {
+    let key = "branchless.hint.abandonedThrottle";
+    let value: Option<String> = repo.get_readonly_config()?.get(key)?;
+    let seconds = match value {
+        None => 0,
+        Some(value) => match parse_duration_config_value(&value) {
+            Ok(seconds) => seconds,
+            Err(err) => eyre::bail!("Invalid value for config key {key}: {err}"),
+        },
+    };
+    Ok(Duration::from_secs(seconds.into()))
 }
 
 /// Render the leading colored "hint" text for use in messaging.
@@ -375,6 +785,307 @@ pub fn print_hint_suppression_notice(effects: &Effects, hint: Hint) -> eyre::Res
     Ok(())
 }
 
+/// Parse a value of type [`ConfigValueType::Duration`] into a number of
+/// seconds.
+fn parse_duration_config_value(value: &str) -> Result<u32, String> {
+    let (amount, multiplier) = match value.char_indices().last() {
+        Some((i, 's')) => (&value[..i], 1),
+        Some((i, 'm')) => (&value[..i], 60),
+        Some((i, 'h')) => (&value[..i], 60 * 60),
+        Some((i, 'd')) => (&value[..i], 24 * 60 * 60),
+        _ => (value, 1),
+    };
+    amount
+        .parse::<u32>()
+        .ok()
+        .and_then(|amount| amount.checked_mul(multiplier))
+        .ok_or_else(|| format!("expected a duration, but got: {value}"))
+}
+
+/// The type of value expected for a config key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigValueType {
+    /// A boolean, such as `true` or `false`.
+    Bool,
+
+    /// An integer.
+    Int,
+
+    /// A number of seconds, optionally with a unit suffix such as `30s`, `5m`,
+    /// `2h` or `1d`.
+    Duration,
+
+    /// A revset expression.
+    Revset,
+
+    /// Arbitrary text.
+    String,
+
+    /// One of the given values (compared case-insensitively).
+    Enum(&'static [&'static str]),
+}
+
+impl ConfigValueType {
+    /// Check that `value` is valid for this type, and convert it into the
+    /// value which should be written to the config. Revset expressions are
+    /// returned as-is; it's up to the caller to check that they parse.
+    pub fn parse(&self, value: &str) -> Result<ConfigValue, String> {
+        match self {
+            ConfigValueType::Bool => match value.to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok(true.into()),
+                "false" | "no" | "off" | "0" => Ok(false.into()),
+                _ => Err(format!("expected a boolean, but got: {value}")),
+            },
+            ConfigValueType::Int => match value.parse::<i32>() {
+                Ok(value) => Ok(value.into()),
+                Err(_) => Err(format!("expected an integer, but got: {value}")),
+            },
+            ConfigValueType::Duration => {
+                let seconds = parse_duration_config_value(value)?;
+                let seconds = i32::try_from(seconds)
+                    .map_err(|_| format!("expected a duration, but got: {value}"))?;
+                Ok(seconds.into())
+            }
+            ConfigValueType::Revset | ConfigValueType::String => Ok(value.into()),
+            ConfigValueType::Enum(variants) => {
+                if variants
+                    .iter()
+                    .any(|variant| variant.eq_ignore_ascii_case(value))
+                {
+                    Ok(value.into())
+                } else {
+                    Err(format!(
+                        "expected one of {}, but got: {value}",
+                        variants.join(", ")
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// A config key recognized by git-branchless.
+#[derive(Clone, Copy, Debug)]
+pub struct ConfigKeySchema {
+    /// The name of the key. If it ends with `*`, then it matches any key with
+    /// the preceding prefix (such as `branchless.test.alias.*`).
+    pub key: &'static str,
+
+    /// The type of value expected for this key.
+    pub value_type: ConfigValueType,
+
+    /// The value which is used when the key is not set, if any.
+    pub default: Option<&'static str>,
+}
+
+macro_rules! config_key {
+    ($key:expr, $value_type:expr) => {
+        ConfigKeySchema {
+            key: $key,
+            value_type: $value_type,
+            default: None,
+        }
+    };
+    ($key:expr, $value_type:expr, $default:expr) => {
+        ConfigKeySchema {
+            key: $key,
+            value_type: $value_type,
+            default: Some($default),
+        }
+    };
+}
+
+/// All of the `branchless.*` config keys which are recognized by
+/// git-branchless.
+pub const CONFIG_SCHEMA: &[ConfigKeySchema] = &[
+    config_key!("branchless.advance.auto", ConfigValueType::Bool, "false"),
+    config_key!(
+        "branchless.commitDescriptors.branches",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.commitDescriptors.differentialRevision",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.commitDescriptors.forgeStatus",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.commitDescriptors.relativeTime",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.commitDescriptors.testStatus",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!("branchless.core.ignoreBranches", ConfigValueType::String),
+    config_key!("branchless.core.mainBranch", ConfigValueType::String),
+    config_key!(
+        PUBLIC_HEAD_BEHAVIOR_CONFIG_KEY,
+        ConfigValueType::Enum(&["allow", "skip", "requireForce"]),
+        "allow"
+    ),
+    config_key!("branchless.core.publicTags", ConfigValueType::String),
+    config_key!(
+        "branchless.hide.confirmUnique",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!(
+        "branchless.hint.abandonedThrottle",
+        ConfigValueType::Duration,
+        "0"
+    ),
+    config_key!(
+        "branchless.hint.addSkippedFiles",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.hint.advanceChildCommits",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.hint.cleanCachedTestResults",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.hint.conflictMarkers",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!(
+        "branchless.hint.moveImplicitHeadArgument",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.hint.restackWarnAbandoned",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.hint.smartlogFixAbandoned",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.hint.testShowVerbose",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!("branchless.mainBranch", ConfigValueType::String),
+    config_key!(
+        "branchless.navigation.autoSwitchBranches",
+        ConfigValueType::Bool,
+        "true"
+    ),
+    config_key!(
+        "branchless.next.interactive",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!(
+        "branchless.rebase.autoStash",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!(
+        "branchless.rebase.committerDateIsAuthorDate",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!("branchless.rebase.regeneratePaths", ConfigValueType::String),
+    config_key!(
+        "branchless.rebase.useRerere",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!(
+        "branchless.record.untrackedFiles",
+        ConfigValueType::Enum(&["add", "disable", "prompt", "skip"]),
+        "disable"
+    ),
+    config_key!(
+        "branchless.restack.preserveTimestamps",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!(RESTACK_WARN_ABANDONED_CONFIG_KEY, ConfigValueType::Bool),
+    config_key!("branchless.revsets.alias.*", ConfigValueType::Revset),
+    config_key!(
+        SMARTLOG_CHILD_ORDER_CONFIG_KEY,
+        ConfigValueType::Enum(&["committerDate", "alphabeticalBranch", "topological"]),
+        "committerDate"
+    ),
+    config_key!(
+        "branchless.smartlog.defaultRevset",
+        ConfigValueType::Revset,
+        SMARTLOG_DEFAULT_REVSET
+    ),
+    config_key!(
+        "branchless.smartlog.detectDuplicates",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!(SMARTLOG_MAX_COMMITS_CONFIG_KEY, ConfigValueType::Int, "0"),
+    config_key!(
+        "branchless.smartlog.obsoleteMarker",
+        ConfigValueType::String
+    ),
+    config_key!(
+        "branchless.smartlog.rewrittenAsText",
+        ConfigValueType::String,
+        "rewritten as"
+    ),
+    config_key!(
+        "branchless.smartlog.showStashes",
+        ConfigValueType::Bool,
+        "false"
+    ),
+    config_key!("branchless.test.alias.*", ConfigValueType::String),
+    config_key!("branchless.test.jobs", ConfigValueType::Int),
+    config_key!(
+        "branchless.test.strategy",
+        ConfigValueType::Enum(&["working-copy", "worktree"]),
+        "working-copy"
+    ),
+    config_key!(
+        THEME_CONFIG_KEY,
+        ConfigValueType::Enum(&["default", "minimal", "highContrast", "nerdfont"]),
+        "default"
+    ),
+    config_key!(
+        "branchless.undo.createSnapshots",
+        ConfigValueType::Bool,
+        "true"
+    ),
+];
+
+/// Look up the schema for the given config key, if it's a known key. Config
+/// keys are compared case-insensitively.
+pub fn find_config_key_schema(key: &str) -> Option<&'static ConfigKeySchema> {
+    CONFIG_SCHEMA
+        .iter()
+        .find(|schema| match schema.key.strip_suffix('*') {
+            Some(prefix) => match key.get(..prefix.len()) {
+                Some(key_prefix) => {
+                    key.len() > prefix.len() && key_prefix.eq_ignore_ascii_case(prefix)
+                }
+                None => false,
+            },
+            None => schema.key.eq_ignore_ascii_case(key),
+        })
+}
+
 /// Environment variables which affect the functioning of `git-branchless`.
 pub mod env_vars {
     use std::path::PathBuf;
@@ -422,6 +1133,31 @@ pub mod env_vars {
     /// manually.
     pub const TEST_SEPARATE_COMMAND_BINARIES: &str = "TEST_SEPARATE_COMMAND_BINARIES";
 
+    /// If set to a non-empty value, all `git-branchless` hooks exit immediately
+    /// without doing anything. This is useful for scripts which create a series
+    /// of commits and don't want side effects such as auto-advancing sibling
+    /// commits (see `branchless.advance.auto`).
+    ///
+    /// Note that events such as new commits and reference updates are also not
+    /// recorded while this is set, so they can't be undone with `git undo`.
+    pub const DISABLE_HOOKS: &str = "GIT_BRANCHLESS_DISABLE_HOOKS";
+
+    /// If set, the tracing spans recorded while running a command are written
+    /// as JSON to the file at this path. This is equivalent to passing
+    /// `--trace`. Nested invocations (such as from hooks) write to the same
+    /// path with a suffix indicating their nesting level.
+    pub const TRACE: &str = "GIT_BRANCHLESS_TRACE";
+
+    /// Determine whether hooks have been disabled by setting the
+    /// [`DISABLE_HOOKS`] environment variable.
+    #[instrument]
+    pub fn should_disable_hooks() -> bool {
+        match std::env::var_os(DISABLE_HOOKS) {
+            Some(value) => !value.is_empty(),
+            None => false,
+        }
+    }
+
     /// Get the path to the Git executable for testing.
     #[instrument]
     pub fn get_path_to_git() -> eyre::Result<PathBuf> {
@@ -3,8 +3,10 @@
 
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt::Debug;
 use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
@@ -80,6 +82,35 @@ pub fn union_all(commits: &[CommitSet]) -> CommitSet {
         .fold(CommitSet::empty(), |acc, elem| acc.union(elem))
 }
 
+/// Summarize everything which determines the heads added by [`Dag::sync`],
+/// so that the sync can be skipped if none of it has changed.
+fn make_sync_key(
+    event_cursor: EventCursor,
+    references_snapshot: &RepoReferencesSnapshot,
+) -> String {
+    let RepoReferencesSnapshot {
+        head_oid,
+        main_branch_oids,
+        branch_oid_to_names,
+        public_tag_oids,
+    } = references_snapshot;
+    let mut hasher = DefaultHasher::new();
+    event_cursor.hash(&mut hasher);
+    head_oid.hash(&mut hasher);
+    main_branch_oids.hash(&mut hasher);
+    branch_oid_to_names
+        .keys()
+        .sorted()
+        .collect_vec()
+        .hash(&mut hasher);
+    public_tag_oids
+        .iter()
+        .sorted()
+        .collect_vec()
+        .hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
 struct GitParentsBlocking {
     repo: Arc<Mutex<Repo>>,
 }
@@ -136,12 +167,19 @@ pub struct Dag {
     /// this is an empty set.
     pub head_commit: CommitSet,
 
-    /// A set containing the commit that the main branch currently points to.
+    /// A set containing the commits that the main branches currently point
+    /// to. There is usually only one main branch, but several can be
+    /// configured with `branchless.core.mainBranch`.
     pub main_branch_commit: CommitSet,
 
     /// A set containing all commits currently pointed to by local branches.
     pub branch_commits: CommitSet,
 
+    /// A set containing all commits pointed to by tags matching
+    /// `branchless.core.publicTags`. These commits and their ancestors are
+    /// considered public, like those of the main branch.
+    pub public_tag_commits: CommitSet,
+
     /// A set containing all commits that have been observed by the
     /// `EventReplayer`.
     observed_commits: CommitSet,
@@ -165,6 +203,7 @@ impl Dag {
             head_commit: self.head_commit.clone(),
             main_branch_commit: self.main_branch_commit.clone(),
             branch_commits: self.branch_commits.clone(),
+            public_tag_commits: self.public_tag_commits.clone(),
             observed_commits: self.observed_commits.clone(),
             obsolete_commits: self.obsolete_commits.clone(),
             public_commits: OnceCell::new(),
@@ -195,6 +234,29 @@ impl Dag {
         Ok(dag)
     }
 
+    /// Like [`Dag::open_and_sync`], but skip updating the DAG if neither the
+    /// event log nor the references have changed since the last time it was
+    /// updated this way. Meant for read-only commands, which are run often
+    /// and would otherwise pay for a full sync every time.
+    #[instrument]
+    pub fn open_and_sync_if_changed(
+        effects: &Effects,
+        repo: &Repo,
+        event_replayer: &EventReplayer,
+        event_cursor: EventCursor,
+        references_snapshot: &RepoReferencesSnapshot,
+    ) -> eyre::Result<Self> {
+        let mut dag = Self::open_without_syncing(
+            effects,
+            repo,
+            event_replayer,
+            event_cursor,
+            references_snapshot,
+        )?;
+        dag.sync_if_changed(effects, repo, event_cursor, references_snapshot)?;
+        Ok(dag)
+    }
+
     /// Initialize a DAG for the given repository, without updating it with new
     /// commits that may have appeared.
     ///
@@ -212,8 +274,9 @@ impl Dag {
         let observed_commits = event_replayer.get_cursor_oids(event_cursor);
         let RepoReferencesSnapshot {
             head_oid,
-            main_branch_oid,
+            main_branch_oids,
             branch_oid_to_names,
+            public_tag_oids,
         } = references_snapshot;
 
         let obsolete_commits: CommitSet = observed_commits
@@ -234,14 +297,16 @@ impl Dag {
             Some(head_oid) => CommitSet::from(*head_oid),
             None => CommitSet::empty(),
         };
-        let main_branch_commit = CommitSet::from(*main_branch_oid);
+        let main_branch_commit: CommitSet = main_branch_oids.iter().copied().collect();
         let branch_commits: CommitSet = branch_oid_to_names.keys().copied().collect();
+        let public_tag_commits: CommitSet = public_tag_oids.iter().copied().collect();
 
         Ok(Self {
             inner: dag,
             head_commit,
             main_branch_commit,
             branch_commits,
+            public_tag_commits,
             observed_commits,
             obsolete_commits,
             public_commits: Default::default(),
@@ -267,7 +332,7 @@ impl Dag {
     /// Update the DAG with all commits reachable from branches.
     #[instrument]
     fn sync(&mut self, effects: &Effects, repo: &Repo) -> eyre::Result<()> {
-        let master_heads = self.main_branch_commit.clone();
+        let master_heads = self.query_public_heads();
         let non_master_heads = self
             .observed_commits
             .union(&self.head_commit)
@@ -275,6 +340,33 @@ impl Dag {
         self.sync_from_oids(effects, repo, master_heads, non_master_heads)
     }
 
+    /// Update the DAG with all commits reachable from branches, unless it was
+    /// already updated for the same event cursor and references. Returns
+    /// whether the DAG was updated.
+    ///
+    /// Since commits are never removed from the DAG, the heads added by the
+    /// last such update are still present as long as the key matches.
+    #[instrument]
+    pub fn sync_if_changed(
+        &mut self,
+        effects: &Effects,
+        repo: &Repo,
+        event_cursor: EventCursor,
+        references_snapshot: &RepoReferencesSnapshot,
+    ) -> eyre::Result<bool> {
+        let sync_key = make_sync_key(event_cursor, references_snapshot);
+        let sync_key_path = repo.get_dag_dir()?.join("last-sync");
+        match std::fs::read_to_string(&sync_key_path) {
+            Ok(last_sync_key) if last_sync_key.trim() == sync_key => return Ok(false),
+            Ok(_) | Err(_) => {}
+        }
+
+        self.sync(effects, repo)?;
+        std::fs::write(&sync_key_path, format!("{sync_key}\n"))
+            .wrap_err_with(|| format!("Writing DAG sync key to {sync_key_path:?}"))?;
+        Ok(true)
+    }
+
     /// Update the DAG with the given heads.
     #[instrument]
     pub fn sync_from_oids(
@@ -342,6 +434,7 @@ impl Dag {
             inner,
             head_commit: self.head_commit.clone(),
             branch_commits: self.branch_commits.clone(),
+            public_tag_commits: self.public_tag_commits.clone(),
             main_branch_commit: self.main_branch_commit.clone(),
             observed_commits: self.observed_commits.clone(),
             obsolete_commits: CommitSet::empty(),
@@ -398,15 +491,21 @@ impl Dag {
         &self.inner
     }
 
+    /// The commits whose ancestors are considered public: the main branch
+    /// commit and any commits pointed to by public tags.
+    fn query_public_heads(&self) -> CommitSet {
+        self.main_branch_commit.union(&self.public_tag_commits)
+    }
+
     /// Determine whether or not the given commit is a public commit (i.e. is an
-    /// ancestor of the main branch).
+    /// ancestor of the main branch or of a public tag).
     #[instrument]
     pub fn is_public_commit(&self, commit_oid: NonZeroOid) -> eyre::Result<bool> {
-        let main_branch_commits = self.commit_set_to_vec(&self.main_branch_commit)?;
-        for main_branch_commit in main_branch_commits {
+        let public_heads = self.commit_set_to_vec(&self.query_public_heads())?;
+        for public_head in public_heads {
             if self.run_blocking(
                 self.inner
-                    .is_ancestor(commit_oid.into(), main_branch_commit.into()),
+                    .is_ancestor(commit_oid.into(), public_head.into()),
             )? {
                 return Ok(true);
             }
@@ -465,7 +564,7 @@ impl Dag {
     pub fn query_public_commits_slow(&self) -> eyre::Result<&CommitSet> {
         self.public_commits.get_or_try_init(|| {
             let public_commits =
-                self.run_blocking(self.inner.ancestors(self.main_branch_commit.clone()))?;
+                self.run_blocking(self.inner.ancestors(self.query_public_heads()))?;
             Ok(public_commits)
         })
     }
@@ -523,7 +622,7 @@ impl Dag {
             let visible_heads = self.query_visible_heads()?;
             let draft_commits = self.run_blocking(
                 self.inner
-                    .only(visible_heads.clone(), self.main_branch_commit.clone()),
+                    .only(visible_heads.clone(), self.query_public_heads()),
             )?;
             Ok(draft_commits)
         })
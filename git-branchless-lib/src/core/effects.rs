@@ -248,6 +248,9 @@ pub mod icons {
     /// Used to indicate a warning.
     pub const EXCLAMATION: &str = "!";
 
+    /// Used to indicate an unknown status.
+    pub const QUESTION_MARK: &str = "?";
+
     /// Used to indicate failure.
     ///
     /// Can't use "✗️" in interactive progress meters because some terminals think its width is >1,
@@ -13,7 +13,7 @@ use std::str::FromStr;
 use std::time::{Duration, SystemTime};
 
 use eyre::Context;
-use tracing::{error, instrument};
+use tracing::{error, instrument, warn};
 
 use crate::core::config::{get_ignore_branches, is_branch_ignored};
 use crate::core::effects::{Effects, OperationType};
@@ -26,6 +26,11 @@ use super::repo_ext::RepoReferencesSnapshot;
 /// which the caller has already started.
 pub const BRANCHLESS_TRANSACTION_ID_ENV_VAR: &str = "BRANCHLESS_TRANSACTION_ID";
 
+/// Set by the hook scripts to the PID of the Git process which invoked the
+/// hook. Hooks invoked by the same Git process share a transaction, so that all
+/// the side effects of a single Git command can be undone at once.
+pub const BRANCHLESS_HOOK_PARENT_PID_ENV_VAR: &str = "BRANCHLESS_HOOK_PARENT_PID";
+
 // Wrapper around the row stored directly in the database.
 #[derive(Clone, Debug)]
 struct Row {
@@ -476,6 +481,143 @@ impl std::fmt::Debug for EventLogDb<'_> {
     }
 }
 
+/// The maximum number of times to retry an operation on the event log database
+/// when it's locked by another process.
+const MAX_BUSY_RETRIES: u32 = 6;
+
+/// The delay before the first retry of an operation on a locked event log
+/// database. The delay is doubled after each retry.
+const INITIAL_BUSY_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Whether the error was caused by the database being locked by another
+/// connection.
+fn is_database_busy_error(err: &eyre::Error) -> bool {
+    err.chain()
+        .any(|err| match err.downcast_ref::<rusqlite::Error>() {
+            Some(rusqlite::Error::SqliteFailure(err, _)) => matches!(
+                err.code,
+                rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+            ),
+            _ => false,
+        })
+}
+
+/// Run the given database operation, retrying it with exponential backoff if it
+/// fails because the database is locked.
+///
+/// SQLite's busy timeout handles most lock contention, but it's not invoked
+/// in some cases, such as when a deferred transaction can't be upgraded to a
+/// write transaction because another connection is writing.
+fn retry_if_busy<T>(mut f: impl FnMut() -> eyre::Result<T>) -> eyre::Result<T> {
+    let mut delay = INITIAL_BUSY_RETRY_DELAY;
+    let mut num_retries = 0;
+    loop {
+        match f() {
+            Err(err) if num_retries < MAX_BUSY_RETRIES && is_database_busy_error(&err) => {
+                warn!(?err, ?delay, "Event log database is locked, retrying");
+                std::thread::sleep(delay);
+                delay *= 2;
+                num_retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Get an opaque representation of the start time of the given process, or
+/// `None` if it can't be determined on this platform.
+#[cfg(target_os = "linux")]
+fn get_process_start_time(pid: &str) -> Option<String> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The process name is parenthesized and may itself contain spaces or
+    // parentheses, so skip past it before splitting. The start time is the
+    // 22nd field overall, which is the 20th field after the process name.
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields
+        .split_whitespace()
+        .nth(19)
+        .map(|field| field.to_owned())
+}
+
+/// Get an opaque representation of the start time of the given process, or
+/// `None` if it can't be determined on this platform.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn get_process_start_time(pid: &str) -> Option<String> {
+    get_process_info_from_ps(pid, "lstart=")
+}
+
+/// Get an opaque representation of the start time of the given process, or
+/// `None` if it can't be determined on this platform.
+#[cfg(not(unix))]
+fn get_process_start_time(_pid: &str) -> Option<String> {
+    None
+}
+
+/// Get the command-line arguments of the given process.
+#[cfg(target_os = "linux")]
+fn get_process_args(pid: &str) -> Option<Vec<String>> {
+    let cmdline = std::fs::read(format!("/proc/{pid}/cmdline")).ok()?;
+    Some(
+        cmdline
+            .split(|byte| *byte == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect(),
+    )
+}
+
+/// Get the command-line arguments of the given process. Arguments containing
+/// whitespace are not reported faithfully, which is acceptable since only the
+/// Git subcommand name is needed.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn get_process_args(pid: &str) -> Option<Vec<String>> {
+    let args = get_process_info_from_ps(pid, "args=")?;
+    Some(args.split_whitespace().map(|arg| arg.to_owned()).collect())
+}
+
+/// Get the command-line arguments of the given process.
+#[cfg(not(unix))]
+fn get_process_args(_pid: &str) -> Option<Vec<String>> {
+    None
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn get_process_info_from_ps(pid: &str, format: &str) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", format, "-p", pid])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let info = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    if info.is_empty() { None } else { Some(info) }
+}
+
+/// Get the subcommand (such as `commit`) being run by the given Git process.
+fn get_process_git_subcommand(pid: &str) -> Option<String> {
+    let args = get_process_args(pid)?;
+    let (program, args) = args.split_first()?;
+
+    // Dashed external commands, such as `git-rebase`, are invoked directly.
+    let program_name = std::path::Path::new(program).file_name()?.to_str()?;
+    if let Some(subcommand) = program_name.strip_prefix("git-") {
+        return Some(subcommand.to_owned());
+    }
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-C" | "-c" | "--git-dir" | "--work-tree" | "--namespace" | "--config-env" => {
+                args.next();
+            }
+            arg if arg.starts_with('-') => {}
+            arg => return Some(arg.to_owned()),
+        }
+    }
+    None
+}
+
 #[instrument]
 fn init_tables(conn: &rusqlite::Connection) -> eyre::Result<()> {
     conn.execute(
@@ -512,6 +654,18 @@ CREATE TABLE IF NOT EXISTS event_transactions (
     )
     .wrap_err("Creating `event_transactions` table")?;
 
+    conn.execute(
+        "
+CREATE TABLE IF NOT EXISTS undo_position (
+    target_event_id INTEGER NOT NULL,
+    before_undo_event_id INTEGER NOT NULL,
+    after_undo_event_id INTEGER NOT NULL
+)
+",
+        rusqlite::params![],
+    )
+    .wrap_err("Creating `undo_position` table")?;
+
     Ok(())
 }
 
@@ -519,7 +673,7 @@ impl<'conn> EventLogDb<'conn> {
     /// Constructor.
     #[instrument]
     pub fn new(conn: &'conn rusqlite::Connection) -> eyre::Result<Self> {
-        init_tables(conn)?;
+        retry_if_busy(|| init_tables(conn))?;
         Ok(EventLogDb { conn })
     }
 
@@ -529,9 +683,13 @@ impl<'conn> EventLogDb<'conn> {
     /// * events: The events to add.
     #[instrument]
     pub fn add_events(&self, events: Vec<Event>) -> eyre::Result<()> {
+        retry_if_busy(|| self.add_events_inner(&events))
+    }
+
+    fn add_events_inner(&self, events: &[Event]) -> eyre::Result<()> {
         let tx = self.conn.unchecked_transaction()?;
         for event in events {
-            let row = match Row::try_from(event) {
+            let row = match Row::try_from(event.clone()) {
                 Ok(row) => row,
                 Err(()) => continue,
             };
@@ -663,7 +821,66 @@ ORDER BY rowid ASC
         now: SystemTime,
         message: impl AsRef<str>,
     ) -> eyre::Result<EventTransactionId> {
-        self.make_transaction_id_inner(now, message.as_ref())
+        retry_if_busy(|| self.make_transaction_id_inner(now, message.as_ref()))
+    }
+
+    /// Create an event transaction ID for use in a hook. If an earlier hook
+    /// was invoked by the same Git process (as indicated by
+    /// [`BRANCHLESS_HOOK_PARENT_PID_ENV_VAR`]), then its transaction is reused
+    /// instead of creating a new one.
+    ///
+    /// The Git process is identified by both its PID and its start time, so
+    /// that an unrelated process which happens to reuse the PID later doesn't
+    /// join the transaction. If the process can't be identified, a new
+    /// transaction is created, as if the hook were not being grouped.
+    pub fn make_hook_transaction_id(
+        &self,
+        repo: &Repo,
+        now: SystemTime,
+        message: impl AsRef<str>,
+    ) -> eyre::Result<EventTransactionId> {
+        let parent_pid = match std::env::var(BRANCHLESS_HOOK_PARENT_PID_ENV_VAR) {
+            Ok(parent_pid)
+                if !parent_pid.is_empty()
+                    && std::env::var_os(BRANCHLESS_TRANSACTION_ID_ENV_VAR).is_none() =>
+            {
+                parent_pid
+            }
+            _ => return self.make_transaction_id(now, message),
+        };
+        let process_key = match get_process_start_time(&parent_pid) {
+            Some(start_time) => format!("{parent_pid}:{start_time}"),
+            None => return self.make_transaction_id(now, message),
+        };
+
+        // Store the transaction in the worktree-specific Git directory, since
+        // Git commands may be running concurrently in different worktrees.
+        let path = repo.get_path().join("branchless-hook-transaction");
+        let existing_event_tx_id = std::fs::read_to_string(&path).ok().and_then(|contents| {
+            let (key, event_tx_id) = contents.trim().rsplit_once(' ')?;
+            if key == process_key {
+                event_tx_id.parse().ok()
+            } else {
+                None
+            }
+        });
+        let event_tx_id = match existing_event_tx_id {
+            Some(event_tx_id) => event_tx_id,
+            None => {
+                // Name the transaction after the Git command which invoked
+                // the hook, rather than after whichever hook happened to run
+                // first.
+                let message = match get_process_git_subcommand(&parent_pid) {
+                    Some(subcommand) => format!("git {subcommand}"),
+                    None => message.as_ref().to_owned(),
+                };
+                let event_tx_id = self.make_transaction_id(now, message)?;
+                std::fs::write(&path, format!("{process_key} {event_tx_id}\n"))
+                    .wrap_err_with(|| format!("Writing hook transaction to {path:?}"))?;
+                event_tx_id
+            }
+        };
+        Ok(event_tx_id)
     }
 
     /// Get the message associated with the given transaction.
@@ -692,6 +909,98 @@ WHERE event_tx_id = :event_tx_id
     }
 }
 
+/// The positions in the event log recorded by the most recent `git undo`, so
+/// that it can be redone later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UndoPosition {
+    /// The cursor which the repository was restored to.
+    pub target_cursor: EventCursor,
+
+    /// The cursor pointing to immediately after the last event before the
+    /// undo was applied.
+    pub before_undo_cursor: EventCursor,
+
+    /// The cursor pointing to immediately after the last event once the undo
+    /// was applied. If more events have been added since then, then the undo
+    /// can no longer be redone.
+    pub after_undo_cursor: EventCursor,
+}
+
+impl EventLogDb<'_> {
+    /// Record the position of the most recent undo, replacing any previously
+    /// recorded position.
+    #[instrument]
+    pub fn set_undo_position(&self, undo_position: UndoPosition) -> eyre::Result<()> {
+        retry_if_busy(|| {
+            let UndoPosition {
+                target_cursor,
+                before_undo_cursor,
+                after_undo_cursor,
+            } = undo_position;
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM undo_position", rusqlite::params![])?;
+            tx.execute(
+                "
+INSERT INTO undo_position VALUES (
+    :target_event_id,
+    :before_undo_event_id,
+    :after_undo_event_id
+)
+",
+                rusqlite::named_params! {
+                    ":target_event_id": target_cursor.event_id,
+                    ":before_undo_event_id": before_undo_cursor.event_id,
+                    ":after_undo_event_id": after_undo_cursor.event_id,
+                },
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Get the position of the most recent undo, if it hasn't been redone or
+    /// cleared yet.
+    #[instrument]
+    pub fn get_undo_position(&self) -> eyre::Result<Option<UndoPosition>> {
+        let mut stmt = self.conn.prepare(
+            "
+SELECT target_event_id, before_undo_event_id, after_undo_event_id
+FROM undo_position
+",
+        )?;
+        let result = stmt
+            .query_map(rusqlite::params![], |row| {
+                let target_event_id: isize = row.get("target_event_id")?;
+                let before_undo_event_id: isize = row.get("before_undo_event_id")?;
+                let after_undo_event_id: isize = row.get("after_undo_event_id")?;
+                Ok(UndoPosition {
+                    target_cursor: EventCursor {
+                        event_id: target_event_id,
+                    },
+                    before_undo_cursor: EventCursor {
+                        event_id: before_undo_event_id,
+                    },
+                    after_undo_cursor: EventCursor {
+                        event_id: after_undo_event_id,
+                    },
+                })
+            })?
+            .next()
+            .transpose()?;
+        Ok(result)
+    }
+
+    /// Forget the position of the most recent undo.
+    #[instrument]
+    pub fn clear_undo_position(&self) -> eyre::Result<()> {
+        retry_if_busy(|| {
+            self.conn
+                .execute("DELETE FROM undo_position", rusqlite::params![])?;
+            Ok(())
+        })
+    }
+}
+
 /// Determine whether a given reference is used to keep a commit alive.
 ///
 /// Returns: Whether or not the given reference is used internally to keep the
@@ -764,7 +1073,7 @@ struct EventInfo {
 /// Thus, all events before to the cursor are considered to be in effect,
 /// and all events after the cursor are considered to not have happened
 /// yet.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct EventCursor {
     event_id: isize,
 }
@@ -777,8 +1086,8 @@ pub struct EventReplayer {
     /// The list of observed events.
     events: Vec<Event>,
 
-    /// The name of the reference representing the main branch.
-    main_branch_reference_name: ReferenceName,
+    /// The names of the references representing the main branches.
+    main_branch_reference_names: Vec<ReferenceName>,
 
     /// The events that have affected each commit.
     commit_history: HashMap<NonZeroOid, Vec<EventInfo>>,
@@ -803,11 +1112,11 @@ impl std::fmt::Debug for EventReplayer {
 }
 
 impl EventReplayer {
-    fn new(main_branch_reference_name: ReferenceName) -> Self {
+    fn new(main_branch_reference_names: Vec<ReferenceName>) -> Self {
         EventReplayer {
             id_counter: 0,
             events: vec![],
-            main_branch_reference_name,
+            main_branch_reference_names,
             commit_history: HashMap::new(),
             ref_locations: HashMap::new(),
         }
@@ -827,8 +1136,12 @@ impl EventReplayer {
     ) -> eyre::Result<Self> {
         let (_effects, _progress) = effects.start_operation(OperationType::ProcessEvents);
 
-        let main_branch_reference_name = repo.get_main_branch()?.get_reference_name()?;
-        let mut result = EventReplayer::new(main_branch_reference_name);
+        let main_branch_reference_names = repo
+            .get_main_branches()?
+            .into_iter()
+            .map(|main_branch| main_branch.get_reference_name())
+            .collect::<eyre::Result<Vec<_>>>()?;
+        let mut result = EventReplayer::new(main_branch_reference_names);
         for event in event_log_db.get_events()? {
             result.process_event(&event);
         }
@@ -1249,7 +1562,7 @@ impl EventReplayer {
         Ok(oid)
     }
 
-    /// Get the OID of the main branch at the cursor's point in time.
+    /// Get the OIDs of the main branches at the cursor's point in time.
     ///
     /// Note that this doesn't handle the case of the user having changed their
     /// main branch configuration. That is, if it was previously `master`, and
@@ -1259,24 +1572,38 @@ impl EventReplayer {
     /// Args:
     /// * `repo`: The Git repository.
     ///
-    /// Returns: A mapping from an OID to the names of branches pointing to that
-    /// OID.
+    /// Returns: The reference name and OID of each main branch.
     #[instrument]
-    fn get_cursor_main_branch_oid(
+    fn get_cursor_main_branch_oids(
         &self,
         cursor: EventCursor,
         repo: &Repo,
-    ) -> eyre::Result<NonZeroOid> {
-        let main_branch_reference_name = repo.get_main_branch()?.get_reference_name()?;
-        let main_branch_oid = self.get_cursor_branch_oid(cursor, &main_branch_reference_name)?;
-        match main_branch_oid {
-            Some(main_branch_oid) => Ok(main_branch_oid),
-            None => {
-                // Assume the main branch just hasn't been observed moving yet,
-                // so its value at the current time is fine to use.
-                repo.get_main_branch_oid()
-            }
+    ) -> eyre::Result<Vec<(&ReferenceName, NonZeroOid)>> {
+        let mut result = Vec::new();
+        for main_branch_reference_name in self.main_branch_reference_names.iter() {
+            let main_branch_oid = match self
+                .get_cursor_branch_oid(cursor, main_branch_reference_name)?
+            {
+                Some(main_branch_oid) => main_branch_oid,
+                None => {
+                    // Assume the main branch just hasn't been observed moving yet,
+                    // so its value at the current time is fine to use.
+                    let main_branch_oid = match repo.find_reference(main_branch_reference_name)? {
+                        Some(reference) => repo.resolve_reference(&reference)?.oid,
+                        None => None,
+                    };
+                    match main_branch_oid {
+                        Some(main_branch_oid) => main_branch_oid,
+                        None => eyre::bail!(
+                            "Could not find commit pointed to by main branch: {:?}",
+                            main_branch_reference_name,
+                        ),
+                    }
+                }
+            };
+            result.push((main_branch_reference_name, main_branch_oid));
         }
+        Ok(result)
     }
 
     /// Get the mapping of branch OIDs to names at the cursor's point in
@@ -1324,7 +1651,7 @@ impl EventReplayer {
             {
                 if !ignore_patterns.is_empty() {
                     let short_name = CategorizedReferenceName::new(ref_name).render_suffix();
-                    if is_branch_ignored(&short_name, &ignore_patterns) {
+                    if is_branch_ignored(repo, &short_name, Some(*ref_oid), &ignore_patterns)? {
                         continue;
                     }
                 }
@@ -1335,11 +1662,14 @@ impl EventReplayer {
             }
         }
 
-        let main_branch_oid = self.get_cursor_main_branch_oid(cursor, repo)?;
-        result
-            .entry(main_branch_oid)
-            .or_default()
-            .insert(self.main_branch_reference_name.clone());
+        for (main_branch_reference_name, main_branch_oid) in
+            self.get_cursor_main_branch_oids(cursor, repo)?
+        {
+            result
+                .entry(main_branch_oid)
+                .or_default()
+                .insert(main_branch_reference_name.clone());
+        }
         Ok(result)
     }
 
@@ -1350,12 +1680,19 @@ impl EventReplayer {
         cursor: EventCursor,
     ) -> eyre::Result<RepoReferencesSnapshot> {
         let head_oid = self.get_cursor_head_oid(cursor);
-        let main_branch_oid = self.get_cursor_main_branch_oid(cursor, repo)?;
+        let main_branch_oids = self
+            .get_cursor_main_branch_oids(cursor, repo)?
+            .into_iter()
+            .map(|(_, main_branch_oid)| main_branch_oid)
+            .collect();
         let branch_oid_to_names = self.get_cursor_branch_oid_to_names(cursor, repo)?;
+        // Tags aren't tracked in the event log, so use their current positions.
+        let public_tag_oids = repo.get_public_tag_oids()?;
         Ok(RepoReferencesSnapshot {
             head_oid,
-            main_branch_oid,
+            main_branch_oids,
             branch_oid_to_names,
+            public_tag_oids,
         })
     }
 
@@ -1402,6 +1739,22 @@ impl EventReplayer {
         let cursor_event_id: usize = cursor.event_id.try_into().unwrap();
         &self.events[cursor_event_id..]
     }
+
+    /// Get all the events that happened between the two event cursors.
+    ///
+    /// Returns: An ordered list of events that happened after `start` and
+    /// before `end`, from least recent to most recent. If `end` is not after
+    /// `start`, the list is empty.
+    pub fn get_events_between_cursors(&self, start: EventCursor, end: EventCursor) -> &[Event] {
+        let start = self.make_cursor(start.event_id);
+        let end = self.make_cursor(end.event_id);
+        if end.event_id <= start.event_id {
+            return &[];
+        }
+        let start_event_id: usize = start.event_id.try_into().unwrap();
+        let end_event_id: usize = end.event_id.try_into().unwrap();
+        &self.events[start_event_id..end_event_id]
+    }
 }
 
 /// Testing helpers.
@@ -1410,7 +1763,7 @@ pub mod testing {
 
     /// Create a new `EventReplayer`, for testing.
     pub fn new_event_replayer(main_branch_reference_name: ReferenceName) -> EventReplayer {
-        EventReplayer::new(main_branch_reference_name)
+        EventReplayer::new(vec![main_branch_reference_name])
     }
 
     /// Create a new transaction ID, for testing.
@@ -0,0 +1,124 @@
+//! Utilities to save and load the last-known status of branches on a forge
+//! (such as GitHub), so that it can be displayed without hitting the network.
+//!
+//! The cache is populated by forge integrations such as `git submit` and read
+//! by the `forgeStatus` commit descriptor in the smartlog.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use eyre::Context;
+use tracing::instrument;
+
+/// The last-known status of a branch on a forge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForgeStatus {
+    /// The forge-specific identifier of the code review associated with the
+    /// branch, such as `#123` for a GitHub pull request.
+    pub identifier: String,
+
+    /// The state of the code review, such as `open` or `closed`.
+    pub state: String,
+}
+
+/// Get the cached forge statuses, keyed by local branch name.
+#[instrument]
+pub fn get_cached_forge_statuses(
+    conn: &rusqlite::Connection,
+) -> eyre::Result<HashMap<String, ForgeStatus>> {
+    init_forge_statuses_table(conn)?;
+
+    let mut stmt = conn.prepare("SELECT branch_name, identifier, state FROM forge_statuses")?;
+    let statuses = stmt
+        .query_map(rusqlite::named_params![], |row| {
+            let branch_name: String = row.get("branch_name")?;
+            let identifier: String = row.get("identifier")?;
+            let state: String = row.get("state")?;
+            Ok((branch_name, ForgeStatus { identifier, state }))
+        })?
+        .collect::<Result<HashMap<_, _>, _>>()
+        .wrap_err("Reading cached forge statuses")?;
+    Ok(statuses)
+}
+
+/// Replace the cached forge statuses with the given ones, keyed by local branch
+/// name.
+#[instrument]
+pub fn cache_forge_statuses(
+    conn: &rusqlite::Connection,
+    statuses: &HashMap<String, ForgeStatus>,
+) -> eyre::Result<()> {
+    init_forge_statuses_table(conn)?;
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM forge_statuses", rusqlite::params![])
+        .wrap_err("Clearing `forge_statuses` table")?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .wrap_err("Calculating forge status timestamp")?
+        .as_secs_f64();
+    for (branch_name, ForgeStatus { identifier, state }) in statuses {
+        tx.execute(
+            "
+            INSERT INTO forge_statuses
+                (timestamp, branch_name, identifier, state)
+            VALUES
+                (:timestamp, :branch_name, :identifier, :state)
+            ",
+            rusqlite::named_params! {
+                ":timestamp": timestamp,
+                ":branch_name": branch_name,
+                ":identifier": identifier,
+                ":state": state,
+            },
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Move the cached forge status for a renamed branch to its new name.
+#[instrument]
+pub fn rename_cached_forge_status(
+    conn: &rusqlite::Connection,
+    old_branch_name: &str,
+    new_branch_name: &str,
+) -> eyre::Result<()> {
+    init_forge_statuses_table(conn)?;
+
+    conn.execute(
+        "
+        UPDATE OR REPLACE forge_statuses
+        SET branch_name = :new_branch_name
+        WHERE branch_name = :old_branch_name
+        ",
+        rusqlite::named_params! {
+            ":old_branch_name": old_branch_name,
+            ":new_branch_name": new_branch_name,
+        },
+    )
+    .wrap_err("Renaming branch in `forge_statuses` table")?;
+
+    Ok(())
+}
+
+/// Ensure the `forge_statuses` table exists; creating it if it does not.
+#[instrument]
+fn init_forge_statuses_table(conn: &rusqlite::Connection) -> eyre::Result<()> {
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS forge_statuses (
+            timestamp REAL NOT NULL,
+            branch_name TEXT NOT NULL PRIMARY KEY,
+            identifier TEXT NOT NULL,
+            state TEXT NOT NULL
+        )
+        ",
+        rusqlite::params![],
+    )
+    .wrap_err("Creating `forge_statuses` table")?;
+
+    Ok(())
+}
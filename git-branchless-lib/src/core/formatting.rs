@@ -4,6 +4,7 @@
 //! "TTY"). In the case of interactive output, we render with prettier non-ASCII
 //! characters and with colors, using shell-specific escape codes.
 
+use std::ffi::OsString;
 use std::fmt::Display;
 
 use cursive::theme::{ConcreteEffects, Effect, Style};
@@ -69,6 +70,52 @@ impl Display for Pluralize<'_> {
     }
 }
 
+/// A named bundle of glyphs and colors used to render output, as selected by
+/// the `branchless.theme` config option.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Theme {
+    /// The standard glyphs and colors.
+    Default,
+
+    /// Plain ASCII glyphs which don't distinguish main branch commits, and no
+    /// colors.
+    Minimal,
+
+    /// Heavier connector lines and bright colors.
+    HighContrast,
+
+    /// Commit markers from the Nerd Fonts icon set. Requires a patched font.
+    NerdFont,
+}
+
+impl Theme {
+    /// All available themes.
+    pub const ALL: &'static [Theme] = &[
+        Theme::Default,
+        Theme::Minimal,
+        Theme::HighContrast,
+        Theme::NerdFont,
+    ];
+
+    /// The name used to select this theme in the configuration.
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::Minimal => "minimal",
+            Theme::HighContrast => "highContrast",
+            Theme::NerdFont => "nerdfont",
+        }
+    }
+
+    /// Look up a theme by its name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|theme| theme.name().eq_ignore_ascii_case(name))
+    }
+}
+
 /// Glyphs to use for rendering the smartlog.
 #[derive(Clone)]
 pub struct Glyphs {
@@ -76,6 +123,10 @@ pub struct Glyphs {
     /// color).
     pub should_write_ansi_escape_codes: bool,
 
+    /// The theme these glyphs were derived from. Determines the color palette
+    /// used when rendering.
+    pub theme: Theme,
+
     /// Line connecting a parent commit to its single child commit.
     pub line: &'static str,
 
@@ -127,6 +178,14 @@ pub struct Glyphs {
     /// commit or merged from this parent commit.
     pub commit_merge: &'static str,
 
+    /// Marker for a commit which is an ancestor of the commit passed to
+    /// `git smartlog --relative-to`.
+    pub relative_to_ancestor: &'static str,
+
+    /// Marker for a commit whose summary matches the pattern passed to
+    /// `git smartlog --highlight`.
+    pub highlighted_commit: &'static str,
+
     /// Character used to point to the currently-checked-out branch.
     pub branch_arrow: &'static str,
 
@@ -149,11 +208,34 @@ pub struct Glyphs {
     pub cycle_lower_left_corner: &'static str,
 }
 
+/// Determine whether color has been explicitly enabled or disabled via the
+/// environment, using the [CLICOLOR](https://bixense.com/clicolors/) and
+/// [NO_COLOR](https://no-color.org/) conventions. `CLICOLOR_FORCE` takes
+/// precedence over `NO_COLOR`, which takes precedence over `CLICOLOR=0`.
+///
+/// Returns `None` if the environment doesn't express a preference, in which
+/// case color should be used only when writing to a terminal.
+pub fn get_color_override_from_env(get_env: impl Fn(&str) -> Option<OsString>) -> Option<bool> {
+    let is_set = |name: &str| get_env(name).is_some_and(|value| !value.is_empty());
+    let is_zero = |name: &str| get_env(name).is_some_and(|value| value == "0");
+
+    if is_set("CLICOLOR_FORCE") && !is_zero("CLICOLOR_FORCE") {
+        Some(true)
+    } else if is_set("NO_COLOR") || is_zero("CLICOLOR") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
 impl Glyphs {
     /// Make the `Glyphs` object appropriate for `stdout`.
     pub fn detect() -> Self {
-        let color_support = concolor::get(concolor::Stream::Stdout);
-        if color_support.color() {
+        let should_color = match get_color_override_from_env(|name| std::env::var_os(name)) {
+            Some(should_color) => should_color,
+            None => concolor::get(concolor::Stream::Stdout).color(),
+        };
+        if should_color {
             Glyphs::pretty()
         } else {
             Glyphs::text()
@@ -164,6 +246,7 @@ impl Glyphs {
     pub fn text() -> Self {
         Glyphs {
             should_write_ansi_escape_codes: false,
+            theme: Theme::Default,
             line: "|",
             line_with_offshoot: "|",
             vertical_ellipsis: ":",
@@ -179,6 +262,8 @@ impl Glyphs {
             commit_main_obsolete_head: "%",
             commit_omitted: "#",
             commit_merge: "&",
+            relative_to_ancestor: "*",
+            highlighted_commit: "!",
             branch_arrow: ">",
             bullet_point: "-",
             cycle_arrow: ">",
@@ -193,6 +278,7 @@ impl Glyphs {
     pub fn pretty() -> Self {
         Glyphs {
             should_write_ansi_escape_codes: true,
+            theme: Theme::Default,
             line: "│",
             line_with_offshoot: "├",
             vertical_ellipsis: "⋮",
@@ -208,6 +294,8 @@ impl Glyphs {
             commit_main_head: "◆",
             commit_main_obsolete: "✕",
             commit_main_obsolete_head: "❖",
+            relative_to_ancestor: "▸",
+            highlighted_commit: "★",
             branch_arrow: "ᐅ",
             bullet_point: "•",
             cycle_arrow: "ᐅ",
@@ -218,6 +306,68 @@ impl Glyphs {
         }
     }
 
+    /// Apply the given theme on top of these glyphs. Themes other than
+    /// `Theme::Minimal` only change the glyphs used for output to a TTY, since
+    /// they may not be representable in plain text.
+    pub fn with_theme(self, theme: Theme) -> Self {
+        let glyphs = Glyphs { theme, ..self };
+        match theme {
+            Theme::Default => glyphs,
+            Theme::Minimal => Glyphs {
+                line: "|",
+                line_with_offshoot: "|",
+                vertical_ellipsis: ":",
+                split: "\\",
+                merge: "/",
+                commit_visible: "*",
+                commit_visible_head: "@",
+                commit_obsolete: "x",
+                commit_obsolete_head: "@",
+                commit_main: "*",
+                commit_main_head: "@",
+                commit_main_obsolete: "x",
+                commit_main_obsolete_head: "@",
+                commit_omitted: ".",
+                commit_merge: "&",
+                relative_to_ancestor: "+",
+                highlighted_commit: "!",
+                branch_arrow: ">",
+                bullet_point: "-",
+                cycle_arrow: ">",
+                cycle_horizontal_line: "-",
+                cycle_vertical_line: "|",
+                cycle_upper_left_corner: ",",
+                cycle_lower_left_corner: "`",
+                ..glyphs
+            },
+            Theme::HighContrast | Theme::NerdFont if !glyphs.should_write_ansi_escape_codes => {
+                glyphs
+            }
+            Theme::HighContrast => Glyphs {
+                line: "┃",
+                line_with_offshoot: "┣",
+                vertical_ellipsis: "┇",
+                split: "━┓",
+                merge: "━┛",
+                cycle_horizontal_line: "━",
+                cycle_vertical_line: "┃",
+                cycle_upper_left_corner: "┏",
+                cycle_lower_left_corner: "┗",
+                ..glyphs
+            },
+            Theme::NerdFont => Glyphs {
+                vertical_ellipsis: "\u{f142}",
+                commit_visible: "\u{f10c}",
+                commit_visible_head: "\u{f111}",
+                commit_obsolete: "\u{f05c}",
+                commit_obsolete_head: "\u{f057}",
+                commit_merge: "\u{f419}",
+                branch_arrow: "\u{f418}",
+                ..glyphs
+            },
+        }
+    }
+
     /// Return a `Glyphs` object suitable for rendering graphs in the reverse of
     /// their usual order.
     pub fn reverse_order(mut self, reverse: bool) -> Self {
@@ -241,7 +391,7 @@ impl Glyphs {
                     width: _,
                 } = span;
                 if self.should_write_ansi_escape_codes {
-                    Ok(render_style_as_ansi(content, *attr)?)
+                    Ok(render_style_as_ansi(content, *attr, self.theme)?)
                 } else {
                     Ok(content.to_string())
                 }
@@ -255,8 +405,8 @@ impl std::fmt::Debug for Glyphs {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "<Glyphs pretty={:?}>",
-            self.should_write_ansi_escape_codes
+            "<Glyphs pretty={:?} theme={:?}>",
+            self.should_write_ansi_escape_codes, self.theme
         )
     }
 }
@@ -365,13 +515,20 @@ impl From<StyledStringBuilder> for StyledString {
     }
 }
 
-fn render_style_as_ansi(content: &str, style: Style) -> eyre::Result<String> {
+fn render_style_as_ansi(content: &str, style: Style, theme: Theme) -> eyre::Result<String> {
     let Style { effects, color } = style;
     let output = {
         use console::style;
         use cursive::theme::{BaseColor, Color, ColorType};
         let output = content.to_string();
-        match color.front {
+        let front = match (theme, color.front) {
+            (Theme::Minimal, _) => ColorType::InheritParent,
+            (Theme::HighContrast, ColorType::Color(Color::Dark(color))) => {
+                ColorType::Color(Color::Light(color))
+            }
+            (_, front) => front,
+        };
+        match front {
             ColorType::Palette(_) => {
                 eyre::bail!("Not implemented: using cursive palette colors")
             }
@@ -406,6 +563,7 @@ fn render_style_as_ansi(content: &str, style: Style) -> eyre::Result<String> {
         let mut output = output;
         for effect in effects.resolve(ConcreteEffects::empty()) {
             output = match effect {
+                Effect::Dim if theme == Theme::HighContrast => output,
                 Effect::Simple => output,
                 Effect::Dim => output.dim(),
                 Effect::Reverse => output.reverse(),
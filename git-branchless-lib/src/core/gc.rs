@@ -18,7 +18,7 @@ use crate::core::eventlog::{
     CommitActivityStatus, EventCursor, EventLogDb, EventReplayer, is_gc_ref,
 };
 use crate::core::formatting::Pluralize;
-use crate::git::{NonZeroOid, Reference, Repo};
+use crate::git::{NonZeroOid, Reference, ReferenceName, Repo};
 
 /// Find references under `refs/branchless/` which point to commits which are no
 /// longer active. These are safe to remove.
@@ -30,7 +30,7 @@ pub fn find_dangling_references<'repo>(
     let mut result = Vec::new();
     for reference in repo.get_all_references()? {
         let reference_name = reference.get_name()?;
-        if !is_gc_ref(&reference_name) {
+        if !is_gc_ref(&reference_name) || is_checkpoint_ref(&reference_name) {
             continue;
         }
 
@@ -94,6 +94,38 @@ pub fn mark_commit_reachable(repo: &Repo, commit_oid: NonZeroOid) -> eyre::Resul
     Ok(())
 }
 
+/// The prefix of references which keep checkpointed commits alive.
+const CHECKPOINT_REF_PREFIX: &str = "refs/branchless/checkpoints/";
+
+/// Determine whether the given reference keeps a checkpointed commit alive.
+///
+/// Unlike other references under `refs/branchless/`, these are kept even after
+/// their commits have been hidden, so that the commits can be recovered later.
+pub fn is_checkpoint_ref(reference_name: &ReferenceName) -> bool {
+    reference_name.as_str().starts_with(CHECKPOINT_REF_PREFIX)
+}
+
+/// Mark a commit as a checkpoint.
+///
+/// Like `mark_commit_reachable`, but the commit stays reachable even once it's
+/// been hidden, and isn't collected by branchless's own garbage collection.
+#[instrument]
+pub fn mark_commit_checkpoint(repo: &Repo, commit_oid: NonZeroOid) -> eyre::Result<()> {
+    let ref_name = format!("{CHECKPOINT_REF_PREFIX}{commit_oid}");
+    eyre::ensure!(
+        Reference::is_valid_name(&ref_name),
+        format!("Invalid ref name to mark commit as a checkpoint: {ref_name}")
+    );
+    repo.create_reference(
+        &ref_name.into(),
+        commit_oid,
+        true,
+        "branchless: marking commit as a checkpoint",
+    )
+    .wrap_err("Creating reference")?;
+    Ok(())
+}
+
 /// Run branchless's garbage collection.
 ///
 /// Frees any references to commits which are no longer visible in the smartlog.
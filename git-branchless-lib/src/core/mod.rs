@@ -1,10 +1,12 @@
 //! Core algorithms and data structures.
 
 pub mod check_out;
+pub mod commit_format;
 pub mod config;
 pub mod dag;
 pub mod effects;
 pub mod eventlog;
+pub mod forge_status_cache;
 pub mod formatting;
 pub mod gc;
 pub mod node_descriptors;
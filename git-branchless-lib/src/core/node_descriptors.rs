@@ -8,21 +8,29 @@ use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
 use bstr::{ByteSlice, ByteVec};
-use cursive::theme::BaseColor;
+use cursive::theme::{BaseColor, Effect};
 use cursive::utils::markup::StyledString;
+use itertools::Itertools;
 use lazy_static::lazy_static;
-use regex::Regex;
+use regex::{Captures, Regex};
 use tracing::instrument;
 
 use crate::core::config::{
     get_commit_descriptors_branches, get_commit_descriptors_differential_revision,
-    get_commit_descriptors_relative_time,
+    get_commit_descriptors_forge_status, get_commit_descriptors_relative_time,
+    get_commit_descriptors_test_status, get_smartlog_detect_duplicates,
+    get_smartlog_rewritten_as_text,
 };
 use crate::git::{
-    CategorizedReferenceName, Commit, NonZeroOid, ReferenceName, Repo, ResolvedReferenceInfo,
+    CategorizedReferenceName, Commit, NonZeroOid, PatchId, ReferenceName, Repo,
+    ResolvedReferenceInfo, SerializedTestResult, TEST_INDETERMINATE_EXIT_CODE,
+    TEST_SUCCESS_EXIT_CODE, get_latest_test_command_path, get_test_result_path,
 };
 
+use super::dag::{CommitSet, Dag};
+use super::effects::{Effects, icons};
 use super::eventlog::{Event, EventCursor, EventReplayer};
+use super::forge_status_cache::{ForgeStatus, get_cached_forge_statuses};
 use super::formatting::{Glyphs, StyledStringBuilder};
 use super::repo_ext::RepoReferencesSnapshot;
 use super::rewrite::find_rewrite_target;
@@ -78,6 +86,14 @@ pub enum Redactor {
 
         /// A mapping from ref name to its redacted version.
         ref_names: Arc<Mutex<HashMap<ReferenceName, ReferenceName>>>,
+
+        /// The OIDs which should be redacted wherever they appear in text
+        /// passed to [`Redactor::redact_oids`]. If empty, OIDs aren't
+        /// redacted.
+        redacted_oids: HashSet<NonZeroOid>,
+
+        /// A mapping from OID to its redacted version.
+        oids: Arc<Mutex<HashMap<NonZeroOid, String>>>,
     },
 }
 
@@ -87,6 +103,27 @@ impl Redactor {
         Self::Enabled {
             preserved_ref_names,
             ref_names: Default::default(),
+            redacted_oids: Default::default(),
+            oids: Default::default(),
+        }
+    }
+
+    /// Also redact the given OIDs, such as the ones known from the event log
+    /// and references, in text passed to [`Redactor::redact_oids`].
+    pub fn with_redacted_oids(self, oids_to_redact: HashSet<NonZeroOid>) -> Self {
+        match self {
+            Redactor::Disabled => Redactor::Disabled,
+            Redactor::Enabled {
+                preserved_ref_names,
+                ref_names,
+                redacted_oids,
+                oids,
+            } => Redactor::Enabled {
+                preserved_ref_names,
+                ref_names,
+                redacted_oids: redacted_oids.into_iter().chain(oids_to_redact).collect(),
+                oids,
+            },
         }
     }
 
@@ -97,6 +134,8 @@ impl Redactor {
             Redactor::Enabled {
                 preserved_ref_names,
                 ref_names,
+                redacted_oids: _,
+                oids: _,
             } => {
                 if preserved_ref_names.contains(&ref_name) || !ref_name.as_str().contains('/') {
                     return ref_name;
@@ -127,6 +166,8 @@ impl Redactor {
             Redactor::Enabled {
                 preserved_ref_names: _,
                 ref_names: _,
+                redacted_oids: _,
+                oids: _,
             } => summary
                 .chars()
                 .map(|char| {
@@ -139,6 +180,49 @@ impl Redactor {
                 .collect(),
         }
     }
+
+    /// Replace the full or abbreviated forms of the OIDs to be redacted in the
+    /// given text with placeholders. The same OID is always given the same
+    /// placeholder, so that the relationships between commits are preserved.
+    pub fn redact_oids(&self, text: &str) -> String {
+        let (redacted_oids, oids) = match self {
+            Redactor::Disabled => return text.to_owned(),
+            Redactor::Enabled {
+                preserved_ref_names: _,
+                ref_names: _,
+                redacted_oids,
+                oids,
+            } => (redacted_oids, oids),
+        };
+        if redacted_oids.is_empty() {
+            return text.to_owned();
+        }
+
+        lazy_static! {
+            static ref HEX_RE: Regex = Regex::new(r"\b[0-9a-f]{7,40}\b").unwrap();
+        }
+        let mut oids = oids.lock().expect("Poisoned mutex");
+        HEX_RE
+            .replace_all(text, |captures: &Captures| {
+                let hex = &captures[0];
+                let oid = redacted_oids
+                    .iter()
+                    .filter(|oid| oid.to_string().starts_with(hex))
+                    .exactly_one();
+                match oid {
+                    Ok(oid) => {
+                        let len = oids.len();
+                        oids.entry(*oid)
+                            .or_insert_with(|| format!("<oid-{}>", len + 1))
+                            .clone()
+                    }
+                    // Either not an OID, or too short to identify a single
+                    // commit.
+                    Err(_) => hex.to_owned(),
+                }
+            })
+            .into_owned()
+    }
 }
 
 /// Interface to display information about a node in the smartlog.
@@ -233,18 +317,93 @@ impl NodeDescriptor for CommitMessageDescriptor<'_> {
     }
 }
 
+/// Mark the commits which are ancestors of a given commit, as with
+/// `git smartlog --relative-to`.
+#[derive(Debug)]
+pub struct RelativeToDescriptor {
+    ancestor_oids: HashSet<NonZeroOid>,
+}
+
+impl RelativeToDescriptor {
+    /// Constructor. `ancestor_oids` are the commits to mark.
+    pub fn new(ancestor_oids: HashSet<NonZeroOid>) -> eyre::Result<Self> {
+        Ok(RelativeToDescriptor { ancestor_oids })
+    }
+}
+
+impl NodeDescriptor for RelativeToDescriptor {
+    #[instrument]
+    fn describe_node(
+        &mut self,
+        glyphs: &Glyphs,
+        object: &NodeObject,
+    ) -> eyre::Result<Option<StyledString>> {
+        if self.ancestor_oids.contains(&object.get_oid()) {
+            Ok(Some(StyledString::styled(
+                glyphs.relative_to_ancestor,
+                BaseColor::Cyan.light(),
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Mark the commits whose summary matches a given pattern, as with
+/// `git smartlog --highlight`.
+#[derive(Debug)]
+pub struct HighlightDescriptor {
+    pattern: Option<Regex>,
+}
+
+impl HighlightDescriptor {
+    /// Constructor. If `pattern` is `None`, no commits are marked.
+    pub fn new(pattern: Option<Regex>) -> eyre::Result<Self> {
+        Ok(HighlightDescriptor { pattern })
+    }
+}
+
+impl NodeDescriptor for HighlightDescriptor {
+    #[instrument]
+    fn describe_node(
+        &mut self,
+        glyphs: &Glyphs,
+        object: &NodeObject,
+    ) -> eyre::Result<Option<StyledString>> {
+        let (pattern, commit) = match (&self.pattern, object) {
+            (Some(pattern), NodeObject::Commit { commit }) => (pattern, commit),
+            (None, _) | (_, NodeObject::GarbageCollected { oid: _ }) => return Ok(None),
+        };
+        let summary = commit.get_summary()?.to_vec().into_string_lossy();
+        if pattern.is_match(&summary) {
+            Ok(Some(StyledString::styled(
+                glyphs.highlighted_commit,
+                BaseColor::Yellow.light(),
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// For obsolete commits, provide the reason that it's obsolete.
 pub struct ObsolescenceExplanationDescriptor<'a> {
     event_replayer: &'a EventReplayer,
     event_cursor: EventCursor,
+    rewritten_as_text: String,
 }
 
 impl<'a> ObsolescenceExplanationDescriptor<'a> {
     /// Constructor.
-    pub fn new(event_replayer: &'a EventReplayer, event_cursor: EventCursor) -> eyre::Result<Self> {
+    pub fn new(
+        repo: &Repo,
+        event_replayer: &'a EventReplayer,
+        event_cursor: EventCursor,
+    ) -> eyre::Result<Self> {
         Ok(ObsolescenceExplanationDescriptor {
             event_replayer,
             event_cursor,
+            rewritten_as_text: get_smartlog_rewritten_as_text(repo)?,
         })
     }
 }
@@ -270,7 +429,11 @@ impl NodeDescriptor for ObsolescenceExplanationDescriptor<'_> {
                     find_rewrite_target(self.event_replayer, self.event_cursor, object.get_oid());
                 rewrite_target.map(|rewritten_oid| {
                     StyledString::styled(
-                        format!("(rewritten as {})", &rewritten_oid.to_string()[..8]),
+                        format!(
+                            "({} {})",
+                            self.rewritten_as_text,
+                            &rewritten_oid.to_string()[..8]
+                        ),
                         BaseColor::Black.light(),
                     )
                 })
@@ -290,6 +453,136 @@ impl NodeDescriptor for ObsolescenceExplanationDescriptor<'_> {
     }
 }
 
+/// For draft commits whose patch is identical to that of another rendered draft
+/// commit (such as after a cherry-pick), indicate the likely duplicates.
+#[derive(Debug)]
+pub struct DuplicateCommitDescriptor {
+    duplicates: HashMap<NonZeroOid, Vec<NonZeroOid>>,
+}
+
+impl DuplicateCommitDescriptor {
+    /// Constructor. Patch IDs are only calculated for the draft commits in
+    /// `commits`, and only if `branchless.smartlog.detectDuplicates` is set.
+    pub fn new(
+        effects: &Effects,
+        repo: &Repo,
+        dag: &Dag,
+        commits: &CommitSet,
+    ) -> eyre::Result<Self> {
+        let mut duplicates: HashMap<NonZeroOid, Vec<NonZeroOid>> = HashMap::new();
+        if get_smartlog_detect_duplicates(repo)? {
+            let draft_commits = commits.intersection(dag.query_draft_commits()?);
+            let mut patch_id_to_oids: HashMap<PatchId, Vec<NonZeroOid>> = HashMap::new();
+            for oid in dag.commit_set_to_vec(&draft_commits)? {
+                let commit = match repo.find_commit(oid)? {
+                    Some(commit) => commit,
+                    None => continue,
+                };
+                if let Some(patch_id) = repo.get_patch_id(effects, &commit)? {
+                    patch_id_to_oids.entry(patch_id).or_default().push(oid);
+                }
+            }
+
+            for oids in patch_id_to_oids.into_values() {
+                if oids.len() < 2 {
+                    continue;
+                }
+                for oid in oids.iter() {
+                    let other_oids = oids.iter().copied().filter(|other| other != oid).collect();
+                    duplicates.insert(*oid, other_oids);
+                }
+            }
+        }
+        Ok(DuplicateCommitDescriptor { duplicates })
+    }
+}
+
+impl NodeDescriptor for DuplicateCommitDescriptor {
+    #[instrument]
+    fn describe_node(
+        &mut self,
+        _glyphs: &Glyphs,
+        object: &NodeObject,
+    ) -> eyre::Result<Option<StyledString>> {
+        let other_oids = match self.duplicates.get(&object.get_oid()) {
+            Some(other_oids) => other_oids,
+            None => return Ok(None),
+        };
+        let mut other_oids: Vec<String> = other_oids
+            .iter()
+            .map(|oid| oid.to_string()[..7].to_string())
+            .collect();
+        other_oids.sort_unstable();
+        Ok(Some(StyledString::styled(
+            format!("(duplicate of {})", other_oids.join(", ")),
+            BaseColor::Red.light(),
+        )))
+    }
+}
+
+/// Display the cached result of the most recent `git test run` command for
+/// each commit. Results are looked up by the commit's tree, so they carry over
+/// to commits that have been rewritten without changing their contents.
+#[derive(Debug)]
+pub struct TestStatusDescriptor<'a> {
+    repo: &'a Repo,
+    latest_test_command: Option<String>,
+}
+
+impl<'a> TestStatusDescriptor<'a> {
+    /// Constructor.
+    pub fn new(repo: &'a Repo) -> eyre::Result<Self> {
+        let latest_test_command = if get_commit_descriptors_test_status(repo)? {
+            match std::fs::read_to_string(get_latest_test_command_path(repo)?) {
+                Ok(command) => Some(command),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                Err(err) => return Err(err.into()),
+            }
+        } else {
+            None
+        };
+        Ok(TestStatusDescriptor {
+            repo,
+            latest_test_command,
+        })
+    }
+}
+
+impl NodeDescriptor for TestStatusDescriptor<'_> {
+    #[instrument]
+    fn describe_node(
+        &mut self,
+        _glyphs: &Glyphs,
+        object: &NodeObject,
+    ) -> eyre::Result<Option<StyledString>> {
+        let latest_test_command = match &self.latest_test_command {
+            Some(latest_test_command) => latest_test_command,
+            None => return Ok(None),
+        };
+        let commit = match object {
+            NodeObject::Commit { commit } => commit,
+            NodeObject::GarbageCollected { oid: _ } => return Ok(None),
+        };
+
+        let result_path = get_test_result_path(self.repo, commit, latest_test_command.to_owned())?;
+        let exit_code = std::fs::read_to_string(result_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<SerializedTestResult>(&contents).ok())
+            .map(|result| result.exit_code);
+        let result = match exit_code {
+            Some(TEST_SUCCESS_EXIT_CODE) => {
+                StyledString::styled(icons::CHECKMARK, BaseColor::Green.light())
+            }
+            Some(TEST_INDETERMINATE_EXIT_CODE) => {
+                StyledString::styled(icons::EXCLAMATION, BaseColor::Yellow.light())
+            }
+            Some(_) => StyledString::styled(icons::CROSS, BaseColor::Red.light()),
+            None => StyledString::styled(icons::QUESTION_MARK, Effect::Dim),
+        };
+        Ok(Some(result))
+    }
+}
+
 /// Display branches that point to a given commit.
 #[derive(Debug)]
 pub struct BranchesDescriptor<'a> {
@@ -440,6 +733,87 @@ impl NodeDescriptor for DifferentialRevisionDescriptor<'_> {
     }
 }
 
+/// Display the last-known status of the code reviews associated with the
+/// branches pointing to a given commit, as cached by forge integrations such as
+/// `git submit`. This never queries the forge itself.
+#[derive(Debug)]
+pub struct ForgeStatusDescriptor<'a> {
+    references_snapshot: &'a RepoReferencesSnapshot,
+    redactor: &'a Redactor,
+    forge_statuses: HashMap<String, ForgeStatus>,
+}
+
+impl<'a> ForgeStatusDescriptor<'a> {
+    /// Constructor.
+    pub fn new(
+        repo: &Repo,
+        references_snapshot: &'a RepoReferencesSnapshot,
+        redactor: &'a Redactor,
+    ) -> eyre::Result<Self> {
+        let forge_statuses = if get_commit_descriptors_forge_status(repo)? {
+            let conn = repo.get_db_conn()?;
+            get_cached_forge_statuses(&conn)?
+        } else {
+            Default::default()
+        };
+        Ok(ForgeStatusDescriptor {
+            references_snapshot,
+            redactor,
+            forge_statuses,
+        })
+    }
+}
+
+impl NodeDescriptor for ForgeStatusDescriptor<'_> {
+    #[instrument]
+    fn describe_node(
+        &mut self,
+        _glyphs: &Glyphs,
+        object: &NodeObject,
+    ) -> eyre::Result<Option<StyledString>> {
+        match self.redactor {
+            Redactor::Enabled { .. } => return Ok(None),
+            Redactor::Disabled => {}
+        }
+        if self.forge_statuses.is_empty() {
+            return Ok(None);
+        }
+
+        let branch_names = match self
+            .references_snapshot
+            .branch_oid_to_names
+            .get(&object.get_oid())
+        {
+            Some(branch_names) => branch_names,
+            None => return Ok(None),
+        };
+        let mut descriptions: Vec<String> = branch_names
+            .iter()
+            .filter_map(
+                |branch_name| match CategorizedReferenceName::new(branch_name) {
+                    reference_name @ CategorizedReferenceName::LocalBranch { .. } => {
+                        self.forge_statuses.get(&reference_name.render_suffix())
+                    }
+                    CategorizedReferenceName::RemoteBranch { .. }
+                    | CategorizedReferenceName::OtherRef { .. } => None,
+                },
+            )
+            .map(|ForgeStatus { identifier, state }| format!("{identifier} {state}"))
+            .collect();
+        if descriptions.is_empty() {
+            return Ok(None);
+        }
+        descriptions.sort_unstable();
+        descriptions.dedup();
+
+        let result = StyledString::styled(
+            format!("[{}]", descriptions.join(", ")),
+            BaseColor::Magenta.light(),
+        );
+        Ok(Some(result))
+    }
+}
+
 /// Display how long ago the given commit was committed.
 #[derive(Debug)]
 pub struct RelativeTimeDescriptor {
@@ -10,7 +10,9 @@ use crate::git::{
     Branch, BranchType, CategorizedReferenceName, ConfigRead, NonZeroOid, ReferenceName, Repo,
 };
 
-use super::config::{get_ignore_branches, get_main_branch_name, is_branch_ignored};
+use super::config::{
+    get_ignore_branches, get_main_branch_names, get_public_tags, is_branch_ignored, is_tag_public,
+};
 
 /// A snapshot of all the positions of references we care about in the repository.
 #[derive(Debug)]
@@ -18,27 +20,49 @@ pub struct RepoReferencesSnapshot {
     /// The location of the `HEAD` reference. This may be `None` if `HEAD` is unborn.
     pub head_oid: Option<NonZeroOid>,
 
-    /// The location of the main branch.
-    pub main_branch_oid: NonZeroOid,
+    /// The locations of the main branches.
+    pub main_branch_oids: Vec<NonZeroOid>,
 
     /// A mapping from commit OID to the branches which point to that commit.
     pub branch_oid_to_names: HashMap<NonZeroOid, HashSet<ReferenceName>>,
+
+    /// The commits pointed to by tags matching `branchless.core.publicTags`.
+    pub public_tag_oids: HashSet<NonZeroOid>,
 }
 
 /// Helper functions on [`Repo`].
 pub trait RepoExt {
-    /// Get the `Branch` for the main branch for the repository.
+    /// Get the `Branch`es for the main branches for the repository, in the
+    /// order they were configured.
+    fn get_main_branches(&self) -> eyre::Result<Vec<Branch<'_>>>;
+
+    /// Get the `Branch` for the main branch for the repository. If there are
+    /// multiple main branches, returns the one nearest to `HEAD`, as per
+    /// `get_nearest_main_branch`.
     fn get_main_branch(&self) -> eyre::Result<Branch<'_>>;
 
-    /// Get the OID corresponding to the main branch.
+    /// Get the `Branch` for the main branch nearest to the given commit, i.e.
+    /// the one whose merge-base with the commit is the most recent. Ties go to
+    /// the first configured main branch.
+    fn get_nearest_main_branch(&self, commit_oid: NonZeroOid) -> eyre::Result<Branch<'_>>;
+
+    /// Get the OID corresponding to the main branch, as per `get_main_branch`.
     fn get_main_branch_oid(&self) -> eyre::Result<NonZeroOid>;
 
+    /// Get the OIDs corresponding to all of the main branches.
+    fn get_main_branch_oids(&self) -> eyre::Result<Vec<NonZeroOid>>;
+
     /// Get a mapping from OID to the names of branches which point to that OID.
     ///
     /// The returned branch names include the `refs/heads/` prefix, so it must
     /// be stripped if desired.
     fn get_branch_oid_to_names(&self) -> eyre::Result<HashMap<NonZeroOid, HashSet<ReferenceName>>>;
 
+    /// Get the commits pointed to by tags matching the
+    /// `branchless.core.publicTags` pattern. Annotated tags are peeled to the
+    /// commit they point to.
+    fn get_public_tag_oids(&self) -> eyre::Result<HashSet<NonZeroOid>>;
+
     /// Get the positions of references in the repository.
     fn get_references_snapshot(&self) -> eyre::Result<RepoReferencesSnapshot>;
 
@@ -47,13 +71,14 @@ pub trait RepoExt {
 }
 
 impl RepoExt for Repo {
-    fn get_main_branch(&self) -> eyre::Result<Branch<'_>> {
-        let main_branch_name = get_main_branch_name(self)?;
-        match self.find_branch(&main_branch_name, BranchType::Local)? {
-            Some(branch) => Ok(branch),
-            None => {
-                let suggestion = format!(
-                    r"
+    fn get_main_branches(&self) -> eyre::Result<Vec<Branch<'_>>> {
+        let mut result = Vec::new();
+        for main_branch_name in get_main_branch_names(self)? {
+            match self.find_branch(&main_branch_name, BranchType::Local)? {
+                Some(branch) => result.push(branch),
+                None => {
+                    let suggestion = format!(
+                        r"
 The main branch {:?} could not be found in your repository
 at path: {:?}.
 These branches exist: {:?}
@@ -63,23 +88,67 @@ Either create it, or update the main branch setting by running:
 
 Note that remote main branches are no longer supported as of v0.6.0. See
 https://github.com/arxanas/git-branchless/discussions/595 for more details.",
-                    get_main_branch_name(self)?,
-                    self.get_path(),
-                    self.get_all_local_branches()?
-                        .into_iter()
-                        .map(|branch| {
-                            branch
-                                .into_reference()
-                                .get_name()
-                                .map(|s| format!("{s:?}"))
-                                .wrap_err("converting branch to reference")
-                        })
-                        .collect::<eyre::Result<Vec<String>>>()?,
-                );
-                Err(eyre::eyre!("Could not find repository main branch")
-                    .with_suggestion(|| suggestion))
+                        main_branch_name,
+                        self.get_path(),
+                        self.get_all_local_branches()?
+                            .into_iter()
+                            .map(|branch| {
+                                branch
+                                    .into_reference()
+                                    .get_name()
+                                    .map(|s| format!("{s:?}"))
+                                    .wrap_err("converting branch to reference")
+                            })
+                            .collect::<eyre::Result<Vec<String>>>()?,
+                    );
+                    return Err(eyre::eyre!("Could not find repository main branch")
+                        .with_suggestion(|| suggestion));
+                }
             }
         }
+        Ok(result)
+    }
+
+    fn get_main_branch(&self) -> eyre::Result<Branch<'_>> {
+        match self.get_head_info()?.oid {
+            Some(head_oid) => self.get_nearest_main_branch(head_oid),
+            None => Ok(self.get_main_branches()?.swap_remove(0)),
+        }
+    }
+
+    fn get_nearest_main_branch(&self, commit_oid: NonZeroOid) -> eyre::Result<Branch<'_>> {
+        let mut main_branches = self.get_main_branches()?;
+        if main_branches.len() == 1 {
+            return Ok(main_branches.swap_remove(0));
+        }
+
+        let mut nearest: Option<(usize, NonZeroOid)> = None;
+        for (index, main_branch) in main_branches.iter().enumerate() {
+            let main_branch_oid = match main_branch.get_oid()? {
+                Some(main_branch_oid) => main_branch_oid,
+                None => continue,
+            };
+            let merge_base_oid = match self.find_merge_base(commit_oid, main_branch_oid)? {
+                Some(merge_base_oid) => merge_base_oid,
+                None => continue,
+            };
+            nearest = match nearest {
+                None => Some((index, merge_base_oid)),
+                Some((_, nearest_merge_base_oid))
+                    if merge_base_oid != nearest_merge_base_oid
+                        && self.find_merge_base(nearest_merge_base_oid, merge_base_oid)?
+                            == Some(nearest_merge_base_oid) =>
+                {
+                    Some((index, merge_base_oid))
+                }
+                nearest @ Some(_) => nearest,
+            };
+        }
+        let index = match nearest {
+            Some((index, _)) => index,
+            None => 0,
+        };
+        Ok(main_branches.swap_remove(index))
     }
 
     #[instrument]
@@ -95,6 +164,21 @@ https://github.com/arxanas/git-branchless/discussions/595 for more details.",
         }
     }
 
+    #[instrument]
+    fn get_main_branch_oids(&self) -> eyre::Result<Vec<NonZeroOid>> {
+        let mut result = Vec::new();
+        for main_branch in self.get_main_branches()? {
+            match main_branch.get_oid()? {
+                Some(main_branch_oid) => result.push(main_branch_oid),
+                None => eyre::bail!(
+                    "Could not find commit pointed to by main branch: {:?}",
+                    main_branch.get_name()?,
+                ),
+            }
+        }
+        Ok(result)
+    }
+
     #[instrument]
     fn get_branch_oid_to_names(&self) -> eyre::Result<HashMap<NonZeroOid, HashSet<ReferenceName>>> {
         let ignore_patterns = get_ignore_branches(self)?;
@@ -102,13 +186,13 @@ https://github.com/arxanas/git-branchless/discussions/595 for more details.",
         for branch in self.get_all_local_branches()? {
             let reference = branch.into_reference();
             let reference_name = reference.get_name()?;
+            let reference_info = self.resolve_reference(&reference)?;
             if !ignore_patterns.is_empty() {
                 let short_name = CategorizedReferenceName::new(&reference_name).render_suffix();
-                if is_branch_ignored(&short_name, &ignore_patterns) {
+                if is_branch_ignored(self, &short_name, reference_info.oid, &ignore_patterns)? {
                     continue;
                 }
             }
-            let reference_info = self.resolve_reference(&reference)?;
             if let Some(reference_oid) = reference_info.oid {
                 result
                     .entry(reference_oid)
@@ -120,15 +204,41 @@ https://github.com/arxanas/git-branchless/discussions/595 for more details.",
         Ok(result)
     }
 
+    #[instrument]
+    fn get_public_tag_oids(&self) -> eyre::Result<HashSet<NonZeroOid>> {
+        let public_tags_pattern = match get_public_tags(self)? {
+            Some(pattern) => pattern,
+            None => return Ok(HashSet::new()),
+        };
+
+        let mut result = HashSet::new();
+        for reference in self.get_all_references()? {
+            let reference_name = reference.get_name()?;
+            let tag_name = match reference_name.as_str().strip_prefix("refs/tags/") {
+                Some(tag_name) => tag_name,
+                None => continue,
+            };
+            if !is_tag_public(tag_name, &public_tags_pattern) {
+                continue;
+            }
+            if let Some(commit) = reference.peel_to_commit()? {
+                result.insert(commit.get_oid());
+            }
+        }
+        Ok(result)
+    }
+
     fn get_references_snapshot(&self) -> eyre::Result<RepoReferencesSnapshot> {
         let head_oid = self.get_head_info()?.oid;
-        let main_branch_oid = self.get_main_branch_oid()?;
+        let main_branch_oids = self.get_main_branch_oids()?;
         let branch_oid_to_names = self.get_branch_oid_to_names()?;
+        let public_tag_oids = self.get_public_tag_oids()?;
 
         Ok(RepoReferencesSnapshot {
             head_oid,
-            main_branch_oid,
+            main_branch_oids,
             branch_oid_to_names,
+            public_tag_oids,
         })
     }
 
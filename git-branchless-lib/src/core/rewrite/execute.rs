@@ -6,19 +6,22 @@ use std::time::SystemTime;
 
 use bstr::BString;
 use eyre::Context;
+use thiserror::Error;
 use tracing::warn;
 
 use crate::core::check_out::{CheckOutCommitOptions, CheckoutTarget, check_out_commit};
+use crate::core::config::get_rebase_auto_stash;
 use crate::core::effects::Effects;
-use crate::core::eventlog::{EventLogDb, EventTransactionId};
+use crate::core::eventlog::{EventLogDb, EventReplayer, EventTransactionId};
 use crate::core::formatting::Pluralize;
 use crate::core::repo_ext::RepoExt;
 use crate::git::{
-    BranchType, CategorizedReferenceName, GitRunInfo, MaybeZeroOid, NonZeroOid, ReferenceName,
-    Repo, ResolvedReferenceInfo,
+    BranchType, CategorizedReferenceName, GitRunInfo, GitRunOpts, GitRunResult, MaybeZeroOid,
+    MergeFileFavor, NonZeroOid, ReferenceName, Repo, ResolvedReferenceInfo,
 };
 use crate::util::{ExitCode, EyreExitOr};
 
+use super::evolve::find_rewrite_target;
 use super::plan::RebasePlan;
 
 /// Given a list of rewritten OIDs, move the branches attached to those OIDs
@@ -31,8 +34,11 @@ pub fn move_branches<'a>(
     event_tx_id: EventTransactionId,
     rewritten_oids_map: &'a HashMap<NonZeroOid, MaybeZeroOid>,
 ) -> eyre::Result<()> {
-    let main_branch = repo.get_main_branch()?;
-    let main_branch_name = main_branch.get_reference_name()?;
+    let main_branches = repo
+        .get_main_branches()?
+        .into_iter()
+        .map(|main_branch| Ok((main_branch.get_reference_name()?, main_branch)))
+        .collect::<eyre::Result<Vec<_>>>()?;
     let branch_oid_to_names = repo.get_branch_oid_to_names()?;
 
     // We may experience an error in the case of a branch move. Ideally, we
@@ -80,7 +86,10 @@ pub fn move_branches<'a>(
 
             MaybeZeroOid::Zero => {
                 for reference_name in names {
-                    if reference_name == &main_branch_name {
+                    if let Some((_, main_branch)) = main_branches
+                        .iter()
+                        .find(|(main_branch_name, _)| main_branch_name == reference_name)
+                    {
                         // Hack? Never delete the main branch. We probably got here by syncing the
                         // main branch with the upstream version, but all main branch commits were
                         // skipped. For a regular branch, we would delete the branch, but for the
@@ -89,7 +98,7 @@ pub fn move_branches<'a>(
                         let target_oid = match main_branch.get_upstream_branch_target()? {
                             Some(target_oid) => {
                                 if let Err(err) = repo.create_reference(
-                                    &main_branch_name,
+                                    reference_name,
                                     target_oid,
                                     true,
                                     "move main branch",
@@ -101,7 +110,10 @@ pub fn move_branches<'a>(
                             }
                             None => {
                                 let mut main_branch_reference =
-                                    repo.get_main_branch()?.into_reference();
+                                    match repo.find_reference(reference_name)? {
+                                        Some(main_branch_reference) => main_branch_reference,
+                                        None => continue,
+                                    };
                                 if let Err(err) = main_branch_reference.delete() {
                                     branch_move_err = Some(eyre::eyre!(err));
                                     break 'outer;
@@ -229,9 +241,24 @@ pub fn check_out_updated_head(
                     }
                 }
                 None => {
-                    // This OID was not rewritten, so check it out again.
+                    // This OID was not rewritten by this operation, but it may
+                    // have been rewritten by an earlier one, in which case
+                    // `HEAD` would be left on a hidden commit.
+                    let oid = match find_orphaned_head_successor(
+                        effects,
+                        repo,
+                        event_log_db,
+                        *previous_head_oid,
+                    )? {
+                        Ok(Some(successor_oid)) => successor_oid,
+                        Ok(None) => *previous_head_oid,
+                        Err(err) => {
+                            writeln!(effects.get_error_stream(), "{err}")?;
+                            *previous_head_oid
+                        }
+                    };
                     ResolvedReferenceInfo {
-                        oid: Some(*previous_head_oid),
+                        oid: Some(oid),
                         reference_name: None,
                     }
                 }
@@ -315,6 +342,51 @@ pub fn check_out_updated_head(
     Ok(result)
 }
 
+/// An error indicating that `HEAD` was left on a commit which had been
+/// rewritten, and couldn't be moved to that commit's successor.
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+pub enum OrphanedHeadError {
+    #[error(
+        "HEAD is at commit {oid}, which was rewritten and then deleted, so HEAD was left there.\nRun `git sl` to find a commit to check out."
+    )]
+    SuccessorDeleted { oid: NonZeroOid },
+
+    #[error(
+        "HEAD is at commit {oid}, which was rewritten as {successor_oid}, but that commit could not be found, so HEAD was left there."
+    )]
+    SuccessorNotFound {
+        oid: NonZeroOid,
+        successor_oid: NonZeroOid,
+    },
+}
+
+/// If `head_oid` was rewritten according to the event log, find the commit it
+/// was ultimately rewritten into.
+fn find_orphaned_head_successor(
+    effects: &Effects,
+    repo: &Repo,
+    event_log_db: &EventLogDb,
+    head_oid: NonZeroOid,
+) -> eyre::Result<Result<Option<NonZeroOid>, OrphanedHeadError>> {
+    let event_replayer = EventReplayer::from_event_log_db(effects, repo, event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    match find_rewrite_target(&event_replayer, event_cursor, head_oid) {
+        None => Ok(Ok(None)),
+        Some(MaybeZeroOid::Zero) => Ok(Err(OrphanedHeadError::SuccessorDeleted { oid: head_oid })),
+        Some(MaybeZeroOid::NonZero(successor_oid)) => {
+            if repo.find_commit(successor_oid)?.is_some() {
+                Ok(Ok(Some(successor_oid)))
+            } else {
+                Ok(Err(OrphanedHeadError::SuccessorNotFound {
+                    oid: head_oid,
+                    successor_oid,
+                }))
+            }
+        }
+    }
+}
+
 /// What to suggest that the user do in order to resolve a merge conflict.
 #[derive(Copy, Clone, Debug)]
 pub enum MergeConflictRemediation {
@@ -327,6 +399,10 @@ pub enum MergeConflictRemediation {
 
     /// Indicate that the user should run `git move -m -s 'siblings(.)'`.
     Insert,
+
+    /// Indicate that the user should run `git cherry-pick`, which resolves
+    /// merge conflicts in the working copy.
+    CherryPick,
 }
 
 /// Information about a failure to merge that occurred while moving commits.
@@ -415,6 +491,16 @@ impl FailedMergeInfo {
                     "To resolve merge conflicts, run: git move -m -s 'siblings(.)'"
                 )?;
             }
+            MergeConflictRemediation::CherryPick => {
+                writeln!(
+                    effects.get_output_stream(),
+                    "To resolve merge conflicts, run: git cherry-pick {}",
+                    match self {
+                        FailedMergeInfo::Conflict { commit_oid, .. }
+                        | FailedMergeInfo::CannotRebaseMergeInMemory { commit_oid } => commit_oid,
+                    }
+                )?;
+            }
         }
 
         Ok(())
@@ -422,13 +508,18 @@ impl FailedMergeInfo {
 }
 
 mod in_memory {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::fmt::Write;
+    use std::time::Instant;
 
     use bstr::{BString, ByteSlice};
     use eyre::Context;
     use tracing::{instrument, warn};
 
+    use crate::core::config::{
+        get_rebase_committer_date_is_author_date, get_rebase_regenerate_paths,
+        get_rebase_use_rerere, is_path_regenerated,
+    };
     use crate::core::effects::{Effects, OperationIcon, OperationType};
     use crate::core::eventlog::EventLogDb;
     use crate::core::gc::mark_commit_reachable;
@@ -437,12 +528,22 @@ mod in_memory {
     use crate::core::rewrite::plan::{OidOrLabel, RebaseCommand, RebasePlan};
     use crate::git::{
         AmendFastOptions, CherryPickFastOptions, CreateCommitFastError, GitRunInfo, MaybeZeroOid,
-        NonZeroOid, Repo,
+        MergeFileFavor, NonZeroOid, Repo,
     };
     use crate::util::EyreExitOr;
 
     use super::{ExecuteRebasePlanOptions, FailedMergeInfo};
 
+    /// Describe the time elapsed since `start_time` as a suffix for a progress
+    /// line, if timing was requested.
+    fn describe_elapsed_time(timing: bool, start_time: Instant) -> String {
+        if timing {
+            format!(" ({:.2}s)", start_time.elapsed().as_secs_f64())
+        } else {
+            String::new()
+        }
+    }
+
     pub enum RebaseInMemoryResult {
         Succeeded {
             rewritten_oids: HashMap<NonZeroOid, MaybeZeroOid>,
@@ -500,8 +601,33 @@ mod in_memory {
             force_on_disk: _,
             dry_run: _,
             resolve_merge_conflicts: _, // May be needed once we can resolve merge conflicts in memory.
+            merge_file_favor,
+            reset_author,
+            timing,
+            exec_on_conflict: _,
             check_out_commit_options: _, // Caller is responsible for checking out to new HEAD.
         } = options;
+        let committer_date_is_author_date = get_rebase_committer_date_is_author_date(repo)?;
+        let regenerate_paths = get_rebase_regenerate_paths(repo)?;
+        let use_rerere = get_rebase_use_rerere(repo)?;
+
+        // The plan only asks for empty commits to be detected (and dropped)
+        // if they shouldn't be kept.
+        let drop_if_empty_oids: HashSet<NonZeroOid> = rebase_plan
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                RebaseCommand::DetectEmptyCommit { commit_oid } => Some(*commit_oid),
+                RebaseCommand::CreateLabel { .. }
+                | RebaseCommand::Reset { .. }
+                | RebaseCommand::Pick { .. }
+                | RebaseCommand::Merge { .. }
+                | RebaseCommand::Replace { .. }
+                | RebaseCommand::Break
+                | RebaseCommand::RegisterExtraPostRewriteHook
+                | RebaseCommand::SkipUpstreamAppliedCommit { .. } => None,
+            })
+            .collect();
 
         let mut current_oid = rebase_plan.first_dest_oid;
         let mut labels: HashMap<String, NonZeroOid> = HashMap::new();
@@ -573,7 +699,9 @@ mod in_memory {
                 RebaseCommand::Pick {
                     original_commit_oid,
                     commits_to_apply_oids,
+                    message,
                 } => {
+                    let start_time = Instant::now();
                     let current_commit = repo
                         .find_commit_or_fail(current_oid)
                         .wrap_err("Finding current commit")?;
@@ -587,18 +715,44 @@ mod in_memory {
                     progress.notify_progress(i, num_picks);
 
                     let commit_message = original_commit.get_message_raw();
-                    let commit_message = commit_message.to_str().with_context(|| {
-                        eyre::eyre!(
-                            "Could not decode commit message for commit: {:?}",
-                            original_commit_oid
-                        )
-                    })?;
+                    let commit_message = match message {
+                        Some(message) => message.as_str(),
+                        None => commit_message.to_str().with_context(|| {
+                            eyre::eyre!(
+                                "Could not decode commit message for commit: {:?}",
+                                original_commit_oid
+                            )
+                        })?,
+                    };
 
-                    let commit_author = original_commit.get_author();
-                    let committer_signature = if *preserve_timestamps {
-                        original_commit.get_committer()
+                    let (commit_author, committer) = if *reset_author {
+                        let author_time = if *preserve_timestamps {
+                            original_commit.get_author().get_time().to_system_time()?
+                        } else {
+                            *now
+                        };
+                        (
+                            repo.get_default_signature()?
+                                .update_timestamp(author_time)?,
+                            repo.get_default_signature()?.update_timestamp(
+                                original_commit
+                                    .get_committer()
+                                    .get_time()
+                                    .to_system_time()?,
+                            )?,
+                        )
+                    } else {
+                        (
+                            original_commit.get_author(),
+                            original_commit.get_committer(),
+                        )
+                    };
+                    let committer_signature = if committer_date_is_author_date {
+                        committer.update_timestamp(commit_author.get_time().to_system_time()?)?
+                    } else if *preserve_timestamps {
+                        committer
                     } else {
-                        original_commit.get_committer().update_timestamp(*now)?
+                        committer.update_timestamp(*now)?
                     };
                     let mut rebased_commit_oid = None;
                     let mut rebased_commit = None;
@@ -634,13 +788,38 @@ mod in_memory {
                         // it once at the end?
 
                         let maybe_tree = if rebased_commit.is_none() {
-                            repo.cherry_pick_fast(
+                            match repo.cherry_pick_fast(
                                 &commit_to_apply,
                                 &current_commit,
                                 &CherryPickFastOptions {
                                     reuse_parent_tree_if_possible: true,
+                                    merge_file_favor: *merge_file_favor,
+                                    use_rerere,
                                 },
-                            )
+                            ) {
+                                // If the only conflicts are in regenerated
+                                // files, then take the version from the commit
+                                // being applied, since the user will regenerate
+                                // them anyways.
+                                Err(CreateCommitFastError::MergeConflict { conflicting_paths })
+                                    if merge_file_favor.is_none()
+                                        && !regenerate_paths.is_empty()
+                                        && conflicting_paths.iter().all(|path| {
+                                            is_path_regenerated(path, &regenerate_paths)
+                                        }) =>
+                                {
+                                    repo.cherry_pick_fast(
+                                        &commit_to_apply,
+                                        &current_commit,
+                                        &CherryPickFastOptions {
+                                            reuse_parent_tree_if_possible: true,
+                                            merge_file_favor: Some(MergeFileFavor::Theirs),
+                                            use_rerere: false,
+                                        },
+                                    )
+                                }
+                                result => result,
+                            }
                         } else {
                             repo.amend_fast(
                                 &rebased_commit.expect("rebased commit should not be None"),
@@ -697,13 +876,15 @@ mod in_memory {
                     if rebased_commit
                         .expect("rebased commit should not be None")
                         .is_empty()
+                        && drop_if_empty_oids.contains(original_commit_oid)
                     {
                         rewritten_oids.insert(*original_commit_oid, MaybeZeroOid::Zero);
                         maybe_set_skipped_head_new_oid(*original_commit_oid, current_oid);
 
                         writeln!(
                             effects.get_output_stream(),
-                            "{commit_num} Skipped now-empty commit: {commit_description}",
+                            "{commit_num} Skipped now-empty commit: {commit_description}{}",
+                            describe_elapsed_time(*timing, start_time),
                         )?;
                     } else {
                         rewritten_oids.insert(
@@ -719,7 +900,8 @@ mod in_memory {
 
                         writeln!(
                             effects.get_output_stream(),
-                            "{commit_num} Committed as: {commit_description}"
+                            "{commit_num} Committed as: {commit_description}{}",
+                            describe_elapsed_time(*timing, start_time),
                         )?;
                     }
                 }
@@ -744,6 +926,7 @@ mod in_memory {
                     replacement_commit_oid,
                     parents,
                 } => {
+                    let start_time = Instant::now();
                     let original_commit = repo
                         .find_commit_or_fail(*commit_oid)
                         .wrap_err("Finding current commit")?;
@@ -777,7 +960,14 @@ mod in_memory {
                         OperationIcon::InProgress,
                         format!("Committing to repository: {replacement_commit_description}"),
                     );
-                    let committer_signature = if *preserve_timestamps {
+                    let committer_signature = if committer_date_is_author_date {
+                        replacement_commit.get_committer().update_timestamp(
+                            replacement_commit
+                                .get_author()
+                                .get_time()
+                                .to_system_time()?,
+                        )?
+                    } else if *preserve_timestamps {
                         replacement_commit.get_committer()
                     } else {
                         replacement_commit.get_committer().update_timestamp(*now)?
@@ -824,7 +1014,8 @@ mod in_memory {
 
                     writeln!(
                         effects.get_output_stream(),
-                        "{commit_num} Committed as: {commit_description}"
+                        "{commit_num} Committed as: {commit_description}{}",
+                        describe_elapsed_time(*timing, start_time),
                     )?;
                 }
 
@@ -833,6 +1024,7 @@ mod in_memory {
                 }
 
                 RebaseCommand::SkipUpstreamAppliedCommit { commit_oid } => {
+                    let start_time = Instant::now();
                     i += 1;
                     let commit_num = format!("[{i}/{num_picks}]");
 
@@ -844,7 +1036,8 @@ mod in_memory {
                     let commit_description = effects.get_glyphs().render(commit_description)?;
                     writeln!(
                         effects.get_output_stream(),
-                        "{commit_num} Skipped commit (was already applied upstream): {commit_description}"
+                        "{commit_num} Skipped commit (was already applied upstream): {commit_description}{}",
+                        describe_elapsed_time(*timing, start_time),
                     )?;
                 }
 
@@ -912,6 +1105,10 @@ mod in_memory {
             force_on_disk: _,
             dry_run: _,
             resolve_merge_conflicts: _,
+            merge_file_favor: _,
+            reset_author: _,
+            timing: _,
+            exec_on_conflict: _,
             check_out_commit_options,
         } = options;
 
@@ -964,17 +1161,20 @@ mod in_memory {
 
 mod on_disk {
     use std::fmt::Write;
+    use std::process::Command;
 
     use eyre::Context;
     use tracing::instrument;
 
+    use crate::core::config::get_rebase_committer_date_is_author_date;
     use crate::core::effects::{Effects, OperationType};
+    use crate::core::eventlog::{BRANCHLESS_TRANSACTION_ID_ENV_VAR, EventTransactionId};
     use crate::core::rewrite::plan::RebaseCommand;
     use crate::core::rewrite::plan::RebasePlan;
     use crate::core::rewrite::rewrite_hooks::save_original_head_info;
-    use crate::git::{GitRunInfo, Repo};
+    use crate::git::{GitRunInfo, MergeFileFavor, Repo};
 
-    use crate::util::ExitCode;
+    use crate::util::{ExitCode, get_sh};
 
     use super::ExecuteRebasePlanOptions;
 
@@ -998,6 +1198,10 @@ mod on_disk {
             force_on_disk: _,
             dry_run: _,
             resolve_merge_conflicts: _,
+            merge_file_favor,
+            reset_author: _,
+            timing: _,
+            exec_on_conflict: _,
             check_out_commit_options: _, // Checkout happens after rebase has concluded.
         } = options;
 
@@ -1091,6 +1295,7 @@ mod on_disk {
             RebaseCommand::Pick {
                 original_commit_oid,
                 commits_to_apply_oids,
+                message: _,
             } => !commits_to_apply_oids
                 .iter()
                 .any(|oid| oid == original_commit_oid),
@@ -1098,6 +1303,17 @@ mod on_disk {
         }) {
             eyre::bail!("Not implemented: replacing commits in an on disk rebase");
         }
+        if rebase_plan.commands.iter().any(|command| {
+            matches!(
+                command,
+                RebaseCommand::Pick {
+                    message: Some(_),
+                    ..
+                }
+            )
+        }) {
+            eyre::bail!("Not implemented: setting commit messages in an on disk rebase");
+        }
 
         let todo_file_path = rebase_state_dir.join("git-rebase-todo");
         #[allow(clippy::format_collect)]
@@ -1133,7 +1349,22 @@ mod on_disk {
             )
         })?;
 
-        if *preserve_timestamps {
+        // Corresponds to the `--strategy-option` flag.
+        if let Some(merge_file_favor) = merge_file_favor {
+            let strategy_file_path = rebase_state_dir.join("strategy");
+            std::fs::write(&strategy_file_path, "recursive")
+                .wrap_err_with(|| format!("Writing `strategy` to: {:?}", &strategy_file_path))?;
+            let strategy_opts_file_path = rebase_state_dir.join("strategy_opts");
+            let strategy_opts = match merge_file_favor {
+                MergeFileFavor::Ours => " --ours",
+                MergeFileFavor::Theirs => " --theirs",
+            };
+            std::fs::write(&strategy_opts_file_path, strategy_opts).wrap_err_with(|| {
+                format!("Writing `strategy_opts` to: {:?}", &strategy_opts_file_path)
+            })?;
+        }
+
+        if *preserve_timestamps || get_rebase_committer_date_is_author_date(repo)? {
             let cdate_is_adate_file_path = rebase_state_dir.join("cdate_is_adate");
             std::fs::write(&cdate_is_adate_file_path, "").wrap_err_with(|| {
                 format!(
@@ -1175,6 +1406,10 @@ mod on_disk {
             force_on_disk: _,
             dry_run: _,
             resolve_merge_conflicts: _,
+            merge_file_favor: _,
+            reset_author: _,
+            timing: _,
+            exec_on_conflict,
             check_out_commit_options: _, // Checkout happens after rebase has concluded.
         } = options;
 
@@ -1187,9 +1422,107 @@ mod on_disk {
             effects.get_output_stream(),
             "Calling Git for on-disk rebase..."
         )?;
-        match git_run_info.run(effects, Some(*event_tx_id), &["rebase", "--continue"])? {
-            Ok(()) => Ok(Ok(ExitCode::success())),
-            Err(err) => Ok(Ok(err)),
+        let exit_code =
+            match git_run_info.run(effects, Some(*event_tx_id), &["rebase", "--continue"])? {
+                Ok(()) => return Ok(Ok(ExitCode::success())),
+                Err(exit_code) => exit_code,
+            };
+        match exec_on_conflict {
+            Some(command) => {
+                let exit_code = resolve_conflicts_with_command(
+                    effects,
+                    git_run_info,
+                    repo,
+                    *event_tx_id,
+                    command,
+                    exit_code,
+                )?;
+                Ok(Ok(exit_code))
+            }
+            None => Ok(Ok(exit_code)),
+        }
+    }
+
+    /// Repeatedly run the user's conflict resolver on the conflicting paths
+    /// and continue the rebase, until the rebase finishes or the resolver
+    /// fails to resolve the conflicts. Returns the exit code of the last
+    /// failed step, or success if the rebase finished.
+    #[instrument]
+    fn resolve_conflicts_with_command(
+        effects: &Effects,
+        git_run_info: &GitRunInfo,
+        repo: &Repo,
+        event_tx_id: EventTransactionId,
+        command: &str,
+        exit_code: ExitCode,
+    ) -> eyre::Result<ExitCode> {
+        let shell_path = match get_sh() {
+            Some(shell_path) => shell_path,
+            None => {
+                writeln!(
+                    effects.get_error_stream(),
+                    "Could not determine path to shell to run conflict resolver."
+                )?;
+                return Ok(exit_code);
+            }
+        };
+        let working_copy_path = match repo.get_working_copy_path() {
+            Some(working_copy_path) => working_copy_path,
+            None => return Ok(exit_code),
+        };
+
+        // Don't prompt for the commit message of the resolved commit.
+        let mut git_run_info = git_run_info.clone();
+        git_run_info.env.insert("GIT_EDITOR".into(), ":".into());
+
+        let mut exit_code = exit_code;
+        loop {
+            let conflicting_paths = repo.get_index()?.get_conflicting_paths()?;
+            if conflicting_paths.is_empty() {
+                // The rebase stopped for some reason other than a merge
+                // conflict.
+                return Ok(exit_code);
+            }
+
+            writeln!(
+                effects.get_output_stream(),
+                "Running conflict resolver: {command}"
+            )?;
+            let exit_status = Command::new(&shell_path)
+                .arg("-c")
+                .arg(format!("{command} \"$@\""))
+                .arg(command)
+                .args(&conflicting_paths)
+                .current_dir(&working_copy_path)
+                .env(BRANCHLESS_TRANSACTION_ID_ENV_VAR, event_tx_id.to_string())
+                .status()
+                .wrap_err("Running conflict resolver")?;
+            if !exit_status.success() {
+                match exit_status.code() {
+                    Some(resolver_exit_code) => writeln!(
+                        effects.get_error_stream(),
+                        "Conflict resolver failed with exit code {resolver_exit_code}. Resolve the conflicts manually and run: git rebase --continue"
+                    )?,
+                    None => writeln!(
+                        effects.get_error_stream(),
+                        "Conflict resolver was terminated by a signal. Resolve the conflicts manually and run: git rebase --continue"
+                    )?,
+                }
+                return Ok(exit_code);
+            }
+            if repo.get_index()?.has_conflicts() {
+                writeln!(
+                    effects.get_error_stream(),
+                    "Conflict resolver did not resolve all conflicts. Resolve the remaining conflicts manually and run: git rebase --continue"
+                )?;
+                return Ok(exit_code);
+            }
+
+            exit_code =
+                match git_run_info.run(effects, Some(event_tx_id), &["rebase", "--continue"])? {
+                    Ok(()) => return Ok(ExitCode::success()),
+                    Err(exit_code) => exit_code,
+                };
         }
     }
 }
@@ -1222,6 +1555,26 @@ pub struct ExecuteRebasePlanOptions {
     /// rather than failing-fast.
     pub resolve_merge_conflicts: bool,
 
+    /// If set, resolve conflicting hunks in favor of the given side rather
+    /// than stopping with a merge conflict, as with `git rebase -X ours` or
+    /// `git rebase -X theirs`.
+    pub merge_file_favor: Option<MergeFileFavor>,
+
+    /// If `true`, set the author (and committer) of each rewritten commit to
+    /// the current user, as with `git commit --amend --reset-author`. Only
+    /// supported for in-memory rebases.
+    pub reset_author: bool,
+
+    /// If `true`, append the time taken to apply each commit to its progress
+    /// line. Only supported for in-memory rebases.
+    pub timing: bool,
+
+    /// If set, when an on-disk rebase stops due to merge conflicts, run this
+    /// shell command with the conflicting paths as arguments. If it exits
+    /// successfully and no conflicts remain, the rebase is continued.
+    /// Otherwise, the rebase is left in progress for the user to resolve.
+    pub exec_on_conflict: Option<String>,
+
     /// If `HEAD` was moved, the options for checking out the new `HEAD` commit.
     pub check_out_commit_options: CheckOutCommitOptions,
 }
@@ -1256,6 +1609,10 @@ pub enum ExecuteRebasePlanResult {
 
 /// Execute the provided rebase plan. Returns the exit status (zero indicates
 /// success).
+///
+/// If `branchless.rebase.autoStash` is set and the working copy has
+/// uncommitted changes, then they are stashed before the rebase and re-applied
+/// once it has concluded.
 pub fn execute_rebase_plan(
     effects: &Effects,
     git_run_info: &GitRunInfo,
@@ -1263,6 +1620,160 @@ pub fn execute_rebase_plan(
     event_log_db: &EventLogDb,
     rebase_plan: &RebasePlan,
     options: &ExecuteRebasePlanOptions,
+) -> eyre::Result<ExecuteRebasePlanResult> {
+    let auto_stash_oid = if !options.dry_run
+        && get_rebase_auto_stash(repo)?
+        && repo.has_changed_files(effects, git_run_info)?
+    {
+        create_auto_stash(effects, git_run_info, repo, options.event_tx_id)?
+    } else {
+        None
+    };
+
+    let result = execute_rebase_plan_inner(
+        effects,
+        git_run_info,
+        repo,
+        event_log_db,
+        rebase_plan,
+        options,
+    );
+
+    if let Some(auto_stash_oid) = auto_stash_oid {
+        if result.is_err() {
+            // The working copy may be in any state, so don't try to apply the
+            // stash on top of it. Just make sure that it's not lost.
+            store_auto_stash(
+                effects,
+                git_run_info,
+                repo,
+                options.event_tx_id,
+                auto_stash_oid,
+            )?;
+        } else if repo.get_current_operation_type().is_some() {
+            // The on-disk rebase stopped partway through, so hand the stash to
+            // Git, which applies it once the rebase is continued or aborted.
+            let auto_stash_path = repo.get_rebase_state_dir_path().join("autostash");
+            std::fs::write(&auto_stash_path, format!("{auto_stash_oid}\n"))
+                .wrap_err_with(|| format!("Writing autostash OID to: {:?}", &auto_stash_path))?;
+        } else {
+            apply_auto_stash(
+                effects,
+                git_run_info,
+                repo,
+                options.event_tx_id,
+                auto_stash_oid,
+            )?;
+        }
+    }
+
+    result
+}
+
+/// Stash the uncommitted changes in the working copy and reset it to `HEAD`.
+/// Returns the OID of the stash commit, or `None` if there was nothing to
+/// stash.
+fn create_auto_stash(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+    event_tx_id: EventTransactionId,
+) -> eyre::Result<Option<NonZeroOid>> {
+    let GitRunResult { stdout, .. } = git_run_info.run_silent(
+        repo,
+        Some(event_tx_id),
+        &["stash", "create", "autostash"],
+        Default::default(),
+    )?;
+    let stdout = String::from_utf8(stdout)?;
+    let auto_stash_oid: NonZeroOid = match stdout.trim() {
+        "" => return Ok(None),
+        oid => oid.parse()?,
+    };
+    let _: GitRunResult = git_run_info.run_silent(
+        repo,
+        Some(event_tx_id),
+        &["reset", "--hard", "--quiet"],
+        Default::default(),
+    )?;
+    writeln!(
+        effects.get_output_stream(),
+        "Created autostash: {}",
+        effects.get_glyphs().render(
+            repo.friendly_describe_commit_from_oid(effects.get_glyphs(), auto_stash_oid)?
+        )?
+    )?;
+    Ok(Some(auto_stash_oid))
+}
+
+/// Re-apply the changes stashed by `create_auto_stash`. If they don't apply
+/// cleanly, they are saved to the stash list instead.
+fn apply_auto_stash(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+    event_tx_id: EventTransactionId,
+    auto_stash_oid: NonZeroOid,
+) -> eyre::Result<()> {
+    let GitRunResult { exit_code, .. } = git_run_info.run_silent(
+        repo,
+        Some(event_tx_id),
+        &["stash", "apply", &auto_stash_oid.to_string()],
+        GitRunOpts {
+            treat_git_failure_as_error: false,
+            stdin: None,
+        },
+    )?;
+    if exit_code.is_success() {
+        writeln!(effects.get_output_stream(), "Applied autostash.")?;
+    } else {
+        writeln!(
+            effects.get_output_stream(),
+            "Applying autostash resulted in conflicts."
+        )?;
+        store_auto_stash(effects, git_run_info, repo, event_tx_id, auto_stash_oid)?;
+    }
+    Ok(())
+}
+
+/// Save the changes stashed by `create_auto_stash` to the stash list, so that
+/// the user can apply them later.
+fn store_auto_stash(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+    event_tx_id: EventTransactionId,
+    auto_stash_oid: NonZeroOid,
+) -> eyre::Result<()> {
+    let _: GitRunResult = git_run_info.run_silent(
+        repo,
+        Some(event_tx_id),
+        &[
+            "stash",
+            "store",
+            "--message",
+            "autostash",
+            &auto_stash_oid.to_string(),
+        ],
+        Default::default(),
+    )?;
+    write!(
+        effects.get_output_stream(),
+        "\
+Your changes are safe in the stash.
+You can run \"git stash pop\" or \"git stash drop\" at any time.
+"
+    )?;
+    Ok(())
+}
+
+fn execute_rebase_plan_inner(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+    event_log_db: &EventLogDb,
+    rebase_plan: &RebasePlan,
+    options: &ExecuteRebasePlanOptions,
 ) -> eyre::Result<ExecuteRebasePlanResult> {
     let ExecuteRebasePlanOptions {
         now: _,
@@ -1272,6 +1783,10 @@ pub fn execute_rebase_plan(
         force_on_disk,
         dry_run,
         resolve_merge_conflicts,
+        merge_file_favor: _,
+        reset_author,
+        timing: _,
+        exec_on_conflict,
         check_out_commit_options: _,
     } = options;
 
@@ -1324,7 +1839,7 @@ pub fn execute_rebase_plan(
             }
         };
 
-        if !resolve_merge_conflicts {
+        if !resolve_merge_conflicts && exec_on_conflict.is_none() {
             return Ok(ExecuteRebasePlanResult::DeclinedToMerge { failed_merge_info });
         }
 
@@ -1345,6 +1860,16 @@ pub fn execute_rebase_plan(
     }
 
     if !force_in_memory {
+        if *reset_author {
+            writeln!(
+                effects.get_output_stream(),
+                "Resetting commit authors is only supported for in-memory rebases; aborting."
+            )?;
+            return Ok(ExecuteRebasePlanResult::Failed {
+                exit_code: ExitCode(1),
+            });
+        }
+
         use on_disk::*;
         match rebase_on_disk(effects, git_run_info, repo, rebase_plan, options)? {
             Ok(exit_code) if exit_code.is_success() => {
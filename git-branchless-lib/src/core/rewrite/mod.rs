@@ -5,21 +5,26 @@ mod execute;
 mod plan;
 pub mod rewrite_hooks;
 
+use std::fmt::Write;
 use std::sync::Mutex;
 
 pub use evolve::{find_abandoned_children, find_rewrite_target};
 pub use execute::{
     ExecuteRebasePlanOptions, ExecuteRebasePlanResult, FailedMergeInfo, MergeConflictRemediation,
-    execute_rebase_plan, move_branches,
+    OrphanedHeadError, execute_rebase_plan, move_branches,
 };
 pub use plan::{
     BuildRebasePlanError, BuildRebasePlanOptions, OidOrLabel, RebaseCommand, RebasePlan,
-    RebasePlanBuilder, RebasePlanPermissions,
+    RebasePlanBuilder, RebasePlanPermissions, write_signed_public_commits_note,
 };
 use tracing::instrument;
 
+use crate::core::config::{PublicHeadBehavior, get_public_head_behavior};
+use crate::core::dag::Dag;
+use crate::core::effects::Effects;
 use crate::core::task::{Resource, ResourcePool};
 use crate::git::Repo;
+use crate::util::ExitCode;
 
 /// A thread-safe [`Repo`] resource pool.
 #[derive(Debug)]
@@ -56,6 +61,57 @@ impl Resource for RepoResource {
 /// Type synonym for [`ResourcePool<RepoResource>`].
 pub type RepoPool = ResourcePool<RepoResource>;
 
+/// Check whether `command_name` should proceed. If `HEAD` is at a public commit, the
+/// `branchless.core.publicHeadBehavior` config setting determines whether the
+/// command runs as usual, is skipped, or requires `--force-rewrite`.
+///
+/// Returns `Some` with the result the command should exit with if it should
+/// not proceed.
+#[instrument]
+pub fn check_public_head(
+    effects: &Effects,
+    repo: &Repo,
+    dag: &Dag,
+    force_rewrite_public_commits: bool,
+    command_name: &str,
+) -> eyre::Result<Option<Result<(), ExitCode>>> {
+    let head_oid = match repo.get_head_info()?.oid {
+        Some(head_oid) => head_oid,
+        None => return Ok(None),
+    };
+    let behavior = get_public_head_behavior(repo)?;
+    if behavior == PublicHeadBehavior::Allow || !dag.is_public_commit(head_oid)? {
+        return Ok(None);
+    }
+
+    let head_commit = repo.find_commit_or_fail(head_oid)?;
+    let head_description = effects
+        .get_glyphs()
+        .render(head_commit.friendly_describe(effects.get_glyphs())?)?;
+    match behavior {
+        PublicHeadBehavior::Allow => Ok(None),
+        PublicHeadBehavior::Skip => {
+            writeln!(
+                effects.get_output_stream(),
+                "Skipping {command_name}: HEAD is at public commit {head_description}"
+            )?;
+            Ok(Some(Ok(())))
+        }
+        PublicHeadBehavior::RequireForce => {
+            if force_rewrite_public_commits {
+                return Ok(None);
+            }
+            writeln!(
+                effects.get_output_stream(),
+                "\
+Refusing to {command_name} while HEAD is at public commit {head_description}
+To proceed anyways, run: git {command_name} -f"
+            )?;
+            Ok(Some(Err(ExitCode(1))))
+        }
+    }
+}
+
 /// Testing helpers.
 pub mod testing {
     use std::collections::HashSet;
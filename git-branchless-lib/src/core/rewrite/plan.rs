@@ -71,6 +71,10 @@ pub enum RebaseCommand {
         ///    single commit, reusing the metadata (message, author, timestamps,
         ///    etc) from `original_commit_oid`.
         commits_to_apply_oids: Vec<NonZeroOid>,
+
+        /// If set, use this message for the new commit instead of the message
+        /// of `original_commit_oid`. Only supported for in-memory rebases.
+        message: Option<String>,
     },
 
     /// Merge two or more parent commits.
@@ -111,6 +115,8 @@ pub enum RebaseCommand {
 
     /// Determine if the current commit is empty. If so, reset the rebase head
     /// to its parent and record that it was empty in the `rewritten-list`.
+    /// In-memory rebases drop a picked commit which became empty only if
+    /// this command is present for it.
     DetectEmptyCommit {
         /// The original commit. If the new commit is empty, then the original
         /// commit will be recorded as skipped.
@@ -135,6 +141,7 @@ impl RebaseCommand {
             RebaseCommand::Pick {
                 original_commit_oid: _,
                 commits_to_apply_oids,
+                message: _,
             } => match commits_to_apply_oids.as_slice() {
                 [] => String::new(),
                 [commit_oid] => format!("pick {commit_oid}"),
@@ -497,6 +504,68 @@ impl<'a> ConstraintGraph<'a> {
             .collect_vec()
     }
 
+    /// Print a human-readable description of each constraint, noting whether
+    /// the commit was moved explicitly (i.e. it's in `requested_commits`) or
+    /// only because one of its ancestors was moved.
+    fn explain(
+        &self,
+        effects: &Effects,
+        repo: &Repo,
+        requested_commits: &HashSet<NonZeroOid>,
+    ) -> eyre::Result<()> {
+        let glyphs = effects.get_glyphs();
+        let describe = |oid: NonZeroOid| -> eyre::Result<String> {
+            glyphs.render(repo.friendly_describe_commit_from_oid(glyphs, oid)?)
+        };
+
+        let mut dest_oids: HashMap<NonZeroOid, Vec<NonZeroOid>> = HashMap::new();
+        for (parent_oid, child_oids) in self.inner.iter() {
+            for child_oid in child_oids {
+                dest_oids.entry(*child_oid).or_default().push(*parent_oid);
+            }
+        }
+        let commits_to_move: CommitSet = dest_oids.keys().copied().collect();
+        let fixups: CommitSet = self.commits_to_fixup().into_iter().collect();
+
+        writeln!(
+            effects.get_output_stream(),
+            "Explanation of the rebase plan:"
+        )?;
+        for child_oid in self.dag.sort(&commits_to_move.union(&fixups))? {
+            if let Some(parent_oids) = dest_oids.get(&child_oid) {
+                let parents = parent_oids
+                    .iter()
+                    .sorted()
+                    .map(|parent_oid| describe(*parent_oid))
+                    .collect::<eyre::Result<Vec<_>>>()?
+                    .join(", ");
+                let reason = if requested_commits.contains(&child_oid) {
+                    "as requested"
+                } else {
+                    "because an ancestor is being moved"
+                };
+                writeln!(
+                    effects.get_output_stream(),
+                    "{} Moving {} onto {parents}, {reason}",
+                    glyphs.bullet_point,
+                    describe(child_oid)?,
+                )?;
+            }
+            if let Some(fixup_commit_oids) = self.fixups.get(&child_oid) {
+                for fixup_commit_oid in fixup_commit_oids.iter().sorted() {
+                    writeln!(
+                        effects.get_output_stream(),
+                        "{} Squashing {} into {}, as requested",
+                        glyphs.bullet_point,
+                        describe(*fixup_commit_oid)?,
+                        describe(child_oid)?,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// All of the parent (aka destination) OIDs
     pub fn parents(&self) -> Vec<NonZeroOid> {
         self.inner.keys().copied().collect_vec()
@@ -569,6 +638,10 @@ pub struct RebasePlanBuilder<'a> {
     /// with.
     replacement_commits: HashMap<NonZeroOid, NonZeroOid>,
 
+    /// Mapping of commits to the messages that their rewritten versions should
+    /// use instead of their original messages.
+    commit_messages: HashMap<NonZeroOid, String>,
+
     /// Cache mapping from commit OID to the paths changed in the diff for that
     /// commit. The value is `None` if the commit doesn't have an associated
     /// diff (i.e. is a merge commit).
@@ -612,10 +685,19 @@ pub struct BuildRebasePlanOptions {
     /// Print the rebase plan for debugging.
     pub dump_rebase_plan: bool,
 
+    /// Print a human-readable explanation of which commits will be moved and
+    /// to where.
+    pub explain: bool,
+
     /// Calculate the patch ID for each upstream commit and compare them to the
     /// patch IDs in the to-be-rebased commits. Commits which have patch IDs
     /// which are already upstream are skipped.
     pub detect_duplicate_commits_via_patch_id: bool,
+
+    /// Keep commits which become empty when they're applied, rather than
+    /// dropping them. If set, commits are also not skipped for having patch
+    /// IDs which are already upstream.
+    pub keep_empty: bool,
 }
 
 /// An error caused when attempting to build a rebase plan.
@@ -705,6 +787,7 @@ Retry with -f/--force-rewrite to proceed anyways.",
                         .get_glyphs()
                         .render(example_bad_commit.friendly_describe(effects.get_glyphs())?)?,
                 )?;
+                write_signed_public_commits_note(effects, repo, dag, public_commits_to_move)?;
             }
 
             BuildRebasePlanError::MoveIllegalCommits {
@@ -724,6 +807,37 @@ This is a bug. Please report it.",
     }
 }
 
+/// If any of the public commits which the user was trying to move are signed,
+/// note that rewriting them would drop their signatures. Intended to follow
+/// the message for `BuildRebasePlanError::MovePublicCommits`.
+pub fn write_signed_public_commits_note(
+    effects: &Effects,
+    repo: &Repo,
+    dag: &Dag,
+    public_commits_to_move: &CommitSet,
+) -> eyre::Result<()> {
+    let mut num_signed_commits = 0;
+    for oid in dag.commit_set_to_vec(public_commits_to_move)? {
+        if let Some(commit) = repo.find_commit(oid)? {
+            if commit.is_signed() {
+                num_signed_commits += 1;
+            }
+        }
+    }
+    if num_signed_commits > 0 {
+        writeln!(
+            effects.get_output_stream(),
+            "Note: {} GPG-signed. Rewriting will drop the signatures.",
+            Pluralize {
+                determiner: None,
+                amount: num_signed_commits,
+                unit: ("of these commits is", "of these commits are"),
+            },
+        )?;
+    }
+    Ok(())
+}
+
 impl<'a> RebasePlanBuilder<'a> {
     /// Constructor.
     pub fn new(dag: &'a Dag, permissions: RebasePlanPermissions) -> Self {
@@ -732,6 +846,7 @@ impl<'a> RebasePlanBuilder<'a> {
             permissions,
             initial_constraints: Default::default(),
             replacement_commits: Default::default(),
+            commit_messages: Default::default(),
             touched_paths_cache: Default::default(),
         }
     }
@@ -935,10 +1050,13 @@ impl<'a> RebasePlanBuilder<'a> {
                         acc.push(RebaseCommand::Pick {
                             original_commit_oid,
                             commits_to_apply_oids,
+                            message: self.commit_messages.get(&original_commit_oid).cloned(),
                         });
-                        acc.push(RebaseCommand::DetectEmptyCommit {
-                            commit_oid: current_commit.get_oid(),
-                        });
+                        if !self.permissions.build_options.keep_empty {
+                            acc.push(RebaseCommand::DetectEmptyCommit {
+                                commit_oid: current_commit.get_oid(),
+                            });
+                        }
                     }
                 };
             }
@@ -1097,6 +1215,19 @@ impl<'a> RebasePlanBuilder<'a> {
         Ok(())
     }
 
+    /// Instruct the rebase planner to use `message` as the commit message when
+    /// rewriting the commit at `oid`. Only supported for in-memory rebases.
+    pub fn set_commit_message(&mut self, oid: NonZeroOid, message: String) -> eyre::Result<()> {
+        if self.commit_messages.contains_key(&oid) {
+            eyre::bail!(
+                "Attempting to set the message for commit {}. Refusing to set a message twice.",
+                oid
+            );
+        }
+        self.commit_messages.insert(oid, message);
+        Ok(())
+    }
+
     /// Create the rebase plan. Returns `None` if there were no commands in the rebase plan.
     pub fn build(
         &self,
@@ -1112,12 +1243,15 @@ impl<'a> RebasePlanBuilder<'a> {
             parent_labels: Default::default(),
         };
 
+        let explain_effects = effects;
         let (effects, _progress) = effects.start_operation(OperationType::BuildRebasePlan);
 
         let BuildRebasePlanOptions {
             force_rewrite_public_commits: _,
             dump_rebase_constraints,
             dump_rebase_plan,
+            explain,
+            keep_empty,
             detect_duplicate_commits_via_patch_id,
         } = &self.permissions.build_options;
         if *dump_rebase_constraints {
@@ -1128,6 +1262,7 @@ impl<'a> RebasePlanBuilder<'a> {
                 state.constraints.get_constraints_sorted_for_debug(),
             );
         }
+        let requested_commits = state.constraints.commits_to_move();
         state.constraints.add_descendant_constraints(&effects)?;
         if *dump_rebase_constraints {
             // For test: don't print to `effects.get_output_stream()`, as it will
@@ -1146,6 +1281,12 @@ impl<'a> RebasePlanBuilder<'a> {
         }
 
         let repo = repo_pool.try_create()?;
+        if *explain {
+            state
+                .constraints
+                .explain(explain_effects, &repo, &requested_commits)?;
+        }
+
         let roots = state.constraints.find_roots();
         let mut acc = Vec::new();
         let mut first_dest_oid = None;
@@ -1182,7 +1323,7 @@ impl<'a> RebasePlanBuilder<'a> {
                 target: OidOrLabel::Oid(first_parent_oid),
             });
 
-            let upstream_patch_ids = if *detect_duplicate_commits_via_patch_id {
+            let upstream_patch_ids = if *detect_duplicate_commits_via_patch_id && !*keep_empty {
                 let (effects, _progress) =
                     effects.start_operation(OperationType::DetectDuplicateCommits);
                 self.get_upstream_patch_ids(
@@ -1238,6 +1379,7 @@ impl<'a> RebasePlanBuilder<'a> {
                 RebaseCommand::Pick {
                     original_commit_oid,
                     commits_to_apply_oids,
+                    message: _,
                 } => {
                     let mut commit_oids = vec![*original_commit_oid];
                     commit_oids.extend(commits_to_apply_oids);
@@ -6,7 +6,7 @@ use std::fmt::Write;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read, Write as WriteIo, stdin};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use console::style;
 use eyre::Context;
@@ -15,7 +15,9 @@ use tempfile::NamedTempFile;
 use tracing::instrument;
 
 use crate::core::check_out::CheckOutCommitOptions;
-use crate::core::config::{Hint, get_hint_enabled, print_hint_suppression_notice};
+use crate::core::config::{
+    Hint, get_hint_abandoned_throttle, get_hint_enabled, print_hint_suppression_notice,
+};
 use crate::core::dag::Dag;
 use crate::core::effects::Effects;
 use crate::core::eventlog::{Event, EventLogDb, EventReplayer};
@@ -131,7 +133,7 @@ pub fn hook_post_rewrite(
 
     let conn = repo.get_db_conn()?;
     let event_log_db = EventLogDb::new(&conn)?;
-    let event_tx_id = event_log_db.make_transaction_id(now, "hook-post-rewrite")?;
+    let event_tx_id = event_log_db.make_hook_transaction_id(&repo, now, "hook-post-rewrite")?;
 
     let (rewritten_oids, rewrite_events) = {
         let rewritten_oids = read_rewritten_list_entries(&mut stdin().lock())?;
@@ -192,7 +194,10 @@ pub fn hook_post_rewrite(
     }
 
     let should_check_abandoned_commits = get_hint_enabled(&repo, Hint::RestackWarnAbandoned)?;
-    if should_check_abandoned_commits && !is_spurious_event {
+    if should_check_abandoned_commits
+        && !is_spurious_event
+        && !is_abandoned_warning_throttled(&repo, now)?
+    {
         let printed_hint = warn_abandoned(
             effects,
             &repo,
@@ -202,12 +207,58 @@ pub fn hook_post_rewrite(
         )?;
         if printed_hint {
             print_hint_suppression_notice(effects, Hint::RestackWarnAbandoned)?;
+            save_abandoned_warning_timestamp(&repo, now)?;
         }
     }
 
     Ok(())
 }
 
+const ABANDONED_WARNING_TIMESTAMP_FILE_NAME: &str = "abandoned_warning_last_shown";
+
+fn get_abandoned_warning_timestamp_path(repo: &Repo) -> eyre::Result<PathBuf> {
+    Ok(repo
+        .get_branchless_dir()?
+        .join(ABANDONED_WARNING_TIMESTAMP_FILE_NAME))
+}
+
+/// Determine whether the abandoned-commit warning was shown recently enough
+/// that it should be suppressed, according to `branchless.hint.abandonedThrottle`.
+#[instrument]
+fn is_abandoned_warning_throttled(repo: &Repo, now: SystemTime) -> eyre::Result<bool> {
+    let throttle = get_hint_abandoned_throttle(repo)?;
+    if throttle.is_zero() {
+        return Ok(false);
+    }
+
+    let path = get_abandoned_warning_timestamp_path(repo)?;
+    let last_shown_secs: u64 = match std::fs::read_to_string(&path) {
+        Ok(contents) => match contents.trim().parse() {
+            Ok(last_shown_secs) => last_shown_secs,
+            Err(_) => return Ok(false),
+        },
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err).wrap_err_with(|| format!("Reading {path:?}")),
+    };
+    let now_secs = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .wrap_err("Calculating current timestamp")?
+        .as_secs();
+    Ok(Duration::from_secs(now_secs.saturating_sub(last_shown_secs)) < throttle)
+}
+
+/// Record that the abandoned-commit warning was shown at the given time.
+#[instrument]
+fn save_abandoned_warning_timestamp(repo: &Repo, now: SystemTime) -> eyre::Result<()> {
+    let path = get_abandoned_warning_timestamp_path(repo)?;
+    let now_secs = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .wrap_err("Calculating current timestamp")?
+        .as_secs();
+    std::fs::write(&path, now_secs.to_string()).wrap_err_with(|| format!("Writing {path:?}"))?;
+    Ok(())
+}
+
 #[instrument(skip(old_commit_oids))]
 fn warn_abandoned(
     effects: &Effects,
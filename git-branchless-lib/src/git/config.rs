@@ -78,6 +78,76 @@ impl Display for ConfigValue {
     }
 }
 
+/// The configuration file which a config value was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The system-wide configuration, e.g. `/etc/gitconfig`.
+    System,
+
+    /// The user's configuration, e.g. `~/.gitconfig` or
+    /// `~/.config/git/config`.
+    Global,
+
+    /// The repository's configuration, e.g. `.git/config`.
+    Local,
+
+    /// The worktree-specific configuration, e.g. `.git/config.worktree`.
+    Worktree,
+
+    /// Some other configuration source, such as one provided by an
+    /// application.
+    Other,
+}
+
+impl From<git2::ConfigLevel> for ConfigSource {
+    fn from(level: git2::ConfigLevel) -> Self {
+        match level {
+            git2::ConfigLevel::ProgramData | git2::ConfigLevel::System => ConfigSource::System,
+            git2::ConfigLevel::XDG | git2::ConfigLevel::Global => ConfigSource::Global,
+            git2::ConfigLevel::Local => ConfigSource::Local,
+            git2::ConfigLevel::Worktree => ConfigSource::Worktree,
+            git2::ConfigLevel::App | git2::ConfigLevel::Highest => ConfigSource::Other,
+        }
+    }
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::System => write!(f, "system"),
+            ConfigSource::Global => write!(f, "global"),
+            ConfigSource::Local => write!(f, "local"),
+            ConfigSource::Worktree => write!(f, "worktree"),
+            ConfigSource::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Where the effective value of a config key was set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfigOrigin {
+    /// The configuration file which the value was read from.
+    pub source: ConfigSource,
+
+    /// Whether the value was read from a file included by that configuration
+    /// file, such as with `include.path` or `includeIf.<condition>.path`.
+    pub is_included: bool,
+}
+
+impl Display for ConfigOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            source,
+            is_included,
+        } = self;
+        if *is_included {
+            write!(f, "{source}, via include")
+        } else {
+            write!(f, "{source}")
+        }
+    }
+}
+
 /// Trait used to make `Config::get` able to return multiple types.
 pub trait GetConfigValue<V> {
     /// Get the given type of value from the config object.
@@ -169,6 +239,25 @@ pub trait ConfigRead {
     /// Get a config key of one of various possible types.
     fn get<V: GetConfigValue<V>, S: AsRef<str>>(&self, key: S) -> eyre::Result<Option<V>>;
 
+    /// Get the effective value of each config key matching a certain glob
+    /// pattern, along with the configuration file it was read from. Each key
+    /// is only listed once, even if it's set in multiple files.
+    fn list_effective<S: AsRef<str>>(
+        &self,
+        glob_pattern: S,
+    ) -> eyre::Result<Vec<(String, String, ConfigSource)>>;
+
+    /// Get the effective value of the given config key as a string, along with
+    /// where it was set. Returns `None` if the key isn't set.
+    fn get_effective<S: AsRef<str>>(&self, key: S) -> eyre::Result<Option<(String, ConfigOrigin)>>;
+
+    /// Get all values of a multi-valued config key, in the order they were
+    /// set. Only the values from the same config file as the effective value
+    /// (as returned by `get`) are included, so that setting the key in one file
+    /// (such as the repository config) overrides the values set in another
+    /// (such as the global config) rather than adding to them.
+    fn get_all<S: AsRef<str>>(&self, key: S) -> eyre::Result<Vec<String>>;
+
     /// Same as `get`, but uses a default value if the config key doesn't exist.
     fn get_or<V: GetConfigValue<V>, S: AsRef<str>>(&self, key: S, default: V) -> eyre::Result<V> {
         let result = self.get(key)?;
@@ -199,6 +288,32 @@ impl ConfigRead for Config {
         V::get_from_config(self, key)
     }
 
+    fn get_all<S: AsRef<str>>(&self, key: S) -> eyre::Result<Vec<String>> {
+        let key = key.as_ref();
+        let origin = match self.inner.get_entry(key) {
+            Ok(entry) => (entry.level(), entry.include_depth()),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(Vec::new()),
+            Err(err) => {
+                return Err(err).wrap_err_with(|| format!("Reading config key {key:?}"));
+            }
+        };
+        let entries = self
+            .inner
+            .multivar(key, None)
+            .wrap_err_with(|| format!("Reading config values for key {key:?}"))?;
+        let mut result = Vec::new();
+        entries
+            .for_each(|entry| {
+                if (entry.level(), entry.include_depth()) == origin {
+                    if let Some(value) = entry.value() {
+                        result.push(value.to_owned());
+                    }
+                }
+            })
+            .wrap_err_with(|| format!("Iterating config values for key {key:?}"))?;
+        Ok(result)
+    }
+
     fn list<S: AsRef<str>>(&self, glob_pattern: S) -> eyre::Result<Vec<(String, String)>> {
         let glob_pattern = glob_pattern.as_ref();
         let entries = self.inner.entries(Some(glob_pattern)).wrap_err_with(|| {
@@ -216,6 +331,49 @@ impl ConfigRead for Config {
             })?;
         Ok(result)
     }
+
+    fn get_effective<S: AsRef<str>>(&self, key: S) -> eyre::Result<Option<(String, ConfigOrigin)>> {
+        let key = key.as_ref();
+        let entry = match self.inner.get_entry(key) {
+            Ok(entry) => entry,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).wrap_err_with(|| format!("Reading config key {key:?}"));
+            }
+        };
+        let origin = ConfigOrigin {
+            source: entry.level().into(),
+            is_included: entry.include_depth() > 0,
+        };
+        Ok(entry.value().map(|value| (value.to_owned(), origin)))
+    }
+
+    fn list_effective<S: AsRef<str>>(
+        &self,
+        glob_pattern: S,
+    ) -> eyre::Result<Vec<(String, String, ConfigSource)>> {
+        let mut names: Vec<String> = Vec::new();
+        for (name, _value) in self.list(glob_pattern)? {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        let mut result = Vec::new();
+        for name in names {
+            let entry = match self.inner.get_entry(&name) {
+                Ok(entry) => entry,
+                Err(err) if err.code() == git2::ErrorCode::NotFound => continue,
+                Err(err) => {
+                    return Err(err).wrap_err_with(|| format!("Reading config key {name:?}"));
+                }
+            };
+            if let Some(value) = entry.value() {
+                result.push((name.clone(), value.to_owned(), entry.level().into()));
+            }
+        }
+        Ok(result)
+    }
 }
 
 /// Write-only interface to Git's configuration.
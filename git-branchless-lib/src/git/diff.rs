@@ -76,6 +76,97 @@ pub fn summarize_diff_for_temporary_commit(diff: &Diff) -> eyre::Result<String>
     Ok(format!("{filename_or_count} ({ins_del})"))
 }
 
+/// Find the files in which the given diff adds lines that look like
+/// unresolved merge conflict markers (`<<<<<<<` or `>>>>>>>`). The returned
+/// paths are sorted and deduplicated.
+pub fn find_added_conflict_markers(diff: &Diff) -> eyre::Result<Vec<PathBuf>> {
+    fn is_conflict_marker(line: &[u8]) -> bool {
+        [b"<<<<<<<", b">>>>>>>"].iter().any(|marker| {
+            line.strip_prefix(marker.as_slice())
+                .map(|rest| matches!(rest.first(), None | Some(b' ' | b'\n' | b'\r')))
+                .unwrap_or(false)
+        })
+    }
+
+    let mut paths = Vec::new();
+    diff.inner.foreach(
+        &mut |_delta, _progress| true,
+        None,
+        None,
+        Some(&mut |delta, _hunk, line| {
+            if line.origin_value() == git2::DiffLineType::Addition
+                && is_conflict_marker(line.content())
+            {
+                if let Some(path) = delta.new_file().path() {
+                    paths.push(path.to_path_buf());
+                }
+            }
+            true
+        }),
+    )?;
+    paths.sort_unstable();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// A run of lines replaced in a single file, as part of a diff.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineHunk {
+    /// The path of the changed file.
+    pub path: PathBuf,
+
+    /// The 1-based line number of the first removed line in the old file. If
+    /// no lines were removed, then this is the line after which the new lines
+    /// are inserted, or 0 if they are inserted at the start of the file.
+    pub old_start: usize,
+
+    /// The number of lines removed from the old file.
+    pub old_lines: usize,
+
+    /// The lines added in place of the removed lines, including their line
+    /// terminators.
+    pub added_lines: Vec<Vec<u8>>,
+}
+
+/// Split the given diff into hunks of changed lines. The diff should have been
+/// generated without context lines. Only files which were modified in place,
+/// and which aren't binary, are included.
+pub fn get_line_hunks(diff: &Diff) -> eyre::Result<Vec<LineHunk>> {
+    let mut result = Vec::new();
+    for delta_idx in 0..diff.inner.deltas().len() {
+        let patch = match git2::Patch::from_diff(&diff.inner, delta_idx)? {
+            Some(patch) => patch,
+            None => continue,
+        };
+        let delta = patch.delta();
+        if delta.status() != git2::Delta::Modified || delta.flags().is_binary() {
+            continue;
+        }
+        let path = match (delta.old_file().path(), delta.new_file().path()) {
+            (Some(old_path), Some(new_path)) if old_path == new_path => new_path.to_owned(),
+            _ => continue,
+        };
+
+        for hunk_idx in 0..patch.num_hunks() {
+            let (hunk, num_lines) = patch.hunk(hunk_idx)?;
+            let mut added_lines = Vec::new();
+            for line_idx in 0..num_lines {
+                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                if line.origin_value() == git2::DiffLineType::Addition {
+                    added_lines.push(line.content().to_vec());
+                }
+            }
+            result.push(LineHunk {
+                path: path.clone(),
+                old_start: hunk.old_start().try_into()?,
+                old_lines: hunk.old_lines().try_into()?,
+                added_lines,
+            });
+        }
+    }
+    Ok(result)
+}
+
 /// Calculate the diff between the index and the working copy.
 pub fn process_diff_for_record(repo: &Repo, diff: &Diff) -> eyre::Result<Vec<File<'static>>> {
     let Diff { inner: diff } = diff;
@@ -1,5 +1,7 @@
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
+use bstr::ByteVec;
 use eyre::Context;
 use tracing::instrument;
 
@@ -69,6 +71,25 @@ impl Index {
         self.inner.has_conflicts()
     }
 
+    /// Get the paths which have unresolved merge conflicts, in sorted order.
+    pub fn get_conflicting_paths(&self) -> eyre::Result<Vec<PathBuf>> {
+        let mut result = BTreeSet::new();
+        for conflict in self.inner.conflicts().wrap_err("Reading index conflicts")? {
+            let conflict = conflict.wrap_err("Reading index conflict")?;
+            for entry in [conflict.ancestor, conflict.our, conflict.their]
+                .into_iter()
+                .flatten()
+            {
+                let path = entry
+                    .path
+                    .into_path_buf()
+                    .wrap_err("Decoding conflicting path")?;
+                result.insert(path);
+            }
+        }
+        Ok(result.into_iter().collect())
+    }
+
     /// Get the (stage 0) entry for the given path.
     pub fn get_entry(&self, path: &Path) -> Option<IndexEntry> {
         self.get_entry_in_stage(path, Stage::Stage0)
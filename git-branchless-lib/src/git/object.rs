@@ -266,6 +266,14 @@ impl<'repo> Commit<'repo> {
         }
     }
 
+    /// Determine if this commit carries a signature (such as a GPG signature).
+    /// Rewriting the commit will drop the signature.
+    pub fn is_signed(&self) -> bool {
+        ["gpgsig", "gpgsig-sha256"]
+            .into_iter()
+            .any(|field| self.inner.header_field_bytes(field).is_ok())
+    }
+
     /// Determine if this commit added, removed, or changed the entry at the
     /// provided file path.
     #[instrument]
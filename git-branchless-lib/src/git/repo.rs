@@ -24,6 +24,7 @@ use cursive::theme::BaseColor;
 use cursive::utils::markup::StyledString;
 use git2::DiffOptions;
 use itertools::Itertools;
+use sha1::{Digest, Sha1};
 use thiserror::Error;
 use tracing::{instrument, warn};
 
@@ -76,6 +77,19 @@ pub enum Error {
         oid: NonZeroOid,
     },
 
+    #[error("could not set HEAD to '{}': {source}", name.as_str())]
+    SetHeadReference {
+        source: git2::Error,
+        name: ReferenceName,
+    },
+
+    #[error("could not blame file {path:?} at commit {commit_oid}: {source}")]
+    BlameFile {
+        source: git2::Error,
+        commit_oid: NonZeroOid,
+        path: PathBuf,
+    },
+
     #[error("could not find object {oid}")]
     FindObject { oid: NonZeroOid },
 
@@ -215,6 +229,12 @@ pub enum Error {
     #[error("could not get branches: {0}")]
     GetBranches(#[source] git2::Error),
 
+    #[error("could not read reflog for '{}': {source}", name.as_str())]
+    ReadReflog {
+        source: git2::Error,
+        name: ReferenceName,
+    },
+
     #[error("could not get remote names: {0}")]
     GetRemoteNames(#[source] git2::Error),
 
@@ -305,6 +325,173 @@ pub fn message_prettify(message: &str, comment_char: Option<char>) -> Result<Str
     Ok(message)
 }
 
+/// A conflict resolution recorded by `git rerere`.
+struct RerereResolution {
+    /// The conflicted file contents, normalized with
+    /// `normalize_rerere_conflict`.
+    preimage: Vec<u8>,
+
+    /// The resolved file contents.
+    postimage: Vec<u8>,
+}
+
+/// Read the resolutions recorded by `git rerere` for a single conflict ID,
+/// i.e. the `rr-cache/<id>` directory. Conflicts which haven't been resolved
+/// yet only have a preimage, and are skipped.
+fn read_rerere_resolutions(
+    conflict_dir: &Path,
+) -> std::result::Result<Vec<RerereResolution>, CreateCommitFastError> {
+    let read_error = |source: io::Error, path: &Path| CreateCommitFastError::ReadRerereCache {
+        source,
+        path: path.to_owned(),
+    };
+    if !conflict_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut result = Vec::new();
+    for entry in std::fs::read_dir(conflict_dir).map_err(|err| read_error(err, conflict_dir))? {
+        let postimage_path = entry.map_err(|err| read_error(err, conflict_dir))?.path();
+        // Newer versions of Git may record several variants for the same
+        // conflict ID as `preimage.N` and `postimage.N`.
+        let suffix = match postimage_path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_prefix("postimage"))
+        {
+            Some(suffix) => suffix.to_owned(),
+            None => continue,
+        };
+        let preimage_path = conflict_dir.join(format!("preimage{suffix}"));
+        let preimage = match std::fs::read(&preimage_path) {
+            Ok(preimage) => preimage,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(read_error(err, &preimage_path)),
+        };
+        let postimage =
+            std::fs::read(&postimage_path).map_err(|err| read_error(err, &postimage_path))?;
+        result.push(RerereResolution {
+            preimage,
+            postimage,
+        });
+    }
+    Ok(result)
+}
+
+/// Make a stage 0 copy of the index entry `entry` pointing to the blob `oid`.
+fn make_stage0_index_entry(
+    entry: &git2::IndexEntry,
+    oid: git2::Oid,
+    len: usize,
+) -> git2::IndexEntry {
+    git2::IndexEntry {
+        ctime: entry.ctime,
+        mtime: entry.mtime,
+        dev: entry.dev,
+        ino: entry.ino,
+        mode: entry.mode,
+        uid: entry.uid,
+        gid: entry.gid,
+        file_size: u32::try_from(len).unwrap_or(u32::MAX),
+        id: oid,
+        flags: 0,
+        flags_extended: 0,
+        path: entry.path.clone(),
+    }
+}
+
+/// A conflicted file, normalized in the same way as `git rerere`.
+struct NormalizedRerereConflict {
+    /// The normalized file contents.
+    content: Vec<u8>,
+
+    /// Each normalized conflict hunk, including its markers.
+    hunks: Vec<Vec<u8>>,
+
+    /// The ID under which `git rerere` records resolutions for this conflict,
+    /// i.e. the name of its directory in `rr-cache`.
+    conflict_id: String,
+}
+
+/// Normalize the conflict markers in `content` in the same way that `git
+/// rerere` does before recording a preimage: marker labels and any merge base
+/// section are removed, and the two sides of each hunk are sorted. Returns
+/// `None` if there were no well-formed conflict hunks.
+fn normalize_rerere_conflict(content: &[u8]) -> Option<NormalizedRerereConflict> {
+    enum State {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    fn is_marker(line: &[u8], marker: &[u8]) -> bool {
+        line.starts_with(marker)
+            && matches!(line.get(marker.len()), None | Some(b' ' | b'\n' | b'\r'))
+    }
+
+    let mut state = State::Outside;
+    // The conflict ID is the hash of the sorted sides of each hunk, each
+    // followed by a NUL byte.
+    let mut hasher = Sha1::new();
+    let mut normalized = Vec::new();
+    let mut hunks = Vec::new();
+    let mut ours = Vec::new();
+    let mut theirs = Vec::new();
+    for line in content.split_inclusive(|&c| c == b'\n') {
+        state = match state {
+            State::Outside if is_marker(line, b"<<<<<<<") => {
+                ours.clear();
+                theirs.clear();
+                State::Ours
+            }
+            State::Outside => {
+                normalized.extend_from_slice(line);
+                State::Outside
+            }
+            State::Ours | State::Base if is_marker(line, b"=======") => State::Theirs,
+            State::Ours if is_marker(line, b"|||||||") => State::Base,
+            State::Ours => {
+                ours.extend_from_slice(line);
+                State::Ours
+            }
+            State::Base => State::Base,
+            State::Theirs if is_marker(line, b">>>>>>>") => {
+                let (first, second) = if ours <= theirs {
+                    (&ours, &theirs)
+                } else {
+                    (&theirs, &ours)
+                };
+                hasher.update(first);
+                hasher.update([0]);
+                hasher.update(second);
+                hasher.update([0]);
+                let mut hunk = b"<<<<<<<\n".to_vec();
+                hunk.extend_from_slice(first);
+                hunk.extend_from_slice(b"=======\n");
+                hunk.extend_from_slice(second);
+                hunk.extend_from_slice(b">>>>>>>\n");
+                normalized.extend_from_slice(&hunk);
+                hunks.push(hunk);
+                State::Outside
+            }
+            State::Theirs => {
+                theirs.extend_from_slice(line);
+                State::Theirs
+            }
+        };
+    }
+
+    match state {
+        State::Outside if !hunks.is_empty() => Some(NormalizedRerereConflict {
+            content: normalized,
+            hunks,
+            conflict_id: format!("{:x}", hasher.finalize()),
+        }),
+        _ => None,
+    }
+}
+
 /// A snapshot of information about a certain reference. Updates to the
 /// reference after this value is obtained are not reflected.
 ///
@@ -322,7 +509,7 @@ pub fn message_prettify(message: &str, comment_char: Option<char>) -> Result<Str
 /// - `HEAD` is unborn. This means that it doesn't even exist yet. This happens
 ///   when a repository has been freshly initialized, but no commits have been
 ///   made, for example.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ResolvedReferenceInfo {
     /// The OID of the commit that `HEAD` points to. If `HEAD` is unborn, then
     /// this is `None`.
@@ -383,12 +570,30 @@ impl FromStr for GitVersion {
     }
 }
 
+/// Which side to favor when resolving conflicting hunks while merging a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeFileFavor {
+    /// Favor the side being merged into (the destination commit).
+    Ours,
+
+    /// Favor the side being merged (the commit being applied).
+    Theirs,
+}
+
 /// Options for `Repo::cherry_pick_fast`.
 #[derive(Clone, Debug)]
 pub struct CherryPickFastOptions {
     /// Detect if a commit is being applied onto a parent with the same tree,
     /// and skip applying the patch in that case.
     pub reuse_parent_tree_if_possible: bool,
+
+    /// If set, resolve conflicting hunks in favor of the given side rather
+    /// than reporting a merge conflict.
+    pub merge_file_favor: Option<MergeFileFavor>,
+
+    /// If set, consult the `git rerere` cache for recorded resolutions of any
+    /// conflicting paths before reporting a merge conflict.
+    pub use_rerere: bool,
 }
 
 /// An error raised when attempting to create create a commit via
@@ -419,6 +624,9 @@ pub enum CreateCommitFastError {
     #[error(transparent)]
     HydrateTree(tree::Error),
 
+    #[error("could not read rerere cache at {path}: {source}")]
+    ReadRerereCache { source: io::Error, path: PathBuf },
+
     #[error(transparent)]
     Repo(#[from] Error),
 
@@ -644,12 +852,21 @@ impl Repo {
     /// Get the connection to the SQLite database for this repository.
     #[instrument]
     pub fn get_db_conn(&self) -> Result<rusqlite::Connection> {
+        // How long SQLite should wait for another process to release its lock
+        // on the database before giving up.
+        const DB_BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
         let dir = self.get_branchless_dir()?;
         let path = dir.join("db.sqlite3");
         let conn = rusqlite::Connection::open(&path).map_err(|err| Error::OpenDatabase {
             source: err,
             path: path.clone(),
         })?;
+        conn.busy_timeout(DB_BUSY_TIMEOUT)
+            .map_err(|err| Error::OpenDatabase {
+                source: err,
+                path: path.clone(),
+            })?;
         Ok(conn)
     }
 
@@ -707,6 +924,19 @@ impl Repo {
         Ok(())
     }
 
+    /// Set the `HEAD` reference to point to the provided reference, such as a
+    /// branch. Does not touch the working copy.
+    #[instrument]
+    pub fn set_head_to_reference(&self, name: &ReferenceName) -> Result<()> {
+        self.inner
+            .set_head(name.as_str())
+            .map_err(|err| Error::SetHeadReference {
+                source: err,
+                name: name.clone(),
+            })?;
+        Ok(())
+    }
+
     /// Detach `HEAD` by making it point directly to its current OID, rather
     /// than to a branch. If `HEAD` is unborn, logs a warning.
     #[instrument]
@@ -1005,13 +1235,10 @@ impl Repo {
         while let Some(line_prefix) = status_bytes.peek() {
             let line = match line_prefix {
                 // Ordinary change entry or unmerged entry.
-                b'1' | b'u' => {
-                    let line = status_bytes
-                        .by_ref()
-                        .take_while(not_null_terminator)
-                        .collect_vec();
-                    line
-                }
+                b'1' | b'u' => status_bytes
+                    .by_ref()
+                    .take_while(not_null_terminator)
+                    .collect_vec(),
                 // Rename or copy change entry.
                 b'2' => {
                     let mut line = status_bytes
@@ -1088,6 +1315,14 @@ impl Repo {
         Ok(Reference { inner: reference })
     }
 
+    /// Get the signature of the current user, as configured by `user.name` and
+    /// `user.email`, with the current time.
+    #[instrument]
+    pub fn get_default_signature(&self) -> Result<Signature<'static>> {
+        let signature = self.inner.signature().map_err(Error::CreateSignature)?;
+        Ok(Signature { inner: signature })
+    }
+
     /// Get a list of all remote names.
     #[instrument]
     pub fn get_all_remote_names(&self) -> Result<Vec<String>> {
@@ -1137,6 +1372,31 @@ impl Repo {
         Ok(all_branches)
     }
 
+    /// Get the OIDs of the commits in the stash list, most recent first, so
+    /// that the OID at index `n` corresponds to `stash@{n}`.
+    #[instrument]
+    pub fn get_stash_oids(&self) -> Result<Vec<NonZeroOid>> {
+        let name = ReferenceName::from("refs/stash");
+        if self.find_reference(&name)?.is_none() {
+            return Ok(Vec::new());
+        }
+        let reflog = self
+            .inner
+            .reflog(name.as_str())
+            .map_err(|source| Error::ReadReflog {
+                source,
+                name: name.clone(),
+            })?;
+        let oids = reflog
+            .iter()
+            .filter_map(|entry| match MaybeZeroOid::from(entry.id_new()) {
+                MaybeZeroOid::NonZero(oid) => Some(oid),
+                MaybeZeroOid::Zero => None,
+            })
+            .collect();
+        Ok(oids)
+    }
+
     /// Look up the branch with the given name. Returns `None` if not found.
     #[instrument]
     pub fn find_branch(&self, name: &str, branch_type: BranchType) -> Result<Option<Branch<'_>>> {
@@ -1285,10 +1545,24 @@ impl Repo {
         cherry_pick_commit: &Commit,
         our_commit: &Commit,
         mainline: u32,
+        merge_file_favor: Option<MergeFileFavor>,
     ) -> Result<Index> {
+        let merge_options = merge_file_favor.map(|merge_file_favor| {
+            let mut merge_options = git2::MergeOptions::new();
+            merge_options.file_favor(match merge_file_favor {
+                MergeFileFavor::Ours => git2::FileFavor::Ours,
+                MergeFileFavor::Theirs => git2::FileFavor::Theirs,
+            });
+            merge_options
+        });
         let index = self
             .inner
-            .cherrypick_commit(&cherry_pick_commit.inner, &our_commit.inner, mainline, None)
+            .cherrypick_commit(
+                &cherry_pick_commit.inner,
+                &our_commit.inner,
+                mainline,
+                merge_options.as_ref(),
+            )
             .map_err(|err| Error::CherryPickCommit {
                 source: err,
                 commit: cherry_pick_commit.get_oid(),
@@ -1315,6 +1589,8 @@ impl Repo {
     ) -> std::result::Result<Tree<'repo>, CreateCommitFastError> {
         let CherryPickFastOptions {
             reuse_parent_tree_if_possible,
+            merge_file_favor,
+            use_rerere,
         } = options;
 
         if *reuse_parent_tree_if_possible {
@@ -1340,8 +1616,15 @@ impl Repo {
         let dehydrated_target_commit =
             self.dehydrate_commit(target_commit, changed_paths.as_slice(), false)?;
 
-        let rebased_index =
-            self.cherry_pick_commit(&dehydrated_patch_commit, &dehydrated_target_commit, 0)?;
+        let mut rebased_index = self.cherry_pick_commit(
+            &dehydrated_patch_commit,
+            &dehydrated_target_commit,
+            0,
+            *merge_file_favor,
+        )?;
+        if *use_rerere && rebased_index.has_conflicts() {
+            self.resolve_conflicts_with_rerere(&mut rebased_index)?;
+        }
         let rebased_tree = {
             if rebased_index.has_conflicts() {
                 let conflicting_paths = {
@@ -1430,6 +1713,126 @@ impl Repo {
         Ok(rebased_tree)
     }
 
+    /// Attempt to resolve the conflicts in `index` using the resolutions
+    /// recorded by `git rerere`. The index is only updated if every conflicting
+    /// path has a recorded resolution; otherwise, it's left untouched.
+    #[instrument]
+    fn resolve_conflicts_with_rerere(
+        &self,
+        index: &mut Index,
+    ) -> std::result::Result<(), CreateCommitFastError> {
+        let rr_cache_dir = self.inner.commondir().join("rr-cache");
+        if !rr_cache_dir.is_dir() {
+            return Ok(());
+        }
+
+        let conflicts = index
+            .inner
+            .conflicts()
+            .map_err(CreateCommitFastError::Git)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(CreateCommitFastError::Git)?;
+        let mut resolved_entries = Vec::new();
+        for conflict in conflicts {
+            // Only content conflicts are recorded by `git rerere`. For add/add
+            // conflicts, the two sides are merged against an empty file.
+            let (ancestor, our, their) = match (conflict.ancestor, conflict.our, conflict.their) {
+                (Some(ancestor), Some(our), Some(their)) => (ancestor, our, their),
+                (None, Some(our), Some(their)) => {
+                    let empty_oid = self.inner.blob(&[]).map_err(CreateCommitFastError::Git)?;
+                    (make_stage0_index_entry(&our, empty_oid, 0), our, their)
+                }
+                _ => return Ok(()),
+            };
+            let merge_result = self
+                .inner
+                .merge_file_from_index(&ancestor, &our, &their, None)
+                .map_err(CreateCommitFastError::Git)?;
+            let NormalizedRerereConflict {
+                content: current,
+                hunks: current_hunks,
+                conflict_id,
+            } = match normalize_rerere_conflict(merge_result.content()) {
+                Some(normalized) => normalized,
+                None => return Ok(()),
+            };
+            let recorded_resolutions = read_rerere_resolutions(&rr_cache_dir.join(conflict_id))?;
+
+            let mut resolved_content = None;
+            for RerereResolution {
+                preimage,
+                postimage,
+            } in &recorded_resolutions
+            {
+                match normalize_rerere_conflict(preimage) {
+                    Some(NormalizedRerereConflict {
+                        hunks: preimage_hunks,
+                        ..
+                    }) if preimage_hunks == current_hunks => {}
+                    _ => continue,
+                }
+                if preimage == &current {
+                    resolved_content = Some(postimage.clone());
+                    break;
+                }
+
+                // The rest of the file may have changed since the resolution
+                // was recorded, so apply it as a three-way merge, as `git
+                // rerere` does.
+                let make_entry = |content: &[u8]| -> std::result::Result<
+                    git2::IndexEntry,
+                    CreateCommitFastError,
+                > {
+                    let oid = self
+                        .inner
+                        .blob(content)
+                        .map_err(CreateCommitFastError::Git)?;
+                    Ok(make_stage0_index_entry(&our, oid, content.len()))
+                };
+                let merge_result = self
+                    .inner
+                    .merge_file_from_index(
+                        &make_entry(preimage)?,
+                        &make_entry(&current)?,
+                        &make_entry(postimage)?,
+                        None,
+                    )
+                    .map_err(CreateCommitFastError::Git)?;
+                if merge_result.is_automergeable() {
+                    resolved_content = Some(merge_result.content().to_vec());
+                    break;
+                }
+            }
+
+            match resolved_content {
+                Some(resolved_content) => resolved_entries.push((our, resolved_content)),
+                None => return Ok(()),
+            }
+        }
+
+        for (our, resolved_content) in resolved_entries {
+            let path = our.path.clone().into_path_buf().map_err(|err| {
+                CreateCommitFastError::DecodePath {
+                    source: err,
+                    item: "our",
+                }
+            })?;
+            let oid = self
+                .inner
+                .blob(&resolved_content)
+                .map_err(CreateCommitFastError::Git)?;
+            index
+                .inner
+                .conflict_remove(&path)
+                .map_err(CreateCommitFastError::Git)?;
+            index
+                .inner
+                .add(&make_stage0_index_entry(&our, oid, resolved_content.len()))
+                .map_err(CreateCommitFastError::Git)?;
+        }
+        Ok(())
+    }
+
     #[instrument]
     fn dehydrate_commit(
         &self,
@@ -1510,6 +1913,45 @@ impl Repo {
         Ok(make_non_zero_oid(oid))
     }
 
+    /// Attribute each line of the file at `path`, as of the commit
+    /// `commit_oid`, to the commit which last changed it. The returned hunks
+    /// are in order of their position in the file.
+    #[instrument]
+    pub fn blame_file(&self, commit_oid: NonZeroOid, path: &Path) -> Result<Vec<BlameHunk>> {
+        let make_error = |source| Error::BlameFile {
+            source,
+            commit_oid,
+            path: path.to_owned(),
+        };
+        let mut options = git2::BlameOptions::new();
+        options.newest_commit(commit_oid.inner);
+        let blame = self
+            .inner
+            .blame_file(path, Some(&mut options))
+            .map_err(make_error)?;
+        blame
+            .iter()
+            .map(|hunk| -> Result<BlameHunk> {
+                let commit_oid = MaybeZeroOid::from(hunk.final_commit_id());
+                let commit_oid = match commit_oid {
+                    MaybeZeroOid::NonZero(commit_oid) => commit_oid,
+                    MaybeZeroOid::Zero => {
+                        return Err(make_error(git2::Error::from_str(
+                            "blamed line was not attributed to any commit",
+                        )));
+                    }
+                };
+                Ok(BlameHunk {
+                    commit_oid,
+                    final_start_line: hunk.final_start_line(),
+                    orig_start_line: hunk.orig_start_line(),
+                    orig_path: hunk.path().map(|path| path.to_owned()),
+                    num_lines: hunk.lines_in_hunk(),
+                })
+            })
+            .collect()
+    }
+
     /// Amends the provided parent commit in memory and returns the resulting tree.
     ///
     /// Only amends the files provided in the options, and only supports amending from
@@ -1595,6 +2037,8 @@ impl Repo {
                     parent_commit,
                     &CherryPickFastOptions {
                         reuse_parent_tree_if_possible: false,
+                        merge_file_favor: None,
+                        use_rerere: false,
                     },
                 )?;
                 self.get_paths_touched_by_commit(commit)?
@@ -1634,6 +2078,28 @@ impl Repo {
     }
 }
 
+/// A run of lines in a file, attributed to the commit which last changed
+/// them. See [`Repo::blame_file`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlameHunk {
+    /// The commit which last changed these lines.
+    pub commit_oid: NonZeroOid,
+
+    /// The 1-based line number of the first line of this hunk in the blamed
+    /// version of the file.
+    pub final_start_line: usize,
+
+    /// The 1-based line number of the first line of this hunk in the version
+    /// of the file at `commit_oid`.
+    pub orig_start_line: usize,
+
+    /// The path of the file at `commit_oid`, if it could be determined.
+    pub orig_path: Option<PathBuf>,
+
+    /// The number of lines in this hunk.
+    pub num_lines: usize,
+}
+
 /// The signature of a commit, identifying who it was made by and when it was made.
 pub struct Signature<'repo> {
     pub(super) inner: git2::Signature<'repo>,
@@ -384,6 +384,28 @@ impl GitRunInfo {
         )
     }
 
+    /// Determine whether the Git executable supports `git worktree`, which is
+    /// required by features that operate in a scratch worktree, such as
+    /// `git test run --strategy worktree`. Older or stripped-down Git
+    /// installations may not provide it.
+    #[instrument]
+    pub fn supports_worktrees(&self, repo: &Repo) -> eyre::Result<bool> {
+        let GitRunResult {
+            exit_code,
+            stdout: _,
+            stderr: _,
+        } = self.run_silent(
+            repo,
+            None,
+            &["worktree", "list"],
+            GitRunOpts {
+                treat_git_failure_as_error: false,
+                stdin: None,
+            },
+        )?;
+        Ok(exit_code.is_success())
+    }
+
     fn run_hook_inner(
         &self,
         effects: &Effects,
@@ -123,6 +123,18 @@ pub fn get_test_tree_dir(repo: &Repo, commit: &Commit) -> Result<PathBuf, RepoEr
     Ok(get_test_dir(repo)?.join(commit.get_tree_oid().to_string()))
 }
 
+/// Get the path to the file where the result of running the given test
+/// command on a particular commit is stored.
+pub fn get_test_result_path(
+    repo: &Repo,
+    commit: &Commit,
+    command: String,
+) -> Result<PathBuf, RepoError> {
+    Ok(get_test_tree_dir(repo, commit)?
+        .join(make_test_command_slug(command))
+        .join("result"))
+}
+
 /// Get the directory where the locks for running tests are stored.
 pub fn get_test_locks_dir(repo: &Repo) -> Result<PathBuf, RepoError> {
     Ok(get_test_dir(repo)?.join("locks"))
@@ -0,0 +1,46 @@
+use branchless::core::dag::Dag;
+use branchless::core::effects::Effects;
+use branchless::core::eventlog::{EventLogDb, EventReplayer};
+use branchless::core::formatting::Glyphs;
+use branchless::core::repo_ext::RepoExt;
+use branchless::testing::{Git, make_git};
+
+fn sync_if_changed(git: &Git) -> eyre::Result<bool> {
+    let effects = Effects::new_suppress_for_test(Glyphs::text());
+    let repo = git.get_repo()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+    let event_replayer = EventReplayer::from_event_log_db(&effects, &repo, &event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    let references_snapshot = repo.get_references_snapshot()?;
+    let mut dag = Dag::open_without_syncing(
+        &effects,
+        &repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+    dag.sync_if_changed(&effects, &repo, event_cursor, &references_snapshot)
+}
+
+#[test]
+fn test_sync_if_changed() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+
+    // The query syncs the DAG, so nothing is left to sync afterwards.
+    git.branchless("query", &["draft()"])?;
+    assert!(!sync_if_changed(&git)?);
+
+    git.commit_file("test2", 2)?;
+    assert!(sync_if_changed(&git)?);
+    assert!(!sync_if_changed(&git)?);
+
+    git.run(&["branch", "foo", "HEAD^"])?;
+    assert!(sync_if_changed(&git)?);
+    assert!(!sync_if_changed(&git)?);
+
+    Ok(())
+}
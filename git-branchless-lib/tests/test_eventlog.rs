@@ -1,4 +1,5 @@
 use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
 use branchless::core::eventlog::testing::{new_event_cursor, new_event_transaction_id};
 use branchless::core::eventlog::{
@@ -70,10 +71,10 @@ fn test_different_event_transaction_ids() -> eyre::Result<()> {
                 1,
             ),
             Id(
-                2,
+                1,
             ),
             Id(
-                3,
+                2,
             ),
         ]
         "###);
@@ -164,3 +165,47 @@ fn test_advance_cursor_by_transaction() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_concurrent_writers_retry_when_locked() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    let repo = git.get_repo()?;
+    let db_path = repo.get_branchless_dir()?.join("db.sqlite3");
+    let conn = repo.get_db_conn()?;
+    let _event_log_db = EventLogDb::new(&conn)?;
+
+    // Hold an exclusive lock on the database, and release it only after the
+    // writers have started.
+    conn.execute_batch("BEGIN EXCLUSIVE")?;
+    let writers = (0..3)
+        .map(|i| {
+            let db_path = db_path.clone();
+            std::thread::spawn(move || -> eyre::Result<()> {
+                let conn = rusqlite::Connection::open(&db_path)?;
+                // Disable SQLite's own waiting, so that the writers rely on
+                // the event log's retries.
+                conn.busy_timeout(Duration::ZERO)?;
+                let event_log_db = EventLogDb::new(&conn)?;
+                let event_tx_id = event_log_db.make_transaction_id(SystemTime::now(), "test")?;
+                event_log_db.add_events(vec![Event::CommitEvent {
+                    timestamp: 0.0,
+                    event_tx_id,
+                    commit_oid: NonZeroOid::from_str(&format!("{}", i + 1))?,
+                }])?;
+                Ok(())
+            })
+        })
+        .collect::<Vec<_>>();
+    std::thread::sleep(Duration::from_millis(50));
+    conn.execute_batch("COMMIT")?;
+
+    for writer in writers {
+        writer.join().expect("writer thread panicked")?;
+    }
+    let event_log_db = EventLogDb::new(&conn)?;
+    assert_eq!(event_log_db.get_events()?.len(), 3);
+
+    Ok(())
+}
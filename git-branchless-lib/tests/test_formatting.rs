@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+use branchless::core::formatting::get_color_override_from_env;
+
+fn color_override(vars: &[(&str, &str)]) -> Option<bool> {
+    let env: HashMap<&str, OsString> = vars
+        .iter()
+        .map(|(name, value)| (*name, OsString::from(value)))
+        .collect();
+    get_color_override_from_env(|name| env.get(name).cloned())
+}
+
+#[test]
+fn test_color_override_from_env() {
+    assert_eq!(color_override(&[]), None);
+
+    assert_eq!(color_override(&[("NO_COLOR", "1")]), Some(false));
+    assert_eq!(color_override(&[("NO_COLOR", "")]), None);
+
+    assert_eq!(color_override(&[("CLICOLOR", "0")]), Some(false));
+    assert_eq!(color_override(&[("CLICOLOR", "1")]), None);
+
+    assert_eq!(color_override(&[("CLICOLOR_FORCE", "1")]), Some(true));
+    assert_eq!(color_override(&[("CLICOLOR_FORCE", "0")]), None);
+    assert_eq!(color_override(&[("CLICOLOR_FORCE", "")]), None);
+}
+
+#[test]
+fn test_color_override_from_env_precedence() {
+    assert_eq!(
+        color_override(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")]),
+        Some(true)
+    );
+    assert_eq!(
+        color_override(&[("CLICOLOR_FORCE", "1"), ("CLICOLOR", "0")]),
+        Some(true)
+    );
+    assert_eq!(
+        color_override(&[("NO_COLOR", "1"), ("CLICOLOR", "1")]),
+        Some(false)
+    );
+    assert_eq!(
+        color_override(&[("CLICOLOR_FORCE", "0"), ("NO_COLOR", "1")]),
+        Some(false)
+    );
+}
@@ -54,6 +54,8 @@ fn test_cherry_pick_fast() -> eyre::Result<()> {
         &initial2_commit,
         &CherryPickFastOptions {
             reuse_parent_tree_if_possible: false,
+            merge_file_favor: None,
+            use_rerere: false,
         },
     )?;
 
@@ -45,6 +45,8 @@ fn test_cache_shared_between_builders() -> eyre::Result<()> {
         force_rewrite_public_commits: true,
         dump_rebase_constraints: false,
         dump_rebase_plan: false,
+        explain: false,
+        keep_empty: false,
         detect_duplicate_commits_via_patch_id: true,
     };
     let permissions = omnipotent_rebase_plan_permissions(&dag, build_options)?;
@@ -735,6 +737,8 @@ fn create_and_execute_plan(
         force_rewrite_public_commits: false,
         dump_rebase_constraints: false,
         dump_rebase_plan: false,
+        explain: false,
+        keep_empty: false,
         detect_duplicate_commits_via_patch_id: true,
     };
     let permissions = omnipotent_rebase_plan_permissions(&dag, build_options)?;
@@ -761,6 +765,10 @@ fn create_and_execute_plan(
         force_on_disk: false,
         dry_run: false,
         resolve_merge_conflicts: true,
+        merge_file_favor: None,
+        reset_author: false,
+        timing: false,
+        exec_on_conflict: None,
         check_out_commit_options: CheckOutCommitOptions {
             additional_args: Default::default(),
             force_detach: false,
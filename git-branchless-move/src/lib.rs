@@ -14,29 +14,83 @@
 
 use std::collections::HashMap;
 use std::fmt::Write;
-use std::time::SystemTime;
+use std::io::{BufRead, IsTerminal, stdin};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use eden_dag::Vertex;
+use eyre::Context;
 use lib::core::repo_ext::RepoExt;
+use lib::try_exit_code;
 use lib::util::{ExitCode, EyreExitOr};
 use rayon::ThreadPoolBuilder;
 use tracing::instrument;
 
+use git_branchless_navigation::prompt::prompt_select_commit;
 use git_branchless_opts::{MoveOptions, ResolveRevsetOptions, Revset};
-use git_branchless_revset::resolve_commits;
+use git_branchless_revset::{resolve_commits, resolve_default_smartlog_commits};
+use git_branchless_smartlog::make_smartlog_graph;
 use lib::core::config::{
     Hint, get_hint_enabled, get_hint_string, get_restack_preserve_timestamps,
     print_hint_suppression_notice,
 };
 use lib::core::dag::{CommitSet, Dag, sorted_commit_set, union_all};
 use lib::core::effects::Effects;
-use lib::core::eventlog::{EventLogDb, EventReplayer};
+use lib::core::eventlog::{Event, EventCursor, EventLogDb, EventReplayer};
+use lib::core::gc::mark_commit_reachable;
+use lib::core::node_descriptors::{
+    BranchesDescriptor, CommitMessageDescriptor, CommitOidDescriptor,
+    DifferentialRevisionDescriptor, Redactor, RelativeTimeDescriptor,
+};
 use lib::core::rewrite::{
     BuildRebasePlanOptions, ExecuteRebasePlanOptions, ExecuteRebasePlanResult,
     MergeConflictRemediation, RebasePlanBuilder, RebasePlanPermissions, RepoResource,
-    execute_rebase_plan,
+    check_public_head, execute_rebase_plan,
+};
+use lib::git::{
+    GitRunInfo, GitRunOpts, GitRunResult, MaybeZeroOid, MergeFileFavor, NonZeroOid, Repo,
 };
-use lib::git::{GitRunInfo, NonZeroOid, Repo};
+
+/// Prompt the user to select the destination commit from among the commits in
+/// the smartlog. Returns `None` if the user cancelled the selection.
+#[instrument]
+fn prompt_select_dest(
+    effects: &Effects,
+    repo: &Repo,
+    dag: &mut Dag,
+    event_replayer: &EventReplayer,
+    event_cursor: EventCursor,
+) -> eyre::Result<Option<NonZeroOid>> {
+    let head_info = repo.get_head_info()?;
+    let references_snapshot = repo.get_references_snapshot()?;
+    let commits = resolve_default_smartlog_commits(effects, repo, dag)?;
+    let graph = make_smartlog_graph(
+        effects,
+        repo,
+        dag,
+        event_replayer,
+        event_cursor,
+        &commits,
+        false,
+    )?;
+    prompt_select_commit(
+        Some("Select the commit to move onto"),
+        "",
+        graph.get_commits(),
+        &mut [
+            &mut CommitOidDescriptor::new(true)?,
+            &mut RelativeTimeDescriptor::new(repo, SystemTime::now())?,
+            &mut BranchesDescriptor::new(
+                repo,
+                &head_info,
+                &references_snapshot,
+                &Redactor::Disabled,
+            )?,
+            &mut DifferentialRevisionDescriptor::new(repo, &Redactor::Disabled)?,
+            &mut CommitMessageDescriptor::new(&Redactor::Disabled)?,
+        ],
+    )
+}
 
 #[instrument]
 fn resolve_base_commit(
@@ -62,31 +116,236 @@ fn resolve_base_commit(
     }
 }
 
+/// Find the parent that the given commit had before it was last moved by `git
+/// move`, by following its rewrite events back through the event log.
+#[instrument]
+fn find_previous_parent(
+    repo: &Repo,
+    event_log_db: &EventLogDb,
+    commit_oid: NonZeroOid,
+) -> eyre::Result<Option<NonZeroOid>> {
+    let events = event_log_db.get_events()?;
+    let mut commit_oid = commit_oid;
+    for event in events.iter().rev() {
+        let (event_tx_id, old_commit_oid) = match event {
+            Event::RewriteEvent {
+                timestamp: _,
+                event_tx_id,
+                old_commit_oid: MaybeZeroOid::NonZero(old_commit_oid),
+                new_commit_oid: MaybeZeroOid::NonZero(new_commit_oid),
+            } if *new_commit_oid == commit_oid => (*event_tx_id, *old_commit_oid),
+            _ => continue,
+        };
+
+        // Commits may have been amended or otherwise rewritten since they
+        // were moved, in which case keep searching for the previous version.
+        if event_log_db.get_transaction_message(event_tx_id)? != "move" {
+            commit_oid = old_commit_oid;
+            continue;
+        }
+
+        let old_commit = match repo.find_commit(old_commit_oid)? {
+            Some(old_commit) => old_commit,
+            None => return Ok(None),
+        };
+        return match old_commit.get_parent_oids().as_slice() {
+            [parent_oid] => Ok(Some(*parent_oid)),
+            _ => Ok(None),
+        };
+    }
+    Ok(None)
+}
+
+/// Create a commit on top of HEAD containing the staged changes. Returns
+/// `None` if there are no staged changes.
+///
+/// The commit is created with `git commit-tree` so that its author, committer,
+/// and timestamps are determined the same way as for `git commit`.
+#[instrument]
+fn create_index_commit(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+    head_oid: Option<NonZeroOid>,
+) -> EyreExitOr<Option<NonZeroOid>> {
+    let head_commit = match head_oid {
+        Some(head_oid) => repo.find_commit_or_fail(head_oid)?,
+        None => {
+            writeln!(
+                effects.get_error_stream(),
+                "No OID for HEAD is available to commit the staged changes onto."
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
+    let mut index = repo.get_index()?;
+    if index.has_conflicts() {
+        writeln!(
+            effects.get_error_stream(),
+            "The index contains unresolved merge conflicts, so it cannot be used as a destination."
+        )?;
+        return Ok(Err(ExitCode(1)));
+    }
+    let tree_oid = repo.write_index_to_tree(&mut index)?;
+    if head_commit.get_tree_oid() == MaybeZeroOid::NonZero(tree_oid) {
+        return Ok(Ok(None));
+    }
+
+    let GitRunResult { stdout, .. } = git_run_info
+        .run_silent(
+            repo,
+            None,
+            &[
+                "commit-tree",
+                &tree_oid.to_string(),
+                "-p",
+                &head_commit.get_oid().to_string(),
+                "-m",
+                "Staged changes",
+            ],
+            GitRunOpts {
+                treat_git_failure_as_error: true,
+                stdin: None,
+            },
+        )
+        .wrap_err("Committing staged changes")?;
+    let index_commit_oid: NonZeroOid = String::from_utf8(stdout)?.trim().parse()?;
+    Ok(Ok(Some(index_commit_oid)))
+}
+
+/// The options for `git move`, as provided on the command-line.
+#[derive(Debug)]
+pub struct MoveCommandOptions {
+    /// The source commits to move, along with their descendants.
+    pub sources: Vec<Revset>,
+
+    /// The destination commit to move the source commits onto.
+    pub dest: Option<Revset>,
+
+    /// Place the moved commits immediately after the given commit.
+    pub after: Option<Revset>,
+
+    /// Place the moved commits immediately before the given commit.
+    pub before: Option<Revset>,
+
+    /// Commits inside the subtrees to move.
+    pub bases: Vec<Revset>,
+
+    /// The specific commits to move.
+    pub exacts: Vec<Revset>,
+
+    /// Options for resolving revset expressions.
+    pub resolve_revset_options: ResolveRevsetOptions,
+
+    /// Options for moving commits.
+    pub move_options: MoveOptions,
+
+    /// Squash the moved commits into the destination commit.
+    pub fixup: bool,
+
+    /// Insert the moved subtree between the destination and its children.
+    pub insert: bool,
+
+    /// Only test whether an in-memory rebase would succeed.
+    pub dry_run: bool,
+
+    /// Create a new worktree at the given path with the moved commits checked
+    /// out.
+    pub worktree: Option<PathBuf>,
+
+    /// Rebuild the moved subtrees onto their existing parents.
+    pub in_place: bool,
+
+    /// Set the author of each moved commit to the current user.
+    pub reset_author: bool,
+
+    /// Commit the staged changes and use that commit as the destination.
+    pub onto_index: bool,
+
+    /// Read additional source commits from stdin.
+    pub source_stdin: bool,
+
+    /// Select the destination commit interactively.
+    pub interactive: bool,
+
+    /// Squash the moved commits into a single commit.
+    pub collapse: bool,
+
+    /// The command to run to resolve merge conflicts, if any.
+    pub exec_on_conflict: Option<String>,
+}
+
 /// Move a subtree from one place to another.
 #[instrument]
 pub fn r#move(
     effects: &Effects,
     git_run_info: &GitRunInfo,
-    sources: Vec<Revset>,
-    dest: Option<Revset>,
-    bases: Vec<Revset>,
-    exacts: Vec<Revset>,
-    resolve_revset_options: &ResolveRevsetOptions,
-    move_options: &MoveOptions,
-    fixup: bool,
-    insert: bool,
-    dry_run: bool,
+    options: MoveCommandOptions,
 ) -> EyreExitOr<()> {
+    let MoveCommandOptions {
+        sources,
+        dest,
+        after,
+        before,
+        bases,
+        exacts,
+        resolve_revset_options,
+        move_options,
+        fixup,
+        insert,
+        dry_run,
+        worktree,
+        in_place,
+        reset_author,
+        onto_index,
+        source_stdin,
+        interactive,
+        collapse,
+        exec_on_conflict,
+    } = options;
+    let resolve_revset_options = &resolve_revset_options;
+    let move_options = &move_options;
+
+    let sources = if source_stdin {
+        let mut sources = sources;
+        for line in stdin().lock().lines() {
+            let line = line?;
+            let line = line.trim();
+            if !line.is_empty() {
+                sources.push(Revset(line.to_string()));
+            }
+        }
+        if sources.is_empty() {
+            writeln!(
+                effects.get_error_stream(),
+                "No commits to move were provided on stdin."
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+        sources
+    } else {
+        sources
+    };
     let sources_provided = !sources.is_empty();
     let bases_provided = !bases.is_empty();
     let exacts_provided = !exacts.is_empty();
     let dest_provided = dest.is_some();
+    let dest_is_previous = matches!(&dest, Some(Revset(expr)) if expr == "-");
     let should_sources_default_to_head = !sources_provided && !bases_provided && !exacts_provided;
+    let placement_flag = if after.is_some() {
+        Some("--after")
+    } else if before.is_some() {
+        Some("--before")
+    } else {
+        None
+    };
+    let is_before = before.is_some();
 
     let repo = Repo::from_current_dir()?;
     let head_oid = repo.get_head_info()?.oid;
 
-    let dest = match dest {
+    let dest = match dest.or(after).or(before) {
         Some(dest) => dest,
         None => match head_oid {
             Some(oid) => Revset(oid.to_string()),
@@ -113,6 +372,66 @@ pub fn r#move(
         &references_snapshot,
     )?;
 
+    let dest = if interactive {
+        if !stdin().is_terminal() {
+            writeln!(
+                effects.get_error_stream(),
+                "The --interactive flag requires standard input to be a terminal. Pass the destination with --dest instead."
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+        match prompt_select_dest(effects, &repo, &mut dag, &event_replayer, event_cursor)? {
+            Some(dest_oid) => Revset(dest_oid.to_string()),
+            None => return Ok(Err(ExitCode(1))),
+        }
+    } else {
+        dest
+    };
+
+    // With `--onto-index`, the staged changes are committed on top of HEAD,
+    // and the resulting commit becomes the destination.
+    let (dest, index_commit_oid) = if onto_index {
+        if should_sources_default_to_head {
+            writeln!(
+                effects.get_error_stream(),
+                "The --onto-index flag requires the commits to move to be provided with --source, --base, or --exact."
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+        let index_commit_oid =
+            match try_exit_code!(create_index_commit(effects, git_run_info, &repo, head_oid)?) {
+                Some(index_commit_oid) => index_commit_oid,
+                None => {
+                    writeln!(
+                        effects.get_error_stream(),
+                        "There are no staged changes to move onto."
+                    )?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            };
+        dag.sync_from_oids(
+            effects,
+            &repo,
+            CommitSet::empty(),
+            CommitSet::from(index_commit_oid),
+        )?;
+        (Revset(index_commit_oid.to_string()), Some(index_commit_oid))
+    } else {
+        (dest, None)
+    };
+
+    if should_sources_default_to_head {
+        if let Some(result) = check_public_head(
+            effects,
+            &repo,
+            &dag,
+            move_options.force_rewrite_public_commits,
+            "move",
+        )? {
+            return Ok(result);
+        }
+    }
+
     let source_oids: CommitSet =
         match resolve_commits(effects, &repo, &mut dag, &sources, resolve_revset_options) {
             Ok(commit_sets) => union_all(&commit_sets),
@@ -179,6 +498,53 @@ pub fn r#move(
         }
     };
 
+    // With `--dest -`, move the commits back to where they were before the
+    // last `git move`.
+    let dest = if dest_is_previous {
+        let mut root_oids = dag.commit_set_to_vec(&dag.query_roots(source_oids.clone())?)?;
+        root_oids.extend(exact_components.keys().copied());
+        if root_oids.is_empty() {
+            writeln!(
+                effects.get_error_stream(),
+                "The --dest - argument requires the commits to move to be provided with --source or --exact."
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+
+        let mut previous_parent_oids = Vec::new();
+        for root_oid in root_oids {
+            match find_previous_parent(&repo, &event_log_db, root_oid)? {
+                Some(previous_parent_oid) => previous_parent_oids.push(previous_parent_oid),
+                None => {
+                    writeln!(
+                        effects.get_error_stream(),
+                        "No previous location was recorded for commit {root_oid}, as it has not been moved."
+                    )?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            }
+        }
+        previous_parent_oids.sort_unstable();
+        previous_parent_oids.dedup();
+        match previous_parent_oids.as_slice() {
+            [previous_parent_oid] => Revset(previous_parent_oid.to_string()),
+            _ => {
+                writeln!(
+                    effects.get_error_stream(),
+                    "The commits to move were previously located on different parents: {}",
+                    previous_parent_oids
+                        .iter()
+                        .map(|oid| oid.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )?;
+                return Ok(Err(ExitCode(1)));
+            }
+        }
+    } else {
+        dest
+    };
+
     let dest_oid: NonZeroOid = match resolve_commits(
         effects,
         &repo,
@@ -205,6 +571,32 @@ pub fn r#move(
         }
     };
 
+    // With `--after`, the commits are inserted directly after the target
+    // commit, so it's the destination. With `--before`, the commits are
+    // inserted after the target commit's parent, and the target commit is then
+    // moved on top of them.
+    let placement_target_oid = placement_flag.map(|_| dest_oid);
+    let before_oid = if is_before { Some(dest_oid) } else { None };
+    let dest_oid = match before_oid {
+        None => dest_oid,
+        Some(before_oid) => {
+            let parents = dag.query_parents(CommitSet::from(before_oid))?;
+            match dag.commit_set_to_vec(&parents)?.as_slice() {
+                [only_parent_oid] => *only_parent_oid,
+                other => {
+                    writeln!(
+                        effects.get_error_stream(),
+                        "The --before flag can only be used with a commit with exactly 1 parent, but {} has {} parents.",
+                        before_oid,
+                        other.len(),
+                    )?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            }
+        }
+    };
+    let insert = insert || placement_flag.is_some();
+
     let base_oids = if should_sources_default_to_head {
         match head_oid {
             Some(head_oid) => CommitSet::from(head_oid),
@@ -231,6 +623,136 @@ pub fn r#move(
     };
     let source_oids = source_oids.union(&base_oids);
 
+    // Moving the main branch is almost always a mistake, so explain that
+    // specifically, rather than falling through to the generic public commit
+    // check.
+    if !move_options.force_rewrite_public_commits {
+        let exact_oids = union_all(&exact_components.values().cloned().collect::<Vec<_>>());
+        let commits_to_move = dag
+            .query_descendants(source_oids.clone())?
+            .union(&exact_oids);
+        for main_branch in repo.get_main_branches()? {
+            let main_branch_oid = match main_branch.get_oid()? {
+                Some(main_branch_oid) => main_branch_oid,
+                None => continue,
+            };
+            if dag.set_contains(&commits_to_move, main_branch_oid)? {
+                let main_branch_name = main_branch.get_name()?;
+                writeln!(
+                    effects.get_error_stream(),
+                    "\
+Refusing to move the main branch {main_branch_name}, since its tip {main_branch_oid} is among the commits to move.
+To move commits on top of the main branch instead, run: git move -s <commit> -d {main_branch_name}
+To move the main branch anyways, run the same command with -f"
+                )?;
+                return Ok(Err(ExitCode(1)));
+            }
+        }
+    }
+
+    // With `--collapse`, the moved commits are squashed into their root, so
+    // they must form a single line of development.
+    let collapse_message = if collapse {
+        let commits_to_collapse =
+            dag.filter_visible_commits(dag.query_descendants(source_oids.clone())?)?;
+        let commits_to_collapse = dag
+            .sort(&commits_to_collapse)?
+            .into_iter()
+            .map(|oid| repo.find_commit_or_fail(oid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let is_linear = dag.set_count(&dag.query_roots(source_oids.clone())?)? == 1
+            && commits_to_collapse.iter().enumerate().all(|(i, commit)| {
+                let parent_oids = commit.get_parent_oids();
+                match i.checked_sub(1) {
+                    None => parent_oids.len() == 1,
+                    Some(prev_index) => parent_oids == [commits_to_collapse[prev_index].get_oid()],
+                }
+            });
+        if !is_linear {
+            writeln!(
+                effects.get_error_stream(),
+                "The --collapse flag can only be used to move a single linear range of commits."
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+
+        let mut message = commits_to_collapse
+            .iter()
+            .map(|commit| commit.get_message_pretty().to_string().trim().to_string())
+            .filter(|message| !message.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        message.push('\n');
+        Some(message)
+    } else {
+        None
+    };
+
+    if let (Some(placement_flag), Some(placement_target_oid)) =
+        (placement_flag, placement_target_oid)
+    {
+        let exact_oids = union_all(&exact_components.values().cloned().collect::<Vec<_>>());
+        let commits_to_move = dag
+            .query_descendants(source_oids.clone())?
+            .union(&exact_oids);
+        if dag.set_contains(&commits_to_move, placement_target_oid)? {
+            writeln!(
+                effects.get_error_stream(),
+                "The {placement_flag} flag cannot be used with a commit which is itself being moved: {placement_target_oid}",
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    }
+
+    // With `--worktree`, the head of the moved commits is checked out in a new
+    // worktree, so there must be exactly one such head, and the current
+    // worktree must not be affected by the move.
+    let worktree_head_oid = match &worktree {
+        None => None,
+        Some(worktree_path) => {
+            let is_non_empty = match std::fs::read_dir(worktree_path) {
+                Ok(mut entries) => entries.next().is_some(),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+                Err(_) => true,
+            };
+            if is_non_empty {
+                writeln!(
+                    effects.get_error_stream(),
+                    "The --worktree path already exists and is not empty: {}",
+                    worktree_path.display(),
+                )?;
+                return Ok(Err(ExitCode(1)));
+            }
+
+            let exact_oids = union_all(&exact_components.values().cloned().collect::<Vec<_>>());
+            let commits_to_move = dag
+                .query_descendants(source_oids.clone())?
+                .union(&exact_oids);
+            if let Some(head_oid) = head_oid {
+                if dag.set_contains(&commits_to_move, head_oid)? {
+                    writeln!(
+                        effects.get_error_stream(),
+                        "The --worktree flag cannot be used to move the currently checked-out commit: {head_oid}",
+                    )?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            }
+
+            let commits_to_move_heads =
+                dag.query_heads(dag.filter_visible_commits(commits_to_move)?)?;
+            match dag.commit_set_to_vec(&commits_to_move_heads)?.as_slice() {
+                [oid] => Some(*oid),
+                _ => {
+                    writeln!(
+                        effects.get_error_stream(),
+                        "The --worktree flag cannot be used when moving subtrees or ranges with multiple heads.",
+                    )?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            }
+        }
+    };
+
     if let Some(head_oid) = head_oid {
         if get_hint_enabled(&repo, Hint::MoveImplicitHeadArgument)? {
             let should_warn_base = !sources_provided
@@ -267,11 +789,29 @@ pub fn r#move(
         force_on_disk,
         detect_duplicate_commits_via_patch_id,
         resolve_merge_conflicts,
+        strategy_option,
         dump_rebase_constraints,
         dump_rebase_plan,
+        explain,
+        keep_empty,
+        timing,
+        no_keep_empty: _,
     } = *move_options;
+    // Only in-memory rebases report the rewritten commits, which are needed to
+    // find the commit to check out in the new worktree. With `--in-place`, an
+    // on-disk rebase would fast-forward over the unchanged commits instead of
+    // rewriting them. With `--collapse`, on-disk rebases can't squash commits.
+    let force_in_memory = force_in_memory || worktree.is_some() || in_place || collapse;
     let now = SystemTime::now();
     let event_tx_id = event_log_db.make_transaction_id(now, "move")?;
+    if let Some(index_commit_oid) = index_commit_oid.filter(|_| !dry_run) {
+        mark_commit_reachable(&repo, index_commit_oid)?;
+        event_log_db.add_events(vec![Event::CommitEvent {
+            timestamp: now.duration_since(UNIX_EPOCH)?.as_secs_f64(),
+            event_tx_id,
+            commit_oid: index_commit_oid,
+        }])?;
+    }
     let pool = ThreadPoolBuilder::new().build()?;
     let repo_pool = RepoResource::new_pool(&repo)?;
     let rebase_plan = {
@@ -279,6 +819,8 @@ pub fn r#move(
             force_rewrite_public_commits,
             dump_rebase_constraints,
             dump_rebase_plan,
+            explain,
+            keep_empty,
             detect_duplicate_commits_via_patch_id,
         };
         let permissions = {
@@ -286,10 +828,12 @@ pub fn r#move(
             let commits_to_move = commits_to_move.union(&union_all(
                 &exact_components.values().cloned().collect::<Vec<_>>(),
             ));
-            let commits_to_move = if insert || fixup {
-                commits_to_move.union(&dag.query_children(CommitSet::from(dest_oid))?)
-            } else {
-                commits_to_move
+            let commits_to_move = match before_oid {
+                Some(before_oid) => commits_to_move.union(&CommitSet::from(before_oid)),
+                None if insert || fixup => {
+                    commits_to_move.union(&dag.query_children(CommitSet::from(dest_oid))?)
+                }
+                None => commits_to_move,
             };
 
             match RebasePlanPermissions::verify_rewrite_set(&dag, build_options, &commits_to_move)?
@@ -311,6 +855,18 @@ pub fn r#move(
                 for commit in commits.iter() {
                     builder.fixup_commit(*commit, dest_oid)?;
                 }
+            } else if in_place {
+                let source_root_commit = repo.find_commit_or_fail(source_root)?;
+                builder.move_subtree(source_root, source_root_commit.get_parent_oids())?;
+            } else if let Some(collapse_message) = &collapse_message {
+                let commits = dag.query_descendants(CommitSet::from(source_root))?;
+                for commit in dag.commit_set_to_vec(&commits)? {
+                    if commit != source_root {
+                        builder.fixup_commit(commit, source_root)?;
+                    }
+                }
+                builder.move_subtree(source_root, vec![dest_oid])?;
+                builder.set_commit_message(source_root, collapse_message.clone())?;
             } else {
                 builder.move_subtree(source_root, vec![dest_oid])?;
             }
@@ -455,7 +1011,8 @@ pub fn r#move(
                     _ => {
                         writeln!(
                             effects.get_output_stream(),
-                            "The --insert flag cannot be used when moving subtrees or ranges with multiple heads."
+                            "The {} flag cannot be used when moving subtrees or ranges with multiple heads.",
+                            placement_flag.unwrap_or("--insert"),
                         )?;
                         return Ok(Err(ExitCode(1)));
                     }
@@ -467,11 +1024,16 @@ pub fn r#move(
                 .cloned()
                 .collect::<Vec<CommitSet>>();
             let exact_oids = union_all(&exact_components);
-            // Children of dest_oid that are not themselves being moved.
-            let dest_children: CommitSet = dag
-                .query_children(CommitSet::from(dest_oid))?
-                .difference(&source_oids)
-                .difference(&exact_oids);
+            // Children of dest_oid that are not themselves being moved. When
+            // inserting before a commit, only that commit is moved, rather
+            // than any of its siblings.
+            let dest_children: CommitSet = match before_oid {
+                Some(before_oid) => CommitSet::from(before_oid),
+                None => dag
+                    .query_children(CommitSet::from(dest_oid))?
+                    .difference(&source_oids)
+                    .difference(&exact_oids),
+            };
             let dest_children = dag.filter_visible_commits(dest_children)?;
 
             for dest_child in dag.commit_set_to_vec(&dest_children)? {
@@ -494,6 +1056,10 @@ pub fn r#move(
                 force_on_disk,
                 dry_run,
                 resolve_merge_conflicts,
+                merge_file_favor: strategy_option.map(MergeFileFavor::from),
+                reset_author,
+                timing,
+                exec_on_conflict,
                 check_out_commit_options: Default::default(),
             };
             execute_rebase_plan(
@@ -512,7 +1078,31 @@ pub fn r#move(
     };
 
     match result {
-        ExecuteRebasePlanResult::Succeeded { rewritten_oids: _ } => Ok(Ok(())),
+        ExecuteRebasePlanResult::Succeeded { rewritten_oids } => {
+            match (worktree, worktree_head_oid) {
+                (Some(worktree_path), Some(worktree_head_oid)) => {
+                    let new_head_oid = match rewritten_oids
+                        .as_ref()
+                        .and_then(|rewritten_oids| rewritten_oids.get(&worktree_head_oid))
+                    {
+                        Some(MaybeZeroOid::NonZero(new_head_oid)) => *new_head_oid,
+                        Some(MaybeZeroOid::Zero) | None => worktree_head_oid,
+                    };
+                    git_run_info.run(
+                        effects,
+                        Some(event_tx_id),
+                        &[
+                            "worktree".as_ref(),
+                            "add".as_ref(),
+                            "--detach".as_ref(),
+                            worktree_path.as_os_str(),
+                            new_head_oid.to_string().as_ref(),
+                        ],
+                    )
+                }
+                _ => Ok(Ok(())),
+            }
+        }
 
         ExecuteRebasePlanResult::WouldSucceed if dry_run => {
             writeln!(
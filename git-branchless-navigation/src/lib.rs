@@ -15,6 +15,7 @@ use std::collections::HashSet;
 
 use std::ffi::OsString;
 use std::fmt::Write;
+use std::io::{IsTerminal, stdin};
 use std::time::SystemTime;
 
 use cursive::theme::BaseColor;
@@ -102,6 +103,10 @@ fn advance(
         None => {
             if get_next_interactive(repo)? {
                 Some(Towards::Interactive)
+            } else if matches!(distance, Distance::AllTheWay { .. }) && stdin().is_terminal() {
+                // The user asked to go as far as possible, so let them pick
+                // which way to go at a fork rather than stopping there.
+                Some(Towards::Interactive)
             } else {
                 None
             }
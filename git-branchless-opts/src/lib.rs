@@ -19,7 +19,7 @@ use std::str::FromStr;
 
 use clap::{Args, Command as ClapCommand, CommandFactory, Parser, ValueEnum};
 use lib::core::untracked_file_cache::UntrackedFileStrategy;
-use lib::git::NonZeroOid;
+use lib::git::{MergeFileFavor, NonZeroOid};
 
 /// A revset expression. Can be a commit hash, branch name, or one of the
 /// various revset functions.
@@ -57,6 +57,25 @@ pub struct ResolveRevsetOptions {
     pub show_hidden_commits: bool,
 }
 
+/// A merge strategy option, as with `git rebase --strategy-option`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum StrategyOption {
+    /// Resolve conflicting hunks in favor of the destination commits.
+    Ours,
+
+    /// Resolve conflicting hunks in favor of the commits being moved.
+    Theirs,
+}
+
+impl From<StrategyOption> for MergeFileFavor {
+    fn from(strategy_option: StrategyOption) -> Self {
+        match strategy_option {
+            StrategyOption::Ours => MergeFileFavor::Ours,
+            StrategyOption::Theirs => MergeFileFavor::Theirs,
+        }
+    }
+}
+
 /// Options for moving commits.
 #[derive(Args, Debug)]
 pub struct MoveOptions {
@@ -78,14 +97,35 @@ pub struct MoveOptions {
     /// Don't attempt to deduplicate commits. Normally, a commit with the same
     /// contents as another commit which has already been applied to the target
     /// branch is skipped. If set, this flag skips that check.
-    #[clap(action(clap::ArgAction::SetFalse), long = "no-deduplicate-commits")]
+    #[clap(
+        action(clap::ArgAction::SetFalse),
+        long = "no-deduplicate-commits",
+        visible_alias = "no-deduplicate"
+    )]
     pub detect_duplicate_commits_via_patch_id: bool,
 
+    /// Keep commits which become empty when they're applied (for example,
+    /// because their changes are already present at the destination), rather
+    /// than dropping them. This also keeps commits which would otherwise be
+    /// skipped as duplicates of commits already applied to the target branch.
+    #[clap(action, long = "keep-empty", overrides_with = "no_keep_empty")]
+    pub keep_empty: bool,
+
+    /// Drop commits which become empty when they're applied. This is the
+    /// default.
+    #[clap(action, long = "no-keep-empty", overrides_with = "keep_empty")]
+    pub no_keep_empty: bool,
+
     /// Attempt to resolve merge conflicts, if any. If a merge conflict
     /// occurs and this option is not set, the operation is aborted.
     #[clap(action, name = "merge", short = 'm', long = "merge")]
     pub resolve_merge_conflicts: bool,
 
+    /// Automatically resolve conflicting hunks in favor of one side, as with
+    /// `git rebase --strategy-option`.
+    #[clap(value_enum, short = 'X', long = "strategy-option")]
+    pub strategy_option: Option<StrategyOption>,
+
     /// Debugging option. Print the constraints used to create the rebase
     /// plan before executing it.
     #[clap(action, long = "debug-dump-rebase-constraints")]
@@ -95,6 +135,16 @@ pub struct MoveOptions {
     /// executing it.
     #[clap(action, long = "debug-dump-rebase-plan")]
     pub dump_rebase_plan: bool,
+
+    /// Before executing, print which commits will be moved and to where, and
+    /// why each of them is being moved.
+    #[clap(action, long = "explain")]
+    pub explain: bool,
+
+    /// Print how long it took to apply each commit. Only supported for
+    /// in-memory rebases.
+    #[clap(action, long = "timing")]
+    pub timing: bool,
 }
 
 /// Options for traversing commits.
@@ -107,6 +157,11 @@ pub struct TraverseCommitsOptions {
     pub num_commits: Option<usize>,
 
     /// Traverse as many commits as possible.
+    ///
+    /// If a commit with multiple candidates is reached, you're prompted to
+    /// select between them when running in a terminal. Otherwise, the
+    /// candidates are listed instead, unless `--oldest`, `--newest`, or
+    /// `--interactive` is passed to select between them.
     #[clap(action, short = 'a', long = "all")]
     pub all_the_way: bool,
 
@@ -254,12 +309,33 @@ pub struct InitArgs {
     #[clap(action, long = "uninstall")]
     pub uninstall: bool,
 
+    /// When uninstalling, also delete all of the data recorded by
+    /// git-branchless for this repository, such as the event log used by
+    /// `git undo`.
+    #[clap(action, long = "purge", requires = "uninstall")]
+    pub purge: bool,
+
     /// Use the provided name as the name of the main branch.
     ///
     /// If not set, it will be auto-detected. If it can't be auto-detected,
     /// then you will be prompted to enter a value for the main branch name.
     #[clap(value_parser, long = "main-branch", conflicts_with = "uninstall")]
     pub main_branch_name: Option<String>,
+    /// Reinitialize the branchless workflow even if it's already set up in
+    /// this repository. Existing data, such as the event log, is kept.
+    #[clap(action, long = "force", conflicts_with = "uninstall")]
+    pub force: bool,
+}
+
+/// Install the git-branchless hooks in the current repository.
+#[derive(Debug, Parser)]
+pub struct InstallHooksArgs {
+    /// Rewrite each hook from scratch, even if it already contains the
+    /// git-branchless hook. Any other content in the hook is kept, but runs
+    /// after the git-branchless hook. Hooks which change are backed up to
+    /// `<hook>.branchless-backup` first.
+    #[clap(action, long = "force")]
+    pub force: bool,
 }
 
 /// Install git-branchless's man-pages to the given path.
@@ -296,6 +372,37 @@ pub struct QueryArgs {
     /// stable for use in scripts.
     #[clap(action, short = 'r', long = "raw", conflicts_with("show_branches"))]
     pub raw: bool,
+
+    /// Print each matching commit according to the given format string,
+    /// similar to `git log --format`. Supported placeholders are `%H` (full
+    /// OID), `%h` (abbreviated OID), `%s` (summary), `%an` (author name), `%ae`
+    /// (author email), `%d` (branches), `%n` (newline) and `%%`.
+    #[clap(
+        value_parser,
+        long = "format",
+        conflicts_with_all(&["show_branches", "raw"])
+    )]
+    pub format: Option<String>,
+
+    /// Like `--format`, but read the format string from the given file. A
+    /// single trailing newline in the file is ignored.
+    #[clap(
+        value_parser,
+        long = "template-file",
+        value_name = "PATH",
+        conflicts_with_all(&["show_branches", "raw", "format"])
+    )]
+    pub template_file: Option<PathBuf>,
+
+    /// Render the matching commits as a smartlog-style graph, rather than as
+    /// a flat list.
+    #[clap(
+        action,
+        short = 'g',
+        long = "graph",
+        conflicts_with_all(&["show_branches", "raw", "format", "template_file"])
+    )]
+    pub graph: bool,
 }
 
 /// Specify commit messages
@@ -347,6 +454,25 @@ pub struct RecordArgs {
     /// How should newly encountered, untracked files be handled?
     #[clap(value_parser, long = "untracked", conflicts_with_all(&["interactive"]))]
     pub untracked_file_strategy: Option<UntrackedFileStrategy>,
+
+    /// Skip the `pre-commit` and `commit-msg` hooks when creating the commit,
+    /// as with `git commit --no-verify`.
+    #[clap(action, short = 'n', long = "no-verify")]
+    pub no_verify: bool,
+    /// Set the author of the new commit, in the form `Name <email>`. The
+    /// committer is left unchanged.
+    #[clap(value_parser, long = "author", value_name = "AUTHOR")]
+    pub author: Option<String>,
+
+    /// If no message was provided and the commit message is left empty in
+    /// the editor, offer to amend the changes into the current commit
+    /// instead of aborting.
+    #[clap(
+        action,
+        long = "amend-if-empty-message",
+        conflicts_with_all(&["interactive", "stash", "insert"])
+    )]
+    pub amend_if_empty_message: bool,
 }
 
 /// Display a nice graph of the commits you've recently worked on.
@@ -373,6 +499,78 @@ pub struct SmartlogArgs {
     #[clap(long)]
     pub exact: bool,
 
+    /// Additionally show up to this many generations of ancestor commits of
+    /// the draft commits being rendered. Any further ancestors are elided.
+    #[clap(long = "ancestors", value_name = "N")]
+    pub ancestors: Option<usize>,
+
+    /// Don't render the commits matching this revset, or their descendants.
+    /// The remaining commits are still rendered. Can be specified multiple
+    /// times.
+    #[clap(long = "exclude", value_name = "REVSET")]
+    pub exclude: Vec<Revset>,
+
+    /// Mark the rendered commits which are ancestors of the given commit
+    /// (including the commit itself).
+    #[clap(long = "relative-to", value_name = "COMMIT")]
+    pub relative_to: Option<Revset>,
+
+    /// Mark the rendered commits whose summary matches the given regular
+    /// expression (such as a plain substring). Other commits are still
+    /// rendered.
+    #[clap(long = "highlight", value_name = "PATTERN")]
+    pub highlight: Option<String>,
+
+    /// Render the smartlog as if the given commit were checked out, without
+    /// actually moving `HEAD`. If a branch name is given, render as if that
+    /// branch were checked out.
+    #[clap(long = "head", value_name = "COMMIT")]
+    pub head: Option<Revset>,
+
+    /// Additionally render the given commits, even if they're hidden or would
+    /// otherwise be excluded, along with the ancestors needed to connect them
+    /// to the main branch. Can be specified multiple times.
+    #[clap(long = "context", value_name = "COMMIT")]
+    pub context: Vec<Revset>,
+
+    /// Only render merge commits, along with the commits needed to connect
+    /// them to the main branch.
+    #[clap(long = "merges-only", conflicts_with("no_merges"))]
+    pub merges_only: bool,
+
+    /// Don't render merge commits. Their remaining ancestors are still
+    /// rendered.
+    #[clap(long = "no-merges")]
+    pub no_merges: bool,
+
+    /// Only render draft commits which modified files under this path
+    /// (relative to the root of the repository), along with the commits
+    /// needed to connect them to the main branch. Can be specified multiple
+    /// times.
+    #[clap(long = "path", value_name = "PATH")]
+    pub paths: Vec<PathBuf>,
+
+    /// After rendering the smartlog, report any anomalies found in the
+    /// rendered commits, such as branches pointing to hidden commits or
+    /// rewritten commits whose replacements are missing.
+    #[clap(long = "verify")]
+    pub verify: bool,
+
+    /// Only render the draft commits and HEAD, without the main branch
+    /// commits and other ancestors which would normally connect them, even if
+    /// the result is disconnected.
+    #[clap(long = "no-context", conflicts_with_all(&["context", "ancestors"]))]
+    pub no_context: bool,
+
+    /// Print the rendered commits as newline-delimited JSON, one object per
+    /// commit, instead of drawing the graph. Each object includes the
+    /// commit's parents in the graph, so that the graph can be reconstructed.
+    #[clap(
+        long = "json-stream",
+        conflicts_with_all(&["reverse", "verify", "highlight"])
+    )]
+    pub json_stream: bool,
+
     /// Options for resolving revset expressions.
     #[clap(flatten)]
     pub resolve_revset_options: ResolveRevsetOptions,
@@ -471,6 +669,15 @@ pub enum Command {
         /// Options for moving commits.
         #[clap(flatten)]
         move_options: MoveOptions,
+
+        /// Fail with a non-zero exit code if any of the commits to advance
+        /// are public, rather than printing guidance on how to proceed.
+        #[clap(
+            action,
+            long = "no-public",
+            conflicts_with = "force_rewrite_public_commits"
+        )]
+        no_public: bool,
     },
 
     /// Amend the current HEAD commit.
@@ -485,19 +692,92 @@ pub enum Command {
         #[clap(long)]
         reparent: bool,
 
+        /// Only rewrite the current HEAD commit, and leave its descendant
+        /// commits abandoned rather than restacking them. They can be restacked
+        /// later with `git restack`.
+        #[clap(long = "no-restack", conflicts_with = "reparent")]
+        no_restack: bool,
+
         /// How should newly encountered, untracked files be handled?
         #[clap(action, long = "untracked")]
         untracked_file_strategy: Option<UntrackedFileStrategy>,
+
+        /// Rather than amending the current HEAD commit, squash each staged
+        /// hunk into the draft commit in the current stack which last changed
+        /// the same lines. Hunks which can't be attributed to a single commit
+        /// are left staged.
+        #[clap(
+            action,
+            long = "absorb",
+            conflicts_with_all(&["reparent", "no_restack", "untracked_file_strategy"])
+        )]
+        absorb: bool,
+
+        /// Keep the original commit around as a hidden checkpoint, which
+        /// won't be garbage-collected and can be recovered with `git unhide`.
+        #[clap(action, long = "checkpoint", conflicts_with = "absorb")]
+        checkpoint: bool,
+
+        /// Replace the message of the amended commit with the full message of
+        /// the given commit.
+        #[clap(value_parser, long = "message-from", conflicts_with = "absorb")]
+        message_from: Option<Revset>,
     },
 
     /// Gather information about recent operations to upload as part of a bug
     /// report.
-    BugReport,
+    BugReport {
+        /// Replace commit hashes in the reported events and smartlogs with
+        /// stable placeholders.
+        #[clap(action, long = "redact")]
+        redact: bool,
+    },
+
+    /// Apply the given commits on top of the current commit, in memory.
+    ///
+    /// Unlike `git cherry-pick`, this doesn't touch the index or working copy
+    /// while applying the commits, and the whole operation can be reverted
+    /// with `git undo`. If the current commit is a branch, the branch is moved
+    /// to the last new commit.
+    CherryPick {
+        /// The commits to apply.
+        #[clap(value_parser, required = true)]
+        revsets: Vec<Revset>,
+
+        /// Options for resolving revset expressions.
+        #[clap(flatten)]
+        resolve_revset_options: ResolveRevsetOptions,
+    },
+
+    /// View and update git-branchless configuration.
+    Config {
+        /// The subcommand to run.
+        #[clap(subcommand)]
+        subcommand: ConfigSubcommand,
+    },
+
+    /// Compare a commit with the commit it was rewritten into (for example, by
+    /// a rebase or amend) using `git difftool`.
+    DiffRewrite {
+        /// The original (pre-rewrite) commit.
+        #[clap(value_parser)]
+        revset: Revset,
+    },
 
     /// Use the partial commit selector UI as a Git-compatible difftool; see
     /// git-difftool(1) for more information on Git difftools.
     Difftool(scm_diff_editor::Opts),
 
+    /// Check that git-branchless's commit graph is consistent with the
+    /// repository, resyncing it if necessary.
+    Doctor {
+        /// Also replay the event log and check that the branches it records
+        /// match the actual branches in the repository. This can be slow for
+        /// large event logs.
+        #[clap(action, long = "deep")]
+        deep: bool,
+    },
+
     /// Run internal garbage collection.
     Gc,
 
@@ -529,9 +809,20 @@ pub enum Command {
     /// Initialize the branchless workflow for this repository.
     Init(InitArgs),
 
+    /// Install the git-branchless hooks in the current repository. Use
+    /// `--force` to repair hooks which have been corrupted or overwritten.
+    InstallHooks(InstallHooksArgs),
+
     /// Install git-branchless's man-pages to the given path.
     InstallManPages(InstallManPagesArgs),
 
+    /// Print the current stack as a list of commits, newest first.
+    ///
+    /// This shows the same information as `git smartlog 'stack()'`, but
+    /// without the graph. The current stack must be linear, i.e. it must not
+    /// contain any merge commits or forks.
+    Log,
+
     /// Move a subtree of commits from one location to another.
     ///
     /// By default, `git move` tries to move the entire current stack if you
@@ -569,11 +860,43 @@ pub enum Command {
         )]
         exact: Vec<Revset>,
 
+        /// Read the source commits from stdin, one per line, such as the
+        /// output of `git query --raw`. Each commit, and all of its
+        /// descendants, will be moved.
+        #[clap(
+            action,
+            long = "source-stdin",
+            conflicts_with_all(&["source", "base", "exact"])
+        )]
+        source_stdin: bool,
+
         /// The destination commit to move all source commits onto. If not
-        /// provided, defaults to the current commit.
+        /// provided, defaults to the current commit. Pass `-` to move the
+        /// source commits back onto the parent they had before they were last
+        /// moved.
         #[clap(value_parser, short = 'd', long = "dest")]
         dest: Option<Revset>,
 
+        /// Place the moved commits immediately after the given commit. The
+        /// given commit's children are moved on top of the moved commits.
+        /// Equivalent to `--dest <commit> --insert`.
+        #[clap(
+            value_parser,
+            long = "after",
+            conflicts_with_all(&["dest", "before", "insert", "fixup"])
+        )]
+        after: Option<Revset>,
+
+        /// Place the moved commits immediately before the given commit. The
+        /// moved commits are placed onto the given commit's parent, and the
+        /// given commit is moved on top of them.
+        #[clap(
+            value_parser,
+            long = "before",
+            conflicts_with_all(&["dest", "after", "insert", "fixup"])
+        )]
+        before: Option<Revset>,
+
         /// Options for resolving revset expressions.
         #[clap(flatten)]
         resolve_revset_options: ResolveRevsetOptions,
@@ -594,6 +917,87 @@ pub enum Command {
         /// Test whether an in-memory rebase would succeed.
         #[clap(action, long = "dry-run", conflicts_with = "force_on_disk")]
         dry_run: bool,
+
+        /// After moving the commits, create a new linked worktree at the given
+        /// path with the head of the moved commits checked out (detached). The
+        /// current worktree is left untouched. The path must not exist or be an
+        /// empty directory.
+        #[clap(
+            value_parser,
+            long = "worktree",
+            value_name = "PATH",
+            conflicts_with_all(&["force_on_disk", "dry_run", "fixup"])
+        )]
+        worktree: Option<PathBuf>,
+
+        /// Rebuild the moved subtrees onto their existing parents, rewriting
+        /// each commit even though the topology doesn't change.
+        #[clap(
+            action,
+            long = "in-place",
+            conflicts_with_all(&["dest", "after", "before", "exact", "insert", "fixup", "force_on_disk"])
+        )]
+        in_place: bool,
+
+        /// Set the author of each moved commit to the current user, as with
+        /// `git commit --amend --reset-author`. Only supported for in-memory
+        /// rebases.
+        #[clap(action, long = "reset-author", conflicts_with = "force_on_disk")]
+        reset_author: bool,
+
+        /// Commit the currently-staged changes on top of the current commit,
+        /// and use that commit as the destination. The working copy and index
+        /// are left untouched.
+        #[clap(
+            action,
+            long = "onto-index",
+            conflicts_with_all(&["dest", "after", "before", "fixup", "in_place"])
+        )]
+        onto_index: bool,
+
+        /// Interactively select the destination commit from the commits in
+        /// the smartlog. Standard input must be a terminal.
+        #[clap(
+            action,
+            short = 'i',
+            long = "interactive",
+            conflicts_with_all(&[
+                "dest",
+                "after",
+                "before",
+                "in_place",
+                "onto_index",
+                "source_stdin"
+            ])
+        )]
+        interactive: bool,
+
+        /// Squash the moved commits into a single commit at the destination,
+        /// whose message is the concatenation of the moved commits' messages.
+        /// The moved commits must form a single linear range.
+        #[clap(
+            action,
+            long = "collapse",
+            conflicts_with_all(&[
+                "base", "exact", "after", "before", "fixup", "insert", "worktree", "in_place",
+                "force_on_disk",
+            ])
+        )]
+        collapse: bool,
+
+        /// If an on-disk rebase stops due to merge conflicts, run this shell
+        /// command with the conflicting paths as arguments. If it succeeds and
+        /// resolves all conflicts, the rebase is continued automatically;
+        /// otherwise, the rebase is left in progress.
+        #[clap(
+            value_parser,
+            long = "exec-on-conflict",
+            value_name = "COMMAND",
+            conflicts_with_all(&[
+                "force_in_memory", "dry_run", "worktree", "in_place", "reset_author", "collapse",
+            ])
+        )]
+        exec_on_conflict: Option<String>,
     },
 
     /// Move to a later commit in the current stack.
@@ -610,6 +1014,15 @@ pub enum Command {
         traverse_commits_options: TraverseCommitsOptions,
     },
 
+    /// Prune administrative entries for linked worktrees whose working
+    /// directories no longer exist, along with any git-branchless state
+    /// associated with them.
+    PruneWorktrees {
+        /// Only report the stale worktrees; don't prune them.
+        #[clap(action, long = "dry-run")]
+        dry_run: bool,
+    },
+
     /// Query the commit graph using the "revset" language and print matching
     /// commits.
     ///
@@ -620,6 +1033,27 @@ pub enum Command {
     /// ancestor commits appearing first.
     Query(QueryArgs),
 
+    /// Show the history of operations recorded by git-branchless, newest
+    /// first. Unlike `git reflog`, internal reference updates are grouped by
+    /// the operation which caused them.
+    Reflog {
+        /// Only show this many of the most recent operations.
+        #[clap(value_parser, short = 'n', long = "max-count")]
+        max_count: Option<usize>,
+    },
+
+    /// Rename a local branch. The rename is recorded as a single operation, so
+    /// it can be reverted with `git undo`.
+    RenameBranch {
+        /// The current name of the branch.
+        #[clap(value_parser)]
+        old_name: String,
+
+        /// The new name of the branch.
+        #[clap(value_parser)]
+        new_name: String,
+    },
+
     /// Restore internal invariants by reconciling the internal operation log
     /// with the state of the Git repository.
     Repair {
@@ -642,6 +1076,11 @@ pub enum Command {
         /// Options for moving commits.
         #[clap(flatten)]
         move_options: MoveOptions,
+
+        /// Also move the stacks containing the restacked commits onto the
+        /// main branch, as `git sync` would, in the same operation.
+        #[clap(action, long = "onto-main")]
+        onto_main: bool,
     },
 
     /// Create a commit by interactively selecting which changes to include.
@@ -654,7 +1093,8 @@ pub enum Command {
             value_parser,
             default_value = "stack() | @",
             default_value_if("commit_to_fixup", clap::builder::ArgPredicate::IsPresent, "@"),
-            default_value_if("messages", clap::builder::ArgPredicate::IsPresent, "@")
+            default_value_if("messages", clap::builder::ArgPredicate::IsPresent, "@"),
+            default_value_if("message_from", clap::builder::ArgPredicate::IsPresent, "@")
         )]
         revsets: Vec<Revset>,
 
@@ -677,6 +1117,14 @@ pub enum Command {
         /// that; otherwise, the editor starts empty.
         #[clap(action, short = 'd', long = "discard", conflicts_with_all(&["messages", "commit_to_fixup"]))]
         discard: bool,
+
+        /// Use the full message of the given commit as the new message.
+        #[clap(
+            value_parser,
+            long = "message-from",
+            conflicts_with_all(&["messages", "commit_to_fixup", "discard"])
+        )]
+        message_from: Option<Revset>,
     },
 
     /// `smartlog` command.
@@ -721,6 +1169,15 @@ pub enum Command {
         move_options: MoveOptions,
     },
 
+    /// Summarize the state of the commit graph: the number of draft and
+    /// abandoned commits, and the current position.
+    Status {
+        /// Print the summary on a single line, such as for use in a shell
+        /// prompt.
+        #[clap(action, short = 's', long = "short")]
+        short: bool,
+    },
+
     /// Push commits to a remote.
     Submit(SubmitArgs),
 
@@ -744,6 +1201,28 @@ pub enum Command {
         )]
         pull: bool,
 
+        /// Fetch the main branch from this remote instead of the remote that
+        /// it tracks. Implies `--pull`.
+        #[clap(long = "remote", value_name = "REMOTE")]
+        remote: Option<String>,
+
+        /// Only move the stack containing `HEAD`, leaving other stacks alone.
+        /// Equivalent to `git sync .`.
+        #[clap(action, long = "current", conflicts_with = "revsets")]
+        current: bool,
+
+        /// After syncing, print a summary of how many stacks were moved, how
+        /// many commits were rebased, and how many duplicate commits were
+        /// dropped.
+        #[clap(action, long = "stat")]
+        stat: bool,
+
+        /// After syncing, push the branches which were moved to their remote
+        /// branches with `--force-with-lease`, as with `git submit`. Nothing is
+        /// pushed if any stack could not be synced.
+        #[clap(action, long = "push")]
+        push: bool,
+
         /// Options for moving commits.
         #[clap(flatten)]
         move_options: MoveOptions,
@@ -768,9 +1247,19 @@ pub enum Command {
         #[clap(action, short = 'i', long = "interactive")]
         interactive: bool,
 
+        /// Reapply the changes reverted by the most recent undo. Fails if
+        /// other operations have happened since then.
+        #[clap(action, long = "redo", conflicts_with("interactive"))]
+        redo: bool,
+
         /// Skip confirmation and apply changes immediately.
         #[clap(action, short = 'y', long = "yes")]
         yes: bool,
+
+        /// Only restore references, leaving the working copy and index as
+        /// they are, rather than checking out the restored `HEAD`.
+        #[clap(action, long = "refs-only")]
+        refs_only: bool,
     },
 
     /// Unhide previously-hidden commits from the smartlog.
@@ -853,6 +1342,12 @@ pub struct GlobalArgs {
     /// Flag to force enable or disable terminal colors.
     #[clap(value_parser, long = "color", value_enum, global = true)]
     pub color: Option<ColorSetting>,
+
+    /// Write the tracing spans recorded while running the command to the given
+    /// file as JSON, for profiling. This can also be set with the
+    /// `GIT_BRANCHLESS_TRACE` environment variable.
+    #[clap(value_parser, long = "trace", global = true, value_name = "FILE")]
+    pub trace: Option<PathBuf>,
 }
 
 /// Branchless workflow for Git.
@@ -870,6 +1365,39 @@ pub struct Opts {
     pub command: Command,
 }
 
+/// `config` subcommands.
+#[derive(Debug, Parser)]
+pub enum ConfigSubcommand {
+    /// Set a config key in the repository configuration. The key must be one
+    /// recognized by git-branchless, and the value must be valid for that key.
+    Set {
+        /// The config key to set, such as `branchless.smartlog.defaultRevset`.
+        #[clap(value_parser)]
+        key: String,
+
+        /// The value to set the config key to.
+        #[clap(value_parser)]
+        value: String,
+    },
+
+    /// Get the value of a config key. The key must be one recognized by
+    /// git-branchless. If the key isn't set, its default value is printed.
+    Get {
+        /// The config key to get, such as `branchless.smartlog.defaultRevset`.
+        #[clap(value_parser)]
+        key: String,
+
+        /// Also print where the value was set, such as in the repository or
+        /// global configuration.
+        #[clap(action, long = "effective")]
+        effective: bool,
+    },
+
+    /// List the effective value of each git-branchless config key, along with
+    /// where it was set.
+    List,
+}
+
 /// `snapshot` subcommands.
 #[derive(Debug, Parser)]
 pub enum SnapshotSubcommand {
@@ -916,6 +1444,16 @@ pub enum TestSubcommand {
         #[clap(value_parser, default_value = "stack() | @")]
         revset: Revset,
 
+        /// The set of commits to test, as an alternative to passing it
+        /// positionally. The commits may include public commits.
+        #[clap(
+            value_parser,
+            long = "on",
+            value_name = "REVSET",
+            conflicts_with("revset")
+        )]
+        on: Option<Revset>,
+
         /// Options for resolving revset expressions.
         #[clap(flatten)]
         resolve_revset_options: ResolveRevsetOptions,
@@ -1103,7 +1641,7 @@ pub fn rewrite_args(args: Vec<OsString>) -> Vec<OsString> {
 
     // For backward-compatibility, convert calls of the form
     // `git-branchless-hook-X Y Z` into `git-branchless hook X Y Z`.
-    let args = match args.as_slice() {
+    match args.as_slice() {
         [first, subcommand, rest @ ..] if exe_name == "git-branchless" => {
             let mut new_args = vec![first.clone()];
             match subcommand
@@ -1122,9 +1660,7 @@ pub fn rewrite_args(args: Vec<OsString>) -> Vec<OsString> {
             new_args
         }
         other => other.to_vec(),
-    };
-
-    args
+    }
 }
 
 #[cfg(test)]
@@ -1,17 +1,26 @@
 use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 use git_branchless_invoke::CommandContext;
 use itertools::Itertools;
+use lib::core::commit_format::CommitFormat;
 use lib::core::dag::Dag;
 use lib::core::effects::{Effects, OperationType};
 use lib::core::eventlog::{EventLogDb, EventReplayer};
-use lib::core::repo_ext::RepoExt;
-use lib::git::{CategorizedReferenceName, GitRunInfo, Repo};
+use lib::core::node_descriptors::{
+    BranchesDescriptor, CommitMessageDescriptor, CommitOidDescriptor,
+    DifferentialRevisionDescriptor, ObsolescenceExplanationDescriptor, Redactor,
+    RelativeTimeDescriptor,
+};
+use lib::core::repo_ext::{RepoExt, RepoReferencesSnapshot};
+use lib::git::{CategorizedReferenceName, GitRunInfo, NonZeroOid, Repo, ResolvedReferenceInfo};
 use lib::util::{ExitCode, EyreExitOr};
 use tracing::instrument;
 
 use git_branchless_opts::{QueryArgs, ResolveRevsetOptions, Revset};
 use git_branchless_revset::resolve_commits;
+use git_branchless_smartlog::{make_smartlog_graph, render_graph};
 
 /// `query` command.
 #[instrument]
@@ -25,6 +34,9 @@ pub fn command_main(ctx: CommandContext, args: QueryArgs) -> EyreExitOr<()> {
         resolve_revset_options,
         show_branches,
         raw,
+        format,
+        template_file,
+        graph,
     } = args;
     query(
         &effects,
@@ -33,10 +45,47 @@ pub fn command_main(ctx: CommandContext, args: QueryArgs) -> EyreExitOr<()> {
         &resolve_revset_options,
         show_branches,
         raw,
+        format,
+        template_file,
+        graph,
     )
 }
 
+/// Get the names to render for the `%d` placeholder for the given commit, in
+/// the style of `git log --decorate`.
+fn get_decorations(
+    head_info: &ResolvedReferenceInfo,
+    references_snapshot: &RepoReferencesSnapshot,
+    commit_oid: NonZeroOid,
+) -> Vec<String> {
+    let mut branch_names = match references_snapshot.branch_oid_to_names.get(&commit_oid) {
+        Some(branch_names) => branch_names.iter().sorted().collect_vec(),
+        None => Vec::new(),
+    };
+
+    let mut result = Vec::new();
+    if head_info.oid == Some(commit_oid) {
+        match &head_info.reference_name {
+            Some(head_reference_name) => {
+                branch_names.retain(|branch_name| *branch_name != head_reference_name);
+                result.push(format!(
+                    "HEAD -> {}",
+                    CategorizedReferenceName::new(head_reference_name).render_suffix()
+                ));
+            }
+            None => result.push("HEAD".to_string()),
+        }
+    }
+    result.extend(
+        branch_names
+            .into_iter()
+            .map(|branch_name| CategorizedReferenceName::new(branch_name).render_suffix()),
+    );
+    result
+}
+
 #[instrument]
+#[allow(clippy::too_many_arguments)]
 fn query(
     effects: &Effects,
     git_run_info: &GitRunInfo,
@@ -44,14 +93,54 @@ fn query(
     resolve_revset_options: &ResolveRevsetOptions,
     show_branches: bool,
     raw: bool,
+    format: Option<String>,
+    template_file: Option<PathBuf>,
+    graph: bool,
 ) -> EyreExitOr<()> {
+    let format = match &template_file {
+        None => format,
+        Some(template_file) => match std::fs::read_to_string(template_file) {
+            Ok(template) => {
+                let template = template
+                    .strip_suffix("\r\n")
+                    .or_else(|| template.strip_suffix('\n'))
+                    .unwrap_or(&template);
+                Some(template.to_string())
+            }
+            Err(err) => {
+                writeln!(
+                    effects.get_error_stream(),
+                    "Could not read template file {}: {err}",
+                    template_file.display()
+                )?;
+                return Ok(Err(ExitCode(1)));
+            }
+        },
+    };
+
+    // Surface format string errors before doing any work.
+    let format = match format.as_deref().map(CommitFormat::parse).transpose() {
+        Ok(format) => format,
+        Err(err) => {
+            match &template_file {
+                Some(template_file) => writeln!(
+                    effects.get_error_stream(),
+                    "Invalid template file {}: {err}",
+                    template_file.display()
+                )?,
+                None => writeln!(effects.get_error_stream(), "{err}")?,
+            }
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
     let repo = Repo::from_current_dir()?;
     let conn = repo.get_db_conn()?;
     let event_log_db = EventLogDb::new(&conn)?;
     let event_replayer = EventReplayer::from_event_log_db(effects, &repo, &event_log_db)?;
     let event_cursor = event_replayer.make_default_cursor();
     let references_snapshot = repo.get_references_snapshot()?;
-    let mut dag = Dag::open_and_sync(
+    let mut dag = Dag::open_and_sync_if_changed(
         effects,
         &repo,
         &event_replayer,
@@ -68,7 +157,45 @@ fn query(
             }
         };
 
-    if show_branches {
+    if graph {
+        let head_info = repo.get_head_info()?;
+        let graph = make_smartlog_graph(
+            effects,
+            &repo,
+            &dag,
+            &event_replayer,
+            event_cursor,
+            &commit_set,
+            false,
+        )?;
+        let lines = render_graph(
+            effects,
+            &repo,
+            &dag,
+            &graph,
+            references_snapshot.head_oid,
+            &mut [
+                &mut CommitOidDescriptor::new(true)?,
+                &mut RelativeTimeDescriptor::new(&repo, SystemTime::now())?,
+                &mut ObsolescenceExplanationDescriptor::new(&repo, &event_replayer, event_cursor)?,
+                &mut BranchesDescriptor::new(
+                    &repo,
+                    &head_info,
+                    &references_snapshot,
+                    &Redactor::Disabled,
+                )?,
+                &mut DifferentialRevisionDescriptor::new(&repo, &Redactor::Disabled)?,
+                &mut CommitMessageDescriptor::new(&Redactor::Disabled)?,
+            ],
+        )?;
+        for line in lines {
+            writeln!(
+                effects.get_output_stream(),
+                "{}",
+                effects.get_glyphs().render(line)?
+            )?;
+        }
+    } else if show_branches {
         let commit_oids = {
             let (effects, _progress) = effects.start_operation(OperationType::SortCommits);
             let _effects = effects;
@@ -95,9 +222,18 @@ fn query(
             let _effects = effects;
             dag.sort(&commit_set)?
         };
+        let head_info = repo.get_head_info()?;
         for commit_oid in commit_oids {
             if raw {
                 writeln!(effects.get_output_stream(), "{commit_oid}")?;
+            } else if let Some(format) = &format {
+                let commit = repo.find_commit_or_fail(commit_oid)?;
+                let decorations = get_decorations(&head_info, &references_snapshot, commit_oid);
+                writeln!(
+                    effects.get_output_stream(),
+                    "{}",
+                    format.render(&commit, &decorations)?
+                )?;
             } else {
                 let commit = repo.find_commit_or_fail(commit_oid)?;
                 writeln!(
@@ -176,6 +176,147 @@ fn test_query_branches() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_query_remote_tracking_branch() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&[
+        "update-ref",
+        "refs/remotes/origin/feature",
+        &test2_oid.to_string(),
+    ])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("query", &["ancestors(origin/feature) - master"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        62fc20d create test1.txt
+        96d1c37 create test2.txt
+        "###);
+    }
+
+    git.run(&["branch", "origin/feature", &test1_oid.to_string()])?;
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "query",
+            &["origin/feature"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"Evaluation error for expression 'origin/feature': the name 'origin/feature' is ambiguous: it refers to both a local branch and a remote-tracking branch; use 'refs/heads/origin/feature' or 'refs/remotes/origin/feature' instead");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("query", &["refs/remotes/origin/feature"])?;
+        insta::assert_snapshot!(stdout, @"96d1c37 create test2.txt");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_query_format() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.run(&["branch", "foo"])?;
+    git.commit_file("test2", 2)?;
+    git.detach_head()?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("query", &["--format", "%h %s%d", "::."])?;
+        insta::assert_snapshot!(stdout, @r###"
+        f777ecc create initial.txt
+        62fc20d create test1.txt (foo)
+        96d1c37 create test2.txt (master)
+        70deb1e create test3.txt (HEAD)
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("query", &["--format", "%H|%an <%ae>|100%%", "."])?;
+        insta::assert_snapshot!(stdout, @"70deb1e28791d8e7dd5a1f0c871a51b91282562f|Testy McTestface <test@example.com>|100%");
+    }
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "query",
+            &["--format", "%h %x", "."],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"unknown placeholder '%x' in format string");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_query_template_file() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.run(&["branch", "foo"])?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+
+    std::fs::write(git.repo_path.join("report.tmpl"), "%h%d%n  %s <%ae>\n")?;
+    {
+        let (stdout, _stderr) = git.branchless(
+            "query",
+            &["--template-file", "report.tmpl", "draft() | foo"],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        62fc20d (foo, master)
+          create test1.txt <test@example.com>
+        96d1c37 (HEAD)
+          create test2.txt <test@example.com>
+        "###);
+    }
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "query",
+            &["--template-file", "missing.tmpl", "."],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"Could not read template file missing.tmpl: No such file or directory (os error 2)");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    std::fs::write(git.repo_path.join("bad.tmpl"), "%h %")?;
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "query",
+            &["--template-file", "bad.tmpl", "."],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"Invalid template file bad.tmpl: incomplete placeholder '%' at end of format string");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_query_hidden_commits() -> eyre::Result<()> {
     let git = make_git()?;
@@ -208,3 +349,48 @@ fn test_query_hidden_commits() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_query_graph() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test3", 3)?;
+    git.run(&["branch", "foo"])?;
+    git.commit_file("test4", 4)?;
+
+    {
+        let (stdout, stderr) = git.branchless("query", &["--graph", "draft() - ."])?;
+        insta::assert_snapshot!(stderr, @"");
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | |
+        | o 96d1c37 create test2.txt
+        |
+        o 98b9119 (foo) create test3.txt
+        |
+        @ 2b633ed create test4.txt
+        "###);
+    }
+
+    {
+        let (stdout, stderr) = git.branchless("query", &["--graph", "foo"])?;
+        insta::assert_snapshot!(stderr, @"");
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 98b9119 (foo) create test3.txt
+        |
+        @ 2b633ed create test4.txt
+        "###);
+    }
+
+    Ok(())
+}
@@ -12,6 +12,7 @@
 use std::collections::HashSet;
 use std::ffi::OsString;
 use std::fmt::Write;
+use std::io::{BufRead, BufReader, Read, stdin};
 use std::time::SystemTime;
 
 use git_branchless_invoke::CommandContext;
@@ -28,7 +29,7 @@ use lib::core::repo_ext::RepoExt;
 use lib::core::rewrite::{
     BuildRebasePlanError, BuildRebasePlanOptions, ExecuteRebasePlanOptions,
     ExecuteRebasePlanResult, MergeConflictRemediation, RebasePlanBuilder, RebasePlanPermissions,
-    RepoResource, execute_rebase_plan,
+    RepoResource, execute_rebase_plan, write_signed_public_commits_note,
 };
 use lib::core::untracked_file_cache::{UntrackedFileStrategy, process_untracked_files};
 use lib::git::{
@@ -63,6 +64,9 @@ pub fn command_main(ctx: CommandContext, args: RecordArgs) -> EyreExitOr<()> {
         insert,
         stash,
         untracked_file_strategy,
+        no_verify,
+        author,
+        amend_if_empty_message,
     } = args;
     record(
         &effects,
@@ -75,9 +79,24 @@ pub fn command_main(ctx: CommandContext, args: RecordArgs) -> EyreExitOr<()> {
         insert,
         stash,
         untracked_file_strategy,
+        no_verify,
+        author,
+        amend_if_empty_message,
     )
 }
 
+/// Parse an author of the form `Name <email>` into its name and email.
+fn parse_author(author: &str) -> Option<(&str, &str)> {
+    let (name, email) = author.split_once('<')?;
+    let name = name.trim();
+    let email = email.trim_end().strip_suffix('>')?;
+    if name.is_empty() || email.is_empty() || email.contains(['<', '>']) {
+        None
+    } else {
+        Some((name, email))
+    }
+}
+
 #[instrument]
 fn record(
     effects: &Effects,
@@ -90,7 +109,25 @@ fn record(
     insert: bool,
     stash: bool,
     untracked_file_strategy: Option<UntrackedFileStrategy>,
+    no_verify: bool,
+    author: Option<String>,
+    amend_if_empty_message: bool,
 ) -> EyreExitOr<()> {
+    let author = match author
+        .as_deref()
+        .map(|author| (author, parse_author(author)))
+    {
+        None => None,
+        Some((_, Some((name, email)))) => Some(format!("{name} <{email}>")),
+        Some((author, None)) => {
+            writeln!(
+                effects.get_error_stream(),
+                "Invalid author: {author}\nThe author should be of the form: Name <email>"
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
     let now = SystemTime::now();
     let repo = Repo::from_dir(&git_run_info.working_directory)?;
     let conn = repo.get_db_conn()?;
@@ -193,6 +230,8 @@ fn record(
                 &snapshot,
                 event_tx_id,
                 messages,
+                no_verify,
+                author.as_deref(),
             )?);
         }
     } else {
@@ -224,6 +263,44 @@ fn record(
         let messages = if messages.is_empty() && stash {
             get_default_stash_message(&repo, effects, &snapshot, &working_copy_changes_type)
                 .map(|message| vec![message])?
+        } else if messages.is_empty()
+            && commit_to_fixup.is_none()
+            && amend_if_empty_message
+            && snapshot.head_commit.is_some()
+        {
+            let commit_template = get_commit_template(&repo)?;
+            let message = edit_message(
+                git_run_info,
+                &repo,
+                commit_template.as_deref().unwrap_or(""),
+            )?;
+            let message = strip_message_comments(&message);
+            if message.is_empty() {
+                if !confirm_amend_with_empty_message(effects, &mut stdin())? {
+                    writeln!(
+                        effects.get_error_stream(),
+                        "Aborting commit due to empty commit message."
+                    )?;
+                    return Ok(Err(ExitCode(1)));
+                }
+
+                let args = {
+                    let mut args = vec!["commit", "--amend", "--no-edit"];
+                    if working_copy_changes_type == WorkingCopyChangesType::Unstaged {
+                        args.push("--all");
+                    }
+                    if no_verify {
+                        args.push("--no-verify");
+                    }
+                    if let Some(author) = &author {
+                        args.extend(["--author", author]);
+                    }
+                    args
+                };
+                try_exit_code!(git_run_info.run_direct_no_wrapping(Some(event_tx_id), &args)?);
+                return Ok(Ok(()));
+            }
+            vec![message]
         } else {
             messages
         };
@@ -237,6 +314,12 @@ fn record(
             if working_copy_changes_type == WorkingCopyChangesType::Unstaged {
                 args.push("--all".to_string());
             }
+            if no_verify {
+                args.push("--no-verify".to_string());
+            }
+            if let Some(author) = &author {
+                args.extend(["--author".to_string(), author.clone()]);
+            }
             if let Some(revset) = commit_to_fixup {
                 let event_replayer =
                     EventReplayer::from_event_log_db(effects, &repo, &event_log_db)?;
@@ -394,6 +477,36 @@ fn record(
     Ok(Ok(()))
 }
 
+/// Remove comment lines and surrounding whitespace from a commit message
+/// returned from the editor, as `git commit` would.
+fn strip_message_comments(message: &str) -> String {
+    message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Ask the user whether to amend the changes into `HEAD`, since no commit
+/// message was provided.
+fn confirm_amend_with_empty_message(effects: &Effects, in_: &mut impl Read) -> eyre::Result<bool> {
+    write!(
+        effects.get_output_stream(),
+        "The commit message is empty. Amend the changes into the current commit instead? [yN] "
+    )?;
+    let mut user_input = String::new();
+    let mut reader = BufReader::new(in_);
+    let confirmed = match reader.read_line(&mut user_input) {
+        Ok(_size) => {
+            let user_input = user_input.trim();
+            user_input == "y" || user_input == "Y"
+        }
+        Err(_) => false,
+    };
+    Ok(confirmed)
+}
+
 #[instrument]
 fn record_interactive(
     effects: &Effects,
@@ -402,6 +515,8 @@ fn record_interactive(
     snapshot: &WorkingCopySnapshot,
     event_tx_id: EventTransactionId,
     messages: Vec<String>,
+    no_verify: bool,
+    author: Option<&str>,
 ) -> EyreExitOr<()> {
     let old_tree = snapshot.commit_stage0.get_tree()?;
     let new_tree = snapshot.commit_unstaged.get_tree()?;
@@ -561,6 +676,12 @@ fn record_interactive(
         if !message.is_empty() {
             args.extend(["--message", &message]);
         }
+        if no_verify {
+            args.push("--no-verify");
+        }
+        if let Some(author) = author {
+            args.extend(["--author", author]);
+        }
         args
     };
     git_run_info.run_direct_no_wrapping(Some(event_tx_id), &args)
@@ -611,6 +732,8 @@ fn insert_before_siblings(
         force_rewrite_public_commits: false,
         dump_rebase_constraints: false,
         dump_rebase_plan: false,
+        explain: false,
+        keep_empty: false,
         detect_duplicate_commits_via_patch_id: true,
     };
 
@@ -687,6 +810,7 @@ To proceed anyways, run: git move -f -s 'siblings(.)",
                     .get_glyphs()
                     .render(example_bad_commit.friendly_describe(effects.get_glyphs())?)?,
             )?;
+            write_signed_public_commits_note(effects, &repo, &dag, &public_commits_to_move)?;
             return Ok(Ok(()));
         }
     };
@@ -699,6 +823,10 @@ To proceed anyways, run: git move -f -s 'siblings(.)",
         force_on_disk: false,
         dry_run: false,
         resolve_merge_conflicts: false,
+        merge_file_favor: None,
+        reset_author: false,
+        timing: false,
+        exec_on_conflict: None,
         check_out_commit_options: Default::default(),
     };
     let result = execute_rebase_plan(
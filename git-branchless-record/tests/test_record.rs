@@ -1169,3 +1169,174 @@ fn test_record_fixup() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg(unix)]
+fn test_record_no_verify() -> eyre::Result<()> {
+    use std::{fs, os::unix::prelude::PermissionsExt};
+
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+
+    let hook_path = git.repo_path.join(".git").join("hooks").join("pre-commit");
+    fs::write(
+        &hook_path,
+        "#!/bin/sh\necho 'pre-commit hook failed' >&2\nexit 1\n",
+    )?;
+    fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))?;
+
+    git.write_file_txt("test1", "contents1\n")?;
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "record",
+            &["-m", "foo"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @"");
+        insta::assert_snapshot!(stderr, @"pre-commit hook failed");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("record", &["--no-verify", "-m", "foo"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        [master 914812a] foo
+         1 file changed, 1 insertion(+), 1 deletion(-)
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_record_author() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+
+    git.write_file_txt("test1", "contents1\n")?;
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "record",
+            &["--author", "Pair Partner", "-m", "foo"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @"");
+        insta::assert_snapshot!(stderr, @r###"
+        Invalid author: Pair Partner
+        The author should be of the form: Name <email>
+        "###);
+    }
+    {
+        let (stdout, _stderr) = git.run(&["log", "-1", "--format=%s"])?;
+        insta::assert_snapshot!(stdout, @"create test1.txt");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless(
+            "record",
+            &["--author", "Pair Partner <pair@example.com>", "-m", "foo"],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        [master 4abe56c] foo
+         Author: Pair Partner <pair@example.com>
+         1 file changed, 1 insertion(+), 1 deletion(-)
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["log", "-1", "--format=%an <%ae>%n%cn <%ce>"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Pair Partner <pair@example.com>
+        Testy McTestface <test@example.com>
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_record_amend_if_empty_message() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+
+    git.write_file_txt("test1", "contents1\n")?;
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "record",
+            &["--amend-if-empty-message"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                input: Some("n\n".to_string()),
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @"The commit message is empty. Amend the changes into the current commit instead? [yN]");
+        insta::assert_snapshot!(stderr, @"Aborting commit due to empty commit message.");
+    }
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "record",
+            &["--amend-if-empty-message"],
+            &GitRunOptions {
+                input: Some("y\n".to_string()),
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        The commit message is empty. Amend the changes into the current commit instead? [yN] [master d9e45f9] create test1.txt
+         Date: Thu Oct 29 12:34:56 2020 -0100
+         1 file changed, 1 insertion(+)
+         create mode 100644 test1.txt
+        "###);
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 2 updates: branch master, ref HEAD
+        branchless: processed commit: d9e45f9 create test1.txt
+        hint: to move child commits onto this commit, run: git advance
+        hint: disable this hint by running: git config --global branchless.hint.advanceChildCommits false
+        branchless: processing 1 rewritten commit
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["log", "--format=%s"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        create test1.txt
+        create initial.txt
+        "###);
+    }
+    {
+        let (stdout, _stderr) = git.run(&["show", "--format=", "HEAD"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        diff --git a/test1.txt b/test1.txt
+        new file mode 100644
+        index 0000000..a024003
+        --- /dev/null
+        +++ b/test1.txt
+        @@ -0,0 +1 @@
+        +contents1
+        "###);
+    }
+
+    Ok(())
+}
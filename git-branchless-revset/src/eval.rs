@@ -10,7 +10,7 @@ use thiserror::Error;
 
 use lib::core::dag::{CommitSet, Dag};
 use lib::core::formatting::Pluralize;
-use lib::git::{ConfigRead, Repo, RepoError, ResolvedReferenceInfo};
+use lib::git::{BranchType, ConfigRead, NonZeroOid, Repo, RepoError};
 use tracing::instrument;
 
 use super::Expr;
@@ -30,6 +30,11 @@ pub enum EvalError {
     #[error("no commit, branch, or reference with the name '{name}' could be found")]
     UnboundName { name: String },
 
+    #[error(
+        "the name '{name}' is ambiguous: it refers to both a local branch and a remote-tracking branch; use 'refs/heads/{name}' or 'refs/remotes/{name}' instead"
+    )]
+    AmbiguousName { name: String },
+
     #[error(
         "no function with the name '{name}' could be found; these functions are available: {}",
         available_names.join(", "),
@@ -138,30 +143,19 @@ fn eval_inner(ctx: &mut Context, expr: &Expr, commitset_hint: &Option<&CommitSet
 #[instrument]
 pub(super) fn eval_name(ctx: &mut Context, name: &str) -> EvalResult {
     if name == "." || name == "@" {
-        let head_info = ctx.repo.get_head_info()?;
-        return match head_info {
-            ResolvedReferenceInfo {
-                oid: Some(oid),
-                reference_name: _,
-            } => Ok(oid.into()),
-            ResolvedReferenceInfo {
-                oid: None,
-                reference_name: _,
-            } => Ok(CommitSet::empty()),
-        };
+        return Ok(ctx.dag.head_commit.clone());
     }
 
-    let commit = ctx.repo.revparse_single_commit(name);
-    let commit_set = match commit {
-        Ok(Some(commit)) => {
-            let commit_set: CommitSet = commit.get_oid().into();
-            commit_set
-        }
-        Ok(None) | Err(_) => {
-            return Err(EvalError::UnboundName {
-                name: name.to_owned(),
-            });
-        }
+    let commit_set: CommitSet = match eval_remote_tracking_branch(ctx, name)? {
+        Some(oid) => oid.into(),
+        None => match ctx.repo.revparse_single_commit(name) {
+            Ok(Some(commit)) => commit.get_oid().into(),
+            Ok(None) | Err(_) => {
+                return Err(EvalError::UnboundName {
+                    name: name.to_owned(),
+                });
+            }
+        },
     };
 
     ctx.dag
@@ -175,6 +169,33 @@ pub(super) fn eval_name(ctx: &mut Context, name: &str) -> EvalResult {
     Ok(commit_set)
 }
 
+/// Resolve a name like `origin/main` to the remote-tracking branch with that
+/// name, if any. If there's also a local branch with the same name pointing
+/// to a different commit, then the name is ambiguous.
+#[instrument]
+fn eval_remote_tracking_branch(ctx: &Context, name: &str) -> Result<Option<NonZeroOid>, EvalError> {
+    if !name.contains('/') {
+        return Ok(None);
+    }
+
+    let remote_oid = match ctx.repo.find_branch(name, BranchType::Remote)? {
+        Some(branch) => branch.get_oid()?,
+        None => return Ok(None),
+    };
+    let local_oid = match ctx.repo.find_branch(name, BranchType::Local)? {
+        Some(branch) => branch.get_oid()?,
+        None => None,
+    };
+    match (local_oid, remote_oid) {
+        (Some(local_oid), Some(remote_oid)) if local_oid != remote_oid => {
+            Err(EvalError::AmbiguousName {
+                name: name.to_owned(),
+            })
+        }
+        (_, remote_oid) => Ok(remote_oid),
+    }
+}
+
 #[instrument]
 pub(super) fn eval_fn(
     ctx: &mut Context,
@@ -376,7 +397,13 @@ mod tests {
             .into_iter()
             .map(|oid| repo.find_commit_or_fail(oid))
             .try_collect()?;
-        commits.sort_by_key(|commit| (commit.get_message_pretty(), commit.get_time()));
+        commits.sort_by_key(|commit| {
+            (
+                commit.get_message_pretty(),
+                commit.get_time(),
+                commit.get_oid(),
+            )
+        });
         Ok(commits)
     }
 
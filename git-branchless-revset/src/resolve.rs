@@ -5,7 +5,7 @@ use git_branchless_opts::{ResolveRevsetOptions, Revset};
 use lib::core::config::get_smartlog_default_revset;
 use lib::core::dag::{CommitSet, Dag};
 use lib::core::effects::Effects;
-use lib::git::Repo;
+use lib::git::{BranchType, Repo};
 use thiserror::Error;
 use tracing::instrument;
 
@@ -93,13 +93,19 @@ pub fn resolve_commits(
         // NB: also update `check_parse_revsets`
 
         // Handle syntax that's supported by Git, but which we haven't
-        // implemented in the revset language.
-        if let Ok(Some(commit)) = repo.revparse_single_commit(revset) {
-            let commit_set = CommitSet::from(commit.get_oid());
-            dag.sync_from_oids(effects, repo, CommitSet::empty(), commit_set.clone())
-                .map_err(|err| ResolveError::OtherError { source: err })?;
-            commit_sets.push(commit_set);
-            continue;
+        // implemented in the revset language. Remote-tracking branch names
+        // are left to the revset evaluator, which checks them for ambiguity
+        // with local branches.
+        let is_remote_tracking_branch =
+            matches!(repo.find_branch(revset, BranchType::Remote), Ok(Some(_)));
+        if !is_remote_tracking_branch {
+            if let Ok(Some(commit)) = repo.revparse_single_commit(revset) {
+                let commit_set = CommitSet::from(commit.get_oid());
+                dag.sync_from_oids(effects, repo, CommitSet::empty(), commit_set.clone())
+                    .map_err(|err| ResolveError::OtherError { source: err })?;
+                commit_sets.push(commit_set);
+                continue;
+            }
         }
 
         let expr = parse(revset).map_err(|err| ResolveError::ParseError {
@@ -54,6 +54,9 @@ pub enum InitialCommitMessages {
     /// The user wants to fixup a commit.
     FixUp(Revset),
 
+    /// The user wants to reuse the message of another commit.
+    MessageFrom(Revset),
+
     /// The user provided explicit messages.
     Messages(Vec<String>),
 }
@@ -119,6 +122,8 @@ pub fn reword(
         force_rewrite_public_commits,
         dump_rebase_constraints: false,
         dump_rebase_plan: false,
+        explain: false,
+        keep_empty: false,
         detect_duplicate_commits_via_patch_id: false,
     };
     let permissions = match RebasePlanPermissions::verify_rewrite_set(
@@ -167,6 +172,32 @@ pub fn reword(
             let message = format!("fixup! {}", message.into_string_lossy());
             InitialCommitMessages::Messages(vec![message])
         }
+        InitialCommitMessages::MessageFrom(revset) => {
+            let message_commits = match resolve_commits_from_hashes(
+                &repo,
+                &mut dag,
+                effects,
+                vec![revset.clone()],
+                resolve_revset_options,
+            )? {
+                Some(commits) => commits,
+                None => return Ok(Err(ExitCode(1))),
+            };
+            let message_commit = match message_commits.as_slice() {
+                [message_commit] => message_commit,
+                other => {
+                    writeln!(
+                        effects.get_error_stream(),
+                        "--message-from expects exactly 1 commit, but '{revset}' evaluated to {}.\nAborting.",
+                        other.len(),
+                    )?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            };
+            let message = message_commit.get_message_raw().to_vec();
+            let message = message.into_string_lossy();
+            InitialCommitMessages::Messages(vec![message])
+        }
     };
 
     let edit_message_fn = |message: &str| edit_message(git_run_info, &repo, message);
@@ -285,6 +316,10 @@ pub fn reword(
         force_on_disk: false,
         dry_run: false,
         resolve_merge_conflicts: false,
+        merge_file_favor: None,
+        reset_author: false,
+        timing: false,
+        exec_on_conflict: None,
         check_out_commit_options: CheckOutCommitOptions {
             additional_args: Default::default(),
             force_detach: false,
@@ -443,6 +478,9 @@ fn prepare_messages(
         InitialCommitMessages::FixUp(_) => {
             eyre::bail!("BUG: Fixup should have already been handled!")
         }
+        InitialCommitMessages::MessageFrom(_) => {
+            eyre::bail!("BUG: MessageFrom should have already been handled!")
+        }
         InitialCommitMessages::Messages(ref messages) => {
             let message = messages.clone().join("\n\n");
             let message = message.trim();
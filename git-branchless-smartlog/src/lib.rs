@@ -12,33 +12,46 @@
 )]
 #![allow(clippy::too_many_arguments, clippy::blocks_in_conditions)]
 
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
+use std::path::PathBuf;
 use std::time::SystemTime;
 
+use cursive_core::theme::Effect;
+use cursive_core::utils::markup::StyledString;
 use git_branchless_invoke::CommandContext;
-use git_branchless_opts::{Revset, SmartlogArgs};
+use git_branchless_opts::{ResolveRevsetOptions, Revset, SmartlogArgs};
 use lib::core::config::{
-    Hint, get_hint_enabled, get_hint_string, get_smartlog_default_revset,
+    Hint, SMARTLOG_MAX_COMMITS_CONFIG_KEY, get_hint_enabled, get_hint_string,
+    get_smartlog_default_revset, get_smartlog_max_commits, get_smartlog_show_stashes,
     print_hint_suppression_notice,
 };
 use lib::core::repo_ext::RepoExt;
 use lib::core::rewrite::find_rewrite_target;
 use lib::util::{ExitCode, EyreExitOr};
+use regex::Regex;
+use serde::Serialize;
 use tracing::instrument;
 
-use lib::core::dag::{CommitSet, Dag};
+use lib::core::dag::{CommitSet, Dag, union_all};
 use lib::core::effects::Effects;
-use lib::core::eventlog::{EventLogDb, EventReplayer};
-use lib::core::formatting::Pluralize;
+use lib::core::eventlog::{Event, EventCursor, EventLogDb, EventReplayer};
+use lib::core::formatting::{Pluralize, StyledStringBuilder, set_effect};
 use lib::core::node_descriptors::{
     BranchesDescriptor, CommitMessageDescriptor, CommitOidDescriptor,
-    DifferentialRevisionDescriptor, ObsolescenceExplanationDescriptor, Redactor,
-    RelativeTimeDescriptor,
+    DifferentialRevisionDescriptor, DuplicateCommitDescriptor, ForgeStatusDescriptor,
+    HighlightDescriptor, NodeDescriptor, NodeObject, ObsolescenceExplanationDescriptor, Redactor,
+    RelativeTimeDescriptor, RelativeToDescriptor, TestStatusDescriptor, render_node_descriptors,
+};
+use lib::core::repo_ext::RepoReferencesSnapshot;
+use lib::git::{
+    BranchType, CategorizedReferenceName, GitRunInfo, MaybeZeroOid, NonZeroOid, Repo,
+    ResolvedReferenceInfo,
 };
-use lib::git::{GitRunInfo, Repo};
 
-pub use graph::{SmartlogGraph, make_smartlog_graph};
+use graph::AncestorInfo;
+pub use graph::{SmartlogGraph, add_stash_nodes, make_smartlog_graph};
 pub use render::{SmartlogOptions, render_graph};
 
 use git_branchless_revset::resolve_commits;
@@ -46,15 +59,17 @@ use git_branchless_revset::resolve_commits;
 mod graph {
     use std::collections::HashMap;
 
+    use lib::core::config::{SmartlogChildOrder, get_smartlog_child_order};
     use lib::core::gc::mark_commit_reachable;
+    use lib::core::repo_ext::RepoExt;
     use tracing::instrument;
 
     use lib::core::dag::{CommitSet, CommitVertex, Dag};
     use lib::core::effects::{Effects, OperationType};
     use lib::core::eventlog::{EventCursor, EventReplayer};
     use lib::core::node_descriptors::NodeObject;
+    use lib::git::{CategorizedReferenceName, NonZeroOid, Repo};
     use lib::git::{Commit, Time};
-    use lib::git::{NonZeroOid, Repo};
 
     #[derive(Debug)]
     pub struct AncestorInfo {
@@ -117,12 +132,20 @@ mod graph {
         /// This allows us to indicate a "false head" to the user. Otherwise,
         /// this commit would look like a normal, descendant-less head.
         pub num_omitted_descendants: usize,
+
+        /// If set, this node is the stash entry `stash@{n}` rather than a
+        /// commit in the commit graph. Stash entries are only rendered when
+        /// `branchless.smartlog.showStashes` is set.
+        pub stash_index: Option<usize>,
     }
 
     /// Graph of commits that the user is working on.
     pub struct SmartlogGraph<'repo> {
         /// The nodes in the graph for use in rendering the smartlog.
         pub nodes: HashMap<NonZeroOid, Node<'repo>>,
+
+        /// Don't indicate that the roots of the graph have omitted ancestors.
+        pub omit_root_ancestors: bool,
     }
 
     impl<'repo> SmartlogGraph<'repo> {
@@ -164,12 +187,22 @@ mod graph {
     ) -> eyre::Result<SmartlogGraph<'repo>> {
         let commits_include_main =
             !dag.set_is_empty(&dag.main_branch_commit.intersection(commits))?;
+        let main_branch_oids = dag.commit_set_to_vec(&dag.main_branch_commit)?;
         let mut graph: HashMap<NonZeroOid, Node> = {
             let mut result = HashMap::new();
             for vertex in dag.commit_set_to_vec(commits)? {
                 let vertex = CommitSet::from(vertex);
                 let merge_bases = if commits_include_main {
-                    dag.query_gca_all(dag.main_branch_commit.union(&vertex))?
+                    // Find the merge-bases with each main branch separately,
+                    // so that commits are attached to their own trunk when
+                    // there are multiple main branches.
+                    let mut merge_bases = CommitSet::empty();
+                    for main_branch_oid in main_branch_oids.iter() {
+                        merge_bases = merge_bases.union(
+                            &dag.query_gca_all(CommitSet::from(*main_branch_oid).union(&vertex))?,
+                        );
+                    }
+                    merge_bases
                 } else {
                     dag.query_gca_all(commits.union(&vertex))?
                 };
@@ -195,6 +228,7 @@ mod graph {
                             is_main: dag.is_public_commit(oid)?,
                             is_obsolete: dag.set_contains(&dag.query_obsolete_commits(), oid)?,
                             num_omitted_descendants: 0, // populated below
+                            stash_index: None,
                         },
                     );
                 }
@@ -205,9 +239,19 @@ mod graph {
         let mut immediate_links: Vec<(NonZeroOid, NonZeroOid, bool)> = Vec::new();
         let mut non_immediate_links: Vec<(NonZeroOid, NonZeroOid, bool)> = Vec::new();
 
-        let non_main_node_oids = graph
-            .iter()
-            .filter_map(|(child_oid, node)| if !node.is_main { Some(child_oid) } else { None });
+        // Commits which are public only because of a public tag aren't part of
+        // the main branch's history, so link them to their parents like draft
+        // commits. Likewise, if there are multiple main branches, only their
+        // shared history is rendered as the main line, and each main branch's
+        // own commits are linked to their parents.
+        let main_branch_ancestors =
+            dag.query_ancestors(dag.query_gca_all(dag.main_branch_commit.clone())?)?;
+        let mut non_main_node_oids = Vec::new();
+        for (child_oid, node) in graph.iter() {
+            if !node.is_main || !dag.set_contains(&main_branch_ancestors, *child_oid)? {
+                non_main_node_oids.push(child_oid);
+            }
+        }
 
         let graph_vertices: CommitSet = graph.keys().cloned().collect();
         for child_oid in non_main_node_oids {
@@ -241,12 +285,16 @@ mod graph {
                 // also on the same branch.
 
                 let parent_set = CommitSet::from(excluded_parent_vertex);
-                let merge_base = dag.query_gca_one(dag.main_branch_commit.union(&parent_set))?;
-
-                let path_to_main_branch = match merge_base {
-                    Some(merge_base) => dag.query_range(CommitSet::from(merge_base), parent_set)?,
-                    None => CommitSet::empty(),
-                };
+                let mut path_to_main_branch = CommitSet::empty();
+                for main_branch_oid in main_branch_oids.iter() {
+                    let merge_base =
+                        dag.query_gca_one(CommitSet::from(*main_branch_oid).union(&parent_set))?;
+                    if let Some(merge_base) = merge_base {
+                        path_to_main_branch = path_to_main_branch.union(
+                            &dag.query_range(CommitSet::from(merge_base), parent_set.clone())?,
+                        );
+                    }
+                }
                 let nearest_branch_ancestor =
                     dag.query_heads_ancestors(path_to_main_branch.intersection(&graph_vertices))?;
 
@@ -307,12 +355,20 @@ mod graph {
             node.num_omitted_descendants = dag.set_count(&descendants_not_in_graph)?;
         }
 
-        Ok(SmartlogGraph { nodes: graph })
+        Ok(SmartlogGraph {
+            nodes: graph,
+            omit_root_ancestors: false,
+        })
     }
 
     /// Sort children nodes of the commit graph in a standard order, for determinism
-    /// in output.
-    fn sort_children(graph: &mut SmartlogGraph) {
+    /// in output. The primary sort key is determined by `child_order`.
+    fn sort_children(
+        repo: &Repo,
+        dag: &Dag,
+        graph: &mut SmartlogGraph,
+        child_order: SmartlogChildOrder,
+    ) -> eyre::Result<()> {
         let commit_times: HashMap<NonZeroOid, Option<Time>> = graph
             .nodes
             .iter()
@@ -326,14 +382,79 @@ mod graph {
                 )
             })
             .collect();
-        for node in graph.nodes.values_mut() {
-            node.children.sort_by_key(
-                |ChildInfo {
-                     oid,
-                     is_merge_child,
-                 }| (&commit_times[oid], *is_merge_child, oid.to_string()),
-            );
+
+        match child_order {
+            SmartlogChildOrder::CommitterDate => {
+                for node in graph.nodes.values_mut() {
+                    node.children.sort_by_key(
+                        |ChildInfo {
+                             oid,
+                             is_merge_child,
+                         }| {
+                            (&commit_times[oid], *is_merge_child, oid.to_string())
+                        },
+                    );
+                }
+            }
+
+            SmartlogChildOrder::AlphabeticalBranch => {
+                let branch_names: HashMap<NonZeroOid, String> = repo
+                    .get_branch_oid_to_names()?
+                    .into_iter()
+                    .filter_map(|(oid, reference_names)| {
+                        reference_names
+                            .iter()
+                            .map(|reference_name| {
+                                CategorizedReferenceName::new(reference_name).render_suffix()
+                            })
+                            .min()
+                            .map(|branch_name| (oid, branch_name))
+                    })
+                    .collect();
+                for node in graph.nodes.values_mut() {
+                    node.children.sort_by_key(
+                        |ChildInfo {
+                             oid,
+                             is_merge_child,
+                         }| {
+                            let branch_name = branch_names.get(oid);
+                            (
+                                branch_name.is_none(),
+                                branch_name,
+                                &commit_times[oid],
+                                *is_merge_child,
+                                oid.to_string(),
+                            )
+                        },
+                    );
+                }
+            }
+
+            SmartlogChildOrder::Topological => {
+                let graph_vertices: CommitSet = graph.nodes.keys().copied().collect();
+                let topological_indexes: HashMap<NonZeroOid, usize> = dag
+                    .sort(&graph_vertices)?
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, oid)| (oid, index))
+                    .collect();
+                for node in graph.nodes.values_mut() {
+                    node.children.sort_by_key(
+                        |ChildInfo {
+                             oid,
+                             is_merge_child,
+                         }| {
+                            (
+                                topological_indexes.get(oid),
+                                *is_merge_child,
+                                oid.to_string(),
+                            )
+                        },
+                    );
+                }
+            }
         }
+        Ok(())
     }
 
     /// Construct the smartlog graph for the repo.
@@ -367,19 +488,81 @@ mod graph {
 
             build_graph(&effects, repo, dag, &commits)?
         };
-        sort_children(&mut graph);
+        let child_order = get_smartlog_child_order(repo)?;
+        sort_children(repo, dag, &mut graph, child_order)?;
         Ok(graph)
     }
+
+    /// Attach each entry in the stash list to the commit it was created on,
+    /// if that commit is in the graph. Stash commits aren't part of the DAG,
+    /// so this should be done only once the graph is otherwise complete.
+    #[instrument]
+    pub fn add_stash_nodes<'repo>(
+        repo: &'repo Repo,
+        graph: &mut SmartlogGraph<'repo>,
+    ) -> eyre::Result<()> {
+        let mut num_stashes_by_base: HashMap<NonZeroOid, usize> = HashMap::new();
+        for (stash_index, stash_oid) in repo.get_stash_oids()?.into_iter().enumerate() {
+            if graph.nodes.contains_key(&stash_oid) {
+                continue;
+            }
+            let stash_commit = match repo.find_commit(stash_oid)? {
+                Some(stash_commit) => stash_commit,
+                None => continue,
+            };
+            // The first parent of a stash commit is the commit that was
+            // checked out when the stash was created.
+            let base_oid = match stash_commit.get_parent_oids().first() {
+                Some(base_oid) => *base_oid,
+                None => continue,
+            };
+            let base_node = match graph.nodes.get_mut(&base_oid) {
+                Some(base_node) => base_node,
+                None => continue,
+            };
+
+            // Render stashes before the base commit's other children, so that
+            // they branch off rather than appearing to continue a stack.
+            let num_stashes = num_stashes_by_base.entry(base_oid).or_default();
+            base_node.children.insert(
+                *num_stashes,
+                ChildInfo {
+                    oid: stash_oid,
+                    is_merge_child: false,
+                },
+            );
+            *num_stashes += 1;
+            graph.nodes.insert(
+                stash_oid,
+                Node {
+                    object: NodeObject::Commit {
+                        commit: stash_commit,
+                    },
+                    parents: vec![base_oid],
+                    children: Vec::new(),
+                    ancestor_info: None,
+                    descendants: Vec::new(),
+                    is_main: false,
+                    is_obsolete: false,
+                    num_omitted_descendants: 0,
+                    stash_index: Some(stash_index),
+                },
+            );
+        }
+        Ok(())
+    }
 }
 
 mod render {
     use std::cmp::Ordering;
     use std::collections::HashSet;
+    use std::path::PathBuf;
 
     use cursive_core::theme::{BaseColor, Effect};
     use cursive_core::utils::markup::StyledString;
     use tracing::instrument;
 
+    use lib::core::config::get_smartlog_obsolete_marker;
     use lib::core::dag::{CommitSet, Dag};
     use lib::core::effects::Effects;
     use lib::core::formatting::{Glyphs, StyledStringBuilder};
@@ -450,6 +633,7 @@ mod render {
     #[instrument(skip(commit_descriptors, graph))]
     fn get_child_output(
         glyphs: &Glyphs,
+        obsolete_marker: &str,
         graph: &SmartlogGraph,
         root_oids: &[NonZeroOid],
         commit_descriptors: &mut [&mut dyn NodeDescriptor],
@@ -512,17 +696,25 @@ mod render {
             let cursor = match (current_node.is_main, current_node.is_obsolete, is_head) {
                 (false, false, false) => glyphs.commit_visible,
                 (false, false, true) => glyphs.commit_visible_head,
-                (false, true, false) => glyphs.commit_obsolete,
+                (false, true, false) => obsolete_marker,
                 (false, true, true) => glyphs.commit_obsolete_head,
                 (true, false, false) => glyphs.commit_main,
                 (true, false, true) => glyphs.commit_main_head,
                 (true, true, false) => glyphs.commit_main_obsolete,
                 (true, true, true) => glyphs.commit_main_obsolete_head,
             };
+            let stash_label = match current_node.stash_index {
+                Some(stash_index) => StyledString::styled(
+                    format!("(stash@{{{stash_index}}}) "),
+                    BaseColor::Magenta.light(),
+                ),
+                None => StyledString::new(),
+            };
             let text = render_node_descriptors(glyphs, &current_node.object, commit_descriptors)?;
             let first_line = StyledStringBuilder::new()
                 .append_plain(cursor)
                 .append_plain(" ")
+                .append(stash_label)
                 .append(text)
                 .build();
             if is_head {
@@ -618,6 +810,7 @@ mod render {
 
             let child_output = get_child_output(
                 glyphs,
+                obsolete_marker,
                 graph,
                 root_oids,
                 commit_descriptors,
@@ -650,6 +843,7 @@ mod render {
     #[instrument(skip(commit_descriptors, graph))]
     fn get_output(
         glyphs: &Glyphs,
+        obsolete_marker: &str,
         dag: &Dag,
         graph: &SmartlogGraph,
         commit_descriptors: &mut [&mut dyn NodeDescriptor],
@@ -670,7 +864,11 @@ mod render {
         };
 
         for (root_idx, root_oid) in root_oids.iter().enumerate() {
-            if !dag.set_is_empty(&dag.query_parents(CommitSet::from(*root_oid))?)? {
+            if graph.omit_root_ancestors {
+                if root_idx > 0 {
+                    lines.push(StyledString::new());
+                }
+            } else if !dag.set_is_empty(&dag.query_parents(CommitSet::from(*root_oid))?)? {
                 let line = if root_idx > 0 && has_real_parent(*root_oid, root_oids[root_idx - 1])? {
                     StyledString::plain(glyphs.line.to_owned())
                 } else {
@@ -684,7 +882,7 @@ mod render {
             }
 
             let last_child_line_char = {
-                if root_idx == root_oids.len() - 1 {
+                if root_idx == root_oids.len() - 1 || graph.omit_root_ancestors {
                     None
                 } else if has_real_parent(root_oids[root_idx + 1], *root_oid)? {
                     Some(glyphs.line)
@@ -695,6 +893,7 @@ mod render {
 
             let child_output = get_child_output(
                 glyphs,
+                obsolete_marker,
                 graph,
                 root_oids,
                 commit_descriptors,
@@ -719,8 +918,11 @@ mod render {
         commit_descriptors: &mut [&mut dyn NodeDescriptor],
     ) -> eyre::Result<Vec<StyledString>> {
         let root_oids = split_commit_graph_by_roots(repo, dag, graph);
+        let glyphs = effects.get_glyphs();
+        let obsolete_marker = get_smartlog_obsolete_marker(repo)?;
         let lines = get_output(
-            effects.get_glyphs(),
+            glyphs,
+            obsolete_marker.as_deref().unwrap_or(glyphs.commit_obsolete),
             dag,
             graph,
             commit_descriptors,
@@ -752,7 +954,357 @@ mod render {
 
         /// Normally HEAD and the main branch are included. Set this to exclude them.
         pub exact: bool,
+
+        /// If set, include up to this many generations of ancestors of the
+        /// draft commits being rendered.
+        pub ancestors: Option<usize>,
+
+        /// Commits which should not be rendered, along with their descendants.
+        pub exclude: Vec<Revset>,
+
+        /// If set, mark the rendered commits which are ancestors of these
+        /// commits.
+        pub relative_to: Option<Revset>,
+
+        /// If set, mark the rendered commits whose summary matches this
+        /// regular expression.
+        pub highlight: Option<String>,
+
+        /// If set, render as if this commit were `HEAD`.
+        pub head: Option<Revset>,
+
+        /// Commits which should be rendered regardless of whether they're
+        /// visible, along with their ancestors back to the main branch.
+        pub context: Vec<Revset>,
+
+        /// If set, only render merge commits.
+        pub merges_only: bool,
+
+        /// If set, don't render merge commits.
+        pub no_merges: bool,
+
+        /// If non-empty, only render draft commits which modified files under
+        /// one of these paths.
+        pub paths: Vec<PathBuf>,
+
+        /// If set, report any anomalies found in the rendered commits.
+        pub verify: bool,
+
+        /// If set, only render the draft commits and `HEAD`, without any of
+        /// the ancestors which would normally connect them.
+        pub no_context: bool,
+
+        /// If set, print the rendered commits as newline-delimited JSON
+        /// instead of drawing the graph.
+        pub json_stream: bool,
+    }
+}
+
+/// Get the commits in the given set which have more than one parent.
+fn get_merge_commits(repo: &Repo, dag: &Dag, commits: &CommitSet) -> eyre::Result<CommitSet> {
+    let mut merge_commits = Vec::new();
+    for oid in dag.commit_set_to_vec(commits)? {
+        if repo.find_commit_or_fail(oid)?.get_parent_count() > 1 {
+            merge_commits.push(oid);
+        }
+    }
+    Ok(merge_commits.into_iter().collect())
+}
+
+/// Get the commits in the given set which modified a file under one of the
+/// given paths.
+fn get_commits_touching_paths(
+    repo: &Repo,
+    dag: &Dag,
+    commits: &CommitSet,
+    paths: &[PathBuf],
+) -> eyre::Result<CommitSet> {
+    let mut result = Vec::new();
+    for oid in dag.commit_set_to_vec(commits)? {
+        // Diff each commit only once, regardless of how many paths there are.
+        let commit = repo.find_commit_or_fail(oid)?;
+        let touched_paths = repo.get_paths_touched_by_commit(&commit)?;
+        if touched_paths
+            .iter()
+            .any(|touched_path| paths.iter().any(|path| touched_path.starts_with(path)))
+        {
+            result.push(oid);
+        }
+    }
+    Ok(result.into_iter().collect())
+}
+
+/// Once merge commits have been removed from the graph, a descendant of a merge
+/// commit may be linked to the nearest rendered ancestor on each side of the
+/// merge. Keep only the link along its first-parent lineage.
+fn retain_first_parent_links(
+    repo: &Repo,
+    dag: &Dag,
+    graph: &mut SmartlogGraph,
+) -> eyre::Result<()> {
+    let mut ancestors_by_descendant: HashMap<NonZeroOid, Vec<NonZeroOid>> = HashMap::new();
+    for (ancestor_oid, node) in graph.nodes.iter() {
+        for descendant in node.descendants.iter() {
+            ancestors_by_descendant
+                .entry(descendant.oid)
+                .or_default()
+                .push(*ancestor_oid);
+        }
+    }
+
+    for (descendant_oid, ancestor_oids) in ancestors_by_descendant {
+        if ancestor_oids.len() < 2 {
+            continue;
+        }
+
+        let mut first_parent_ancestor_oid = None;
+        let mut current_oid = descendant_oid;
+        while let Some(parent_oid) = repo
+            .find_commit_or_fail(current_oid)?
+            .get_parent_oids()
+            .first()
+            .copied()
+        {
+            if ancestor_oids.contains(&parent_oid) {
+                first_parent_ancestor_oid = Some(parent_oid);
+                break;
+            }
+            if graph.nodes.contains_key(&parent_oid) {
+                break;
+            }
+            current_oid = parent_oid;
+        }
+        let first_parent_ancestor_oid = match first_parent_ancestor_oid {
+            Some(first_parent_ancestor_oid) => first_parent_ancestor_oid,
+            None => continue,
+        };
+
+        for ancestor_oid in ancestor_oids {
+            if ancestor_oid != first_parent_ancestor_oid {
+                if let Some(ancestor_node) = graph.nodes.get_mut(&ancestor_oid) {
+                    ancestor_node
+                        .descendants
+                        .retain(|descendant| descendant.oid != descendant_oid);
+                }
+            }
+        }
+        let distance = dag.set_count(
+            &dag.query_range(
+                CommitSet::from(first_parent_ancestor_oid),
+                CommitSet::from(descendant_oid),
+            )?
+            .difference(
+                &[first_parent_ancestor_oid, descendant_oid]
+                    .into_iter()
+                    .collect(),
+            ),
+        )?;
+        if let Some(descendant_node) = graph.nodes.get_mut(&descendant_oid) {
+            descendant_node.ancestor_info = Some(AncestorInfo {
+                oid: first_parent_ancestor_oid,
+                distance,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Remove the nodes which aren't draft commits or `HEAD`, such as the main
+/// branch commits which the draft commits are based on, along with any links
+/// to them.
+fn remove_context_nodes(
+    dag: &Dag,
+    graph: &mut SmartlogGraph,
+    head_oid: Option<NonZeroOid>,
+) -> eyre::Result<()> {
+    let draft_commits = dag.query_draft_commits()?;
+    let mut context_oids = HashSet::new();
+    for oid in graph.nodes.keys() {
+        if Some(*oid) != head_oid && !dag.set_contains(draft_commits, *oid)? {
+            context_oids.insert(*oid);
+        }
+    }
+
+    graph.nodes.retain(|oid, _node| !context_oids.contains(oid));
+    for node in graph.nodes.values_mut() {
+        node.parents.retain(|oid| !context_oids.contains(oid));
+        node.children
+            .retain(|child| !context_oids.contains(&child.oid));
+        node.descendants
+            .retain(|descendant| !context_oids.contains(&descendant.oid));
+        if let Some(ancestor_info) = &node.ancestor_info {
+            if context_oids.contains(&ancestor_info.oid) {
+                node.ancestor_info = None;
+            }
+        }
+    }
+    graph.omit_root_ancestors = true;
+    Ok(())
+}
+
+/// Find structural problems with the rendered commits which indicate latent
+/// issues in the repository or event log:
+///
+/// - commits which were rewritten into commits which no longer exist,
+/// - commits whose rewrite history loops back on itself (which the event
+///   replayer has to break arbitrarily), and
+/// - branches which point to hidden commits.
+fn find_graph_anomalies(
+    effects: &Effects,
+    repo: &Repo,
+    dag: &Dag,
+    event_replayer: &EventReplayer,
+    event_cursor: EventCursor,
+    graph: &SmartlogGraph,
+    references_snapshot: &RepoReferencesSnapshot,
+) -> eyre::Result<Vec<StyledString>> {
+    let glyphs = effects.get_glyphs();
+    let mut graph_oids: Vec<NonZeroOid> = graph.nodes.keys().copied().collect();
+    graph_oids.sort();
+
+    let rewrites: HashMap<NonZeroOid, NonZeroOid> = event_replayer
+        .get_events_between_cursors(event_replayer.make_cursor(0), event_cursor)
+        .iter()
+        .filter_map(|event| match event {
+            Event::RewriteEvent {
+                timestamp: _,
+                event_tx_id: _,
+                old_commit_oid: MaybeZeroOid::NonZero(old_commit_oid),
+                new_commit_oid: MaybeZeroOid::NonZero(new_commit_oid),
+            } if old_commit_oid != new_commit_oid => Some((*old_commit_oid, *new_commit_oid)),
+            _ => None,
+        })
+        .collect();
+
+    let mut anomalies = Vec::new();
+    for oid in graph_oids.iter().copied() {
+        if let Some(MaybeZeroOid::NonZero(rewritten_oid)) =
+            find_rewrite_target(event_replayer, event_cursor, oid)
+        {
+            if repo.find_commit(rewritten_oid)?.is_none() {
+                anomalies.push(
+                    StyledStringBuilder::new()
+                        .append_plain("Commit ")
+                        .append(repo.friendly_describe_commit_from_oid(glyphs, oid)?)
+                        .append_plain(format!(
+                            " was rewritten as {rewritten_oid}, which does not exist"
+                        ))
+                        .build(),
+                );
+            }
+        }
+
+        let mut visited_oids = HashSet::new();
+        let mut current_oid = oid;
+        while let Some(next_oid) = rewrites.get(&current_oid) {
+            if *next_oid == oid {
+                anomalies.push(
+                    StyledStringBuilder::new()
+                        .append_plain("Commit ")
+                        .append(repo.friendly_describe_commit_from_oid(glyphs, oid)?)
+                        .append_plain(" has a cyclic rewrite history")
+                        .build(),
+                );
+                break;
+            }
+            if !visited_oids.insert(*next_oid) {
+                break;
+            }
+            current_oid = *next_oid;
+        }
+    }
+
+    let hidden_commits = dag.query_obsolete_commits();
+    for oid in graph_oids {
+        if !dag.set_contains(&hidden_commits, oid)? {
+            continue;
+        }
+        let mut branch_names: Vec<String> = references_snapshot
+            .branch_oid_to_names
+            .get(&oid)
+            .into_iter()
+            .flatten()
+            .map(|reference_name| CategorizedReferenceName::new(reference_name).render_suffix())
+            .collect();
+        branch_names.sort();
+        for branch_name in branch_names {
+            anomalies.push(
+                StyledStringBuilder::new()
+                    .append_plain(format!("Branch {branch_name} points to hidden commit "))
+                    .append(repo.friendly_describe_commit_from_oid(glyphs, oid)?)
+                    .build(),
+            );
+        }
     }
+
+    Ok(anomalies)
+}
+
+/// A commit in the smartlog graph, as printed by `--json-stream`.
+#[derive(Serialize)]
+struct JsonCommit {
+    oid: String,
+    parents: Vec<String>,
+    ancestor: Option<String>,
+    summary: Option<String>,
+    branches: Vec<String>,
+    is_head: bool,
+    is_main: bool,
+    is_obsolete: bool,
+}
+
+/// Write each commit in the graph as a JSON object on its own line, parents
+/// before children, so that consumers can render the graph incrementally.
+fn write_json_stream(
+    effects: &Effects,
+    dag: &Dag,
+    graph: &SmartlogGraph,
+    references_snapshot: &RepoReferencesSnapshot,
+) -> eyre::Result<()> {
+    let graph_commits: CommitSet = graph.nodes.keys().copied().collect();
+    let mut previous_main_oid = None;
+    for oid in dag.sort(&graph_commits)? {
+        let node = &graph.nodes[&oid];
+        // Main branch commits aren't linked to each other in the graph, since
+        // the commits between them are elided, so link each to the previous
+        // one instead.
+        let ancestor_oid = match &node.ancestor_info {
+            Some(ancestor_info) => Some(ancestor_info.oid),
+            None if node.is_main && node.parents.is_empty() => previous_main_oid,
+            None => None,
+        };
+        if node.is_main {
+            previous_main_oid = Some(oid);
+        }
+        let summary = match &node.object {
+            NodeObject::Commit { commit } => Some(commit.get_summary()?.to_string()),
+            NodeObject::GarbageCollected { oid: _ } => None,
+        };
+        let mut branches: Vec<String> = references_snapshot
+            .branch_oid_to_names
+            .get(&oid)
+            .into_iter()
+            .flatten()
+            .map(|name| CategorizedReferenceName::new(name).render_suffix())
+            .collect();
+        branches.sort_unstable();
+        let json_commit = JsonCommit {
+            oid: oid.to_string(),
+            parents: node.parents.iter().map(|oid| oid.to_string()).collect(),
+            ancestor: ancestor_oid.map(|oid| oid.to_string()),
+            summary,
+            branches,
+            is_head: references_snapshot.head_oid == Some(oid),
+            is_main: node.is_main,
+            is_obsolete: node.is_obsolete,
+        };
+        writeln!(
+            effects.get_output_stream(),
+            "{}",
+            serde_json::to_string(&json_commit)?
+        )?;
+    }
+    Ok(())
 }
 
 /// Display a nice graph of commits you've recently worked on.
@@ -768,6 +1320,18 @@ pub fn smartlog(
         resolve_revset_options,
         reverse,
         exact,
+        ancestors,
+        exclude,
+        relative_to,
+        highlight,
+        head,
+        context,
+        merges_only,
+        no_merges,
+        paths,
+        verify,
+        no_context,
+        json_stream,
     } = options;
 
     let repo = Repo::from_dir(&git_run_info.working_directory)?;
@@ -790,7 +1354,7 @@ pub fn smartlog(
             }
         }
     };
-    let mut dag = Dag::open_and_sync(
+    let mut dag = Dag::open_and_sync_if_changed(
         effects,
         &repo,
         &event_replayer,
@@ -798,6 +1362,56 @@ pub fn smartlog(
         &references_snapshot,
     )?;
 
+    let (head_info, references_snapshot, mut dag) = match head {
+        None => (head_info, references_snapshot, dag),
+        Some(head) => {
+            let head_oid = match resolve_commits(
+                effects,
+                &repo,
+                &mut dag,
+                std::slice::from_ref(&head),
+                &resolve_revset_options,
+            ) {
+                Ok(commit_sets) => match dag.commit_set_to_vec(&union_all(&commit_sets))?[..] {
+                    [head_oid] => head_oid,
+                    ref commits => {
+                        writeln!(
+                            effects.get_error_stream(),
+                            "--head expects exactly 1 commit, but '{head}' evaluated to {}.",
+                            commits.len()
+                        )?;
+                        return Ok(Err(ExitCode(1)));
+                    }
+                },
+                Err(err) => {
+                    err.describe(effects)?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            };
+            let reference_name = match repo.find_branch(&head.0, BranchType::Local)? {
+                Some(branch) => Some(branch.get_reference_name()?),
+                None => None,
+            };
+            let head_info = ResolvedReferenceInfo {
+                oid: Some(head_oid),
+                reference_name,
+            };
+            let references_snapshot = RepoReferencesSnapshot {
+                head_oid: Some(head_oid),
+                ..references_snapshot
+            };
+            // The new `HEAD` commit was already synced while resolving it.
+            let dag = Dag::open_without_syncing(
+                effects,
+                &repo,
+                &event_replayer,
+                event_cursor,
+                &references_snapshot,
+            )?;
+            (head_info, references_snapshot, dag)
+        }
+    };
+
     let revset = match revset {
         Some(revset) => revset,
         None => Revset(get_smartlog_default_revset(&repo)?),
@@ -813,8 +1427,104 @@ pub fn smartlog(
                 return Ok(Err(ExitCode(1)));
             }
         };
+    let commits = match ancestors {
+        Some(ancestors) => {
+            let mut commits = commits;
+            let mut frontier = commits.intersection(dag.query_draft_commits()?);
+            for _ in 0..ancestors {
+                let parents = dag.filter_visible_commits(dag.query_parents(frontier)?)?;
+                frontier = parents
+                    .difference(&commits)
+                    .intersection(dag.query_draft_commits()?);
+                commits = commits.union(&parents);
+            }
+            commits
+        }
+        None => commits,
+    };
+    let commits = if exclude.is_empty() {
+        commits
+    } else {
+        let excluded_commits =
+            match resolve_commits(effects, &repo, &mut dag, &exclude, &resolve_revset_options) {
+                Ok(commit_sets) => union_all(&commit_sets),
+                Err(err) => {
+                    err.describe(effects)?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            };
+        commits.difference(&dag.query_descendants(excluded_commits)?)
+    };
+    let commits = if merges_only || no_merges {
+        let merge_commits = get_merge_commits(&repo, &dag, &commits)?;
+        if merges_only {
+            merge_commits
+        } else {
+            commits.difference(&merge_commits)
+        }
+    } else {
+        commits
+    };
+    let commits = if paths.is_empty() {
+        commits
+    } else {
+        let draft_commits = commits.intersection(dag.query_draft_commits()?);
+        let untouched_commits = draft_commits.difference(&get_commits_touching_paths(
+            &repo,
+            &dag,
+            &draft_commits,
+            &paths,
+        )?);
+        commits.difference(&untouched_commits)
+    };
+    let (commits, truncated_from) = match get_smartlog_max_commits(&repo)? {
+        Some(max_commits) => {
+            let num_commits = dag.set_count(&commits)?;
+            if num_commits > max_commits {
+                let mut commits = dag
+                    .commit_set_to_vec(&commits)?
+                    .into_iter()
+                    .map(|oid| repo.find_commit_or_fail(oid))
+                    .collect::<Result<Vec<_>, _>>()?;
+                commits.sort_by_key(|commit| {
+                    Reverse((commit.get_committer().get_time(), commit.get_oid()))
+                });
+                let commits: CommitSet = commits
+                    .into_iter()
+                    .take(max_commits)
+                    .map(|commit| commit.get_oid())
+                    .collect();
+                (commits, Some(num_commits))
+            } else {
+                (commits, None)
+            }
+        }
+        None => (commits, None),
+    };
+    let commits = if context.is_empty() {
+        commits
+    } else {
+        let context_commits = match resolve_commits(
+            effects,
+            &repo,
+            &mut dag,
+            &context,
+            &ResolveRevsetOptions {
+                show_hidden_commits: true,
+            },
+        ) {
+            Ok(commit_sets) => union_all(&commit_sets),
+            Err(err) => {
+                err.describe(effects)?;
+                return Ok(Err(ExitCode(1)));
+            }
+        };
+        // Include the ancestors of the context commits which aren't on the
+        // main branch, so that they're connected to the rest of the graph.
+        commits.union(&dag.query_only(context_commits, dag.main_branch_commit.clone())?)
+    };
 
-    let graph = make_smartlog_graph(
+    let mut graph = make_smartlog_graph(
         effects,
         &repo,
         &dag,
@@ -823,6 +1533,59 @@ pub fn smartlog(
         &commits,
         exact,
     )?;
+    if no_merges {
+        retain_first_parent_links(&repo, &dag, &mut graph)?;
+    }
+    if no_context {
+        remove_context_nodes(&dag, &mut graph, references_snapshot.head_oid)?;
+    }
+
+    if json_stream {
+        write_json_stream(effects, &dag, &graph, &references_snapshot)?;
+        return Ok(Ok(()));
+    }
+
+    let highlight_pattern = match highlight {
+        Some(highlight) => match Regex::new(&highlight) {
+            Ok(pattern) => Some(pattern),
+            Err(err) => {
+                writeln!(
+                    effects.get_error_stream(),
+                    "Invalid highlight pattern {highlight:?}: {err}"
+                )?;
+                return Ok(Err(ExitCode(1)));
+            }
+        },
+        None => None,
+    };
+
+    let relative_to_ancestor_oids = match relative_to {
+        Some(relative_to) => {
+            let relative_to_commits = match resolve_commits(
+                effects,
+                &repo,
+                &mut dag,
+                &[relative_to],
+                &resolve_revset_options,
+            ) {
+                Ok(commit_sets) => union_all(&commit_sets),
+                Err(err) => {
+                    err.describe(effects)?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            };
+            let graph_commits: CommitSet = graph.nodes.keys().copied().collect();
+            let ancestors = dag
+                .query_ancestors(relative_to_commits)?
+                .intersection(&graph_commits);
+            dag.commit_set_to_vec(&ancestors)?.into_iter().collect()
+        }
+        None => HashSet::new(),
+    };
+
+    if get_smartlog_show_stashes(&repo)? {
+        add_stash_nodes(&repo, &mut graph)?;
+    }
 
     let mut lines = render_graph(
         &effects.reverse_order(reverse),
@@ -831,12 +1594,22 @@ pub fn smartlog(
         &graph,
         references_snapshot.head_oid,
         &mut [
+            &mut RelativeToDescriptor::new(relative_to_ancestor_oids)?,
+            &mut HighlightDescriptor::new(highlight_pattern)?,
             &mut CommitOidDescriptor::new(true)?,
             &mut RelativeTimeDescriptor::new(&repo, SystemTime::now())?,
             &mut ObsolescenceExplanationDescriptor::new(
+                &repo,
                 &event_replayer,
                 event_replayer.make_default_cursor(),
             )?,
+            &mut DuplicateCommitDescriptor::new(
+                effects,
+                &repo,
+                &dag,
+                &graph.nodes.keys().copied().collect(),
+            )?,
+            &mut TestStatusDescriptor::new(&repo)?,
             &mut BranchesDescriptor::new(
                 &repo,
                 &head_info,
@@ -844,6 +1617,7 @@ pub fn smartlog(
                 &Redactor::Disabled,
             )?,
             &mut DifferentialRevisionDescriptor::new(&repo, &Redactor::Disabled)?,
+            &mut ForgeStatusDescriptor::new(&repo, &references_snapshot, &Redactor::Disabled)?,
             &mut CommitMessageDescriptor::new(&Redactor::Disabled)?,
         ],
     )?
@@ -860,6 +1634,50 @@ pub fn smartlog(
         )?;
     }
 
+    if let Some(num_commits) = truncated_from {
+        writeln!(
+            effects.get_output_stream(),
+            "Showing only the {} most recent of {num_commits} commits, as configured by {SMARTLOG_MAX_COMMITS_CONFIG_KEY}.",
+            dag.set_count(&commits)?,
+        )?;
+        writeln!(
+            effects.get_output_stream(),
+            "To narrow the smartlog instead, pass a revset, such as: git smartlog 'stack()'"
+        )?;
+    }
+
+    if verify {
+        let anomalies = find_graph_anomalies(
+            effects,
+            &repo,
+            &dag,
+            &event_replayer,
+            event_cursor,
+            &graph,
+            &references_snapshot,
+        )?;
+        if anomalies.is_empty() {
+            writeln!(effects.get_output_stream(), "No anomalies found.")?;
+        } else {
+            writeln!(
+                effects.get_output_stream(),
+                "Found {}:",
+                Pluralize {
+                    determiner: None,
+                    amount: anomalies.len(),
+                    unit: ("anomaly", "anomalies"),
+                },
+            )?;
+            for anomaly in anomalies {
+                writeln!(
+                    effects.get_output_stream(),
+                    "- {}",
+                    effects.get_glyphs().render(anomaly)?
+                )?;
+            }
+        }
+    }
+
     if !resolve_revset_options.show_hidden_commits
         && get_hint_enabled(&repo, Hint::SmartlogFixAbandoned)?
     {
@@ -902,6 +1720,84 @@ pub fn smartlog(
     Ok(Ok(()))
 }
 
+/// Print the current stack as a flat list of commits, newest first, without
+/// rendering any graph connectors. Fails if the stack is not linear.
+#[instrument]
+pub fn log(effects: &Effects, git_run_info: &GitRunInfo) -> EyreExitOr<()> {
+    let repo = Repo::from_dir(&git_run_info.working_directory)?;
+    let head_info = repo.get_head_info()?;
+    let references_snapshot = repo.get_references_snapshot()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+    let event_replayer = EventReplayer::from_event_log_db(effects, &repo, &event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    let dag = Dag::open_and_sync_if_changed(
+        effects,
+        &repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+
+    let stack = dag.query_stack_commits(dag.head_commit.clone())?;
+    let commits = dag
+        .sort(&stack)?
+        .into_iter()
+        .map(|oid| repo.find_commit_or_fail(oid))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // `sort` returns the commits in topological order, parents first, so the
+    // stack is linear exactly when each commit has a single parent, which is
+    // the commit immediately preceding it (except for the root of the stack).
+    let is_linear = commits.iter().enumerate().all(|(i, commit)| {
+        let parent_oids = commit.get_parent_oids();
+        match i.checked_sub(1) {
+            None => parent_oids.len() == 1,
+            Some(prev_index) => parent_oids == [commits[prev_index].get_oid()],
+        }
+    });
+    if !is_linear {
+        writeln!(
+            effects.get_error_stream(),
+            "The current stack is not linear (it contains merges or forks), so it cannot be displayed as a list."
+        )?;
+        writeln!(
+            effects.get_error_stream(),
+            "Run `git smartlog 'stack()'` to view it as a graph instead."
+        )?;
+        return Ok(Err(ExitCode(1)));
+    }
+
+    let glyphs = effects.get_glyphs();
+    let mut commit_descriptors: [&mut dyn NodeDescriptor; 9] = [
+        &mut CommitOidDescriptor::new(true)?,
+        &mut RelativeTimeDescriptor::new(&repo, SystemTime::now())?,
+        &mut ObsolescenceExplanationDescriptor::new(&repo, &event_replayer, event_cursor)?,
+        &mut DuplicateCommitDescriptor::new(effects, &repo, &dag, &stack)?,
+        &mut TestStatusDescriptor::new(&repo)?,
+        &mut BranchesDescriptor::new(&repo, &head_info, &references_snapshot, &Redactor::Disabled)?,
+        &mut DifferentialRevisionDescriptor::new(&repo, &Redactor::Disabled)?,
+        &mut ForgeStatusDescriptor::new(&repo, &references_snapshot, &Redactor::Disabled)?,
+        &mut CommitMessageDescriptor::new(&Redactor::Disabled)?,
+    ];
+    for commit in commits.into_iter().rev() {
+        let is_head = Some(commit.get_oid()) == references_snapshot.head_oid;
+        let line = render_node_descriptors(
+            glyphs,
+            &NodeObject::Commit { commit },
+            &mut commit_descriptors,
+        )?;
+        let line = if is_head {
+            set_effect(line, Effect::Bold)
+        } else {
+            line
+        };
+        writeln!(effects.get_output_stream(), "{}", glyphs.render(line)?)?;
+    }
+
+    Ok(Ok(()))
+}
+
 /// `smartlog` command.
 #[instrument]
 pub fn command_main(ctx: CommandContext, args: SmartlogArgs) -> EyreExitOr<()> {
@@ -915,6 +1811,18 @@ pub fn command_main(ctx: CommandContext, args: SmartlogArgs) -> EyreExitOr<()> {
         resolve_revset_options,
         reverse,
         exact,
+        ancestors,
+        exclude,
+        relative_to,
+        highlight,
+        head,
+        context,
+        merges_only,
+        no_merges,
+        paths,
+        verify,
+        no_context,
+        json_stream,
     } = args;
 
     smartlog(
@@ -926,6 +1834,18 @@ pub fn command_main(ctx: CommandContext, args: SmartlogArgs) -> EyreExitOr<()> {
             resolve_revset_options,
             reverse,
             exact,
+            ancestors,
+            exclude,
+            relative_to,
+            highlight,
+            head,
+            context,
+            merges_only,
+            no_merges,
+            paths,
+            verify,
+            no_context,
+            json_stream,
         },
     )
 }
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use lib::core::forge_status_cache::{ForgeStatus, cache_forge_statuses};
 use lib::testing::{GitRunOptions, extract_hint_command, make_git};
 
 #[test]
@@ -60,6 +63,79 @@ fn test_tree() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_same_date_siblings_order() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    for name in ["test1", "test2", "test3", "test4"] {
+        git.run(&["checkout", "--detach", "master"])?;
+        git.commit_file(name, 1)?;
+    }
+
+    let stdout = git.smartlog()?;
+    insta::assert_snapshot!(stdout, @r###"
+    O f777ecc (master) create initial.txt
+    |\
+    | o 4bb7b5f create test3.txt
+    |\
+    | o 62fc20d create test1.txt
+    |\
+    | o b55002e create test2.txt
+    |
+    @ cd9a7b6 create test4.txt
+    "###);
+    for _ in 0..3 {
+        assert_eq!(git.smartlog()?, stdout);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_show_stashes() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.write_file_txt("test1", "stashed contents 1\n")?;
+    git.run(&["stash", "push", "-m", "first stash"])?;
+    git.commit_file("test2", 2)?;
+    git.write_file_txt("test2", "stashed contents 2\n")?;
+    git.run(&["stash", "push", "-m", "second stash"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        @ 96d1c37 create test2.txt
+        "###);
+    }
+
+    git.run(&["config", "branchless.smartlog.showStashes", "true"])?;
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--verify"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |\
+        | o (stash@{1}) 446133f On (no branch): first stash
+        |
+        @ 96d1c37 create test2.txt
+        |
+        o (stash@{0}) da26be5 On (no branch): second stash
+        No anomalies found.
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_rebase() -> eyre::Result<()> {
     let git = make_git()?;
@@ -401,6 +477,36 @@ fn test_show_rewritten_commit_hash() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_smartlog_obsolete_marker() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "HEAD^"])?;
+    git.run(&["commit", "--amend", "-m", "test1 version 1"])?;
+    git.run(&["config", "branchless.smartlog.obsoleteMarker", "~"])?;
+    git.run(&["config", "branchless.smartlog.rewrittenAsText", "now"])?;
+    git.run(&["config", "branchless.hint.smartlogFixAbandoned", "false"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | @ 407cc43 test1 version 1
+        |
+        ~ 62fc20d (now 407cc439) create test1.txt
+        |
+        o 96d1c37 create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_smartlog_orphaned_root() -> eyre::Result<()> {
     let git = make_git()?;
@@ -812,3 +918,892 @@ fn test_exact() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_smartlog_ancestors() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.commit_file("test4", 4)?;
+    git.commit_file("test5", 5)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--ancestors", "0", "@"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        :
+        # 4 omitted commits
+        :
+        @ f81d55c create test5.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--ancestors", "1", "@"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        :
+        # 3 omitted commits
+        :
+        o 355e173 create test4.txt
+        |
+        @ f81d55c create test5.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_relative_to() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("smartlog", &["--relative-to", &test1_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O * f777ecc (master) create initial.txt
+        |
+        o * 62fc20d create test1.txt
+        |\
+        | o 96d1c37 create test2.txt
+        |
+        @ 4838e49 create test3.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--relative-to", "HEAD"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O * f777ecc (master) create initial.txt
+        |
+        o * 62fc20d create test1.txt
+        |\
+        | o 96d1c37 create test2.txt
+        |
+        @ * 4838e49 create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_highlight() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file_with_contents_and_message("test1", 1, "test1 contents", "fix: parse foo")?;
+    git.commit_file_with_contents_and_message("test2", 2, "test2 contents", "add bar")?;
+    git.commit_file_with_contents_and_message("test3", 3, "test3 contents", "fix: render bar")?;
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--highlight", "bar"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 08508c1 fix: parse foo test1.txt
+        |
+        o ! 3316212 add bar test2.txt
+        |
+        @ ! 3e926fe fix: render bar test3.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--highlight", "^fix: .*bar"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 08508c1 fix: parse foo test1.txt
+        |
+        o 3316212 add bar test2.txt
+        |
+        @ ! 3e926fe fix: render bar test3.txt
+        "###);
+    }
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "smartlog",
+            &["--highlight", "("],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @r###"
+        Invalid highlight pattern "(": regex parse error:
+            (
+            ^
+        error: unclosed group
+        "###);
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_context() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.branchless("hide", &["-r", &test2_oid.to_string()])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("smartlog", &["--context", &test2_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        x 96d1c37 (manually hidden) create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_path() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.run(&["checkout", "-b", "foo"])?;
+    git.write_file_txt("src/foo/test1", "test1")?;
+    git.run(&["add", "."])?;
+    git.run(&["commit", "-m", "touch src/foo"])?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.write_file_txt("src/bar/test3", "test3")?;
+    git.run(&["add", "."])?;
+    git.run(&["commit", "-m", "touch src/bar"])?;
+    git.write_file_txt("src/foo/test4", "test4")?;
+    git.run(&["add", "."])?;
+    git.run(&["commit", "-m", "touch src/foo again"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--path", "src/foo/"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o bb0ec2d touch src/foo
+        | :
+        | # 1 omitted descendant commit
+        :
+        # 1 omitted commit
+        :
+        @ 2b3dc30 touch src/foo again
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("smartlog", &["--path", "src/bar", "--path", "test2.txt"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 8b4b00a touch src/bar
+        | |
+        | @ 2b3dc30 touch src/foo again
+        :
+        # 1 omitted commit
+        :
+        o f63a1ee (foo) create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_head() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["branch", "foo"])?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--head", &test1_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        @ 62fc20d (foo) create test1.txt
+        |
+        o 96d1c37 create test2.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--head", "foo"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        @ 62fc20d (> foo) create test1.txt
+        |
+        o 96d1c37 create test2.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d (foo) create test1.txt
+        |
+        @ 96d1c37 create test2.txt
+        "###);
+    }
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "smartlog",
+            &["--head", "draft()"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"--head expects exactly 1 commit, but 'draft()' evaluated to 2.");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_exclude() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["branch", "foo"])?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test3", 3)?;
+    git.run(&["branch", "bar"])?;
+    git.commit_file("test4", 4)?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | |
+        | o 96d1c37 (foo) create test2.txt
+        |
+        o 98b9119 (bar) create test3.txt
+        |
+        @ 2b633ed create test4.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("smartlog", &["--exclude", &test1_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 98b9119 (bar) create test3.txt
+        |
+        @ 2b633ed create test4.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless(
+            "smartlog",
+            &["--exclude", "foo", "--exclude", "bar", "draft()"],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | :
+        | # 1 omitted descendant commit
+        :
+        # 1 omitted commit
+        :
+        @ 2b633ed create test4.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_merges() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.run(&["checkout", "-b", "test1", "master"])?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "-b", "test2and3", "master"])?;
+    git.commit_file("test2", 2)?;
+    git.run_with_options(
+        &["merge", "test1"],
+        &GitRunOptions {
+            time: 3,
+            ..Default::default()
+        },
+    )?;
+    git.commit_file("test4", 4)?;
+    git.run(&["checkout", "master"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |\
+        | o 62fc20d (test1) create test1.txt
+        | & (merge) 65841b8 Merge branch 'test1' into test2and3
+        |
+        o fe65c1f create test2.txt
+        |
+        | & (merge) 62fc20d (test1) create test1.txt
+        |/
+        o 65841b8 Merge branch 'test1' into test2and3
+        |
+        o 7034367 (test2and3) create test4.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--no-merges"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |\
+        | o 62fc20d (test1) create test1.txt
+        |
+        o fe65c1f create test2.txt
+        :
+        # 1 omitted commit
+        :
+        o 7034367 (test2and3) create test4.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--merges-only"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        :
+        # 2 omitted commits
+        :
+        o 65841b8 Merge branch 'test1' into test2and3
+        :
+        # 1 omitted descendant commit
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_max_commits() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    let test3_oid = git.commit_file("test3", 3)?;
+    git.commit_file("test4", 4)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test5", 5)?;
+
+    git.run(&["config", "branchless.smartlog.maxCommits", "3"])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | # 2 omitted commits
+        | :
+        | o 70deb1e create test3.txt
+        | |
+        | o 355e173 create test4.txt
+        |
+        @ aff9c67 (> master) create test5.txt
+        Showing only the 3 most recent of 5 commits, as configured by branchless.smartlog.maxCommits.
+        To narrow the smartlog instead, pass a revset, such as: git smartlog 'stack()'
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &[&format!("{test3_oid}::")])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | # 2 omitted commits
+        | :
+        | o 70deb1e create test3.txt
+        | |
+        | o 355e173 create test4.txt
+        |
+        @ aff9c67 (> master) create test5.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_detect_duplicates() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+    git.run(&["cherry-pick", &test1_oid.to_string()])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        |
+        o fe65c1f create test2.txt
+        |
+        @ 8a1ddf3 create test1.txt
+        "###);
+    }
+
+    git.run(&["config", "branchless.smartlog.detectDuplicates", "true"])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d (duplicate of 8a1ddf3) create test1.txt
+        |
+        o fe65c1f create test2.txt
+        |
+        @ 8a1ddf3 (duplicate of 62fc20d) create test1.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_child_order() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.run(&["checkout", "-b", "zeta"])?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.run(&["checkout", "-b", "alpha"])?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+    git.run(&["checkout", "-b", "mid"])?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |\
+        | o 62fc20d (zeta) create test1.txt
+        |\
+        | o fe65c1f (mid) create test2.txt
+        |
+        o 98b9119 (alpha) create test3.txt
+        "###);
+    }
+
+    git.run(&[
+        "config",
+        "branchless.smartlog.childOrder",
+        "alphabeticalBranch",
+    ])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |\
+        | o 98b9119 (alpha) create test3.txt
+        |\
+        | o fe65c1f (mid) create test2.txt
+        |
+        o 62fc20d (zeta) create test1.txt
+        "###);
+    }
+
+    git.run(&["config", "branchless.smartlog.childOrder", "topological"])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |\
+        | o 62fc20d (zeta) create test1.txt
+        |\
+        | o 98b9119 (alpha) create test3.txt
+        |
+        o fe65c1f (mid) create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_public_tags() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["tag", "-a", "v1.0", "-m", "Release 1.0"])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("query", &["draft()"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        62fc20d create test1.txt
+        96d1c37 create test2.txt
+        70deb1e create test3.txt
+        "###);
+    }
+
+    git.run(&["config", "branchless.core.publicTags", "v*"])?;
+    {
+        let (stdout, _stderr) = git.branchless("query", &["draft()"])?;
+        insta::assert_snapshot!(stdout, @"70deb1e create test3.txt");
+    }
+    {
+        let (stdout, _stderr) = git.branchless("query", &["public()"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        f777ecc create initial.txt
+        62fc20d create test1.txt
+        96d1c37 create test2.txt
+        "###);
+    }
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        O 62fc20d create test1.txt
+        |
+        O 96d1c37 create test2.txt
+        |
+        @ 70deb1e create test3.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "move",
+            &["-x", "62fc20d", "-d", "master"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        You are trying to rewrite 2 public commits, such as: 96d1c37 create test2.txt
+        It is generally not advised to rewrite public commits, because your
+        collaborators will have difficulty merging your changes.
+        Retry with -f/--force-rewrite to proceed anyways.
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_forge_status() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.run(&["checkout", "-b", "foo"])?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "-b", "bar"])?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let repo = git.get_repo()?;
+        let conn = repo.get_db_conn()?;
+        cache_forge_statuses(
+            &conn,
+            &HashMap::from([
+                (
+                    "foo".to_string(),
+                    ForgeStatus {
+                        identifier: "#123".to_string(),
+                        state: "open".to_string(),
+                    },
+                ),
+                (
+                    "unrelated".to_string(),
+                    ForgeStatus {
+                        identifier: "#124".to_string(),
+                        state: "closed".to_string(),
+                    },
+                ),
+            ]),
+        )?;
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d (foo) [#123 open] create test1.txt
+        |
+        @ 96d1c37 (> bar) create test2.txt
+        "###);
+    }
+
+    git.run(&[
+        "config",
+        "branchless.commitDescriptors.forgeStatus",
+        "false",
+    ])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d (foo) create test1.txt
+        |
+        @ 96d1c37 (> bar) create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_log_linear_stack() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+    git.run(&["branch", "foo"])?;
+    git.commit_file("test3", 3)?;
+    git.commit_file("test4", 4)?;
+    git.run(&["checkout", "HEAD^"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("log", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        355e173 create test4.txt
+        70deb1e create test3.txt
+        96d1c37 (foo) create test2.txt
+        "###);
+    }
+
+    git.commit_file("test5", 5)?;
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "log",
+            &[],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @"");
+        insta::assert_snapshot!(stderr, @r###"
+        The current stack is not linear (it contains merges or forks), so it cannot be displayed as a list.
+        Run `git smartlog 'stack()'` to view it as a graph instead.
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_theme() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "HEAD^"])?;
+
+    git.run(&["config", "branchless.theme", "minimal"])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        * 62fc20d (master) create test1.txt
+        |
+        @ 96d1c37 create test2.txt
+        |
+        * 70deb1e create test3.txt
+        "###);
+    }
+
+    git.run(&["config", "branchless.theme", "fancy"])?;
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "smartlog",
+            &[],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"Unknown theme 'fancy' set in branchless.theme. Valid themes are: default, minimal, highContrast, nerdfont");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_verify() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--verify"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        @ 96d1c37 create test2.txt
+        No anomalies found.
+        "###);
+    }
+
+    git.branchless("hide", &[&test2_oid.to_string()])?;
+    git.run(&["branch", "foo", &test2_oid.to_string()])?;
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--verify"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        % 96d1c37 (manually hidden) (foo) create test2.txt
+        Found 1 anomaly:
+        - Branch foo points to hidden commit 96d1c37 create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_json_stream() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["branch", "foo"])?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--json-stream"])?;
+        for line in stdout.lines() {
+            let value: serde_json::Value = serde_json::from_str(line)?;
+            assert!(value.is_object(), "not a JSON object: {line}");
+        }
+        insta::assert_snapshot!(stdout, @r###"
+        {"oid":"f777ecc9b0db5ed372b2615695191a8a17f79f24","parents":[],"ancestor":null,"summary":"create initial.txt","branches":[],"is_head":false,"is_main":true,"is_obsolete":false}
+        {"oid":"98b9119d16974f372e76cb64a3b77c528fc0b18b","parents":[],"ancestor":"f777ecc9b0db5ed372b2615695191a8a17f79f24","summary":"create test3.txt","branches":["master"],"is_head":true,"is_main":true,"is_obsolete":false}
+        {"oid":"62fc20d2a290daea0d52bdc2ed2ad4be6491010e","parents":["f777ecc9b0db5ed372b2615695191a8a17f79f24"],"ancestor":null,"summary":"create test1.txt","branches":[],"is_head":false,"is_main":false,"is_obsolete":false}
+        {"oid":"96d1c37a3d4363611c49f7e52186e189a04c531f","parents":["62fc20d2a290daea0d52bdc2ed2ad4be6491010e"],"ancestor":null,"summary":"create test2.txt","branches":["foo"],"is_head":false,"is_main":false,"is_obsolete":false}
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_smartlog_no_context() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test4", 4)?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 62fc20d create test1.txt
+        |\
+        | o 96d1c37 create test2.txt
+        | |
+        | o 70deb1e create test3.txt
+        |
+        @ bf0d52a (> master) create test4.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("smartlog", &["--no-context"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        o 96d1c37 create test2.txt
+        |
+        o 70deb1e create test3.txt
+
+        @ bf0d52a (> master) create test4.txt
+        "###);
+    }
+
+    Ok(())
+}
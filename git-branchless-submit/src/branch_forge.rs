@@ -3,7 +3,7 @@ use std::fmt::Write;
 use std::time::SystemTime;
 
 use itertools::Itertools;
-use lib::core::config::get_main_branch_name;
+use lib::core::config::get_main_branch_names;
 use lib::core::dag::{CommitSet, Dag};
 use lib::core::effects::{Effects, OperationType};
 use lib::core::eventlog::EventLogDb;
@@ -38,7 +38,7 @@ impl Forge for BranchForge<'_> {
             branch_name: String,
             remote_name: Option<String>,
         }
-        let main_branch_name = get_main_branch_name(self.repo)?;
+        let main_branch_names = get_main_branch_names(self.repo)?;
         let branch_infos: HashMap<ReferenceName, BranchInfo> = {
             let branch_reference_names = self
                 .dag
@@ -62,7 +62,7 @@ impl Forge for BranchForge<'_> {
                     CategorizedReferenceName::RemoteBranch { .. }
                     | CategorizedReferenceName::OtherRef { .. } => continue,
                 };
-                if branch_name == main_branch_name {
+                if main_branch_names.contains(&branch_name) {
                     continue;
                 }
                 let branch = self
@@ -124,8 +124,7 @@ impl Forge for BranchForge<'_> {
                 Err(exit_code) => {
                     writeln!(
                         self.effects.get_output_stream(),
-                        "Failed to fetch from remote: {}",
-                        remote_name
+                        "Failed to fetch from remote: {remote_name}"
                     )?;
                     return Ok(Err(exit_code));
                 }
@@ -9,12 +9,12 @@ use cursive_core::theme::Effect;
 use cursive_core::utils::markup::StyledString;
 use indexmap::IndexMap;
 use itertools::Itertools;
-use lib::core::config::get_main_branch_name;
 use lib::core::dag::CommitSet;
 use lib::core::dag::Dag;
 use lib::core::effects::Effects;
 use lib::core::effects::OperationType;
 use lib::core::eventlog::EventLogDb;
+use lib::core::forge_status_cache::{ForgeStatus, cache_forge_statuses};
 use lib::core::repo_ext::RepoExt;
 use lib::core::repo_ext::RepoReferencesSnapshot;
 use lib::git::CategorizedReferenceName;
@@ -125,6 +125,7 @@ impl Forge for GithubForge<'_> {
         let effects = self.effects;
         let pull_request_infos =
             try_exit_code!(self.client.query_repo_pull_request_infos(effects)?);
+        self.cache_forge_statuses(&pull_request_infos)?;
         let references_snapshot = self.repo.get_references_snapshot()?;
 
         let mut result = HashMap::new();
@@ -380,6 +381,7 @@ impl Forge for GithubForge<'_> {
 
         let pull_request_infos =
             try_exit_code!(self.client.query_repo_pull_request_infos(effects)?);
+        self.cache_forge_statuses(&pull_request_infos)?;
         let references_snapshot = self.repo.get_references_snapshot()?;
         let mut branch_forge = BranchForge {
             effects,
@@ -515,6 +517,45 @@ impl GithubForge<'_> {
         }
     }
 
+    /// Save the status of each local branch's pull request, so that it can be
+    /// displayed in the smartlog without querying GitHub.
+    #[instrument]
+    fn cache_forge_statuses(
+        &self,
+        pull_request_infos: &HashMap<String, client::PullRequestInfo>,
+    ) -> eyre::Result<()> {
+        let mut forge_statuses = HashMap::new();
+        for branch in self.repo.get_all_local_branches()? {
+            let remote_branch_name =
+                match branch.get_upstream_branch_name_without_push_remote_name()? {
+                    Some(remote_branch_name) => remote_branch_name,
+                    None => continue,
+                };
+            let pull_request_info = match pull_request_infos.get(&remote_branch_name) {
+                Some(pull_request_info) => pull_request_info,
+                None => continue,
+            };
+            let state = if pull_request_info.closed {
+                "closed"
+            } else if pull_request_info.is_draft {
+                "draft"
+            } else {
+                "open"
+            };
+            forge_statuses.insert(
+                branch.get_name()?.to_owned(),
+                ForgeStatus {
+                    identifier: format!("#{}", pull_request_info.number),
+                    state: state.to_owned(),
+                },
+            );
+        }
+
+        let conn = self.repo.get_db_conn()?;
+        cache_forge_statuses(&conn, &forge_statuses)?;
+        Ok(())
+    }
+
     #[instrument]
     fn make_updated_pull_request_info(
         &self,
@@ -666,10 +707,14 @@ impl GithubForge<'_> {
         );
 
         let stack_ancestor_oids = {
-            let main_branch_oid = CommitSet::from(references_snapshot.main_branch_oid);
+            let main_branch_oids: CommitSet = references_snapshot
+                .main_branch_oids
+                .iter()
+                .copied()
+                .collect();
             let stack_ancestor_oids = self
                 .dag
-                .query_only(CommitSet::from(commit_oid), main_branch_oid)?
+                .query_only(CommitSet::from(commit_oid), main_branch_oids)?
                 .difference(&CommitSet::from(commit_oid));
             self.dag.commit_set_to_vec(&stack_ancestor_oids)?
         };
@@ -685,7 +730,7 @@ impl GithubForge<'_> {
         };
         let base_ref_name = match nearest_ancestor_with_pull_request_info {
             Some(info) => info.head_ref_name.clone(),
-            None => get_main_branch_name(self.repo)?,
+            None => self.repo.get_main_branch()?.get_name()?.to_owned(),
         };
 
         Ok(Ok(client::UpdatePullRequestArgs {
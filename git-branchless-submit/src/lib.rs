@@ -277,6 +277,7 @@ fn submit(
         try_exit_code!(ResolvedTestOptions::resolve(
             now,
             effects,
+            git_run_info,
             &dag,
             &repo,
             event_tx_id,
@@ -517,6 +518,55 @@ repository. To submit them, retry this operation with the --create option.",
     Ok(Ok(()))
 }
 
+/// Push the local branches pointing to the commits in `commit_set` to their
+/// remote branches with `--force-with-lease`, in the same way as `git submit`
+/// does for branches which have already been submitted. Branches which aren't
+/// associated with a remote branch, or which are already up-to-date, are
+/// skipped. Returns the names of the branches which were pushed.
+#[instrument]
+pub fn push_branches(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+    dag: &Dag,
+    event_log_db: &EventLogDb,
+    references_snapshot: &RepoReferencesSnapshot,
+    commit_set: CommitSet,
+) -> EyreExitOr<BTreeSet<String>> {
+    let mut forge = BranchForge {
+        effects,
+        git_run_info,
+        repo,
+        dag,
+        event_log_db,
+        references_snapshot,
+    };
+    let statuses = try_exit_code!(forge.query_status(commit_set)?);
+    let commits_to_update: HashMap<NonZeroOid, CommitStatus> = statuses
+        .into_iter()
+        .filter(|(_commit_oid, commit_status)| {
+            matches!(commit_status.submit_status, SubmitStatus::NeedsUpdate)
+        })
+        .collect();
+    let updated_branch_names: BTreeSet<String> = commits_to_update
+        .values()
+        .flat_map(|commit_status| commit_status.local_commit_name.clone())
+        .collect();
+    if !commits_to_update.is_empty() {
+        // The branch forge doesn't consult the submit options when updating
+        // branches.
+        let submit_options = SubmitOptions {
+            create: false,
+            draft: false,
+            execution_strategy: TestExecutionStrategy::WorkingCopy,
+            num_jobs: 1,
+            message: None,
+        };
+        try_exit_code!(forge.update(commits_to_update, &submit_options)?);
+    }
+    Ok(Ok(updated_branch_names))
+}
+
 #[instrument]
 fn select_forge<'a>(
     effects: &'a Effects,
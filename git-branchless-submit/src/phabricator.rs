@@ -347,6 +347,8 @@ impl Forge for PhabricatorForge<'_> {
             force_rewrite_public_commits: false,
             dump_rebase_constraints: false,
             dump_rebase_plan: false,
+            explain: false,
+            keep_empty: false,
             detect_duplicate_commits_via_patch_id: false,
         };
         let execute_options = ExecuteRebasePlanOptions {
@@ -357,6 +359,10 @@ impl Forge for PhabricatorForge<'_> {
             force_on_disk: false,
             dry_run: false,
             resolve_merge_conflicts: false,
+            merge_file_favor: None,
+            reset_author: false,
+            timing: false,
+            exec_on_conflict: None,
             check_out_commit_options: CheckOutCommitOptions {
                 render_smartlog: false,
                 ..Default::default()
@@ -597,6 +603,8 @@ Differential Revision: https://phabricator.example.com/D000$(git rev-list --coun
             force_rewrite_public_commits: false,
             dump_rebase_constraints: false,
             dump_rebase_plan: false,
+            explain: false,
+            keep_empty: false,
             detect_duplicate_commits_via_patch_id: false,
         };
         let execute_options = ExecuteRebasePlanOptions {
@@ -607,6 +615,10 @@ Differential Revision: https://phabricator.example.com/D000$(git rev-list --coun
             force_on_disk: false,
             dry_run: false,
             resolve_merge_conflicts: false,
+            merge_file_favor: None,
+            reset_author: false,
+            timing: false,
+            exec_on_conflict: None,
             check_out_commit_options: CheckOutCommitOptions {
                 render_smartlog: false,
                 ..Default::default()
@@ -113,9 +113,9 @@ fn test_github_forge_reorder_commits() -> eyre::Result<()> {
         Local state:
         O f777ecc (master) create initial.txt
         |
-        o 62fc20d (mock-github-username/create-test1-txt) create test1.txt
+        o 62fc20d (mock-github-username/create-test1-txt) [#1 open] create test1.txt
         |
-        @ 96d1c37 (mock-github-username/create-test2-txt) create test2.txt
+        @ 96d1c37 (mock-github-username/create-test2-txt) [#2 open] create test2.txt
 
 
         Remote state:
@@ -184,9 +184,9 @@ fn test_github_forge_reorder_commits() -> eyre::Result<()> {
         Local state:
         O f777ecc (master) create initial.txt
         |
-        @ fe65c1f (> mock-github-username/create-test2-txt) create test2.txt
+        @ fe65c1f (> mock-github-username/create-test2-txt) [#2 open] create test2.txt
         |
-        o 0770943 (mock-github-username/create-test1-txt) create test1.txt
+        o 0770943 (mock-github-username/create-test1-txt) [#1 open] create test1.txt
 
 
         Remote state:
@@ -276,9 +276,9 @@ fn test_github_forge_mock_client_closes_pull_requests() -> eyre::Result<()> {
         Local state:
         O f777ecc (master) create initial.txt
         |
-        o 62fc20d (mock-github-username/create-test1-txt) create test1.txt
+        o 62fc20d (mock-github-username/create-test1-txt) [#1 open] create test1.txt
         |
-        @ 96d1c37 (mock-github-username/create-test2-txt) create test2.txt
+        @ 96d1c37 (mock-github-username/create-test2-txt) [#2 open] create test2.txt
 
 
         Remote state:
@@ -343,7 +343,7 @@ fn test_github_forge_mock_client_closes_pull_requests() -> eyre::Result<()> {
         :
         O 047b7ad (master) create test1.txt
         |
-        @ fa46633 (> mock-github-username/create-test2-txt) create test2.txt
+        @ fa46633 (> mock-github-username/create-test2-txt) [#2 open] create test2.txt
         "###);
     }
 
@@ -369,7 +369,7 @@ fn test_github_forge_mock_client_closes_pull_requests() -> eyre::Result<()> {
         :
         O 047b7ad (master) create test1.txt
         |
-        @ fa46633 (> mock-github-username/create-test2-txt) create test2.txt
+        @ fa46633 (> mock-github-username/create-test2-txt) [#2 open] create test2.txt
 
 
         Remote state:
@@ -467,7 +467,7 @@ fn test_github_forge_no_include_unsubmitted_commits_in_stack() -> eyre::Result<(
         Local state:
         O f777ecc (master) create initial.txt
         |
-        o 62fc20d (mock-github-username/create-test1-txt) create test1.txt
+        o 62fc20d (mock-github-username/create-test1-txt) [#1 open] create test1.txt
         |
         o 96d1c37 create test2.txt
         |
@@ -561,7 +561,7 @@ fn test_github_forge_multiple_commits_in_pull_request() -> eyre::Result<()> {
         |
         o 96d1c37 create test2.txt
         |
-        @ 70deb1e (mock-github-username/create-test3-txt) create test3.txt
+        @ 70deb1e (mock-github-username/create-test3-txt) [#1 open] create test3.txt
 
 
         Remote state:
@@ -50,7 +50,7 @@ use lib::core::rewrite::{
     RebasePlan, RebasePlanBuilder, RebasePlanPermissions, RepoResource, execute_rebase_plan,
 };
 use lib::git::{
-    Commit, ConfigRead, GitRunInfo, GitRunResult, MaybeZeroOid, NonZeroOid, Repo,
+    Commit, ConfigRead, GitRunInfo, GitRunResult, MaybeZeroOid, MergeFileFavor, NonZeroOid, Repo,
     SerializedNonZeroOid, SerializedTestResult, TEST_ABORT_EXIT_CODE, TEST_INDETERMINATE_EXIT_CODE,
     TEST_SUCCESS_EXIT_CODE, TestCommand, WorkingCopyChangesType, get_latest_test_command_path,
     get_test_locks_dir, get_test_tree_dir, get_test_worktrees_dir, make_test_command_slug,
@@ -221,6 +221,7 @@ impl ResolvedTestOptions {
     pub fn resolve(
         now: SystemTime,
         effects: &Effects,
+        git_run_info: &GitRunInfo,
         dag: &Dag,
         repo: &Repo,
         event_tx_id: EventTransactionId,
@@ -358,6 +359,18 @@ The --jobs option can only be used with --strategy worktree, but --strategy work
             }
         };
 
+        let (resolved_num_jobs, resolved_execution_strategy) = match resolved_execution_strategy {
+            TestExecutionStrategy::Worktree if !git_run_info.supports_worktrees(repo)? => {
+                writeln!(
+                    effects.get_output_stream(),
+                    "\
+Git worktrees are not supported by this Git installation, so commits will be tested one at a time in the working copy instead."
+                )?;
+                (1, TestExecutionStrategy::WorkingCopy)
+            }
+            resolved_execution_strategy => (resolved_num_jobs, resolved_execution_strategy),
+        };
+
         if resolved_interactive != *interactive {
             writeln!(
                 effects.get_output_stream(),
@@ -391,8 +404,13 @@ BUG: Expected resolved_interactive ({resolved_interactive:?}) to match interacti
                 force_on_disk,
                 detect_duplicate_commits_via_patch_id,
                 resolve_merge_conflicts,
+                strategy_option,
                 dump_rebase_constraints,
                 dump_rebase_plan,
+                explain,
+                keep_empty,
+                timing,
+                no_keep_empty: _,
             } = move_options;
 
             let force_in_memory = true;
@@ -408,6 +426,8 @@ BUG: Expected resolved_interactive ({resolved_interactive:?}) to match interacti
                 force_rewrite_public_commits: *force_rewrite_public_commits,
                 dump_rebase_constraints: *dump_rebase_constraints,
                 dump_rebase_plan: *dump_rebase_plan,
+                explain: *explain,
+                keep_empty: *keep_empty,
                 detect_duplicate_commits_via_patch_id: *detect_duplicate_commits_via_patch_id,
             };
             let execute_options = ExecuteRebasePlanOptions {
@@ -418,6 +438,10 @@ BUG: Expected resolved_interactive ({resolved_interactive:?}) to match interacti
                 force_on_disk: *force_on_disk,
                 dry_run: false,
                 resolve_merge_conflicts: *resolve_merge_conflicts,
+                merge_file_favor: strategy_option.map(MergeFileFavor::from),
+                reset_author: false,
+                timing: *timing,
+                exec_on_conflict: None,
                 check_out_commit_options: CheckOutCommitOptions {
                     render_smartlog: false,
                     ..Default::default()
@@ -480,6 +504,7 @@ pub fn command_main(ctx: CommandContext, args: TestArgs) -> EyreExitOr<()> {
             exec: command,
             command: command_alias,
             revset,
+            on,
             resolve_revset_options,
             verbosity,
             strategy,
@@ -504,7 +529,7 @@ pub fn command_main(ctx: CommandContext, args: TestArgs) -> EyreExitOr<()> {
                 verbosity: Verbosity::from(verbosity),
                 apply_fixes: false,
             },
-            revset,
+            on.unwrap_or(revset),
             &resolve_revset_options,
             None,
         ),
@@ -517,6 +542,7 @@ pub fn command_main(ctx: CommandContext, args: TestArgs) -> EyreExitOr<()> {
             verbosity,
         } => subcommand_show(
             &effects,
+            &git_run_info,
             &RawTestOptions {
                 exec: command,
                 command: command_alias,
@@ -611,6 +637,7 @@ fn subcommand_run(
     let options = try_exit_code!(ResolvedTestOptions::resolve(
         now,
         effects,
+        git_run_info,
         &dag,
         &repo,
         event_tx_id,
@@ -734,6 +761,10 @@ fn set_abort_trap(
             force_on_disk: true,
             dry_run: false,
             resolve_merge_conflicts: false,
+            merge_file_favor: None,
+            reset_author: false,
+            timing: false,
+            exec_on_conflict: None,
             check_out_commit_options: CheckOutCommitOptions {
                 render_smartlog: false,
                 ..Default::default()
@@ -2836,6 +2867,7 @@ To abort testing entirely, run:      {exit127}",
 #[instrument]
 fn subcommand_show(
     effects: &Effects,
+    git_run_info: &GitRunInfo,
     options: &RawTestOptions,
     revset: Revset,
     resolve_revset_options: &ResolveRevsetOptions,
@@ -2868,6 +2900,7 @@ fn subcommand_show(
     let options = try_exit_code!(ResolvedTestOptions::resolve(
         now,
         effects,
+        git_run_info,
         &dag,
         &repo,
         event_tx_id,
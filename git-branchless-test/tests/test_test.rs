@@ -593,6 +593,75 @@ fn test_test_worktree_strategy() -> eyre::Result<()> {
     Ok(())
 }
 
+#[cfg(unix)] // Uses a shell script as a stand-in for the Git executable.
+#[test]
+fn test_test_worktree_strategy_unsupported() -> eyre::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let git = make_git()?;
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+
+    // Simulate a Git installation which doesn't support `git worktree`.
+    let fake_git_path = git.repo_path.join(".git").join("fake-git");
+    std::fs::write(
+        &fake_git_path,
+        format!(
+            "\
+#!/bin/sh
+for arg in \"$@\"; do
+    if [ \"$arg\" = worktree ]; then
+        echo \"git: 'worktree' is not a git command.\" >&2
+        exit 1
+    fi
+done
+exec {:?} \"$@\"
+",
+            git.path_to_git
+        ),
+    )?;
+    std::fs::set_permissions(&fake_git_path, std::fs::Permissions::from_mode(0o755))?;
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "test",
+            &[
+                "run",
+                "--strategy",
+                "worktree",
+                "--jobs",
+                "2",
+                "-x",
+                "echo hello",
+                "@",
+            ],
+            &GitRunOptions {
+                env: hashmap! {
+                    "TEST_GIT".to_string() => fake_git_path.to_str().unwrap().to_string(),
+                },
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @r###"
+        Stopped at 62fc20d (create test1.txt)
+        branchless: processing 1 update: branch master
+        "###);
+        insta::assert_snapshot!(stdout, @r###"
+        Git worktrees are not supported by this Git installation, so commits will be tested one at a time in the working copy instead.
+        branchless: running command: <repo-path>/.git/fake-git diff --quiet
+        Calling Git for on-disk rebase...
+        branchless: running command: <repo-path>/.git/fake-git rebase --continue
+        Using command execution strategy: working-copy
+        branchless: running command: <repo-path>/.git/fake-git rebase --abort
+        ✓ Passed: 62fc20d create test1.txt
+        Ran command on 1 commit: echo hello
+        1 passed, 0 failed, 0 skipped
+        "###);
+    }
+
+    Ok(())
+}
+
 #[cfg(unix)] // Paths don't match on Windows.
 #[test]
 fn test_test_config_strategy() -> eyre::Result<()> {
@@ -1897,3 +1966,95 @@ fn test_test_no_cache() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_test_status_descriptor() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let (_stdout, _stderr) = git.branchless_with_options(
+            "test",
+            &["run", "--exec", "test ! -e test2.txt"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+    }
+    git.commit_file("test3", 3)?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        o 96d1c37 create test2.txt
+        |
+        @ 70deb1e create test3.txt
+        "###);
+    }
+
+    git.run(&["config", "branchless.commitDescriptors.testStatus", "true"])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc ? (master) create initial.txt
+        |
+        o 62fc20d ✓ create test1.txt
+        |
+        o 96d1c37 X create test2.txt
+        |
+        @ 70deb1e ? create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_test_run_on() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    if !git.supports_log_exclude_decoration()? {
+        return Ok(());
+    }
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("test", &["run", "-x", "exit 0", "--on", "draft()"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> diff --quiet
+        Calling Git for on-disk rebase...
+        branchless: running command: <git-executable> rebase --continue
+        Using command execution strategy: working-copy
+        branchless: running command: <git-executable> rebase --abort
+        ✓ Passed: 62fc20d create test1.txt
+        ✓ Passed: fe65c1f create test2.txt
+        ✓ Passed: 0206717 create test3.txt
+        Ran command on 3 commits: exit 0
+        3 passed, 0 failed, 0 skipped
+        "###);
+    }
+
+    Ok(())
+}
@@ -36,16 +36,20 @@ use tracing::instrument;
 use crate::tui::{SingletonView, with_siv};
 use git_branchless_revset::resolve_default_smartlog_commits;
 use git_branchless_smartlog::{make_smartlog_graph, render_graph};
-use lib::core::dag::{CommitSet, Dag};
+use lib::core::dag::Dag;
 use lib::core::effects::Effects;
-use lib::core::eventlog::{Event, EventCursor, EventLogDb, EventReplayer, EventTransactionId};
+use lib::core::eventlog::{
+    Event, EventCursor, EventLogDb, EventReplayer, EventTransactionId, UndoPosition,
+};
 use lib::core::formatting::{Glyphs, Pluralize, StyledStringBuilder};
 use lib::core::node_descriptors::{
     BranchesDescriptor, CommitMessageDescriptor, CommitOidDescriptor,
     DifferentialRevisionDescriptor, ObsolescenceExplanationDescriptor, Redactor,
     RelativeTimeDescriptor,
 };
-use lib::git::{CategorizedReferenceName, GitRunInfo, MaybeZeroOid, Repo, ResolvedReferenceInfo};
+use lib::git::{
+    CategorizedReferenceName, GitRunInfo, MaybeZeroOid, ReferenceName, Repo, ResolvedReferenceInfo,
+};
 
 fn render_cursor_smartlog(
     effects: &Effects,
@@ -59,7 +63,11 @@ fn render_cursor_smartlog(
     dag.sync_from_oids(
         effects,
         repo,
-        CommitSet::from(references_snapshot.main_branch_oid),
+        references_snapshot
+            .main_branch_oids
+            .iter()
+            .copied()
+            .collect(),
         references_snapshot
             .branch_oid_to_names
             .keys()
@@ -95,7 +103,7 @@ fn render_cursor_smartlog(
         &mut [
             &mut CommitOidDescriptor::new(true)?,
             &mut RelativeTimeDescriptor::new(repo, SystemTime::now())?,
-            &mut ObsolescenceExplanationDescriptor::new(event_replayer, event_cursor)?,
+            &mut ObsolescenceExplanationDescriptor::new(repo, event_replayer, event_cursor)?,
             &mut BranchesDescriptor::new(
                 repo,
                 &head_info,
@@ -721,10 +729,76 @@ fn inverse_event(
     Ok(inverse_event)
 }
 
+/// Make a copy of an event which was previously undone, so that it can be
+/// applied again as part of a new transaction.
+fn reapply_event(
+    event: Event,
+    now: SystemTime,
+    event_tx_id: EventTransactionId,
+) -> eyre::Result<Event> {
+    let timestamp = now.duration_since(SystemTime::UNIX_EPOCH)?.as_secs_f64();
+    let event = match event {
+        Event::CommitEvent { commit_oid, .. } => Event::CommitEvent {
+            timestamp,
+            event_tx_id,
+            commit_oid,
+        },
+        Event::ObsoleteEvent { commit_oid, .. } => Event::ObsoleteEvent {
+            timestamp,
+            event_tx_id,
+            commit_oid,
+        },
+        Event::UnobsoleteEvent { commit_oid, .. } => Event::UnobsoleteEvent {
+            timestamp,
+            event_tx_id,
+            commit_oid,
+        },
+        Event::RewriteEvent {
+            old_commit_oid,
+            new_commit_oid,
+            ..
+        } => Event::RewriteEvent {
+            timestamp,
+            event_tx_id,
+            old_commit_oid,
+            new_commit_oid,
+        },
+        Event::RefUpdateEvent {
+            ref_name,
+            old_oid,
+            new_oid,
+            ..
+        } => Event::RefUpdateEvent {
+            timestamp,
+            event_tx_id,
+            ref_name,
+            old_oid,
+            new_oid,
+            message: None,
+        },
+        Event::WorkingCopySnapshot {
+            head_oid,
+            commit_oid,
+            ref_name,
+            ..
+        } => Event::WorkingCopySnapshot {
+            timestamp,
+            event_tx_id,
+            head_oid,
+            commit_oid,
+            ref_name,
+        },
+    };
+    Ok(event)
+}
+
 #[derive(Clone, Debug)]
 struct UndoCheckoutTarget {
     target: CheckoutTarget,
     options: CheckOutCommitOptions,
+
+    /// Where `HEAD` should point once the target has been checked out.
+    head_info: ResolvedReferenceInfo,
 }
 
 fn extract_checkout_target(
@@ -750,13 +824,17 @@ fn extract_checkout_target(
                         reset: false,
                         render_smartlog: true,
                     },
+                    head_info: ResolvedReferenceInfo {
+                        oid: Some(*new_oid),
+                        reference_name: None,
+                    },
                 });
             }
 
             Event::WorkingCopySnapshot {
                 timestamp: _,
                 event_tx_id: _,
-                head_oid: _,
+                head_oid,
                 commit_oid,
                 ref_name,
             } => {
@@ -774,6 +852,13 @@ fn extract_checkout_target(
                         reset: false,
                         render_smartlog: true,
                     },
+                    head_info: ResolvedReferenceInfo {
+                        oid: match head_oid {
+                            MaybeZeroOid::NonZero(head_oid) => Some(*head_oid),
+                            MaybeZeroOid::Zero => None,
+                        },
+                        reference_name: ref_name.clone(),
+                    },
                 })
             }
 
@@ -784,50 +869,17 @@ fn extract_checkout_target(
     Ok((checkout_target, new_events))
 }
 
-#[instrument(skip(in_))]
-fn undo_events(
+/// Ask the user to confirm applying the given events.
+fn confirm_events(
     in_: &mut impl Read,
     effects: &Effects,
     repo: &Repo,
-    git_run_info: &GitRunInfo,
-    event_log_db: &mut EventLogDb,
-    event_replayer: &EventReplayer,
-    event_cursor: EventCursor,
+    events: &[Event],
     skip_confirmation: bool,
-) -> EyreExitOr<()> {
-    let now = SystemTime::now();
-    let event_tx_id = event_log_db.make_transaction_id(now, "undo")?;
-    let head_info = repo.get_head_info()?;
-    let inverse_events: Vec<Event> = event_replayer
-        .get_events_since_cursor(event_cursor)
-        .iter()
-        .rev()
-        .filter(|event| {
-            !matches!(
-                event,
-                Event::RefUpdateEvent {
-                    timestamp: _,
-                    event_tx_id: _,
-                    ref_name,
-                    old_oid: MaybeZeroOid::Zero,
-                    new_oid: _,
-                    message: _,
-                } if ref_name.as_str() == "HEAD"
-            )
-        })
-        .map(|event| inverse_event(event.clone(), now, event_tx_id))
-        .collect::<eyre::Result<Vec<Event>>>()?;
-
-    if inverse_events.is_empty() {
-        writeln!(
-            effects.get_output_stream(),
-            "No undo actions to apply, exiting."
-        )?;
-        return Ok(Ok(()));
-    }
+) -> eyre::Result<bool> {
     writeln!(effects.get_output_stream(), "Will apply these actions:")?;
-    let events = describe_events_numbered(effects.get_glyphs(), repo, &inverse_events)?;
-    for line in events {
+    let lines = describe_events_numbered(effects.get_glyphs(), repo, events)?;
+    for line in lines {
         writeln!(
             effects.get_output_stream(),
             "{}",
@@ -835,33 +887,50 @@ fn undo_events(
         )?;
     }
 
-    let confirmed = if skip_confirmation {
-        true
-    } else {
-        write!(effects.get_output_stream(), "Confirm? [yN] ")?;
-        let mut user_input = String::new();
-        let mut reader = BufReader::new(in_);
-        match reader.read_line(&mut user_input) {
-            Ok(_size) => {
-                let user_input = user_input.trim();
-                user_input == "y" || user_input == "Y"
-            }
-            Err(_) => false,
+    if skip_confirmation {
+        return Ok(true);
+    }
+    write!(effects.get_output_stream(), "Confirm? [yN] ")?;
+    let mut user_input = String::new();
+    let mut reader = BufReader::new(in_);
+    let confirmed = match reader.read_line(&mut user_input) {
+        Ok(_size) => {
+            let user_input = user_input.trim();
+            user_input == "y" || user_input == "Y"
         }
+        Err(_) => false,
     };
-    if !confirmed {
-        writeln!(effects.get_output_stream(), "Aborted.")?;
-        return Ok(Err(ExitCode(1)));
-    }
+    Ok(confirmed)
+}
 
-    let num_inverse_events = Pluralize {
-        determiner: None,
-        amount: inverse_events.len(),
-        unit: ("inverse event", "inverse events"),
-    }
-    .to_string();
+fn is_initial_head_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::RefUpdateEvent {
+            timestamp: _,
+            event_tx_id: _,
+            ref_name,
+            old_oid: MaybeZeroOid::Zero,
+            new_oid: _,
+            message: _,
+        } if ref_name.as_str() == "HEAD"
+    )
+}
 
-    let (checkout_target, filtered_events) = extract_checkout_target(&inverse_events)?;
+/// Apply the given events to the repository, updating references, commit
+/// visibility, and the working copy as necessary. If `refs_only` is set, `HEAD`
+/// is moved without touching the working copy or index.
+fn apply_events(
+    effects: &Effects,
+    repo: &Repo,
+    git_run_info: &GitRunInfo,
+    event_log_db: &mut EventLogDb,
+    event_tx_id: EventTransactionId,
+    events: &[Event],
+    refs_only: bool,
+) -> EyreExitOr<()> {
+    let head_info = repo.get_head_info()?;
+    let (checkout_target, filtered_events) = extract_checkout_target(events)?;
     if checkout_target.is_some() {
         repo.detach_head(&head_info)?;
     }
@@ -887,6 +956,9 @@ fn undo_events(
             } => match repo.find_reference(ref_name)? {
                 Some(mut reference) => {
                     reference.delete().wrap_err("Applying `RefUpdateEvent`")?;
+                    // Updating the reference via libgit2 doesn't invoke the
+                    // `reference-transaction` hook, so record it ourselves.
+                    event_log_db.add_events(vec![event.clone()])?;
                 }
                 None => {
                     writeln!(
@@ -914,6 +986,7 @@ fn undo_events(
             } => {
                 // Create or update the given reference.
                 repo.create_reference(ref_name, *new_oid, true, "branchless undo")?;
+                event_log_db.add_events(vec![event.clone()])?;
             }
 
             Event::WorkingCopySnapshot { .. } => {
@@ -929,32 +1002,275 @@ fn undo_events(
         }
     }
 
-    if let Some(UndoCheckoutTarget { target, options }) = checkout_target {
-        try_exit_code!(
-            check_out_commit(
+    match checkout_target {
+        None => {}
+        Some(UndoCheckoutTarget {
+            target: _,
+            options: _,
+            head_info,
+        }) if refs_only => {
+            update_head_without_checkout(
                 effects,
-                git_run_info,
                 repo,
+                git_run_info,
                 event_log_db,
                 event_tx_id,
-                Some(target),
-                &options,
-            )
-            .wrap_err("Updating to previous HEAD location")?
-        );
+                &head_info,
+            )?;
+        }
+        Some(UndoCheckoutTarget {
+            target,
+            options,
+            head_info: _,
+        }) => {
+            try_exit_code!(
+                check_out_commit(
+                    effects,
+                    git_run_info,
+                    repo,
+                    event_log_db,
+                    event_tx_id,
+                    Some(target),
+                    &options,
+                )
+                .wrap_err("Updating to previous HEAD location")?
+            );
+        }
     }
 
+    Ok(Ok(()))
+}
+
+/// Point `HEAD` at the given location without checking it out, and warn if
+/// the working copy no longer matches it. The reference updates are made via
+/// libgit2, which doesn't invoke the `reference-transaction` hook, so they're
+/// recorded in the event log explicitly.
+fn update_head_without_checkout(
+    effects: &Effects,
+    repo: &Repo,
+    git_run_info: &GitRunInfo,
+    event_log_db: &EventLogDb,
+    event_tx_id: EventTransactionId,
+    head_info: &ResolvedReferenceInfo,
+) -> eyre::Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs_f64();
+    let get_oid = |reference_name: &ReferenceName| -> eyre::Result<MaybeZeroOid> {
+        let oid = match repo.find_reference(reference_name)? {
+            Some(reference) => reference.peel_to_commit()?.map(|commit| commit.get_oid()),
+            None => None,
+        };
+        Ok(oid.into())
+    };
+    let head_ref_name = ReferenceName::from("HEAD");
+    let old_head_oid = get_oid(&head_ref_name)?;
+
+    let mut events = Vec::new();
+    let new_head_oid = match head_info {
+        ResolvedReferenceInfo {
+            oid: Some(oid),
+            reference_name: Some(reference_name),
+        } => {
+            let old_oid = get_oid(reference_name)?;
+            repo.create_reference(reference_name, *oid, true, "branchless undo")?;
+            repo.set_head_to_reference(reference_name)?;
+            if old_oid != MaybeZeroOid::NonZero(*oid) {
+                events.push(Event::RefUpdateEvent {
+                    timestamp,
+                    event_tx_id,
+                    ref_name: reference_name.clone(),
+                    old_oid,
+                    new_oid: (*oid).into(),
+                    message: None,
+                });
+            }
+            *oid
+        }
+        ResolvedReferenceInfo {
+            oid: Some(oid),
+            reference_name: None,
+        } => {
+            repo.set_head(*oid)?;
+            *oid
+        }
+        ResolvedReferenceInfo {
+            oid: None,
+            reference_name: _,
+        } => return Ok(()),
+    };
+    if old_head_oid != MaybeZeroOid::NonZero(new_head_oid) {
+        events.push(Event::RefUpdateEvent {
+            timestamp,
+            event_tx_id,
+            ref_name: head_ref_name,
+            old_oid: old_head_oid,
+            new_oid: new_head_oid.into(),
+            message: None,
+        });
+    }
+    event_log_db.add_events(events)?;
+
+    let status = git_run_info.run_silent(
+        repo,
+        None,
+        &["status", "--porcelain", "--untracked-files=no"],
+        Default::default(),
+    )?;
+    if !status.stdout.is_empty() {
+        writeln!(
+            effects.get_output_stream(),
+            "Warning: the working copy and index were left as-is, and now differ from HEAD."
+        )?;
+    }
+    Ok(())
+}
+
+#[instrument(skip(in_))]
+fn undo_events(
+    in_: &mut impl Read,
+    effects: &Effects,
+    repo: &Repo,
+    git_run_info: &GitRunInfo,
+    event_log_db: &mut EventLogDb,
+    event_replayer: &EventReplayer,
+    event_cursor: EventCursor,
+    skip_confirmation: bool,
+    refs_only: bool,
+) -> EyreExitOr<()> {
+    let now = SystemTime::now();
+    let event_tx_id = event_log_db.make_transaction_id(now, "undo")?;
+    let inverse_events: Vec<Event> = event_replayer
+        .get_events_since_cursor(event_cursor)
+        .iter()
+        .rev()
+        .filter(|event| !is_initial_head_event(event))
+        .map(|event| inverse_event(event.clone(), now, event_tx_id))
+        .collect::<eyre::Result<Vec<Event>>>()?;
+
+    if inverse_events.is_empty() {
+        writeln!(
+            effects.get_output_stream(),
+            "No undo actions to apply, exiting."
+        )?;
+        return Ok(Ok(()));
+    }
+    if !confirm_events(in_, effects, repo, &inverse_events, skip_confirmation)? {
+        writeln!(effects.get_output_stream(), "Aborted.")?;
+        return Ok(Err(ExitCode(1)));
+    }
+
+    let num_inverse_events = Pluralize {
+        determiner: None,
+        amount: inverse_events.len(),
+        unit: ("inverse event", "inverse events"),
+    }
+    .to_string();
+
+    try_exit_code!(apply_events(
+        effects,
+        repo,
+        git_run_info,
+        event_log_db,
+        event_tx_id,
+        &inverse_events,
+        refs_only,
+    )?);
+
+    let after_undo_cursor =
+        EventReplayer::from_event_log_db(effects, repo, event_log_db)?.make_default_cursor();
+    event_log_db.set_undo_position(UndoPosition {
+        target_cursor: event_cursor,
+        before_undo_cursor: event_replayer.make_default_cursor(),
+        after_undo_cursor,
+    })?;
+
     writeln!(effects.get_output_stream(), "Applied {num_inverse_events}.")?;
     Ok(Ok(()))
 }
 
+#[instrument(skip(in_))]
+fn redo_events(
+    in_: &mut impl Read,
+    effects: &Effects,
+    repo: &Repo,
+    git_run_info: &GitRunInfo,
+    event_log_db: &mut EventLogDb,
+    event_replayer: &EventReplayer,
+    skip_confirmation: bool,
+    refs_only: bool,
+) -> EyreExitOr<()> {
+    let UndoPosition {
+        target_cursor,
+        before_undo_cursor,
+        after_undo_cursor,
+    } = match event_log_db.get_undo_position()? {
+        Some(undo_position) => undo_position,
+        None => {
+            writeln!(effects.get_output_stream(), "No undo to redo, exiting.")?;
+            return Ok(Ok(()));
+        }
+    };
+    if event_replayer.make_default_cursor() != after_undo_cursor {
+        writeln!(
+            effects.get_output_stream(),
+            "Cannot redo: other operations have happened since the last undo."
+        )?;
+        return Ok(Err(ExitCode(1)));
+    }
+
+    let now = SystemTime::now();
+    let event_tx_id = event_log_db.make_transaction_id(now, "redo")?;
+    let redo_events: Vec<Event> = event_replayer
+        .get_events_between_cursors(target_cursor, before_undo_cursor)
+        .iter()
+        .filter(|event| !is_initial_head_event(event))
+        .map(|event| reapply_event(event.clone(), now, event_tx_id))
+        .collect::<eyre::Result<Vec<Event>>>()?;
+
+    if redo_events.is_empty() {
+        writeln!(
+            effects.get_output_stream(),
+            "No redo actions to apply, exiting."
+        )?;
+        return Ok(Ok(()));
+    }
+    if !confirm_events(in_, effects, repo, &redo_events, skip_confirmation)? {
+        writeln!(effects.get_output_stream(), "Aborted.")?;
+        return Ok(Err(ExitCode(1)));
+    }
+
+    let num_redo_events = Pluralize {
+        determiner: None,
+        amount: redo_events.len(),
+        unit: ("event", "events"),
+    }
+    .to_string();
+
+    try_exit_code!(apply_events(
+        effects,
+        repo,
+        git_run_info,
+        event_log_db,
+        event_tx_id,
+        &redo_events,
+        refs_only,
+    )?);
+    event_log_db.clear_undo_position()?;
+
+    writeln!(effects.get_output_stream(), "Reapplied {num_redo_events}.")?;
+    Ok(Ok(()))
+}
+
 /// Restore the repository to a previous state interactively.
 #[instrument]
 pub fn undo(
     effects: &Effects,
     git_run_info: &GitRunInfo,
     interactive: bool,
+    redo: bool,
     skip_confirmation: bool,
+    refs_only: bool,
 ) -> EyreExitOr<()> {
     let repo = Repo::from_current_dir()?;
     let references_snapshot = repo.get_references_snapshot()?;
@@ -975,6 +1291,19 @@ pub fn undo(
         )?
     };
 
+    if redo {
+        return redo_events(
+            &mut stdin(),
+            effects,
+            &repo,
+            git_run_info,
+            &mut event_log_db,
+            &event_replayer,
+            skip_confirmation,
+            refs_only,
+        );
+    }
+
     let event_cursor = {
         if interactive {
             let result = with_siv(effects, |effects, siv| {
@@ -998,6 +1327,7 @@ pub fn undo(
         &event_replayer,
         event_cursor,
         skip_confirmation,
+        refs_only,
     )?;
     Ok(result)
 }
@@ -1042,6 +1372,27 @@ pub mod testing {
             event_replayer,
             event_cursor,
             false,
+            false,
+        )
+    }
+
+    pub fn redo_events(
+        in_: &mut impl Read,
+        effects: &Effects,
+        repo: &Repo,
+        git_run_info: &GitRunInfo,
+        event_log_db: &mut EventLogDb,
+        event_replayer: &EventReplayer,
+    ) -> EyreExitOr<()> {
+        super::redo_events(
+            in_,
+            effects,
+            repo,
+            git_run_info,
+            event_log_db,
+            event_replayer,
+            false,
+            false,
         )
     }
 }
@@ -1088,6 +1439,12 @@ mod tests {
                         reset: false,
                         render_smartlog: true,
                     },
+                    head_info: ResolvedReferenceInfo {
+                        oid: Some(
+                            NonZeroOid(3000000000000000000000000000000000000000),
+                        ),
+                        reference_name: None,
+                    },
                 },
             ),
             [],
@@ -0,0 +1,504 @@
+//! Absorb staged changes into the draft commits which they modify.
+//!
+//! Each staged hunk is attributed (via blame) to the commit in the current
+//! stack which last touched the lines it changes. The hunk is then squashed
+//! into that commit, and the stack is restacked. Hunks which can't be
+//! attributed to a single commit in the stack are left staged.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use eyre::Context;
+use git_branchless_opts::MoveOptions;
+use itertools::Itertools;
+use lib::core::check_out::{CheckOutCommitOptions, CheckoutTarget, check_out_commit};
+use lib::core::config::get_restack_preserve_timestamps;
+use lib::core::dag::{CommitSet, Dag};
+use lib::core::effects::Effects;
+use lib::core::eventlog::{Event, EventLogDb, EventReplayer};
+use lib::core::formatting::Pluralize;
+use lib::core::gc::mark_commit_reachable;
+use lib::core::repo_ext::RepoExt;
+use lib::core::rewrite::{
+    BuildRebasePlanOptions, ExecuteRebasePlanOptions, ExecuteRebasePlanResult,
+    MergeConflictRemediation, RebasePlanBuilder, RebasePlanPermissions, RepoResource,
+    execute_rebase_plan, move_branches,
+};
+use lib::git::{
+    BlameHunk, GitRunInfo, LineHunk, MaybeZeroOid, NonZeroOid, Repo, ResolvedReferenceInfo,
+    get_line_hunks, hydrate_tree,
+};
+use lib::try_exit_code;
+use lib::util::{ExitCode, EyreExitOr};
+use rayon::ThreadPoolBuilder;
+use tracing::instrument;
+
+/// A staged hunk, translated into the coordinates of the version of the file
+/// in the commit that it will be absorbed into.
+#[derive(Clone, Debug)]
+struct AbsorbEdit {
+    path: PathBuf,
+
+    /// The 0-based index of the first line to replace.
+    start: usize,
+
+    /// The number of lines to replace.
+    num_lines: usize,
+
+    added_lines: Vec<Vec<u8>>,
+}
+
+/// Find the commit which last changed the given 1-based line, and the line
+/// number of that line in the commit's version of the file.
+fn blame_line(blame: &[BlameHunk], line: usize) -> Option<(NonZeroOid, usize, Option<&PathBuf>)> {
+    blame
+        .iter()
+        .find(|hunk| hunk.final_start_line <= line && line < hunk.final_start_line + hunk.num_lines)
+        .map(|hunk| {
+            (
+                hunk.commit_oid,
+                hunk.orig_start_line + (line - hunk.final_start_line),
+                hunk.orig_path.as_ref(),
+            )
+        })
+}
+
+/// Determine which commit in the stack the given hunk should be absorbed into,
+/// if any. All the lines that the hunk touches (or, for a pure insertion, the
+/// lines around it) must have last been changed by the same commit.
+fn find_absorb_target(
+    blame: &[BlameHunk],
+    hunk: &LineHunk,
+    stack_oids: &HashSet<NonZeroOid>,
+) -> Option<(NonZeroOid, AbsorbEdit)> {
+    let LineHunk {
+        path,
+        old_start,
+        old_lines,
+        added_lines,
+    } = hunk;
+    let lines = if *old_lines > 0 {
+        (*old_start..*old_start + *old_lines).collect_vec()
+    } else {
+        // An insertion after line `old_start`: look at its neighbors.
+        [*old_start, *old_start + 1]
+            .into_iter()
+            .filter(|line| *line >= 1 && blame_line(blame, *line).is_some())
+            .collect_vec()
+    };
+    let attributions: Vec<_> = lines
+        .iter()
+        .map(|line| blame_line(blame, *line))
+        .collect::<Option<_>>()?;
+
+    let (commit_oid, first_orig_line, _) = *attributions.first()?;
+    if !stack_oids.contains(&commit_oid) {
+        return None;
+    }
+    for (i, (line_commit_oid, orig_line, orig_path)) in attributions.iter().enumerate() {
+        if *line_commit_oid != commit_oid
+            || *orig_line != first_orig_line + i
+            || orig_path.is_some_and(|orig_path| orig_path != path)
+        {
+            return None;
+        }
+    }
+
+    let start = if *old_lines > 0 {
+        first_orig_line - 1
+    } else if lines.first() == Some(old_start) {
+        // Insert after the line before the hunk.
+        first_orig_line
+    } else {
+        // Insert before the line after the hunk.
+        first_orig_line - 1
+    };
+    Some((
+        commit_oid,
+        AbsorbEdit {
+            path: path.clone(),
+            start,
+            num_lines: *old_lines,
+            added_lines: added_lines.clone(),
+        },
+    ))
+}
+
+/// Apply the given edits to the contents of a file.
+fn apply_edits(contents: &[u8], edits: &[&AbsorbEdit]) -> Vec<u8> {
+    let mut lines: Vec<Vec<u8>> = contents
+        .split_inclusive(|c| *c == b'\n')
+        .map(|line| line.to_vec())
+        .collect();
+    for edit in edits
+        .iter()
+        .sorted_by_key(|edit| std::cmp::Reverse(edit.start))
+    {
+        let end = (edit.start + edit.num_lines).min(lines.len());
+        let start = edit.start.min(end);
+        lines.splice(start..end, edit.added_lines.iter().cloned());
+    }
+    lines.concat()
+}
+
+/// Absorb the staged changes into the draft commits in the current stack which
+/// last changed the same lines.
+#[instrument]
+pub fn absorb(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    move_options: &MoveOptions,
+) -> EyreExitOr<()> {
+    let now = SystemTime::now();
+    let timestamp = now.duration_since(SystemTime::UNIX_EPOCH)?.as_secs_f64();
+    let repo = Repo::from_current_dir()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+    let event_replayer = EventReplayer::from_event_log_db(effects, &repo, &event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    let references_snapshot = repo.get_references_snapshot()?;
+    let mut dag = Dag::open_and_sync(
+        effects,
+        &repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+
+    let head_info = repo.get_head_info()?;
+    let head_oid = match head_info.oid {
+        Some(oid) => oid,
+        None => {
+            writeln!(
+                effects.get_output_stream(),
+                "No commit is currently checked out. Check out a commit to amend and then try again.",
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+    let head_commit = repo.find_commit_or_fail(head_oid)?;
+
+    let mut index = repo.get_index()?;
+    if index.has_conflicts() {
+        writeln!(
+            effects.get_output_stream(),
+            "Cannot amend, because there are unresolved merge conflicts. Resolve the merge conflicts and try again."
+        )?;
+        return Ok(Err(ExitCode(1)));
+    }
+
+    // The stack is the linear sequence of draft commits ending at `HEAD`.
+    let stack_oids: HashSet<NonZeroOid> = {
+        let draft_commits = dag.query_draft_commits()?;
+        let mut stack_oids = HashSet::new();
+        let mut current_commit = head_commit.clone();
+        while dag.set_contains(draft_commits, current_commit.get_oid())? {
+            stack_oids.insert(current_commit.get_oid());
+            match current_commit.get_only_parent() {
+                Some(parent_commit) => current_commit = parent_commit,
+                None => break,
+            }
+        }
+        stack_oids
+    };
+
+    let head_tree = head_commit.get_tree()?;
+    let index_tree_oid = repo.write_index_to_tree(&mut index)?;
+    if index_tree_oid == head_tree.get_oid() {
+        writeln!(
+            effects.get_output_stream(),
+            "There are no staged changes. Nothing to absorb."
+        )?;
+        return Ok(Ok(()));
+    }
+    let index_tree = repo.find_tree_or_fail(index_tree_oid)?;
+    let hunks = {
+        let diff = repo.get_diff_between_trees(effects, Some(&head_tree), &index_tree, 0)?;
+        get_line_hunks(&diff)?
+    };
+
+    let mut blames: HashMap<PathBuf, Vec<BlameHunk>> = HashMap::new();
+    let mut edits: BTreeMap<NonZeroOid, Vec<AbsorbEdit>> = BTreeMap::new();
+    for hunk in hunks.iter() {
+        let blame = match blames.get(&hunk.path) {
+            Some(blame) => blame,
+            None => {
+                let blame = repo.blame_file(head_oid, &hunk.path)?;
+                blames.entry(hunk.path.clone()).or_insert(blame)
+            }
+        };
+        if let Some((commit_oid, edit)) = find_absorb_target(blame, hunk, &stack_oids) {
+            edits.entry(commit_oid).or_default().push(edit);
+        }
+    }
+    let num_absorbed_hunks: usize = edits.values().map(|edits| edits.len()).sum();
+    if num_absorbed_hunks == 0 {
+        writeln!(
+            effects.get_output_stream(),
+            "None of the staged changes could be attributed to a single draft commit in the current stack, so nothing was absorbed."
+        )?;
+        return Ok(Ok(()));
+    }
+
+    let build_options = BuildRebasePlanOptions {
+        force_rewrite_public_commits: move_options.force_rewrite_public_commits,
+        dump_rebase_constraints: move_options.dump_rebase_constraints,
+        dump_rebase_plan: move_options.dump_rebase_plan,
+        explain: move_options.explain,
+        keep_empty: move_options.keep_empty,
+        detect_duplicate_commits_via_patch_id: move_options.detect_duplicate_commits_via_patch_id,
+    };
+    let target_oids: CommitSet = edits.keys().copied().collect();
+    let descendants = {
+        let descendants = dag.query_descendants(target_oids.clone())?;
+        let descendants = dag.filter_visible_commits(descendants)?;
+        descendants.difference(&target_oids)
+    };
+    if let Err(err) = RebasePlanPermissions::verify_rewrite_set(
+        &dag,
+        build_options.clone(),
+        &target_oids.union(&descendants),
+    )? {
+        err.describe(effects, &repo, &dag)?;
+        return Ok(Err(ExitCode(1)));
+    };
+
+    let event_tx_id = event_log_db.make_transaction_id(now, "amend absorb")?;
+    {
+        let (snapshot, _status) =
+            repo.get_status(effects, git_run_info, &index, &head_info, Some(event_tx_id))?;
+        let ResolvedReferenceInfo {
+            oid,
+            reference_name,
+        } = &head_info;
+        event_log_db.add_events(vec![Event::WorkingCopySnapshot {
+            timestamp,
+            event_tx_id,
+            head_oid: MaybeZeroOid::from(*oid),
+            commit_oid: snapshot.base_commit.get_oid(),
+            ref_name: reference_name.clone(),
+        }])?;
+    }
+
+    // Amend each target commit in place, as `git amend` would. Their
+    // descendants are restacked afterwards.
+    let preserve_timestamps = get_restack_preserve_timestamps(&repo)?;
+    let mut amended_oids: HashMap<NonZeroOid, NonZeroOid> = HashMap::new();
+    for (target_oid, target_edits) in edits.iter() {
+        let target_commit = repo.find_commit_or_fail(*target_oid)?;
+        let target_tree = target_commit.get_tree()?;
+        let mut entries = HashMap::new();
+        for (path, path_edits) in target_edits
+            .iter()
+            .into_group_map_by(|edit| edit.path.clone())
+        {
+            let entry = match target_tree.get_path(&path)? {
+                Some(entry) => entry,
+                None => eyre::bail!(
+                    "Path {path:?} was attributed to commit {target_oid}, but it doesn't exist there"
+                ),
+            };
+            let blob = repo.find_blob_or_fail(entry.get_oid())?;
+            let contents = apply_edits(blob.get_content(), &path_edits);
+            let blob_oid = repo.create_blob_from_contents(&contents)?;
+            entries.insert(path, Some((blob_oid, entry.get_filemode())));
+        }
+        let amended_tree_oid = hydrate_tree(&repo, Some(&target_tree), entries)?;
+        let amended_tree = repo.find_tree_or_fail(amended_tree_oid)?;
+
+        let (author, committer) = (target_commit.get_author(), target_commit.get_committer());
+        let (author, committer) = if preserve_timestamps {
+            (author, committer)
+        } else {
+            (
+                author.update_timestamp(now)?,
+                committer.update_timestamp(now)?,
+            )
+        };
+        let amended_oid = target_commit.amend_commit(
+            None,
+            Some(&author),
+            Some(&committer),
+            None,
+            Some(&amended_tree),
+        )?;
+        mark_commit_reachable(&repo, amended_oid)
+            .wrap_err("Marking commit as reachable for GC purposes.")?;
+        amended_oids.insert(*target_oid, amended_oid);
+    }
+    event_log_db.add_events(
+        amended_oids
+            .iter()
+            .map(|(target_oid, amended_oid)| Event::RewriteEvent {
+                timestamp,
+                event_tx_id,
+                old_commit_oid: MaybeZeroOid::NonZero(*target_oid),
+                new_commit_oid: MaybeZeroOid::NonZero(*amended_oid),
+            })
+            .collect(),
+    )?;
+    dag.sync_from_oids(
+        effects,
+        &repo,
+        CommitSet::empty(),
+        amended_oids.values().copied().collect(),
+    )?;
+    move_branches(
+        effects,
+        git_run_info,
+        &repo,
+        event_tx_id,
+        &amended_oids
+            .iter()
+            .map(|(target_oid, amended_oid)| (*target_oid, MaybeZeroOid::NonZero(*amended_oid)))
+            .collect(),
+    )?;
+    if let Some(amended_head_oid) = amended_oids.get(&head_oid) {
+        let target = match &head_info.reference_name {
+            Some(name) => CheckoutTarget::Reference(name.clone()),
+            None => CheckoutTarget::Oid(*amended_head_oid),
+        };
+        try_exit_code!(check_out_commit(
+            effects,
+            git_run_info,
+            &repo,
+            &event_log_db,
+            event_tx_id,
+            Some(target),
+            &CheckOutCommitOptions {
+                additional_args: Default::default(),
+                force_detach: false,
+                reset: true,
+                render_smartlog: false,
+            },
+        )?);
+    }
+
+    // Restack everything on top of the amended commits, including any amended
+    // commits whose parents were themselves amended or restacked.
+    let get_new_parent_oids = |commit_oid: NonZeroOid| -> eyre::Result<Vec<NonZeroOid>> {
+        let commit = repo.find_commit_or_fail(commit_oid)?;
+        Ok(commit
+            .get_parent_oids()
+            .into_iter()
+            .map(|parent_oid| amended_oids.get(&parent_oid).copied().unwrap_or(parent_oid))
+            .collect())
+    };
+    let permissions = match RebasePlanPermissions::verify_rewrite_set(
+        &dag,
+        build_options,
+        &descendants.union(&amended_oids.values().copied().collect()),
+    )? {
+        Ok(permissions) => permissions,
+        Err(err) => {
+            err.describe(effects, &repo, &dag)?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+    let mut builder = RebasePlanBuilder::new(&dag, permissions);
+    for descendant_oid in dag.commit_set_to_vec(&descendants)? {
+        builder.move_subtree(descendant_oid, get_new_parent_oids(descendant_oid)?)?;
+    }
+    let to_restack = descendants.union(&target_oids);
+    for (target_oid, amended_oid) in amended_oids.iter() {
+        let target_commit = repo.find_commit_or_fail(*target_oid)?;
+        let parent_oids = target_commit.get_parent_oids();
+        if !dag.set_is_empty(&to_restack.intersection(&parent_oids.iter().copied().collect()))? {
+            builder.move_subtree(*amended_oid, get_new_parent_oids(*target_oid)?)?;
+        }
+    }
+    let thread_pool = ThreadPoolBuilder::new().build()?;
+    let repo_pool = RepoResource::new_pool(&repo)?;
+    let rebase_plan = match builder.build(effects, &thread_pool, &repo_pool)? {
+        Ok(rebase_plan) => rebase_plan,
+        Err(err) => {
+            err.describe(effects, &repo, &dag)?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
+    if let Some(rebase_plan) = rebase_plan {
+        let execute_options = ExecuteRebasePlanOptions {
+            now,
+            event_tx_id,
+            force_in_memory: move_options.force_in_memory,
+            force_on_disk: move_options.force_on_disk,
+            dry_run: false,
+            preserve_timestamps,
+            resolve_merge_conflicts: move_options.resolve_merge_conflicts,
+            merge_file_favor: None,
+            reset_author: false,
+            timing: move_options.timing,
+            exec_on_conflict: None,
+            check_out_commit_options: CheckOutCommitOptions {
+                additional_args: Default::default(),
+                force_detach: false,
+                // Leave the working copy as-is, since it already contains the
+                // absorbed changes.
+                reset: true,
+                render_smartlog: false,
+            },
+        };
+        match execute_rebase_plan(
+            effects,
+            git_run_info,
+            &repo,
+            &event_log_db,
+            &rebase_plan,
+            &execute_options,
+        )? {
+            ExecuteRebasePlanResult::Succeeded { rewritten_oids: _ }
+            | ExecuteRebasePlanResult::WouldSucceed => {}
+
+            ExecuteRebasePlanResult::DeclinedToMerge { failed_merge_info } => {
+                failed_merge_info.describe(effects, &repo, MergeConflictRemediation::Restack)?;
+                return Ok(Err(ExitCode(1)));
+            }
+
+            ExecuteRebasePlanResult::Failed { exit_code } => {
+                return Ok(Err(exit_code));
+            }
+        }
+    }
+
+    // `HEAD` now contains the absorbed hunks, so restoring the original index
+    // leaves only the hunks which couldn't be absorbed staged.
+    let mut index = repo.get_index()?;
+    index.update_from_tree(&index_tree)?;
+
+    for (target_oid, target_edits) in edits.iter() {
+        writeln!(
+            effects.get_output_stream(),
+            "Absorbed {} into {}",
+            Pluralize {
+                determiner: None,
+                amount: target_edits.len(),
+                unit: ("hunk", "hunks"),
+            },
+            effects.get_glyphs().render(
+                repo.friendly_describe_commit_from_oid(effects.get_glyphs(), *target_oid)?
+            )?,
+        )?;
+    }
+    let num_unabsorbed_hunks = hunks.len() - num_absorbed_hunks;
+    if num_unabsorbed_hunks > 0 {
+        writeln!(
+            effects.get_output_stream(),
+            "Left {} staged, since {} couldn't be attributed to a single draft commit.",
+            Pluralize {
+                determiner: None,
+                amount: num_unabsorbed_hunks,
+                unit: ("hunk", "hunks"),
+            },
+            if num_unabsorbed_hunks == 1 {
+                "it"
+            } else {
+                "they"
+            },
+        )?;
+    }
+
+    Ok(Ok(()))
+}
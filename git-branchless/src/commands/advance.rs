@@ -57,7 +57,7 @@ use lib::core::repo_ext::RepoExt;
 use lib::core::rewrite::{
     BuildRebasePlanError, BuildRebasePlanOptions, ExecuteRebasePlanOptions,
     ExecuteRebasePlanResult, MergeConflictRemediation, RebasePlanBuilder, RebasePlanPermissions,
-    RepoResource, execute_rebase_plan,
+    RepoResource, check_public_head, execute_rebase_plan, write_signed_public_commits_note,
 };
 use lib::git::{GitRunInfo, NonZeroOid, Repo};
 use lib::util::{ExitCode, EyreExitOr};
@@ -70,6 +70,7 @@ pub fn advance(
     effects: &Effects,
     git_run_info: &GitRunInfo,
     move_options: &MoveOptions,
+    no_public: bool,
 ) -> EyreExitOr<()> {
     let now = SystemTime::now();
     let repo = Repo::from_current_dir()?;
@@ -100,6 +101,16 @@ pub fn advance(
         }
     };
 
+    if let Some(result) = check_public_head(
+        effects,
+        &repo,
+        &dag,
+        move_options.force_rewrite_public_commits,
+        "advance",
+    )? {
+        return Ok(result);
+    }
+
     let head_commit = repo.find_commit_or_fail(head_oid)?;
     let head_commit_set = CommitSet::from(head_oid);
     let parents = dag.query_parents(head_commit_set.clone())?;
@@ -112,6 +123,27 @@ pub fn advance(
         return Ok(Ok(()));
     }
 
+    if no_public {
+        let public_siblings = siblings.intersection(dag.query_public_commits_slow()?);
+        if let Some(example_public_oid) = dag.set_first(&public_siblings)? {
+            let example_public_oid = NonZeroOid::try_from(example_public_oid)?;
+            let example_public_commit = repo.find_commit_or_fail(example_public_oid)?;
+            writeln!(
+                effects.get_output_stream(),
+                "Refusing to advance {}, such as: {}",
+                Pluralize {
+                    determiner: None,
+                    amount: dag.set_count(&public_siblings)?,
+                    unit: ("public commit", "public commits")
+                },
+                effects
+                    .get_glyphs()
+                    .render(example_public_commit.friendly_describe(effects.get_glyphs())?)?,
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    }
+
     let sibling_count = dag.set_count(&siblings)?;
     writeln!(
         effects.get_output_stream(),
@@ -130,6 +162,8 @@ pub fn advance(
         force_rewrite_public_commits: move_options.force_rewrite_public_commits,
         dump_rebase_constraints: move_options.dump_rebase_constraints,
         dump_rebase_plan: move_options.dump_rebase_plan,
+        explain: move_options.explain,
+        keep_empty: move_options.keep_empty,
         detect_duplicate_commits_via_patch_id: move_options.detect_duplicate_commits_via_patch_id,
     };
 
@@ -139,9 +173,20 @@ pub fn advance(
             Ok(permissions) => {
                 let head_commit_parents: HashSet<_> =
                     head_commit.get_parent_oids().into_iter().collect();
+                // Move the siblings in order of author date (with the OID as
+                // a tiebreaker), so that the resulting plan doesn't depend on
+                // the iteration order of the commit set.
+                let mut sibling_commits = dag
+                    .commit_set_to_vec(&siblings)?
+                    .into_iter()
+                    .map(|sibling_oid| repo.find_commit_or_fail(sibling_oid))
+                    .collect::<Result<Vec<_>, _>>()?;
+                sibling_commits
+                    .sort_by_key(|commit| (commit.get_author().get_time(), commit.get_oid()));
+
                 let mut builder = RebasePlanBuilder::new(&dag, permissions);
-                for sibling_oid in dag.commit_set_to_vec(&siblings)? {
-                    let sibling_commit = repo.find_commit_or_fail(sibling_oid)?;
+                for sibling_commit in sibling_commits {
+                    let sibling_oid = sibling_commit.get_oid();
                     let parent_oids = sibling_commit.get_parent_oids();
                     let new_parent_oids = parent_oids
                         .into_iter()
@@ -206,6 +251,7 @@ To proceed anyways, run: git advance -f",
                     .get_glyphs()
                     .render(example_bad_commit.friendly_describe(effects.get_glyphs())?)?,
             )?;
+            write_signed_public_commits_note(effects, &repo, &dag, &public_commits_to_move)?;
             return Ok(Ok(()));
         }
     };
@@ -218,6 +264,10 @@ To proceed anyways, run: git advance -f",
         force_on_disk: move_options.force_on_disk,
         dry_run: false,
         resolve_merge_conflicts: move_options.resolve_merge_conflicts,
+        merge_file_favor: None,
+        reset_author: false,
+        timing: move_options.timing,
+        exec_on_conflict: None,
         check_out_commit_options: CheckOutCommitOptions {
             additional_args: Default::default(),
             force_detach: false,
@@ -2,9 +2,11 @@
 //!
 //! This command amends the HEAD commit with changes to files
 //! that are already tracked in the repo. Following the amend,
-//! the command performs a restack.
+//! the command performs a restack, unless `--no-restack` was passed. If
+//! `branchless.advance.auto` is set, sibling commits are then moved onto the
+//! amended commit, as they would be after a new commit.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use std::fmt::Write;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -12,23 +14,26 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use bstr::ByteSlice;
 
 use eyre::Context;
-use git_branchless_opts::{MoveOptions, ResolveRevsetOptions};
+use git_branchless_opts::{MoveOptions, ResolveRevsetOptions, Revset};
+use git_branchless_revset::resolve_commits;
 use itertools::Itertools;
 use lib::core::check_out::{CheckOutCommitOptions, CheckoutTarget, check_out_commit};
-use lib::core::config::get_restack_preserve_timestamps;
+use lib::core::config::{get_advance_auto, get_restack_preserve_timestamps};
 use lib::core::dag::{CommitSet, Dag};
 use lib::core::effects::Effects;
-use lib::core::eventlog::{Event, EventLogDb, EventReplayer};
+use lib::core::eventlog::{Event, EventLogDb, EventReplayer, EventTransactionId};
 use lib::core::formatting::Pluralize;
-use lib::core::gc::mark_commit_reachable;
+use lib::core::gc::{mark_commit_checkpoint, mark_commit_reachable};
 use lib::core::repo_ext::RepoExt;
 use lib::core::rewrite::{
-    BuildRebasePlanOptions, ExecuteRebasePlanOptions, ExecuteRebasePlanResult, RebasePlanBuilder,
-    RebasePlanPermissions, RepoResource, execute_rebase_plan, move_branches,
+    BuildRebasePlanOptions, ExecuteRebasePlanOptions, ExecuteRebasePlanResult,
+    MergeConflictRemediation, RebasePlanBuilder, RebasePlanPermissions, RepoResource,
+    execute_rebase_plan, move_branches,
 };
 use lib::core::untracked_file_cache::{UntrackedFileStrategy, process_untracked_files};
 use lib::git::{
-    AmendFastOptions, GitRunInfo, MaybeZeroOid, Repo, ResolvedReferenceInfo, StatusEntry,
+    AmendFastOptions, GitRunInfo, MaybeZeroOid, NonZeroOid, Repo, ResolvedReferenceInfo,
+    StatusEntry,
 };
 use lib::try_exit_code;
 use lib::util::{ExitCode, EyreExitOr};
@@ -43,7 +48,10 @@ pub fn amend(
     resolve_revset_options: &ResolveRevsetOptions,
     move_options: &MoveOptions,
     reparent: bool,
+    no_restack: bool,
     untracked_file_strategy: Option<UntrackedFileStrategy>,
+    checkpoint: bool,
+    message_from: Option<Revset>,
 ) -> EyreExitOr<()> {
     let now = SystemTime::now();
     let timestamp = now.duration_since(SystemTime::UNIX_EPOCH)?.as_secs_f64();
@@ -74,6 +82,38 @@ pub fn amend(
     };
     let head_commit = repo.find_commit_or_fail(head_oid)?;
 
+    let message = match message_from {
+        None => None,
+        Some(revset) => {
+            let message_oid = match resolve_commits(
+                effects,
+                &repo,
+                &mut dag,
+                &[revset.clone()],
+                resolve_revset_options,
+            ) {
+                Ok(commit_sets) => match dag.commit_set_to_vec(&commit_sets[0])?.as_slice() {
+                    [only_commit_oid] => *only_commit_oid,
+                    other => {
+                        let Revset(expr) = revset;
+                        writeln!(
+                            effects.get_error_stream(),
+                            "Expected revset to expand to exactly 1 commit (got {count}): {expr}",
+                            count = other.len(),
+                        )?;
+                        return Ok(Err(ExitCode(1)));
+                    }
+                },
+                Err(err) => {
+                    err.describe(effects)?;
+                    return Ok(Err(ExitCode(1)));
+                }
+            };
+            let message_commit = repo.find_commit_or_fail(message_oid)?;
+            Some(message_commit.get_message_raw().to_str_lossy().into_owned())
+        }
+    };
+
     let index = repo.get_index()?;
     if index.has_conflicts() {
         writeln!(
@@ -87,10 +127,16 @@ pub fn amend(
         force_rewrite_public_commits: move_options.force_rewrite_public_commits,
         dump_rebase_constraints: move_options.dump_rebase_constraints,
         dump_rebase_plan: move_options.dump_rebase_plan,
+        explain: move_options.explain,
+        keep_empty: move_options.keep_empty,
         detect_duplicate_commits_via_patch_id: move_options.detect_duplicate_commits_via_patch_id,
     };
-    let commits_to_verify = dag.query_descendants(CommitSet::from(head_oid))?;
-    let commits_to_verify = dag.filter_visible_commits(commits_to_verify)?;
+    let commits_to_verify = if no_restack {
+        CommitSet::from(head_oid)
+    } else {
+        let descendants = dag.query_descendants(CommitSet::from(head_oid))?;
+        dag.filter_visible_commits(descendants)?
+    };
     if let Err(err) =
         RebasePlanPermissions::verify_rewrite_set(&dag, build_options, &commits_to_verify)?
     {
@@ -153,7 +199,7 @@ pub fn amend(
                 .collect_vec(),
         }
     };
-    if opts.is_empty() {
+    if opts.is_empty() && message.is_none() {
         writeln!(
             effects.get_output_stream(),
             "There are no uncommitted or staged changes. Nothing to amend."
@@ -177,7 +223,7 @@ pub fn amend(
         None,
         Some(&author),
         Some(&committer),
-        None,
+        message.as_deref(),
         Some(&amended_tree),
     )?;
 
@@ -208,6 +254,10 @@ pub fn amend(
             result
         })?;
 
+        if checkpoint {
+            mark_commit_checkpoint(&repo, head_oid).wrap_err("Marking commit as a checkpoint.")?;
+        }
+
         let target = match &head_info.reference_name {
             Some(name) => CheckoutTarget::Reference(name.clone()),
             None => CheckoutTarget::Oid(amended_commit_oid),
@@ -228,13 +278,18 @@ pub fn amend(
         )?);
     }
 
-    let rebase_plan = {
+    let rebase_plan = if no_restack {
+        // Leave any descendants abandoned; the user can restack them later.
+        None
+    } else {
         let build_options = BuildRebasePlanOptions {
             force_rewrite_public_commits: move_options.force_rewrite_public_commits,
             detect_duplicate_commits_via_patch_id: move_options
                 .detect_duplicate_commits_via_patch_id,
             dump_rebase_constraints: move_options.dump_rebase_constraints,
             dump_rebase_plan: move_options.dump_rebase_plan,
+            explain: move_options.explain,
+            keep_empty: move_options.keep_empty,
         };
         let children = dag.query_children(CommitSet::from(head_oid))?;
         let descendants = dag.query_descendants(children)?;
@@ -315,6 +370,10 @@ pub fn amend(
             dry_run: false,
             preserve_timestamps: get_restack_preserve_timestamps(&repo)?,
             resolve_merge_conflicts: move_options.resolve_merge_conflicts,
+            merge_file_favor: None,
+            reset_author: false,
+            timing: move_options.timing,
+            exec_on_conflict: None,
             check_out_commit_options: CheckOutCommitOptions {
                 additional_args: Default::default(),
                 force_detach: false,
@@ -350,11 +409,7 @@ pub fn amend(
             }
 
             ExecuteRebasePlanResult::DeclinedToMerge { failed_merge_info } => {
-                failed_merge_info.describe(
-                    effects,
-                    &repo,
-                    lib::core::rewrite::MergeConflictRemediation::Restack,
-                )?;
+                failed_merge_info.describe(effects, &repo, MergeConflictRemediation::Restack)?;
                 writeln!(
                     effects.get_output_stream(),
                     "Amending without restacking descendant commits: {}",
@@ -370,7 +425,24 @@ pub fn amend(
         }
     }
 
+    if !no_restack && get_advance_auto(&repo)? {
+        advance_siblings(
+            effects,
+            git_run_info,
+            &repo,
+            &event_log_db,
+            now,
+            event_tx_id,
+            head_oid,
+            amended_commit_oid,
+        )?;
+    }
+
     match opts {
+        // Only the message was amended.
+        _ if opts.is_empty() => {
+            writeln!(effects.get_output_stream(), "Amended commit message.")?;
+        }
         AmendFastOptions::FromIndex { paths } => {
             let staged_changes = Pluralize {
                 determiner: None,
@@ -400,5 +472,136 @@ pub fn amend(
         }
     }
 
+    if checkpoint {
+        writeln!(
+            effects.get_output_stream(),
+            "Saved the original commit as a checkpoint. To recover it, run: git unhide {}",
+            head_commit.get_short_oid()?
+        )?;
+    }
+
     Ok(Ok(()))
 }
+
+/// Move the siblings of the amended commit onto it, in the same way that the
+/// `post-commit` hook does when `branchless.advance.auto` is set.
+#[instrument]
+fn advance_siblings(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+    event_log_db: &EventLogDb,
+    now: SystemTime,
+    event_tx_id: EventTransactionId,
+    old_commit_oid: NonZeroOid,
+    amended_commit_oid: NonZeroOid,
+) -> eyre::Result<()> {
+    let event_replayer = EventReplayer::from_event_log_db(effects, repo, event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    let references_snapshot = repo.get_references_snapshot()?;
+    let dag = Dag::open_and_sync(
+        effects,
+        repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+
+    let amended_commit_set = CommitSet::from(amended_commit_oid);
+    let parents = dag.query_parents(amended_commit_set.clone())?;
+    let children = dag.query_children(parents)?;
+    let siblings = children
+        .difference(&amended_commit_set)
+        .difference(&CommitSet::from(old_commit_oid));
+    let siblings = dag.filter_visible_commits(siblings)?;
+    if dag.set_is_empty(&siblings)? {
+        return Ok(());
+    }
+
+    let build_options = BuildRebasePlanOptions {
+        force_rewrite_public_commits: false,
+        dump_rebase_constraints: false,
+        dump_rebase_plan: false,
+        explain: false,
+        keep_empty: false,
+        detect_duplicate_commits_via_patch_id: true,
+    };
+    let permissions =
+        match RebasePlanPermissions::verify_rewrite_set(&dag, build_options, &siblings)? {
+            Ok(permissions) => permissions,
+            Err(_err) => return Ok(()),
+        };
+
+    let amended_commit = repo.find_commit_or_fail(amended_commit_oid)?;
+    let amended_commit_parents: HashSet<_> = amended_commit.get_parent_oids().into_iter().collect();
+    let mut builder = RebasePlanBuilder::new(&dag, permissions);
+    for sibling_oid in dag.commit_set_to_vec(&siblings)? {
+        let sibling_commit = repo.find_commit_or_fail(sibling_oid)?;
+        let new_parent_oids = sibling_commit
+            .get_parent_oids()
+            .into_iter()
+            .map(|parent_oid| {
+                if amended_commit_parents.contains(&parent_oid) {
+                    amended_commit_oid
+                } else {
+                    parent_oid
+                }
+            })
+            .collect_vec();
+        builder.move_subtree(sibling_oid, new_parent_oids)?;
+    }
+    let thread_pool = ThreadPoolBuilder::new().build()?;
+    let repo_pool = RepoResource::new_pool(repo)?;
+    let rebase_plan = match builder.build(effects, &thread_pool, &repo_pool)? {
+        Ok(Some(rebase_plan)) => rebase_plan,
+        Ok(None) | Err(_) => return Ok(()),
+    };
+
+    writeln!(
+        effects.get_output_stream(),
+        "Advancing {} onto {}.",
+        Pluralize {
+            determiner: None,
+            amount: dag.set_count(&siblings)?,
+            unit: ("commit", "commits"),
+        },
+        effects
+            .get_glyphs()
+            .render(amended_commit.friendly_describe(effects.get_glyphs())?)?,
+    )?;
+    let execute_options = ExecuteRebasePlanOptions {
+        now,
+        event_tx_id,
+        preserve_timestamps: get_restack_preserve_timestamps(repo)?,
+        force_in_memory: true,
+        force_on_disk: false,
+        dry_run: false,
+        resolve_merge_conflicts: false,
+        merge_file_favor: None,
+        reset_author: false,
+        timing: false,
+        exec_on_conflict: None,
+        check_out_commit_options: CheckOutCommitOptions {
+            additional_args: Default::default(),
+            force_detach: false,
+            reset: false,
+            render_smartlog: false,
+        },
+    };
+    match execute_rebase_plan(
+        effects,
+        git_run_info,
+        repo,
+        event_log_db,
+        &rebase_plan,
+        &execute_options,
+    )? {
+        ExecuteRebasePlanResult::Succeeded { rewritten_oids: _ }
+        | ExecuteRebasePlanResult::WouldSucceed
+        | ExecuteRebasePlanResult::Failed { exit_code: _ } => {}
+        ExecuteRebasePlanResult::DeclinedToMerge { failed_merge_info } => {
+            failed_merge_info.describe(effects, repo, MergeConflictRemediation::Insert)?;
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 //! Automatically collects information which may be relevant for a bug report.
 
 use std::collections::HashSet;
+use std::rc::Rc;
 use std::time::SystemTime;
 
 use bugreport::bugreport;
@@ -23,7 +24,7 @@ use lib::core::node_descriptors::{
     DifferentialRevisionDescriptor, ObsolescenceExplanationDescriptor, Redactor,
     RelativeTimeDescriptor,
 };
-use lib::git::{GitRunInfo, Repo, ResolvedReferenceInfo};
+use lib::git::{ConfigRead, GitRunInfo, MaybeZeroOid, NonZeroOid, Repo, ResolvedReferenceInfo};
 
 use git_branchless_init::{ALL_HOOKS, Hook, determine_hook_path};
 
@@ -151,7 +152,7 @@ fn describe_event_cursor(
         &mut [
             &mut CommitOidDescriptor::new(true)?,
             &mut RelativeTimeDescriptor::new(repo, now)?,
-            &mut ObsolescenceExplanationDescriptor::new(event_replayer, event_cursor)?,
+            &mut ObsolescenceExplanationDescriptor::new(repo, event_replayer, event_cursor)?,
             &mut BranchesDescriptor::new(repo, head_info, references_snapshot, redactor)?,
             &mut DifferentialRevisionDescriptor::new(repo, redactor)?,
             &mut CommitMessageDescriptor::new(redactor)?,
@@ -171,7 +172,11 @@ fn describe_event_cursor(
     .concat())
 }
 
-fn collect_events(effects: &Effects, git_run_info: &GitRunInfo) -> eyre::Result<ReportEntry> {
+fn collect_events(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    redactor: &Redactor,
+) -> eyre::Result<ReportEntry> {
     let now = SystemTime::now();
     let repo = Repo::from_dir(&git_run_info.working_directory)?;
     let head_info = repo.get_head_info()?;
@@ -188,12 +193,6 @@ fn collect_events(effects: &Effects, git_run_info: &GitRunInfo) -> eyre::Result<
         &references_snapshot,
     )?;
 
-    let redactor = Redactor::new({
-        let mut preserved_ref_names = HashSet::new();
-        preserved_ref_names.insert(repo.get_main_branch()?.get_reference_name()?);
-        preserved_ref_names
-    });
-
     let mut event_text_lines = Vec::new();
     let num_events = 5;
     for i in 0..num_events {
@@ -206,28 +205,28 @@ fn collect_events(effects: &Effects, git_run_info: &GitRunInfo) -> eyre::Result<
             &mut dag,
             &head_info,
             &references_snapshot,
-            &redactor,
+            redactor,
             event_cursor,
         )?;
 
         event_text_lines.extend(lines);
     }
+    let event_text = redactor.redact_oids(&event_text_lines.join("\n"));
     Ok(ReportEntry::Text(format!(
         "
 <details>
-<summary>Show {} events</summary>
+<summary>Show {num_events} events</summary>
 
-{}
+{event_text}
 
-</details>",
-        num_events,
-        event_text_lines.join("\n")
+</details>"
     )))
 }
 
 struct EventCollector {
     effects: Effects,
     git_run_info: GitRunInfo,
+    redactor: Rc<Redactor>,
 }
 
 impl Collector for EventCollector {
@@ -239,7 +238,7 @@ impl Collector for EventCollector {
         &mut self,
         _crate_info: &bugreport::CrateInfo,
     ) -> Result<ReportEntry, CollectionError> {
-        collect_events(&self.effects, &self.git_run_info)
+        collect_events(&self.effects, &self.git_run_info, &self.redactor)
             .map_err(|e| CollectionError::CouldNotRetrieve(format!("Error: {e}")))
     }
 }
@@ -318,21 +317,159 @@ impl Collector for HookCollector {
     }
 }
 
+struct ConfigCollector {
+    git_run_info: GitRunInfo,
+    redactor: Rc<Redactor>,
+}
+
+fn collect_config(git_run_info: &GitRunInfo, redactor: &Redactor) -> eyre::Result<ReportEntry> {
+    let repo = Repo::from_dir(&git_run_info.working_directory)?;
+    let config = repo.get_readonly_config()?;
+    let entries = config.list(r"^branchless\.")?;
+    let num_entries = entries.len();
+    let config_lines = entries
+        .into_iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .join("\n");
+    let config_lines = redactor.redact_oids(&config_lines);
+
+    Ok(ReportEntry::Text(format!(
+        "
+<details>
+<summary>Show {num_entries} config entries</summary>
+
+```
+{config_lines}
+```
+
+</details>"
+    )))
+}
+
+impl Collector for ConfigCollector {
+    fn description(&self) -> &str {
+        "Config"
+    }
+
+    fn collect(
+        &mut self,
+        _crate_info: &bugreport::CrateInfo,
+    ) -> Result<ReportEntry, CollectionError> {
+        collect_config(&self.git_run_info, &self.redactor)
+            .map_err(|e| CollectionError::CouldNotRetrieve(format!("Error: {e}")))
+    }
+}
+
+/// Get the OIDs known from the event log and the references in the repository,
+/// which are the ones that may appear in the report.
+fn get_known_oids(
+    repo: &Repo,
+    references_snapshot: &RepoReferencesSnapshot,
+) -> eyre::Result<HashSet<NonZeroOid>> {
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+
+    let mut oids: HashSet<NonZeroOid> = references_snapshot
+        .branch_oid_to_names
+        .keys()
+        .copied()
+        .collect();
+    oids.extend(references_snapshot.head_oid);
+    for event in event_log_db.get_events()? {
+        // Explicitly list all variants and fields here so we're forced to audit it if we add any.
+        let (maybe_zero_oids, commit_oid) = match event {
+            Event::RewriteEvent {
+                timestamp: _,
+                event_tx_id: _,
+                old_commit_oid,
+                new_commit_oid,
+            } => (vec![old_commit_oid, new_commit_oid], None),
+            Event::RefUpdateEvent {
+                timestamp: _,
+                event_tx_id: _,
+                ref_name: _,
+                old_oid,
+                new_oid,
+                message: _,
+            } => (vec![old_oid, new_oid], None),
+            Event::CommitEvent {
+                timestamp: _,
+                event_tx_id: _,
+                commit_oid,
+            }
+            | Event::ObsoleteEvent {
+                timestamp: _,
+                event_tx_id: _,
+                commit_oid,
+            }
+            | Event::UnobsoleteEvent {
+                timestamp: _,
+                event_tx_id: _,
+                commit_oid,
+            } => (vec![], Some(commit_oid)),
+            Event::WorkingCopySnapshot {
+                timestamp: _,
+                event_tx_id: _,
+                head_oid,
+                commit_oid,
+                ref_name: _,
+            } => (vec![head_oid], Some(commit_oid)),
+        };
+        oids.extend(commit_oid);
+        oids.extend(maybe_zero_oids.into_iter().filter_map(|oid| match oid {
+            MaybeZeroOid::NonZero(oid) => Some(oid),
+            MaybeZeroOid::Zero => None,
+        }));
+    }
+    Ok(oids)
+}
+
+/// Make the redactor used for the whole report. Ref names and commit messages
+/// are always redacted, and OIDs are redacted if `redact_oids` is set.
+fn make_redactor(git_run_info: &GitRunInfo, redact_oids: bool) -> eyre::Result<Redactor> {
+    let repo = Repo::from_dir(&git_run_info.working_directory)?;
+    let redactor = Redactor::new({
+        let mut preserved_ref_names = HashSet::new();
+        for main_branch in repo.get_main_branches()? {
+            preserved_ref_names.insert(main_branch.get_reference_name()?);
+        }
+        preserved_ref_names
+    });
+    if redact_oids {
+        let references_snapshot = repo.get_references_snapshot()?;
+        let known_oids = get_known_oids(&repo, &references_snapshot)?;
+        Ok(redactor.with_redacted_oids(known_oids))
+    } else {
+        Ok(redactor)
+    }
+}
+
 /// Generate information suitable for inclusion in a bug report.
-pub fn bug_report(effects: &Effects, git_run_info: &GitRunInfo) -> EyreExitOr<()> {
+pub fn bug_report(effects: &Effects, git_run_info: &GitRunInfo, redact: bool) -> EyreExitOr<()> {
     use bugreport::collector::*;
+    // The individual collectors report their own errors (such as not being in
+    // a repository), so don't fail the whole report here.
+    let redactor = Rc::new(
+        make_redactor(git_run_info, redact)
+            .unwrap_or_else(|_err| Redactor::new(Default::default())),
+    );
     bugreport!()
         .info(SoftwareVersion::default())
         .info(OperatingSystem::default())
         .info(CommandLine::default())
         .info(EnvironmentVariables::list(&["SHELL", "EDITOR"]))
         .info(CommandOutput::new("Git version", "git", &["version"]))
+        .info(ConfigCollector {
+            git_run_info: git_run_info.clone(),
+            redactor: Rc::clone(&redactor),
+        })
         .info(HookCollector {
             git_run_info: git_run_info.clone(),
         })
         .info(EventCollector {
             effects: effects.clone(),
             git_run_info: git_run_info.clone(),
+            redactor,
         })
         .print::<Markdown>();
 
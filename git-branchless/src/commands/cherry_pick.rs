@@ -0,0 +1,184 @@
+//! Copy commits onto the current commit, without hiding the originals.
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bstr::ByteSlice;
+use git_branchless_opts::{ResolveRevsetOptions, Revset};
+use git_branchless_revset::resolve_commits;
+use lib::core::check_out::{CheckOutCommitOptions, CheckoutTarget, check_out_commit};
+use lib::core::config::get_restack_preserve_timestamps;
+use lib::core::dag::{Dag, sorted_commit_set, union_all};
+use lib::core::effects::Effects;
+use lib::core::eventlog::{Event, EventLogDb, EventReplayer};
+use lib::core::gc::mark_commit_reachable;
+use lib::core::repo_ext::RepoExt;
+use lib::core::rewrite::{FailedMergeInfo, MergeConflictRemediation};
+use lib::git::{CherryPickFastOptions, CreateCommitFastError, GitRunInfo, Repo};
+use lib::try_exit_code;
+use lib::util::{ExitCode, EyreExitOr};
+use tracing::instrument;
+
+/// Apply the given commits on top of `HEAD`, in topological order. The
+/// original commits are left in place.
+#[instrument]
+pub fn cherry_pick(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    revsets: Vec<Revset>,
+    resolve_revset_options: &ResolveRevsetOptions,
+) -> EyreExitOr<()> {
+    let repo = Repo::from_current_dir()?;
+    let head_info = repo.get_head_info()?;
+    let references_snapshot = repo.get_references_snapshot()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+    let event_replayer = EventReplayer::from_event_log_db(effects, &repo, &event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    let mut dag = Dag::open_and_sync(
+        effects,
+        &repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+
+    let head_oid = match head_info.oid {
+        Some(head_oid) => head_oid,
+        None => {
+            writeln!(
+                effects.get_error_stream(),
+                "No commit is currently checked out to cherry-pick onto."
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
+    let commits = match resolve_commits(effects, &repo, &mut dag, &revsets, resolve_revset_options)
+    {
+        Ok(commit_sets) => sorted_commit_set(&repo, &dag, &union_all(&commit_sets))?,
+        Err(err) => {
+            err.describe(effects)?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+    if commits.is_empty() {
+        writeln!(effects.get_output_stream(), "No commits to cherry-pick.")?;
+        return Ok(Ok(()));
+    }
+    if let Some(merge_commit) = commits.iter().find(|commit| commit.get_parent_count() > 1) {
+        writeln!(
+            effects.get_error_stream(),
+            "Merge commits can't be cherry-picked: {}",
+            effects
+                .get_glyphs()
+                .render(merge_commit.friendly_describe(effects.get_glyphs())?)?,
+        )?;
+        return Ok(Err(ExitCode(1)));
+    }
+
+    let now = SystemTime::now();
+    let preserve_timestamps = get_restack_preserve_timestamps(&repo)?;
+    let mut current_oid = head_oid;
+    let mut new_commit_oids = Vec::new();
+    for (i, commit) in commits.iter().enumerate() {
+        let current_commit = repo.find_commit_or_fail(current_oid)?;
+        let tree = match repo.cherry_pick_fast(
+            commit,
+            &current_commit,
+            &CherryPickFastOptions {
+                reuse_parent_tree_if_possible: true,
+                merge_file_favor: None,
+                use_rerere: false,
+            },
+        ) {
+            Ok(tree) => tree,
+            Err(CreateCommitFastError::MergeConflict { conflicting_paths }) => {
+                FailedMergeInfo::Conflict {
+                    commit_oid: commit.get_oid(),
+                    conflicting_paths,
+                }
+                .describe(effects, &repo, MergeConflictRemediation::CherryPick)?;
+                return Ok(Err(ExitCode(1)));
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let committer = if preserve_timestamps {
+            commit.get_committer()
+        } else {
+            commit.get_committer().update_timestamp(now)?
+        };
+        let new_commit_oid = repo.create_commit(
+            None,
+            &commit.get_author(),
+            &committer,
+            &commit.get_message_raw().to_str_lossy(),
+            &tree,
+            vec![&current_commit],
+        )?;
+        writeln!(
+            effects.get_output_stream(),
+            "[{}/{}] Committed as: {}",
+            i + 1,
+            commits.len(),
+            effects.get_glyphs().render(
+                repo.friendly_describe_commit_from_oid(effects.get_glyphs(), new_commit_oid)?
+            )?,
+        )?;
+        current_oid = new_commit_oid;
+        new_commit_oids.push(new_commit_oid);
+    }
+
+    let event_tx_id = event_log_db.make_transaction_id(now, "cherry-pick")?;
+    for new_commit_oid in new_commit_oids.iter().copied() {
+        mark_commit_reachable(&repo, new_commit_oid)?;
+    }
+    event_log_db.add_events(
+        new_commit_oids
+            .iter()
+            .map(|new_commit_oid| -> eyre::Result<Event> {
+                Ok(Event::CommitEvent {
+                    timestamp: now.duration_since(UNIX_EPOCH)?.as_secs_f64(),
+                    event_tx_id,
+                    commit_oid: *new_commit_oid,
+                })
+            })
+            .collect::<eyre::Result<_>>()?,
+    )?;
+
+    // Like `git cherry-pick`, advance the current branch, if any, to the new
+    // commits.
+    try_exit_code!(check_out_commit(
+        effects,
+        git_run_info,
+        &repo,
+        &event_log_db,
+        event_tx_id,
+        Some(CheckoutTarget::Oid(current_oid)),
+        &CheckOutCommitOptions {
+            force_detach: true,
+            render_smartlog: head_info.reference_name.is_none(),
+            ..Default::default()
+        },
+    )?);
+    if let Some(reference_name) = head_info.reference_name {
+        repo.create_reference(
+            &reference_name,
+            current_oid,
+            true,
+            "branchless: cherry-pick",
+        )?;
+        try_exit_code!(check_out_commit(
+            effects,
+            git_run_info,
+            &repo,
+            &event_log_db,
+            event_tx_id,
+            Some(CheckoutTarget::Reference(reference_name)),
+            &CheckOutCommitOptions::default(),
+        )?);
+    }
+
+    Ok(Ok(()))
+}
@@ -0,0 +1,143 @@
+//! View and update git-branchless configuration, checking config keys and
+//! values against the set of keys that git-branchless recognizes.
+
+use std::fmt::Write;
+
+use git_branchless_revset::parse;
+use lib::core::config::{CONFIG_SCHEMA, ConfigValueType, find_config_key_schema};
+use lib::core::effects::Effects;
+use lib::git::{Config, ConfigRead, ConfigWrite, GitRunInfo, Repo};
+use lib::util::{ExitCode, EyreExitOr};
+use tracing::instrument;
+
+/// Set the given config key in the repository configuration, if the key is
+/// known and the value is valid for it.
+#[instrument]
+pub fn set(effects: &Effects, git_run_info: &GitRunInfo, key: &str, value: &str) -> EyreExitOr<()> {
+    let repo = Repo::from_dir(&git_run_info.working_directory)?;
+
+    let schema = match find_config_key_schema(key) {
+        Some(schema) => schema,
+        None => {
+            writeln!(effects.get_error_stream(), "Unknown config key: {key}")?;
+            writeln!(
+                effects.get_error_stream(),
+                "To see the known config keys, run: git branchless config list"
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
+    let config_value = match schema.value_type.parse(value) {
+        Ok(config_value) => config_value,
+        Err(err) => {
+            writeln!(
+                effects.get_error_stream(),
+                "Invalid value for config key {key}: {err}"
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+    if schema.value_type == ConfigValueType::Revset {
+        if let Err(err) = parse(value) {
+            writeln!(
+                effects.get_error_stream(),
+                "Invalid value for config key {key}: {err}"
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    }
+
+    let message = format!("Set {key} to: {config_value}");
+    let mut config = Config::open(&repo.get_config_path()?)?;
+    config.set(key, config_value)?;
+    writeln!(effects.get_output_stream(), "{message}")?;
+    Ok(Ok(()))
+}
+
+/// Print the value of the given config key, falling back to its default if
+/// it's not set. If `effective` is set, also print where the value was set.
+#[instrument]
+pub fn get(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    key: &str,
+    effective: bool,
+) -> EyreExitOr<()> {
+    let repo = Repo::from_dir(&git_run_info.working_directory)?;
+
+    let schema = match find_config_key_schema(key) {
+        Some(schema) => schema,
+        None => {
+            writeln!(effects.get_error_stream(), "Unknown config key: {key}")?;
+            writeln!(
+                effects.get_error_stream(),
+                "To see the known config keys, run: git branchless config list"
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
+    let config = repo.get_readonly_config()?;
+    let (value, origin) = match config.get_effective(key)? {
+        Some((value, origin)) => (value, origin.to_string()),
+        None => match schema.default {
+            Some(default) => (default.to_string(), "default".to_string()),
+            None => {
+                writeln!(effects.get_error_stream(), "Config key is not set: {key}")?;
+                return Ok(Err(ExitCode(1)));
+            }
+        },
+    };
+    if effective {
+        writeln!(effects.get_output_stream(), "{value} ({origin})")?;
+    } else {
+        writeln!(effects.get_output_stream(), "{value}")?;
+    }
+    Ok(Ok(()))
+}
+
+/// List the effective value of each known config key, as well as any unknown
+/// `branchless.*` keys which have been set.
+#[instrument]
+pub fn list(effects: &Effects, git_run_info: &GitRunInfo) -> EyreExitOr<()> {
+    let repo = Repo::from_dir(&git_run_info.working_directory)?;
+    let config = repo.get_readonly_config()?;
+    let entries = config.list_effective(r"^branchless\.")?;
+
+    for schema in CONFIG_SCHEMA {
+        let mut is_set = false;
+        for (name, value, source) in entries.iter() {
+            if find_config_key_schema(name).map(|entry_schema| entry_schema.key) == Some(schema.key)
+            {
+                let name = if schema.key.ends_with('*') {
+                    name.as_str()
+                } else {
+                    schema.key
+                };
+                writeln!(effects.get_output_stream(), "{name} = {value} ({source})")?;
+                is_set = true;
+            }
+        }
+        if !is_set {
+            if let Some(default) = schema.default {
+                writeln!(
+                    effects.get_output_stream(),
+                    "{} = {default} (default)",
+                    schema.key
+                )?;
+            }
+        }
+    }
+
+    for (name, value, source) in entries.iter() {
+        if find_config_key_schema(name).is_none() {
+            writeln!(
+                effects.get_output_stream(),
+                "{name} = {value} ({source}, unknown key)"
+            )?;
+        }
+    }
+
+    Ok(Ok(()))
+}
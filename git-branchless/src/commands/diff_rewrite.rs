@@ -0,0 +1,97 @@
+//! Compare a commit with the version it was rewritten into.
+
+use std::fmt::Write;
+
+use git_branchless_opts::{ResolveRevsetOptions, Revset};
+use git_branchless_revset::resolve_commits;
+use lib::core::dag::Dag;
+use lib::core::effects::Effects;
+use lib::core::eventlog::{EventLogDb, EventReplayer};
+use lib::core::repo_ext::RepoExt;
+use lib::core::rewrite::find_rewrite_target;
+use lib::git::{GitRunInfo, MaybeZeroOid, NonZeroOid, Repo};
+use lib::util::{ExitCode, EyreExitOr};
+
+/// Look up the commit that the given commit was rewritten into according to
+/// the event log, and launch `git difftool` to compare the two.
+pub fn diff_rewrite(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    revset: Revset,
+) -> EyreExitOr<()> {
+    let repo = Repo::from_current_dir()?;
+    let references_snapshot = repo.get_references_snapshot()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+    let event_replayer = EventReplayer::from_event_log_db(effects, &repo, &event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    let mut dag = Dag::open_and_sync(
+        effects,
+        &repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+
+    // The commit being looked up has usually been rewritten, so it's probably
+    // hidden.
+    let resolve_revset_options = ResolveRevsetOptions {
+        show_hidden_commits: true,
+    };
+    let old_oid: NonZeroOid = match resolve_commits(
+        effects,
+        &repo,
+        &mut dag,
+        std::slice::from_ref(&revset),
+        &resolve_revset_options,
+    ) {
+        Ok(commit_sets) => match dag.commit_set_to_vec(&commit_sets[0])?.as_slice() {
+            [only_commit_oid] => *only_commit_oid,
+            other => {
+                let Revset(expr) = revset;
+                writeln!(
+                    effects.get_error_stream(),
+                    "Expected revset to expand to exactly 1 commit (got {}): {}",
+                    other.len(),
+                    expr,
+                )?;
+                return Ok(Err(ExitCode(1)));
+            }
+        },
+        Err(err) => {
+            err.describe(effects)?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
+    let old_commit = repo.find_commit_or_fail(old_oid)?;
+    let new_oid = match find_rewrite_target(&event_replayer, event_cursor, old_oid) {
+        Some(MaybeZeroOid::NonZero(new_oid)) => new_oid,
+        Some(MaybeZeroOid::Zero) => {
+            writeln!(
+                effects.get_output_stream(),
+                "Commit was deleted rather than rewritten: {}",
+                effects
+                    .get_glyphs()
+                    .render(old_commit.friendly_describe(effects.get_glyphs())?)?,
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+        None => {
+            writeln!(
+                effects.get_output_stream(),
+                "Commit has not been rewritten: {}",
+                effects
+                    .get_glyphs()
+                    .render(old_commit.friendly_describe(effects.get_glyphs())?)?,
+            )?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+
+    git_run_info.run(
+        effects,
+        None,
+        &["difftool", &old_oid.to_string(), &new_oid.to_string()],
+    )
+}
@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use itertools::Itertools;
+use lib::core::dag::Dag;
+use lib::core::effects::Effects;
+use lib::core::eventlog::{EventLogDb, EventReplayer};
+use lib::core::formatting::Pluralize;
+use lib::core::repo_ext::{RepoExt, RepoReferencesSnapshot};
+use lib::git::{CategorizedReferenceName, NonZeroOid, ReferenceName, Repo};
+use lib::util::{ExitCode, EyreExitOr};
+
+/// Map each branch in the snapshot to the commit that it points to.
+fn get_branch_oids(
+    references_snapshot: &RepoReferencesSnapshot,
+) -> BTreeMap<ReferenceName, NonZeroOid> {
+    references_snapshot
+        .branch_oid_to_names
+        .iter()
+        .flat_map(|(oid, reference_names)| {
+            reference_names
+                .iter()
+                .map(move |reference_name| (reference_name.clone(), *oid))
+        })
+        .collect()
+}
+
+/// Compare the references recorded in the event log against the actual
+/// references in the repository, and describe any differences.
+fn find_event_log_drift(
+    recorded_snapshot: &RepoReferencesSnapshot,
+    actual_snapshot: &RepoReferencesSnapshot,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let recorded_main_branch_oids: BTreeSet<_> =
+        recorded_snapshot.main_branch_oids.iter().collect();
+    let actual_main_branch_oids: BTreeSet<_> = actual_snapshot.main_branch_oids.iter().collect();
+    if recorded_main_branch_oids != actual_main_branch_oids {
+        problems.push(format!(
+            "The main branch points to {}, but the event log has it at {}",
+            actual_main_branch_oids.iter().join(", "),
+            recorded_main_branch_oids.iter().join(", "),
+        ));
+    }
+
+    let recorded_branch_oids = get_branch_oids(recorded_snapshot);
+    let actual_branch_oids = get_branch_oids(actual_snapshot);
+    let branch_names: BTreeSet<_> = recorded_branch_oids
+        .keys()
+        .chain(actual_branch_oids.keys())
+        .collect();
+    for branch_name in branch_names {
+        let rendered_name = CategorizedReferenceName::new(branch_name).render_suffix();
+        match (
+            recorded_branch_oids.get(branch_name),
+            actual_branch_oids.get(branch_name),
+        ) {
+            (Some(recorded_oid), Some(actual_oid)) if recorded_oid != actual_oid => {
+                problems.push(format!(
+                    "Branch {rendered_name} points to {actual_oid}, but the event log has it at {recorded_oid}"
+                ));
+            }
+            (Some(_), Some(_)) | (None, None) => {}
+            (Some(recorded_oid), None) => {
+                problems.push(format!(
+                    "Branch {rendered_name} was deleted, but the event log has it at {recorded_oid}"
+                ));
+            }
+            (None, Some(actual_oid)) => {
+                problems.push(format!(
+                    "Branch {rendered_name} points to {actual_oid}, but it isn't in the event log"
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+/// Check that the commit graph and event log agree with the actual state of
+/// the repository.
+pub fn doctor(effects: &Effects, deep: bool) -> EyreExitOr<()> {
+    let repo = Repo::from_current_dir()?;
+    let references_snapshot = repo.get_references_snapshot()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+    let event_replayer = EventReplayer::from_event_log_db(effects, &repo, &event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+
+    let dag = Dag::open_without_syncing(
+        effects,
+        &repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+    let referenced_commits = dag
+        .head_commit
+        .union(&dag.main_branch_commit)
+        .union(&dag.branch_commits);
+    let missing_commits = referenced_commits.difference(&dag.query_all()?);
+    let missing_oids = dag.commit_set_to_vec(&missing_commits)?;
+    drop(dag);
+    if !missing_oids.is_empty() {
+        writeln!(
+            effects.get_output_stream(),
+            "Found {} missing from the commit graph: {}",
+            Pluralize {
+                determiner: None,
+                amount: missing_oids.len(),
+                unit: ("referenced commit", "referenced commits"),
+            },
+            missing_oids.iter().sorted().join(", "),
+        )?;
+        Dag::open_and_sync(
+            effects,
+            &repo,
+            &event_replayer,
+            event_cursor,
+            &references_snapshot,
+        )?;
+        writeln!(effects.get_output_stream(), "Resynced the commit graph.")?;
+    }
+
+    let problems = if deep {
+        let recorded_snapshot = event_replayer.get_references_snapshot(&repo, event_cursor)?;
+        find_event_log_drift(&recorded_snapshot, &references_snapshot)
+    } else {
+        Vec::new()
+    };
+    if problems.is_empty() {
+        writeln!(effects.get_output_stream(), "No problems found.")?;
+        return Ok(Ok(()));
+    }
+
+    for problem in problems.iter() {
+        writeln!(effects.get_output_stream(), "{problem}")?;
+    }
+    writeln!(
+        effects.get_output_stream(),
+        "Found {} between the event log and the repository. Operations such as `git undo` may not behave as expected.",
+        Pluralize {
+            determiner: None,
+            amount: problems.len(),
+            unit: ("difference", "differences"),
+        },
+    )?;
+    Ok(Err(ExitCode(1)))
+}
@@ -1,24 +1,44 @@
 //! Handle obsoleting commits when explicitly requested by the user (as opposed to
 //! automatically as the result of a rewrite operation).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
+use std::io::{BufRead, BufReader, Read, stdin};
 use std::time::SystemTime;
 
 use git_branchless_opts::{ResolveRevsetOptions, Revset};
-use lib::core::dag::{Dag, union_all};
+use lib::core::config::get_hide_confirm_unique;
+use lib::core::dag::{CommitSet, Dag, union_all};
 use lib::core::effects::Effects;
 use lib::core::eventlog::{CommitActivityStatus, Event};
 use lib::core::eventlog::{EventLogDb, EventReplayer};
 use lib::core::formatting::{Glyphs, Pluralize};
-use lib::core::repo_ext::RepoExt;
+use lib::core::repo_ext::{RepoExt, RepoReferencesSnapshot};
 use lib::core::rewrite::move_branches;
-use lib::git::{CategorizedReferenceName, GitRunInfo, MaybeZeroOid, NonZeroOid, Repo};
+use lib::git::{CategorizedReferenceName, Commit, GitRunInfo, MaybeZeroOid, NonZeroOid, Repo};
 use lib::util::{ExitCode, EyreExitOr};
 use tracing::instrument;
 
 use git_branchless_revset::resolve_commits;
 
+/// A range like `main..feature` or `main::feature` includes every commit
+/// between the two endpoints, which may include public commits if `feature`
+/// wasn't based on `main`. If the resolved set contains any non-public commits,
+/// only keep those. A set consisting entirely of public commits must have been
+/// requested explicitly, so it's kept as-is.
+fn without_public_commits(
+    dag: &Dag,
+    commit_set: CommitSet,
+    public_commits: &CommitSet,
+) -> eyre::Result<CommitSet> {
+    let non_public_commits = commit_set.difference(public_commits);
+    if dag.set_is_empty(&non_public_commits)? {
+        Ok(commit_set)
+    } else {
+        Ok(non_public_commits)
+    }
+}
+
 /// Hide the hashes provided on the command-line.
 #[instrument]
 pub fn hide(
@@ -55,9 +75,33 @@ pub fn hide(
             }
         };
 
+    let public_commits = dag.query_public_commits_slow()?.clone();
+    let commit_sets = commit_sets
+        .into_iter()
+        .map(|commit_set| without_public_commits(&dag, commit_set, &public_commits))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let draft_commits = dag.query_draft_commits()?.clone();
+
     let commits = union_all(&commit_sets);
     let commits = if recursive {
-        dag.filter_visible_commits(dag.query_descendants(commits)?)?
+        // Only expand to draft descendants, so that hiding a commit doesn't
+        // also hide the main branch built on top of it.
+        let descendants = dag
+            .query_descendants(commits.clone())?
+            .intersection(&draft_commits);
+        // If branches are being kept, then the commits they point to (and
+        // their ancestors) should stay visible too.
+        let descendants = if delete_branches {
+            descendants
+        } else {
+            let branch_commits: CommitSet = references_snapshot
+                .branch_oid_to_names
+                .keys()
+                .copied()
+                .collect();
+            descendants.difference(&dag.query_ancestors(branch_commits)?)
+        };
+        dag.filter_visible_commits(commits.union(&descendants))?
     } else {
         commits
     };
@@ -67,6 +111,34 @@ pub fn hide(
         .map(|commit_oid| repo.find_commit_or_fail(commit_oid))
         .collect::<Result<Vec<_>, _>>()?;
 
+    if get_hide_confirm_unique(&repo)? {
+        let unique_commits =
+            find_unique_commits(&dag, &references_snapshot, &commits, delete_branches)?;
+        if !unique_commits.is_empty() {
+            writeln!(
+                effects.get_output_stream(),
+                "Warning: {} will not be reachable from any remaining branch or HEAD:",
+                Pluralize {
+                    determiner: None,
+                    amount: unique_commits.len(),
+                    unit: ("commit", "commits"),
+                }
+            )?;
+            for commit_oid in unique_commits {
+                let commit = repo.find_commit_or_fail(commit_oid)?;
+                writeln!(
+                    effects.get_output_stream(),
+                    "- {}",
+                    glyphs.render(commit.friendly_describe(&glyphs)?)?,
+                )?;
+            }
+            if !confirm_hide_unique(effects, &mut stdin())? {
+                writeln!(effects.get_output_stream(), "Aborting hide.")?;
+                return Ok(Err(ExitCode(1)));
+            }
+        }
+    }
+
     let timestamp = now.duration_since(SystemTime::UNIX_EPOCH)?.as_secs_f64();
     let event_tx_id = event_log_db.make_transaction_id(now, "hide")?;
     let events = commits
@@ -182,6 +254,49 @@ pub fn hide(
     Ok(Ok(()))
 }
 
+/// Find the commits among `commits` which won't be reachable from any branch
+/// or `HEAD` once they're hidden, i.e. the commits whose work would only be
+/// recoverable via `git undo`.
+fn find_unique_commits(
+    dag: &Dag,
+    references_snapshot: &RepoReferencesSnapshot,
+    commits: &[Commit],
+    delete_branches: bool,
+) -> eyre::Result<Vec<NonZeroOid>> {
+    let hidden_oids: HashSet<NonZeroOid> = commits.iter().map(|commit| commit.get_oid()).collect();
+    let remaining_heads: CommitSet = references_snapshot
+        .branch_oid_to_names
+        .keys()
+        .copied()
+        .filter(|oid| !(delete_branches && hidden_oids.contains(oid)))
+        .chain(
+            references_snapshot
+                .head_oid
+                .filter(|oid| !hidden_oids.contains(oid)),
+        )
+        .collect();
+    let reachable = dag.query_ancestors(remaining_heads)?;
+    let unique_commits = hidden_oids
+        .into_iter()
+        .collect::<CommitSet>()
+        .difference(&reachable);
+    dag.sort(&unique_commits)
+}
+
+fn confirm_hide_unique(effects: &Effects, in_: &mut impl Read) -> eyre::Result<bool> {
+    write!(effects.get_output_stream(), "Hide anyways? [yN] ")?;
+    let mut user_input = String::new();
+    let mut reader = BufReader::new(in_);
+    let confirmed = match reader.read_line(&mut user_input) {
+        Ok(_size) => {
+            let user_input = user_input.trim();
+            user_input == "y" || user_input == "Y"
+        }
+        Err(_) => false,
+    };
+    Ok(confirmed)
+}
+
 /// Unhide the hashes provided on the command-line.
 #[instrument]
 pub fn unhide(
@@ -206,8 +321,17 @@ pub fn unhide(
         &references_snapshot,
     )?;
 
+    // Revset functions only return visible commits by default, which would
+    // leave nothing to unhide for expressions like `main..feature`, so always
+    // include hidden commits regardless of the provided options.
+    let ResolveRevsetOptions {
+        show_hidden_commits: _,
+    } = resolve_revset_options;
+    let resolve_revset_options = ResolveRevsetOptions {
+        show_hidden_commits: true,
+    };
     let commit_sets =
-        match resolve_commits(effects, &repo, &mut dag, &revsets, resolve_revset_options) {
+        match resolve_commits(effects, &repo, &mut dag, &revsets, &resolve_revset_options) {
             Ok(commit_sets) => commit_sets,
             Err(err) => {
                 err.describe(effects)?;
@@ -215,6 +339,12 @@ pub fn unhide(
             }
         };
 
+    let public_commits = dag.query_public_commits_slow()?.clone();
+    let commit_sets = commit_sets
+        .into_iter()
+        .map(|commit_set| without_public_commits(&dag, commit_set, &public_commits))
+        .collect::<eyre::Result<Vec<_>>>()?;
+
     let commits = union_all(&commit_sets);
     let commits = if recursive {
         dag.query_descendants(commits)?
@@ -1,13 +1,22 @@
 //! Sub-commands of `git-branchless`.
 
+mod absorb;
 mod advance;
 mod amend;
 mod bug_report;
+mod cherry_pick;
+mod config;
+mod diff_rewrite;
+mod doctor;
 mod hide;
+mod prune_worktrees;
+mod reflog;
+mod rename_branch;
 mod repair;
 mod restack;
 mod snapshot;
 mod split;
+mod status;
 mod sync;
 mod wrap;
 
@@ -18,8 +27,8 @@ use lib::util::ExitCode;
 use lib::{core::gc, util::EyreExitOr};
 
 use git_branchless_opts::{
-    Command, MessageArgs, Opts, ResolveRevsetOptions, SnapshotSubcommand, WrappedCommand,
-    rewrite_args,
+    Command, ConfigSubcommand, MessageArgs, Opts, ResolveRevsetOptions, SnapshotSubcommand,
+    WrappedCommand, rewrite_args,
 };
 use lib::git::GitRunInfo;
 
@@ -34,24 +43,61 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
     } = opts;
 
     let exit_code = match command {
-        Command::Advance { move_options } => {
-            advance::advance(&effects, &git_run_info, &move_options)?
-        }
+        Command::Advance {
+            move_options,
+            no_public,
+        } => advance::advance(&effects, &git_run_info, &move_options, no_public)?,
+
+        Command::Amend {
+            move_options,
+            reparent: _,
+            no_restack: _,
+            untracked_file_strategy: _,
+            absorb: true,
+            checkpoint: _,
+            message_from: _,
+        } => absorb::absorb(&effects, &git_run_info, &move_options)?,
 
         Command::Amend {
             move_options,
             reparent,
+            no_restack,
             untracked_file_strategy,
+            absorb: false,
+            checkpoint,
+            message_from,
         } => amend::amend(
             &effects,
             &git_run_info,
             &ResolveRevsetOptions::default(),
             &move_options,
             reparent,
+            no_restack,
             untracked_file_strategy,
+            checkpoint,
+            message_from,
         )?,
 
-        Command::BugReport => bug_report::bug_report(&effects, &git_run_info)?,
+        Command::BugReport { redact } => bug_report::bug_report(&effects, &git_run_info, redact)?,
+
+        Command::CherryPick {
+            revsets,
+            resolve_revset_options,
+        } => cherry_pick::cherry_pick(&effects, &git_run_info, revsets, &resolve_revset_options)?,
+
+        Command::Config { subcommand } => match subcommand {
+            ConfigSubcommand::Set { key, value } => {
+                config::set(&effects, &git_run_info, &key, &value)?
+            }
+            ConfigSubcommand::Get { key, effective } => {
+                config::get(&effects, &git_run_info, &key, effective)?
+            }
+            ConfigSubcommand::List => config::list(&effects, &git_run_info)?,
+        },
+
+        Command::DiffRewrite { revset } => {
+            diff_rewrite::diff_rewrite(&effects, &git_run_info, revset)?
+        }
 
         Command::Difftool(opts) => {
             let result = scm_diff_editor::run(opts);
@@ -64,6 +110,8 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
             }
         }
 
+        Command::Doctor { deep } => doctor::doctor(&effects, deep)?,
+
         Command::Switch { switch_options } => {
             git_branchless_navigation::switch(&effects, &git_run_info, &switch_options)?
         }
@@ -91,13 +139,19 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
 
         Command::Init(args) => git_branchless_init::command_main(ctx, args)?,
 
+        Command::InstallHooks(args) => git_branchless_init::command_install_hooks(ctx, args)?,
+
         Command::InstallManPages(args) => {
             git_branchless_init::command_install_man_pages(ctx, args)?
         }
 
+        Command::Log => git_branchless_smartlog::log(&effects, &git_run_info)?,
+
         Command::Move {
             source,
             dest,
+            after,
+            before,
             base,
             exact,
             resolve_revset_options,
@@ -105,18 +159,38 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
             fixup,
             insert,
             dry_run,
+            worktree,
+            in_place,
+            reset_author,
+            onto_index,
+            source_stdin,
+            interactive,
+            collapse,
+            exec_on_conflict,
         } => git_branchless_move::r#move(
             &effects,
             &git_run_info,
-            source,
-            dest,
-            base,
-            exact,
-            &resolve_revset_options,
-            &move_options,
-            fixup,
-            insert,
-            dry_run,
+            git_branchless_move::MoveCommandOptions {
+                sources: source,
+                dest,
+                after,
+                before,
+                bases: base,
+                exacts: exact,
+                resolve_revset_options,
+                move_options,
+                fixup,
+                insert,
+                dry_run,
+                worktree,
+                in_place,
+                reset_author,
+                onto_index,
+                source_stdin,
+                interactive,
+                collapse,
+                exec_on_conflict,
+            },
         )?,
 
         Command::Next {
@@ -137,14 +211,25 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
             &traverse_commits_options,
         )?,
 
+        Command::PruneWorktrees { dry_run } => {
+            prune_worktrees::prune_worktrees(&effects, &git_run_info, dry_run)?
+        }
+
         Command::Query(args) => git_branchless_query::command_main(ctx, args)?,
 
+        Command::Reflog { max_count } => reflog::reflog(&effects, max_count)?,
+
+        Command::RenameBranch { old_name, new_name } => {
+            rename_branch::rename_branch(&effects, &git_run_info, &old_name, &new_name)?
+        }
+
         Command::Repair { dry_run } => repair::repair(&effects, dry_run)?,
 
         Command::Restack {
             revsets,
             resolve_revset_options,
             move_options,
+            onto_main,
         } => restack::restack(
             &effects,
             &git_run_info,
@@ -152,6 +237,7 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
             &resolve_revset_options,
             &move_options,
             MergeConflictRemediation::Retry,
+            onto_main,
         )?,
 
         Command::Record(args) => git_branchless_record::command_main(ctx, args)?,
@@ -166,11 +252,14 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
             resolve_revset_options,
             force_rewrite_public_commits,
             discard,
+            message_from,
         } => {
             let messages = if discard {
                 git_branchless_reword::InitialCommitMessages::Discard
             } else if let Some(commit_to_fixup) = commit_to_fixup {
                 git_branchless_reword::InitialCommitMessages::FixUp(commit_to_fixup)
+            } else if let Some(message_from) = message_from {
+                git_branchless_reword::InitialCommitMessages::MessageFrom(message_from)
             } else {
                 git_branchless_reword::InitialCommitMessages::Messages(messages)
             };
@@ -226,10 +315,16 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
             )?
         }
 
+        Command::Status { short } => status::status(&effects, short)?,
+
         Command::Submit(args) => git_branchless_submit::command_main(ctx, args)?,
 
         Command::Sync {
             pull,
+            remote,
+            current,
+            stat,
+            push,
             move_options,
             revsets,
             resolve_revset_options,
@@ -237,6 +332,10 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
             &effects,
             &git_run_info,
             pull,
+            remote,
+            current,
+            stat,
+            push,
             &move_options,
             revsets,
             &resolve_revset_options,
@@ -244,9 +343,12 @@ fn command_main(ctx: CommandContext, opts: Opts) -> EyreExitOr<()> {
 
         Command::Test(args) => git_branchless_test::command_main(ctx, args)?,
 
-        Command::Undo { interactive, yes } => {
-            git_branchless_undo::undo(&effects, &git_run_info, interactive, yes)?
-        }
+        Command::Undo {
+            interactive,
+            redo,
+            yes,
+            refs_only,
+        } => git_branchless_undo::undo(&effects, &git_run_info, interactive, redo, yes, refs_only)?,
 
         Command::Unhide {
             revsets,
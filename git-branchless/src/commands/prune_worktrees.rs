@@ -0,0 +1,158 @@
+//! Clean up administrative entries for linked worktrees whose working
+//! directories have been deleted.
+//!
+//! Git keeps an entry under `$GIT_COMMON_DIR/worktrees` for each linked
+//! worktree. If the worktree directory is removed without using `git worktree
+//! remove`, the entry remains until `git worktree prune` is run, and any
+//! git-branchless state associated with the worktree (such as the locks for
+//! `git test` worktrees) is left behind as well.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use eyre::Context;
+use lib::core::effects::Effects;
+use lib::core::formatting::Pluralize;
+use lib::git::{
+    GitRunInfo, GitRunOpts, GitRunResult, Repo, get_test_locks_dir, get_test_worktrees_dir,
+};
+use lib::util::EyreExitOr;
+use tracing::instrument;
+
+/// A worktree administrative entry whose working directory no longer exists.
+#[derive(Debug)]
+struct StaleWorktree {
+    /// The name of the entry under `$GIT_COMMON_DIR/worktrees`.
+    name: String,
+
+    /// The path to the (now-missing) working directory.
+    path: PathBuf,
+}
+
+/// Find the worktree entries whose working directories no longer exist.
+/// Locked worktrees are skipped, since `git worktree prune` won't remove them.
+fn find_stale_worktrees(worktrees_dir: &Path) -> eyre::Result<Vec<StaleWorktree>> {
+    let entries = match std::fs::read_dir(worktrees_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).wrap_err_with(|| format!("Reading worktrees dir: {worktrees_dir:?}"));
+        }
+    };
+
+    let mut result = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let admin_dir = entry.path();
+        if !admin_dir.is_dir() || admin_dir.join("locked").exists() {
+            continue;
+        }
+
+        let gitdir_path = admin_dir.join("gitdir");
+        let gitdir = match std::fs::read_to_string(&gitdir_path) {
+            Ok(gitdir) => PathBuf::from(gitdir.trim_end()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).wrap_err_with(|| format!("Reading gitdir: {gitdir_path:?}"));
+            }
+        };
+
+        // The `gitdir` file points to the `.git` file inside the working
+        // directory; if it's gone, then the worktree has been deleted.
+        if !gitdir.exists() {
+            let path = match gitdir.parent() {
+                Some(path) => path.to_path_buf(),
+                None => gitdir,
+            };
+            result.push(StaleWorktree {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                path,
+            });
+        }
+    }
+    result.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+    Ok(result)
+}
+
+/// Remove any git-branchless state associated with the given stale worktree.
+fn clear_worktree_state(repo: &Repo, stale_worktree: &StaleWorktree) -> eyre::Result<()> {
+    let test_worktrees_dir = get_test_worktrees_dir(repo)?;
+    if stale_worktree.path.parent() != Some(test_worktrees_dir.as_path()) {
+        return Ok(());
+    }
+
+    // `git test` worktrees are named `testing-worktree-<id>` and are guarded
+    // by a lock file named `worktree-<id>.lock`.
+    let worker_id = match stale_worktree
+        .path
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .and_then(|file_name| file_name.strip_prefix("testing-worktree-"))
+    {
+        Some(worker_id) => worker_id,
+        None => return Ok(()),
+    };
+    let lock_path = get_test_locks_dir(repo)?.join(format!("worktree-{worker_id}.lock"));
+    match std::fs::remove_file(&lock_path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).wrap_err_with(|| format!("Removing lock file: {lock_path:?}")),
+    }
+}
+
+/// Prune the administrative entries for worktrees whose working directories
+/// no longer exist.
+#[instrument]
+pub fn prune_worktrees(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    dry_run: bool,
+) -> EyreExitOr<()> {
+    let repo = Repo::from_current_dir()?;
+    let parent_repo = repo.open_worktree_parent_repo()?;
+    let common_dir = match parent_repo.as_ref() {
+        Some(parent_repo) => parent_repo.get_path(),
+        None => repo.get_path(),
+    };
+    let stale_worktrees = find_stale_worktrees(&common_dir.join("worktrees"))?;
+
+    if stale_worktrees.is_empty() {
+        writeln!(effects.get_output_stream(), "No stale worktrees found.")?;
+        return Ok(Ok(()));
+    }
+
+    writeln!(
+        effects.get_output_stream(),
+        "{} {}:",
+        if dry_run { "Would prune" } else { "Pruning" },
+        Pluralize {
+            determiner: None,
+            amount: stale_worktrees.len(),
+            unit: ("stale worktree", "stale worktrees"),
+        },
+    )?;
+    for stale_worktree in stale_worktrees.iter() {
+        writeln!(
+            effects.get_output_stream(),
+            "- {} ({})",
+            stale_worktree.name,
+            stale_worktree.path.display(),
+        )?;
+    }
+
+    if dry_run {
+        writeln!(
+            effects.get_output_stream(),
+            "(This was a dry-run; run without --dry-run to apply changes.)"
+        )?;
+        return Ok(Ok(()));
+    }
+
+    let GitRunResult { .. } =
+        git_run_info.run_silent(&repo, None, &["worktree", "prune"], GitRunOpts::default())?;
+    for stale_worktree in stale_worktrees.iter() {
+        clear_worktree_state(&repo, stale_worktree)?;
+    }
+
+    Ok(Ok(()))
+}
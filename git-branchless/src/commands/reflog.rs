@@ -0,0 +1,133 @@
+//! Show a focused history of the operations that git-branchless has recorded
+//! in its event log.
+
+use std::fmt::Write;
+use std::time::SystemTime;
+
+use itertools::Itertools;
+use lib::core::effects::Effects;
+use lib::core::eventlog::{Event, EventLogDb, EventTransactionId, should_ignore_ref_updates};
+use lib::core::formatting::Pluralize;
+use lib::core::node_descriptors::RelativeTimeDescriptor;
+use lib::git::{CategorizedReferenceName, MaybeZeroOid, Repo};
+use lib::util::EyreExitOr;
+use tracing::instrument;
+
+/// Summarize the events in a single transaction as human-readable lines.
+fn describe_transaction_events(events: &[Event]) -> Vec<String> {
+    let mut num_commits = 0;
+    let mut num_rewritten = 0;
+    let mut num_hidden = 0;
+    let mut num_unhidden = 0;
+    let mut num_snapshots = 0;
+    let mut ref_names = Vec::new();
+    for event in events {
+        match event {
+            Event::CommitEvent { .. } => num_commits += 1,
+            Event::RewriteEvent {
+                new_commit_oid: MaybeZeroOid::Zero,
+                ..
+            }
+            | Event::ObsoleteEvent { .. } => num_hidden += 1,
+            Event::RewriteEvent {
+                new_commit_oid: MaybeZeroOid::NonZero(_),
+                ..
+            } => num_rewritten += 1,
+            Event::UnobsoleteEvent { .. } => num_unhidden += 1,
+            Event::WorkingCopySnapshot { .. } => num_snapshots += 1,
+            Event::RefUpdateEvent { ref_name, .. } => {
+                if !should_ignore_ref_updates(ref_name) {
+                    let ref_name = CategorizedReferenceName::new(ref_name).friendly_describe();
+                    if !ref_names.contains(&ref_name) {
+                        ref_names.push(ref_name);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (amount, verb, unit) in [
+        (num_commits, "Committed", ("commit", "commits")),
+        (num_rewritten, "Rewrote", ("commit", "commits")),
+        (num_hidden, "Hid", ("commit", "commits")),
+        (num_unhidden, "Unhid", ("commit", "commits")),
+        (
+            num_snapshots,
+            "Took",
+            ("working copy snapshot", "working copy snapshots"),
+        ),
+    ] {
+        if amount > 0 {
+            lines.push(format!(
+                "{verb} {}",
+                Pluralize {
+                    determiner: None,
+                    amount,
+                    unit,
+                }
+            ));
+        }
+    }
+    if !ref_names.is_empty() {
+        lines.push(format!("Updated {}", ref_names.join(", ")));
+    }
+    lines
+}
+
+/// Print the operations recorded in the event log, grouped by transaction,
+/// newest first.
+#[instrument]
+pub fn reflog(effects: &Effects, max_count: Option<usize>) -> EyreExitOr<()> {
+    let repo = Repo::from_current_dir()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+    let events = event_log_db.get_events()?;
+
+    let now = SystemTime::now();
+    let relative_time_provider = RelativeTimeDescriptor::new(&repo, now)?;
+
+    let transactions = events
+        .into_iter()
+        .chunk_by(|event| event.get_event_tx_id())
+        .into_iter()
+        .map(|(event_tx_id, events)| (event_tx_id, events.collect_vec()))
+        .collect_vec();
+    let transactions = transactions
+        .into_iter()
+        .rev()
+        .take(max_count.unwrap_or(usize::MAX))
+        .collect_vec();
+
+    if transactions.is_empty() {
+        writeln!(
+            effects.get_output_stream(),
+            "There are no operations in the event log."
+        )?;
+        return Ok(Ok(()));
+    }
+
+    for (event_tx_id, events) in transactions {
+        let message = match event_tx_id {
+            EventTransactionId::Id(_) => event_log_db.get_transaction_message(event_tx_id)?,
+            EventTransactionId::Suppressed => "(no message)".to_string(),
+        };
+        let relative_time = if relative_time_provider.is_enabled() {
+            format!(
+                " ({} ago)",
+                RelativeTimeDescriptor::describe_time_delta(now, events[0].get_timestamp())?
+            )
+        } else {
+            String::new()
+        };
+        writeln!(
+            effects.get_output_stream(),
+            "Transaction {event_tx_id}{relative_time}: {message}"
+        )?;
+        for line in describe_transaction_events(&events) {
+            writeln!(effects.get_output_stream(), "    {line}")?;
+        }
+    }
+
+    Ok(Ok(()))
+}
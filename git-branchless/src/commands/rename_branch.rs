@@ -0,0 +1,85 @@
+//! Rename a local branch, recording the rename in the event log so that it can
+//! be undone as a single operation.
+
+use std::fmt::Write;
+use std::time::SystemTime;
+
+use bstr::BString;
+use lib::core::effects::Effects;
+use lib::core::eventlog::EventLogDb;
+use lib::core::forge_status_cache::rename_cached_forge_status;
+use lib::git::{BranchType, GitRunInfo, MaybeZeroOid, Repo};
+use lib::util::{ExitCode, EyreExitOr};
+use tracing::instrument;
+
+/// Rename the branch `old_name` to `new_name`.
+#[instrument]
+pub fn rename_branch(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    old_name: &str,
+    new_name: &str,
+) -> EyreExitOr<()> {
+    let now = SystemTime::now();
+    let repo = Repo::from_current_dir()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+
+    let mut branch = match repo.find_branch(old_name, BranchType::Local)? {
+        Some(branch) => branch,
+        None => {
+            writeln!(effects.get_error_stream(), "Branch not found: {old_name}")?;
+            return Ok(Err(ExitCode(1)));
+        }
+    };
+    if repo.find_branch(new_name, BranchType::Local)?.is_some() {
+        writeln!(
+            effects.get_error_stream(),
+            "A branch named {new_name} already exists."
+        )?;
+        return Ok(Err(ExitCode(1)));
+    }
+
+    let old_reference_name = branch.get_reference_name()?;
+    let branch_oid = match branch.get_oid()? {
+        Some(branch_oid) => MaybeZeroOid::NonZero(branch_oid),
+        None => MaybeZeroOid::Zero,
+    };
+
+    let head_info = repo.get_head_info()?;
+    let event_tx_id = event_log_db.make_transaction_id(now, "rename-branch")?;
+    branch.rename(new_name, false)?;
+    let new_reference_name = match repo.find_branch(new_name, BranchType::Local)? {
+        Some(branch) => branch.get_reference_name()?,
+        None => eyre::bail!("Could not find branch after renaming it: {new_name}"),
+    };
+    rename_cached_forge_status(&conn, old_name, new_name)?;
+
+    // Record the rename as the deletion of the old branch and the creation of
+    // the new branch, in the same transaction. If the branch was checked out,
+    // also record `HEAD`, so that undoing the rename doesn't leave `HEAD`
+    // pointing to a nonexistent branch.
+    let mut branch_moves_stdin = format!(
+        "{branch_oid} {zero} {old_reference_name}\n{zero} {branch_oid} {new_reference_name}\n",
+        zero = MaybeZeroOid::Zero,
+        old_reference_name = old_reference_name.as_str(),
+        new_reference_name = new_reference_name.as_str(),
+    );
+    if head_info.reference_name.as_ref() == Some(&old_reference_name) {
+        branch_moves_stdin.push_str(&format!("{branch_oid} {branch_oid} HEAD\n"));
+    }
+    git_run_info.run_hook(
+        effects,
+        &repo,
+        "reference-transaction",
+        event_tx_id,
+        &["committed"],
+        Some(BString::from(branch_moves_stdin)),
+    )?;
+
+    writeln!(
+        effects.get_output_stream(),
+        "Renamed branch {old_name} to {new_name}."
+    )?;
+    Ok(Ok(()))
+}
@@ -76,9 +76,10 @@ use lib::core::eventlog::{EventCursor, EventLogDb, EventReplayer};
 use lib::core::rewrite::{
     BuildRebasePlanOptions, ExecuteRebasePlanOptions, ExecuteRebasePlanResult,
     MergeConflictRemediation, RebasePlanBuilder, RebasePlanPermissions, RepoPool, RepoResource,
-    execute_rebase_plan, find_abandoned_children, find_rewrite_target, move_branches,
+    check_public_head, execute_rebase_plan, find_abandoned_children, find_rewrite_target,
+    move_branches,
 };
-use lib::git::{GitRunInfo, NonZeroOid, Repo};
+use lib::git::{GitRunInfo, MergeFileFavor, NonZeroOid, Repo};
 
 #[instrument(skip(commits))]
 fn restack_commits(
@@ -94,6 +95,7 @@ fn restack_commits(
     build_options: BuildRebasePlanOptions,
     execute_options: &ExecuteRebasePlanOptions,
     merge_conflict_remediation: MergeConflictRemediation,
+    onto_main: bool,
 ) -> EyreExitOr<()> {
     let repo = repo_pool.try_create()?;
     let commit_set: CommitSet = match commits {
@@ -123,6 +125,36 @@ fn restack_commits(
         result
     };
 
+    // If requested, also move the stacks containing the restacked commits
+    // onto the main branch, so that both happen in the same rebase.
+    let stack_root_moves: Vec<(NonZeroOid, NonZeroOid)> = if onto_main {
+        let dest_oids: CommitSet = rebases
+            .iter()
+            .map(|RebaseInfo { dest_oid, .. }| *dest_oid)
+            .collect();
+        let stack_roots =
+            dag.query_roots(dag.query_only(dest_oids, dag.main_branch_commit.clone())?)?;
+        let mut result = Vec::new();
+        for root_oid in dag.commit_set_to_vec(&stack_roots)? {
+            let main_branch = repo.get_nearest_main_branch(root_oid)?;
+            let main_branch_oid = match main_branch.get_oid()? {
+                Some(main_branch_oid) => main_branch_oid,
+                None => eyre::bail!(
+                    "Could not find commit pointed to by main branch: {:?}",
+                    main_branch.get_name()?,
+                ),
+            };
+            let root_commit = repo.find_commit_or_fail(root_oid)?;
+            let only_parent_oid = root_commit.get_only_parent().map(|parent| parent.get_oid());
+            if only_parent_oid != Some(main_branch_oid) {
+                result.push((root_oid, main_branch_oid));
+            }
+        }
+        result
+    } else {
+        Vec::new()
+    };
+
     let rebase_plan = {
         let permissions = match RebasePlanPermissions::verify_rewrite_set(
             dag,
@@ -135,6 +167,7 @@ fn restack_commits(
                          abandoned_child_oids,
                      }| abandoned_child_oids.iter().copied(),
                 )
+                .chain(stack_root_moves.iter().map(|(root_oid, _)| *root_oid))
                 .collect(),
         )? {
             Ok(permissions) => permissions,
@@ -153,6 +186,9 @@ fn restack_commits(
                 builder.move_subtree(child_oid, vec![dest_oid])?;
             }
         }
+        for (root_oid, main_branch_oid) in stack_root_moves {
+            builder.move_subtree(root_oid, vec![main_branch_oid])?;
+        }
         match builder.build(effects, thread_pool, repo_pool)? {
             Ok(Some(rebase_plan)) => rebase_plan,
             Ok(None) => {
@@ -270,6 +306,7 @@ pub fn restack(
     resolve_revset_options: &ResolveRevsetOptions,
     move_options: &MoveOptions,
     merge_conflict_remediation: MergeConflictRemediation,
+    onto_main: bool,
 ) -> EyreExitOr<()> {
     let now = SystemTime::now();
     let repo = Repo::from_current_dir()?;
@@ -288,6 +325,16 @@ pub fn restack(
         &references_snapshot,
     )?;
 
+    if let Some(result) = check_public_head(
+        effects,
+        &repo,
+        &dag,
+        move_options.force_rewrite_public_commits,
+        "restack",
+    )? {
+        return Ok(result);
+    }
+
     let commit_sets =
         match resolve_commits(effects, &repo, &mut dag, &revsets, resolve_revset_options) {
             Ok(commit_sets) => commit_sets,
@@ -312,13 +359,20 @@ pub fn restack(
         force_on_disk,
         detect_duplicate_commits_via_patch_id,
         resolve_merge_conflicts,
+        strategy_option,
         dump_rebase_constraints,
         dump_rebase_plan,
+        explain,
+        keep_empty,
+        timing,
+        no_keep_empty: _,
     } = *move_options;
     let build_options = BuildRebasePlanOptions {
         force_rewrite_public_commits,
         dump_rebase_constraints,
         dump_rebase_plan,
+        explain,
+        keep_empty,
         detect_duplicate_commits_via_patch_id,
     };
     let execute_options = ExecuteRebasePlanOptions {
@@ -329,6 +383,10 @@ pub fn restack(
         force_on_disk,
         dry_run: false,
         resolve_merge_conflicts,
+        merge_file_favor: strategy_option.map(MergeFileFavor::from),
+        reset_author: false,
+        timing,
+        exec_on_conflict: None,
         check_out_commit_options: CheckOutCommitOptions {
             additional_args: Default::default(),
             force_detach: false,
@@ -352,6 +410,7 @@ pub fn restack(
         build_options,
         &execute_options,
         merge_conflict_remediation,
+        onto_main,
     )?);
 
     try_exit_code!(restack_branches(
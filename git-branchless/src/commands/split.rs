@@ -26,8 +26,8 @@ use lib::{
         },
     },
     git::{
-        CherryPickFastOptions, GitRunInfo, MaybeZeroOid, NonZeroOid, Repo, ResolvedReferenceInfo,
-        make_empty_tree, summarize_diff_for_temporary_commit,
+        CherryPickFastOptions, GitRunInfo, MaybeZeroOid, MergeFileFavor, NonZeroOid, Repo,
+        ResolvedReferenceInfo, make_empty_tree, summarize_diff_for_temporary_commit,
     },
     try_exit_code,
     util::{ExitCode, EyreExitOr},
@@ -78,8 +78,13 @@ pub fn split(
         force_on_disk,
         detect_duplicate_commits_via_patch_id,
         resolve_merge_conflicts,
+        strategy_option,
         dump_rebase_constraints,
         dump_rebase_plan,
+        explain,
+        keep_empty,
+        timing,
+        no_keep_empty: _,
     } = *move_options;
 
     let target_oid: NonZeroOid = match resolve_commits(
@@ -113,6 +118,8 @@ pub fn split(
             force_rewrite_public_commits,
             dump_rebase_constraints,
             dump_rebase_plan,
+            explain,
+            keep_empty,
             detect_duplicate_commits_via_patch_id,
         },
         &vec![target_oid].into_iter().collect(),
@@ -173,8 +180,7 @@ pub fn split(
         (_, [..]) => {
             writeln!(
                 effects.get_error_stream(),
-                "Cannot split merge commit {}.",
-                target_oid
+                "Cannot split merge commit {target_oid}."
             )?;
             return Ok(Err(ExitCode(1)));
         }
@@ -353,6 +359,8 @@ pub fn split(
                 &remainder_commit,
                 &CherryPickFastOptions {
                     reuse_parent_tree_if_possible: true,
+                    merge_file_favor: None,
+                    use_rerere: false,
                 },
             )?;
             let extracted_commit_oid = repo.create_commit(
@@ -558,6 +566,10 @@ pub fn split(
                 force_on_disk,
                 dry_run: false,
                 resolve_merge_conflicts,
+                merge_file_favor: strategy_option.map(MergeFileFavor::from),
+                reset_author: false,
+                timing,
+                exec_on_conflict: None,
                 check_out_commit_options: CheckOutCommitOptions {
                     additional_args: Default::default(),
                     force_detach: rebase_force_detach,
@@ -0,0 +1,64 @@
+//! Print a summary of the state of the commit graph, without rendering the
+//! smartlog.
+
+use std::fmt::Write;
+
+use lib::core::dag::Dag;
+use lib::core::effects::Effects;
+use lib::core::eventlog::{EventLogDb, EventReplayer};
+use lib::core::repo_ext::RepoExt;
+use lib::git::{CategorizedReferenceName, Repo};
+use lib::util::EyreExitOr;
+use tracing::instrument;
+
+/// Print the number of draft and abandoned commits, and the current position.
+#[instrument]
+pub fn status(effects: &Effects, short: bool) -> EyreExitOr<()> {
+    let repo = Repo::from_current_dir()?;
+    let head_info = repo.get_head_info()?;
+    let references_snapshot = repo.get_references_snapshot()?;
+    let conn = repo.get_db_conn()?;
+    let event_log_db = EventLogDb::new(&conn)?;
+    let event_replayer = EventReplayer::from_event_log_db(effects, &repo, &event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    let dag = Dag::open_and_sync(
+        effects,
+        &repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+
+    let obsolete_commits = dag.query_obsolete_commits();
+    let draft_commits = dag.query_draft_commits()?.difference(&obsolete_commits);
+    let abandoned_commits = dag
+        .filter_visible_commits(dag.query_children(obsolete_commits.clone())?)?
+        .difference(&obsolete_commits);
+    let num_draft_commits = dag.set_count(&draft_commits)?;
+    let num_abandoned_commits = dag.set_count(&abandoned_commits)?;
+
+    let position = match (&head_info.reference_name, head_info.oid) {
+        (Some(reference_name), _) => CategorizedReferenceName::new(reference_name).render_suffix(),
+        (None, Some(head_oid)) => repo.find_commit_or_fail(head_oid)?.get_short_oid()?,
+        (None, None) => "no commit".to_string(),
+    };
+
+    if short {
+        writeln!(
+            effects.get_output_stream(),
+            "{num_draft_commits} draft, {num_abandoned_commits} abandoned, on {position}"
+        )?;
+    } else {
+        writeln!(
+            effects.get_output_stream(),
+            "Draft commits: {num_draft_commits}"
+        )?;
+        writeln!(
+            effects.get_output_stream(),
+            "Abandoned commits: {num_abandoned_commits}"
+        )?;
+        writeln!(effects.get_output_stream(), "On: {position}")?;
+    }
+
+    Ok(Ok(()))
+}
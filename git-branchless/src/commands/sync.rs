@@ -2,6 +2,7 @@
 
 use cursive_core::theme::BaseColor;
 use lib::try_exit_code;
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::time::SystemTime;
 
@@ -13,6 +14,7 @@ use rayon::{ThreadPool, ThreadPoolBuilder};
 
 use git_branchless_opts::{MoveOptions, ResolveRevsetOptions, Revset};
 use git_branchless_revset::{check_revset_syntax, resolve_commits};
+use git_branchless_submit::{STYLE_PUSHED, push_branches};
 use lib::core::config::get_restack_preserve_timestamps;
 use lib::core::dag::{CommitSet, Dag, sorted_commit_set, union_all};
 use lib::core::effects::{Effects, OperationType, WithProgress};
@@ -20,12 +22,13 @@ use lib::core::eventlog::{EventLogDb, EventReplayer};
 use lib::core::formatting::{Pluralize, StyledStringBuilder};
 use lib::core::rewrite::{
     BuildRebasePlanError, BuildRebasePlanOptions, ExecuteRebasePlanOptions,
-    ExecuteRebasePlanResult, FailedMergeInfo, RebasePlan, RebasePlanBuilder, RebasePlanPermissions,
-    RepoPool, RepoResource, execute_rebase_plan,
+    ExecuteRebasePlanResult, FailedMergeInfo, RebaseCommand, RebasePlan, RebasePlanBuilder,
+    RebasePlanPermissions, RepoPool, RepoResource, execute_rebase_plan,
 };
 use lib::core::task::ResourcePool;
 use lib::git::{
-    CategorizedReferenceName, Commit, GitRunInfo, NonZeroOid, Repo, ResolvedReferenceInfo,
+    BranchType, CategorizedReferenceName, Commit, GitRunInfo, MergeFileFavor, NonZeroOid,
+    ReferenceName, Repo, ResolvedReferenceInfo,
 };
 
 fn get_stack_roots(dag: &Dag, commit_sets: Vec<CommitSet>) -> eyre::Result<CommitSet> {
@@ -45,15 +48,109 @@ fn get_stack_roots(dag: &Dag, commit_sets: Vec<CommitSet>) -> eyre::Result<Commi
         .map_err(|err| eyre::eyre!("Could not query DAG for stack roots: {err}"))
 }
 
+/// Get the OIDs which each local branch points to, so that the branches moved
+/// by a sync can be determined afterwards.
+fn get_local_branch_oids(repo: &Repo) -> eyre::Result<HashMap<ReferenceName, NonZeroOid>> {
+    let references_snapshot = repo.get_references_snapshot()?;
+    Ok(references_snapshot
+        .branch_oid_to_names
+        .into_iter()
+        .flat_map(|(oid, names)| names.into_iter().map(move |name| (name, oid)))
+        .filter(|(name, _oid)| {
+            matches!(
+                CategorizedReferenceName::new(name),
+                CategorizedReferenceName::LocalBranch { .. }
+            )
+        })
+        .collect())
+}
+
+/// Counts accumulated over the course of a sync, for `git sync --stat` and
+/// `git sync --push`.
+#[derive(Debug, Default)]
+struct SyncStats {
+    num_stacks_moved: usize,
+    num_stacks_up_to_date: usize,
+    num_stacks_unsynced: usize,
+    num_commits_rebased: usize,
+    num_duplicate_commits_dropped: usize,
+}
+
+impl SyncStats {
+    fn record_rebase_plan(&mut self, rebase_plan: &RebasePlan) {
+        self.num_stacks_moved += 1;
+        for command in &rebase_plan.commands {
+            match command {
+                RebaseCommand::Pick { .. } | RebaseCommand::Merge { .. } => {
+                    self.num_commits_rebased += 1;
+                }
+                RebaseCommand::SkipUpstreamAppliedCommit { .. } => {
+                    self.num_duplicate_commits_dropped += 1;
+                }
+                RebaseCommand::CreateLabel { .. }
+                | RebaseCommand::Reset { .. }
+                | RebaseCommand::Replace { .. }
+                | RebaseCommand::Break
+                | RebaseCommand::RegisterExtraPostRewriteHook
+                | RebaseCommand::DetectEmptyCommit { .. } => {}
+            }
+        }
+    }
+
+    fn describe(&self, effects: &Effects) -> eyre::Result<()> {
+        let Self {
+            num_stacks_moved,
+            num_stacks_up_to_date,
+            num_stacks_unsynced: _,
+            num_commits_rebased,
+            num_duplicate_commits_dropped,
+        } = self;
+        writeln!(
+            effects.get_output_stream(),
+            "Moved {}, rebased {}, dropped {}, skipped {}.",
+            Pluralize {
+                determiner: None,
+                amount: *num_stacks_moved,
+                unit: ("stack", "stacks"),
+            },
+            Pluralize {
+                determiner: None,
+                amount: *num_commits_rebased,
+                unit: ("commit", "commits"),
+            },
+            Pluralize {
+                determiner: None,
+                amount: *num_duplicate_commits_dropped,
+                unit: ("duplicate commit", "duplicate commits"),
+            },
+            Pluralize {
+                determiner: None,
+                amount: *num_stacks_up_to_date,
+                unit: ("up-to-date stack", "up-to-date stacks"),
+            },
+        )?;
+        Ok(())
+    }
+}
+
 /// Move all commit stacks on top of the main branch.
 pub fn sync(
     effects: &Effects,
     git_run_info: &GitRunInfo,
     pull: bool,
+    remote: Option<String>,
+    current: bool,
+    stat: bool,
+    push: bool,
     move_options: &MoveOptions,
     revsets: Vec<Revset>,
     resolve_revset_options: &ResolveRevsetOptions,
 ) -> EyreExitOr<()> {
+    let revsets = if current {
+        vec![Revset(".".to_string())]
+    } else {
+        revsets
+    };
     let repo = Repo::from_current_dir()?;
     let conn = repo.get_db_conn()?;
     let event_log_db = EventLogDb::new(&conn)?;
@@ -63,9 +160,35 @@ pub fn sync(
     // Try to surface parse errors early, before potentially doing commit graph or network
     // side-effects.
     check_revset_syntax(&repo, &revsets)?;
+    let local_branch_oids = get_local_branch_oids(&repo)?;
 
-    if pull {
-        try_exit_code!(git_run_info.run(effects, Some(event_tx_id), &["fetch", "--all"])?);
+    let pull = pull || remote.is_some();
+    match &remote {
+        Some(remote) => {
+            let remote_names = repo.get_all_remote_names()?;
+            if !remote_names.contains(remote) {
+                writeln!(
+                    effects.get_error_stream(),
+                    "No remote named {remote:?}. Known remotes: {}",
+                    if remote_names.is_empty() {
+                        "(none)".to_string()
+                    } else {
+                        remote_names.join(", ")
+                    }
+                )?;
+                return Ok(Err(ExitCode(1)));
+            }
+            try_exit_code!(git_run_info.run(effects, Some(event_tx_id), &["fetch", remote])?);
+        }
+        None => {
+            if pull {
+                try_exit_code!(git_run_info.run(
+                    effects,
+                    Some(event_tx_id),
+                    &["fetch", "--all"]
+                )?);
+            }
+        }
     }
 
     let MoveOptions {
@@ -74,14 +197,21 @@ pub fn sync(
         force_on_disk,
         detect_duplicate_commits_via_patch_id,
         resolve_merge_conflicts,
+        strategy_option,
         dump_rebase_constraints,
         dump_rebase_plan,
+        explain,
+        keep_empty,
+        timing,
+        no_keep_empty: _,
     } = *move_options;
     let build_options = BuildRebasePlanOptions {
         force_rewrite_public_commits,
         detect_duplicate_commits_via_patch_id,
         dump_rebase_constraints,
         dump_rebase_plan,
+        explain,
+        keep_empty,
     };
     let now = SystemTime::now();
     let event_tx_id = event_log_db.make_transaction_id(now, "sync")?;
@@ -93,6 +223,10 @@ pub fn sync(
         force_on_disk,
         dry_run: false,
         resolve_merge_conflicts,
+        merge_file_favor: strategy_option.map(MergeFileFavor::from),
+        reset_author: false,
+        timing,
+        exec_on_conflict: None,
         check_out_commit_options: CheckOutCommitOptions {
             additional_args: Default::default(),
             force_detach: false,
@@ -103,6 +237,7 @@ pub fn sync(
     let thread_pool = ThreadPoolBuilder::new().build()?;
     let repo_pool = RepoResource::new_pool(&repo)?;
 
+    let mut stats = SyncStats::default();
     let head_info = repo.get_head_info()?;
     if pull {
         try_exit_code!(execute_main_branch_sync_plan(
@@ -115,12 +250,14 @@ pub fn sync(
             &thread_pool,
             &repo_pool,
             &head_info,
+            remote.as_deref(),
+            &mut stats,
         )?);
     }
 
     // The main branch might have changed since we synced with `master`, so read its information again.
 
-    execute_sync_plans(
+    let result = execute_sync_plans(
         effects,
         git_run_info,
         &repo,
@@ -131,7 +268,96 @@ pub fn sync(
         &repo_pool,
         revsets,
         resolve_revset_options,
-    )
+        &mut stats,
+    )?;
+    if stat {
+        stats.describe(effects)?;
+    }
+    try_exit_code!(result);
+
+    if push {
+        try_exit_code!(push_moved_branches(
+            effects,
+            git_run_info,
+            &repo,
+            &event_log_db,
+            &local_branch_oids,
+            &stats,
+        )?);
+    }
+    Ok(Ok(()))
+}
+
+fn push_moved_branches(
+    effects: &Effects,
+    git_run_info: &GitRunInfo,
+    repo: &Repo,
+    event_log_db: &EventLogDb,
+    original_local_branch_oids: &HashMap<ReferenceName, NonZeroOid>,
+    stats: &SyncStats,
+) -> EyreExitOr<()> {
+    if stats.num_stacks_unsynced > 0 {
+        writeln!(
+            effects.get_output_stream(),
+            "Not pushing branches, since some stacks could not be synced."
+        )?;
+        return Ok(Ok(()));
+    }
+
+    let moved_branch_oids: CommitSet = get_local_branch_oids(repo)?
+        .into_iter()
+        .filter(|(name, oid)| {
+            original_local_branch_oids
+                .get(name)
+                .is_some_and(|original_oid| original_oid != oid)
+        })
+        .map(|(_name, oid)| oid)
+        .collect();
+
+    let event_replayer = EventReplayer::from_event_log_db(effects, repo, event_log_db)?;
+    let event_cursor = event_replayer.make_default_cursor();
+    let references_snapshot = repo.get_references_snapshot()?;
+    let dag = Dag::open_and_sync(
+        effects,
+        repo,
+        &event_replayer,
+        event_cursor,
+        &references_snapshot,
+    )?;
+    if dag.set_is_empty(&moved_branch_oids)? {
+        return Ok(Ok(()));
+    }
+
+    let pushed_branch_names = try_exit_code!(push_branches(
+        effects,
+        git_run_info,
+        repo,
+        &dag,
+        event_log_db,
+        &references_snapshot,
+        moved_branch_oids,
+    )?);
+    if !pushed_branch_names.is_empty() {
+        writeln!(
+            effects.get_output_stream(),
+            "Pushed {}: {}",
+            Pluralize {
+                determiner: None,
+                amount: pushed_branch_names.len(),
+                unit: ("branch", "branches"),
+            },
+            pushed_branch_names
+                .into_iter()
+                .map(|branch_name| effects.get_glyphs().render(
+                    StyledStringBuilder::new()
+                        .append_styled(branch_name, *STYLE_PUSHED)
+                        .build(),
+                ))
+                .collect::<eyre::Result<Vec<_>>>()?
+                .join(", ")
+        )?;
+    }
+    Ok(Ok(()))
 }
 
 fn execute_main_branch_sync_plan(
@@ -144,6 +370,8 @@ fn execute_main_branch_sync_plan(
     thread_pool: &ThreadPool,
     repo_pool: &RepoPool,
     head_info: &ResolvedReferenceInfo,
+    remote: Option<&str>,
+    stats: &mut SyncStats,
 ) -> EyreExitOr<()> {
     let event_replayer = EventReplayer::from_event_log_db(effects, repo, event_log_db)?;
     let event_cursor = event_replayer.make_default_cursor();
@@ -169,7 +397,30 @@ fn execute_main_branch_sync_plan(
             .build(),
     )?;
 
-    let upstream_main_branch = match local_main_branch.get_upstream_branch()? {
+    let upstream_main_branch = match remote {
+        Some(remote) => {
+            // Look for the branch of the same name on the given remote as the
+            // one which the main branch tracks.
+            let upstream_branch_name =
+                match local_main_branch.get_upstream_branch_name_without_push_remote_name()? {
+                    Some(upstream_branch_name) => upstream_branch_name,
+                    None => local_main_branch.get_name()?.to_owned(),
+                };
+            let remote_branch_name = format!("{remote}/{upstream_branch_name}");
+            match repo.find_branch(&remote_branch_name, BranchType::Remote)? {
+                Some(upstream_main_branch) => Some(upstream_main_branch),
+                None => {
+                    writeln!(
+                        effects.get_output_stream(),
+                        "Remote branch {remote_branch_name} does not exist, so not pulling {local_main_branch_description}."
+                    )?;
+                    return Ok(Ok(()));
+                }
+            }
+        }
+        None => local_main_branch.get_upstream_branch()?,
+    };
+    let upstream_main_branch = match upstream_main_branch {
         Some(upstream_main_branch) => upstream_main_branch,
         None => {
             writeln!(
@@ -293,6 +544,7 @@ fn execute_main_branch_sync_plan(
         event_log_db,
         execute_options,
         vec![(root_commit_oid, Some(rebase_plan))],
+        stats,
     )
 }
 
@@ -307,6 +559,7 @@ fn execute_sync_plans(
     repo_pool: &ResourcePool<RepoResource>,
     revsets: Vec<Revset>,
     resolve_revset_options: &ResolveRevsetOptions,
+    stats: &mut SyncStats,
 ) -> EyreExitOr<()> {
     let event_replayer = EventReplayer::from_event_log_db(effects, repo, event_log_db)?;
     let event_cursor = event_replayer.make_default_cursor();
@@ -326,7 +579,6 @@ fn execute_sync_plans(
                 return Ok(Err(ExitCode(1)));
             }
         };
-    let main_branch_oid = repo.get_main_branch_oid()?;
     let root_commit_oids = get_stack_roots(&dag, commit_sets)?;
     let root_commits = sorted_commit_set(repo, &dag, &root_commit_oids)?;
     let permissions =
@@ -357,6 +609,17 @@ fn execute_sync_plans(
                     let repo = repo_pool.try_create()?;
                     let root_commit = repo.find_commit_or_fail(root_commit_oid)?;
 
+                    // If there are multiple main branches, move each stack onto
+                    // the one it was originally based on.
+                    let main_branch = repo.get_nearest_main_branch(root_commit_oid)?;
+                    let main_branch_oid = match main_branch.get_oid()? {
+                        Some(main_branch_oid) => main_branch_oid,
+                        None => eyre::bail!(
+                            "Could not find commit pointed to by main branch: {:?}",
+                            main_branch.get_name()?,
+                        ),
+                    };
+
                     let only_parent_id =
                         root_commit.get_only_parent().map(|parent| parent.get_oid());
                     if only_parent_id == Some(main_branch_oid) {
@@ -388,6 +651,7 @@ fn execute_sync_plans(
         event_log_db,
         execute_options,
         root_commit_and_plans,
+        stats,
     )
 }
 
@@ -398,20 +662,30 @@ fn execute_plans(
     event_log_db: &EventLogDb,
     execute_options: &ExecuteRebasePlanOptions,
     root_commit_and_plans: Vec<(NonZeroOid, Option<RebasePlan>)>,
+    stats: &mut SyncStats,
 ) -> EyreExitOr<()> {
-    let (success_commits, failed_merge_commits, skipped_commits) = {
+    let (success_commits, failed_merge_commits, skipped_commits, failed_commit, remaining_commits) = {
         let mut success_commits: Vec<Commit> = Vec::new();
         let mut failed_merge_commits: Vec<(Commit, FailedMergeInfo)> = Vec::new();
         let mut skipped_commits: Vec<Commit> = Vec::new();
+        let mut failed_commit: Option<(Commit, ExitCode)> = None;
+        let mut remaining_commits: Vec<Commit> = Vec::new();
 
         let (effects, progress) = effects.start_operation(OperationType::SyncCommits);
         for (root_commit_oid, rebase_plan) in
             root_commit_and_plans.into_iter().with_progress(progress)
         {
             let root_commit = repo.find_commit_or_fail(root_commit_oid)?;
+            if failed_commit.is_some() {
+                if rebase_plan.is_some() {
+                    remaining_commits.push(root_commit);
+                }
+                continue;
+            }
             let rebase_plan = match rebase_plan {
                 Some(rebase_plan) => rebase_plan,
                 None => {
+                    stats.num_stacks_up_to_date += 1;
                     skipped_commits.push(root_commit);
                     continue;
                 }
@@ -427,6 +701,7 @@ fn execute_plans(
             )?;
             match result {
                 ExecuteRebasePlanResult::Succeeded { rewritten_oids: _ } => {
+                    stats.record_rebase_plan(&rebase_plan);
                     success_commits.push(root_commit);
                 }
                 ExecuteRebasePlanResult::WouldSucceed => {
@@ -436,13 +711,23 @@ fn execute_plans(
                     failed_merge_commits.push((root_commit, failed_merge_info));
                 }
                 ExecuteRebasePlanResult::Failed { exit_code } => {
-                    return Ok(Err(exit_code));
+                    failed_commit = Some((root_commit, exit_code));
                 }
             }
         }
 
-        (success_commits, failed_merge_commits, skipped_commits)
+        (
+            success_commits,
+            failed_merge_commits,
+            skipped_commits,
+            failed_commit,
+            remaining_commits,
+        )
     };
+    let num_success_commits = success_commits.len();
+    let num_unsynced_commits =
+        failed_merge_commits.len() + remaining_commits.len() + usize::from(failed_commit.is_some());
+    stats.num_stacks_unsynced += num_unsynced_commits;
 
     for success_commit in success_commits {
         writeln!(
@@ -502,5 +787,45 @@ fn execute_plans(
         )?;
     }
 
-    Ok(Ok(()))
+    if let Some((failed_commit, _exit_code)) = &failed_commit {
+        writeln!(
+            effects.get_output_stream(),
+            "Failed to sync {}",
+            effects
+                .get_glyphs()
+                .render(failed_commit.friendly_describe(effects.get_glyphs())?)?
+        )?;
+    }
+
+    for remaining_commit in remaining_commits {
+        writeln!(
+            effects.get_output_stream(),
+            "Not attempting to sync {}",
+            effects
+                .get_glyphs()
+                .render(remaining_commit.friendly_describe(effects.get_glyphs())?)?
+        )?;
+    }
+
+    if failed_commit.is_some() {
+        writeln!(
+            effects.get_output_stream(),
+            "Synced {}, but {} could not be synced. Run `git sync` again once the problems are resolved to sync the remaining stacks.",
+            Pluralize {
+                determiner: None,
+                amount: num_success_commits,
+                unit: ("stack", "stacks"),
+            },
+            Pluralize {
+                determiner: None,
+                amount: num_unsynced_commits,
+                unit: ("stack", "stacks"),
+            },
+        )?;
+    }
+
+    match failed_commit {
+        Some((_failed_commit, exit_code)) => Ok(Err(exit_code)),
+        None => Ok(Ok(())),
+    }
 }
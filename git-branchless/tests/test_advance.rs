@@ -1,4 +1,7 @@
-use lib::testing::{Git, make_git};
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use lib::testing::{Git, GitRunOptions, make_git};
 
 #[test]
 fn test_advance_basic() -> eyre::Result<()> {
@@ -130,6 +133,50 @@ fn test_advance_multiple_children() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_advance_deterministic_order() -> eyre::Result<()> {
+    fn run_advance() -> eyre::Result<(String, String)> {
+        let git = make_git()?;
+        git.init_repo()?;
+
+        git.detach_head()?;
+        let test1_oid = git.commit_file("test1", 1)?;
+        git.commit_file("test2", 2)?;
+        git.run(&["checkout", &test1_oid.to_string()])?;
+        git.commit_file("test3", 3)?;
+        git.run(&["checkout", &test1_oid.to_string()])?;
+        git.commit_file("test4", 4)?;
+
+        git.branchless("advance", &[])?;
+        let (stdout, _stderr) = git.branchless("query", &["--raw", "children(@)"])?;
+        let children = stdout.lines().sorted().join("\n");
+        let smartlog = git.smartlog()?;
+        Ok((children, smartlog))
+    }
+
+    if !make_git()?.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+
+    let (first_children, first_smartlog) = run_advance()?;
+    let (second_children, second_smartlog) = run_advance()?;
+    assert_eq!(first_children, second_children);
+    assert_eq!(first_smartlog, second_smartlog);
+    insta::assert_snapshot!(first_smartlog, @r###"
+    O f777ecc (master) create initial.txt
+    |
+    o 62fc20d create test1.txt
+    |
+    @ bf0d52a create test4.txt
+    |\
+    | o 44352d0 create test2.txt
+    |
+    o 0a4a701 create test3.txt
+    "###);
+
+    Ok(())
+}
+
 #[test]
 fn test_advance_bare_repo_worktree() -> eyre::Result<()> {
     let git = make_git()?;
@@ -242,3 +289,133 @@ fn test_advance_auto() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_advance_auto_disabled_by_env_var() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+
+    git.run(&["checkout", "-b", "branch-1"])?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "-b", "branch-2"])?;
+    git.commit_file("test2", 2)?;
+
+    git.run(&["checkout", "branch-1"])?;
+    git.run(&["config", "branchless.advance.auto", "true"])?;
+
+    // Commit with hooks disabled. The hook should not run at all, so branch-2
+    // should not be auto-advanced.
+    git.write_file_txt("test3", "test3 contents\n")?;
+    git.run(&["add", "."])?;
+    {
+        let (_stdout, stderr) = git.run_with_options(
+            &["commit", "-m", "create test3.txt"],
+            &GitRunOptions {
+                time: 3,
+                env: {
+                    let mut env: HashMap<String, String> = HashMap::new();
+                    env.insert("GIT_BRANCHLESS_DISABLE_HOOKS".to_string(), "1".to_string());
+                    env
+                },
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"");
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |\
+        | o 96d1c37 (branch-2) create test2.txt
+        |
+        @ 4838e49 (> branch-1) create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_advance_no_public() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "HEAD^"])?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("advance", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Advancing 1 commit onto fe65c1f create test2.txt.
+        You are trying to rewrite 1 public commit, such as: 62fc20d create test1.txt
+        It is generally not advised to rewrite public commits, because your
+        collaborators will have difficulty merging your changes.
+        To proceed anyways, run: git advance -f
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "advance",
+            &["--no-public"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @"Refusing to advance 1 public commit, such as: 62fc20d create test1.txt");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_advance_public_head_behavior() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+
+    git.run(&["config", "branchless.core.publicHeadBehavior", "skip"])?;
+    {
+        let (stdout, _stderr) = git.branchless("advance", &[])?;
+        insta::assert_snapshot!(stdout, @"Skipping advance: HEAD is at public commit 62fc20d create test1.txt");
+    }
+
+    git.run(&[
+        "config",
+        "branchless.core.publicHeadBehavior",
+        "requireForce",
+    ])?;
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "advance",
+            &[],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Refusing to advance while HEAD is at public commit 62fc20d create test1.txt
+        To proceed anyways, run: git advance -f
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("advance", &["-f"])?;
+        insta::assert_snapshot!(stdout, @"No child commits to advance.");
+    }
+
+    Ok(())
+}
@@ -926,6 +926,52 @@ fn test_amend_undo_detached_head() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_amend_no_restack() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "HEAD^"])?;
+
+    git.write_file_txt("test2", "updated contents")?;
+
+    {
+        let (stdout, _stderr) = git.branchless("amend", &["--no-restack"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> reset 7ac317b9d1dd1bbdf46e8ee692b9b9e280f28a50 --
+        Amended with 1 uncommitted change.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |\
+        | @ 7ac317b create test2.txt
+        |
+        x 96d1c37 (rewritten as 7ac317b9) create test2.txt
+        |
+        o 70deb1e create test3.txt
+        hint: there is 1 abandoned commit in your commit graph
+        hint: to fix this, run: git restack
+        hint: disable this hint by running: git config --global branchless.hint.smartlogFixAbandoned false
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_amend_reparent() -> eyre::Result<()> {
     let git = make_git()?;
@@ -1498,3 +1544,273 @@ fn test_amend_with_branch_name_matching_file() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_amend_absorb() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.write_file_txt("foo", "foo1\nfoo2\nfoo3\n")?;
+    git.run(&["add", "."])?;
+    git.run(&["commit", "-m", "create foo.txt"])?;
+    git.write_file_txt("bar", "bar1\nbar2\nbar3\n")?;
+    git.run(&["add", "."])?;
+    git.run(&["commit", "-m", "create bar.txt"])?;
+    git.commit_file("test3", 3)?;
+
+    git.write_file_txt("foo", "foo1\nfoo2 updated\nfoo3\n")?;
+    git.write_file_txt("bar", "bar1\nbar2\nbar3\nbar4\n")?;
+    git.write_file_txt("initial", "initial updated\n")?;
+    git.run(&["add", "."])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("amend", &["--absorb"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/2] Committed as: 273fb29 create bar.txt
+        [2/2] Committed as: 7b65cf5 create test3.txt
+        branchless: processing 2 rewritten commits
+        branchless: running command: <git-executable> reset 7b65cf55689a4b595f32b5c78b5f1e81e0249947 --
+        Unstaged changes after reset:
+        M	initial.txt
+        In-memory rebase succeeded.
+        Absorbed 1 hunk into 98eaf26 create bar.txt
+        Absorbed 1 hunk into c4c90ba create foo.txt
+        Left 1 hunk staged, since it couldn't be attributed to a single draft commit.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o e790818 create foo.txt
+        |
+        o 273fb29 create bar.txt
+        |
+        @ 7b65cf5 create test3.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["log", "-p", "--format=%s", "master..HEAD"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        create test3.txt
+
+        diff --git a/test3.txt b/test3.txt
+        new file mode 100644
+        index 0000000..a474f4e
+        --- /dev/null
+        +++ b/test3.txt
+        @@ -0,0 +1 @@
+        +test3 contents
+        create bar.txt
+
+        diff --git a/bar.txt b/bar.txt
+        new file mode 100644
+        index 0000000..a62fec5
+        --- /dev/null
+        +++ b/bar.txt
+        @@ -0,0 +1,4 @@
+        +bar1
+        +bar2
+        +bar3
+        +bar4
+        create foo.txt
+
+        diff --git a/foo.txt b/foo.txt
+        new file mode 100644
+        index 0000000..48a6260
+        --- /dev/null
+        +++ b/foo.txt
+        @@ -0,0 +1,3 @@
+        +foo1
+        +foo2 updated
+        +foo3
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["status", "--short"])?;
+        insta::assert_snapshot!(stdout, @"M  initial.txt");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_amend_advance_auto() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.run(&["checkout", "-b", "branch-1"])?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "-b", "branch-2"])?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "branch-1"])?;
+    git.commit_file("test3", 3)?;
+    git.run(&["config", "branchless.advance.auto", "true"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |\
+        | o 96d1c37 (branch-2) create test2.txt
+        |
+        @ 4838e49 (> branch-1) create test3.txt
+        "###);
+    }
+
+    git.write_file_txt("test3", "updated contents")?;
+    {
+        let (stdout, _stderr) = git.branchless("amend", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: processing 1 update: branch branch-1
+        branchless: running command: <git-executable> reset branch-1 --
+        Advancing 1 commit onto ed9a6e8 create test3.txt.
+        Attempting rebase in-memory...
+        [1/1] Committed as: 8294422 create test2.txt
+        branchless: processing 1 update: branch branch-2
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout branch-1 --
+        In-memory rebase succeeded.
+        Amended with 1 uncommitted change.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        @ ed9a6e8 (> branch-1) create test3.txt
+        |
+        o 8294422 (branch-2) create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_amend_checkpoint() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+
+    git.write_file_txt("test1", "updated contents")?;
+    {
+        let (stdout, _stderr) = git.branchless("amend", &["--checkpoint"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> reset 3b98a960e6ebde39a933c25413b43bce8c0fd128 --
+        Amended with 1 uncommitted change.
+        Saved the original commit as a checkpoint. To recover it, run: git unhide 62fc20d
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("gc", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: collecting garbage
+        branchless: 1 dangling reference deleted
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["show-ref"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        3b98a960e6ebde39a933c25413b43bce8c0fd128 refs/branchless/3b98a960e6ebde39a933c25413b43bce8c0fd128
+        62fc20d2a290daea0d52bdc2ed2ad4be6491010e refs/branchless/checkpoints/62fc20d2a290daea0d52bdc2ed2ad4be6491010e
+        f777ecc9b0db5ed372b2615695191a8a17f79f24 refs/heads/master
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("unhide", &[&test1_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Unhid commit: 62fc20d create test1.txt
+        To hide this 1 commit, run: git undo
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | @ 3b98a96 create test1.txt
+        |
+        o 62fc20d create test1.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_amend_message_from() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+
+    git.write_file_txt("test2", "updated contents")?;
+    {
+        let (stdout, _stderr) = git.branchless("amend", &["--message-from", "HEAD^"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> reset b33b0c0b33d7ded36fa50fd3fbd424eaf529b042 --
+        Amended with 1 uncommitted change.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        @ b33b0c0 create test1.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("amend", &["--message-from", "master"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> reset 488d4de90e50023a5a87dba78a2cbe88411429c2 --
+        Amended commit message.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        @ 488d4de create initial.txt
+        "###);
+    }
+
+    Ok(())
+}
@@ -91,6 +91,52 @@ fn test_profiling() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_trace() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+
+    let trace_path = git.repo_path.join("trace.json");
+    // Global options must come before the subcommand.
+    git.run_with_options(
+        &[
+            "branchless",
+            "--trace",
+            trace_path.to_str().unwrap(),
+            "smartlog",
+        ],
+        &Default::default(),
+    )?;
+    let trace = std::fs::read_to_string(&trace_path)?;
+    assert!(trace.contains(r#""name":"smartlog""#), "{trace}");
+    assert!(
+        trace.contains(r#""name":"open_and_sync_if_changed""#),
+        "{trace}"
+    );
+
+    let trace_path = git.repo_path.join("trace-env.json");
+    git.branchless_with_options(
+        "smartlog",
+        &[],
+        &GitRunOptions {
+            env: {
+                let mut env: HashMap<String, String> = HashMap::new();
+                env.insert(
+                    "GIT_BRANCHLESS_TRACE".to_string(),
+                    trace_path.to_str().unwrap().to_string(),
+                );
+                env
+            },
+            ..Default::default()
+        },
+    )?;
+    let trace = std::fs::read_to_string(&trace_path)?;
+    assert!(trace.contains(r#""name":"smartlog""#), "{trace}");
+
+    Ok(())
+}
+
 #[test]
 fn test_sparse_checkout() -> eyre::Result<()> {
     let git = make_git()?;
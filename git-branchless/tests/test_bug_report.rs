@@ -47,7 +47,7 @@ fn test_bug_report() -> eyre::Result<()> {
         #!/bin/sh
         ## START BRANCHLESS CONFIG
 
-        git branchless hook post-applypatch "$@"
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-applypatch "$@"
 
         ## END BRANCHLESS CONFIG
         ```
@@ -57,7 +57,7 @@ fn test_bug_report() -> eyre::Result<()> {
         #!/bin/sh
         ## START BRANCHLESS CONFIG
 
-        git branchless hook post-checkout "$@"
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-checkout "$@"
 
         ## END BRANCHLESS CONFIG
         ```
@@ -67,7 +67,7 @@ fn test_bug_report() -> eyre::Result<()> {
         #!/bin/sh
         ## START BRANCHLESS CONFIG
 
-        git branchless hook post-commit "$@"
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-commit "$@"
 
         ## END BRANCHLESS CONFIG
         ```
@@ -77,7 +77,7 @@ fn test_bug_report() -> eyre::Result<()> {
         #!/bin/sh
         ## START BRANCHLESS CONFIG
 
-        git branchless hook post-merge "$@"
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-merge "$@"
 
         ## END BRANCHLESS CONFIG
         ```
@@ -87,7 +87,7 @@ fn test_bug_report() -> eyre::Result<()> {
         #!/bin/sh
         ## START BRANCHLESS CONFIG
 
-        git branchless hook post-rewrite "$@"
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-rewrite "$@"
 
         ## END BRANCHLESS CONFIG
         ```
@@ -97,7 +97,7 @@ fn test_bug_report() -> eyre::Result<()> {
         #!/bin/sh
         ## START BRANCHLESS CONFIG
 
-        git branchless hook pre-auto-gc "$@"
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook pre-auto-gc "$@"
 
         ## END BRANCHLESS CONFIG
         ```
@@ -109,7 +109,7 @@ fn test_bug_report() -> eyre::Result<()> {
 
         # Avoid canceling the reference transaction in the case that `branchless` fails
         # for whatever reason.
-        git branchless hook reference-transaction "$@" || (
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook reference-transaction "$@" || (
         echo 'branchless: Failed to process reference transaction!'
         echo 'branchless: Some events (e.g. branch updates) may have been lost.'
         echo 'branchless: This is a bug. Please report it.'
@@ -126,32 +126,30 @@ fn test_bug_report() -> eyre::Result<()> {
         <details>
         <summary>Show 5 events</summary>
 
-        ##### Event ID: 6, transaction ID: 4 (message: post-commit)
+        ##### Event ID: 4, transaction ID: 2 (message: git commit)
 
-        1. `CommitEvent { timestamp: <redacted for test>, event_tx_id: Id(4), commit_oid: NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f) }`
+        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(2), ref_name: ReferenceName("HEAD"), old_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e, new_oid: 96d1c37a3d4363611c49f7e52186e189a04c531f, message: None }`
+        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(2), ref_name: ReferenceName("refs/heads/master"), old_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e, new_oid: 96d1c37a3d4363611c49f7e52186e189a04c531f, message: None }`
+        1. `CommitEvent { timestamp: <redacted for test>, event_tx_id: Id(2), commit_oid: NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f) }`
         ```
         :
         @ 96d1c37 (> master) xxxxxx xxxxxxxxx
         ```
-        ##### Event ID: 4, transaction ID: 3 (message: reference-transaction)
+        ##### Event ID: 1, transaction ID: 1 (message: git commit)
 
-        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(3), ref_name: ReferenceName("HEAD"), old_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e, new_oid: 96d1c37a3d4363611c49f7e52186e189a04c531f, message: None }`
-        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(3), ref_name: ReferenceName("refs/heads/master"), old_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e, new_oid: 96d1c37a3d4363611c49f7e52186e189a04c531f, message: None }`
+        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(1), ref_name: ReferenceName("HEAD"), old_oid: f777ecc9b0db5ed372b2615695191a8a17f79f24, new_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e, message: None }`
+        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(1), ref_name: ReferenceName("refs/heads/master"), old_oid: f777ecc9b0db5ed372b2615695191a8a17f79f24, new_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e, message: None }`
+        1. `CommitEvent { timestamp: <redacted for test>, event_tx_id: Id(1), commit_oid: NonZeroOid(62fc20d2a290daea0d52bdc2ed2ad4be6491010e) }`
         ```
         :
         @ 96d1c37 (> master) xxxxxx xxxxxxxxx
         ```
-        ##### Event ID: 3, transaction ID: 2 (message: post-commit)
-
-        1. `CommitEvent { timestamp: <redacted for test>, event_tx_id: Id(2), commit_oid: NonZeroOid(62fc20d2a290daea0d52bdc2ed2ad4be6491010e) }`
+        There are no previous available events.
         ```
         :
         @ 96d1c37 (> master) xxxxxx xxxxxxxxx
         ```
-        ##### Event ID: 1, transaction ID: 1 (message: reference-transaction)
-
-        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(1), ref_name: ReferenceName("HEAD"), old_oid: f777ecc9b0db5ed372b2615695191a8a17f79f24, new_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e, message: None }`
-        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(1), ref_name: ReferenceName("refs/heads/master"), old_oid: f777ecc9b0db5ed372b2615695191a8a17f79f24, new_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e, message: None }`
+        There are no previous available events.
         ```
         :
         @ 96d1c37 (> master) xxxxxx xxxxxxxxx
@@ -168,3 +166,112 @@ fn test_bug_report() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_bug_report_redact() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? || git.produces_auto_merge_refs()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&[
+        "config",
+        "branchless.test.alias.diff",
+        &format!("git diff {}", &test1_oid.to_string()[..10]),
+    ])?;
+    // Hex strings which aren't known commit OIDs shouldn't be redacted.
+    git.run(&["config", "branchless.test.alias.show", "git show deadbeef"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("bug-report", &["--redact"])?;
+        let stdout = redact_timestamp(stdout);
+
+        let section_headers = stdout
+            .lines()
+            .filter(|line| line.starts_with("#### "))
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(section_headers, @r###"
+        #### Software version
+        #### Operating system
+        #### Command-line
+        #### Environment variables
+        #### Git version
+        #### Config
+        #### Hooks
+        #### Events
+        "###);
+
+        let config = stdout
+            .split_once("#### Config")
+            .and_then(|(_, stdout)| stdout.split_once("#### Hooks"))
+            .map(|(config, _)| config.trim())
+            .unwrap();
+        insta::assert_snapshot!(config, @r###"
+        <details>
+        <summary>Show 5 config entries</summary>
+
+        ```
+        branchless.commitDescriptors.relativetime=false
+        branchless.restack.preservetimestamps=true
+        branchless.core.mainbranch=master
+        branchless.test.alias.diff=git diff <oid-1>
+        branchless.test.alias.show=git show deadbeef
+        ```
+
+        </details>
+        "###);
+
+        let events = match stdout.split_once("#### Events") {
+            Some((_, stdout)) => stdout,
+            None => &stdout,
+        };
+        let events = events.trim();
+        insta::assert_snapshot!(events, @r###"
+        <details>
+        <summary>Show 5 events</summary>
+
+        ##### Event ID: 4, transaction ID: 2 (message: git commit)
+
+        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(2), ref_name: ReferenceName("HEAD"), old_oid: <oid-1>, new_oid: <oid-2>, message: None }`
+        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(2), ref_name: ReferenceName("refs/heads/master"), old_oid: <oid-1>, new_oid: <oid-2>, message: None }`
+        1. `CommitEvent { timestamp: <redacted for test>, event_tx_id: Id(2), commit_oid: NonZeroOid(<oid-2>) }`
+        ```
+        :
+        @ <oid-2> (> master) xxxxxx xxxxxxxxx
+        ```
+        ##### Event ID: 1, transaction ID: 1 (message: git commit)
+
+        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(1), ref_name: ReferenceName("HEAD"), old_oid: <oid-3>, new_oid: <oid-1>, message: None }`
+        1. `RefUpdateEvent { timestamp: <redacted for test>, event_tx_id: Id(1), ref_name: ReferenceName("refs/heads/master"), old_oid: <oid-3>, new_oid: <oid-1>, message: None }`
+        1. `CommitEvent { timestamp: <redacted for test>, event_tx_id: Id(1), commit_oid: NonZeroOid(<oid-1>) }`
+        ```
+        :
+        @ <oid-2> (> master) xxxxxx xxxxxxxxx
+        ```
+        There are no previous available events.
+        ```
+        :
+        @ <oid-2> (> master) xxxxxx xxxxxxxxx
+        ```
+        There are no previous available events.
+        ```
+        :
+        @ <oid-2> (> master) xxxxxx xxxxxxxxx
+        ```
+        There are no previous available events.
+        ```
+        :
+        @ <oid-2> (> master) xxxxxx xxxxxxxxx
+        ```
+
+        </details>
+        "###);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use lib::testing::{GitRunOptions, make_git};
+
+#[test]
+fn test_cherry_pick_onto_new_base() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.run(&["config", "branchless.restack.preserveTimestamps", "true"])?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    let test3_oid = git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test4", 4)?;
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("cherry-pick", &[&format!("{test2_oid}::{test3_oid}")])?;
+        insta::assert_snapshot!(stdout, @r###"
+        [1/2] Committed as: fa82175 create test2.txt
+        [2/2] Committed as: 127dd23 create test3.txt
+        branchless: running command: <git-executable> checkout 127dd237f00dd00a00c761dce29190947fc859c7 --
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | |
+        | o 96d1c37 create test2.txt
+        | |
+        | o 70deb1e create test3.txt
+        |
+        o 8f7aef5 create test4.txt
+        |
+        o fa82175 create test2.txt
+        |
+        @ 127dd23 create test3.txt
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | |
+        | o 96d1c37 create test2.txt
+        | |
+        | o 70deb1e create test3.txt
+        |
+        o 8f7aef5 create test4.txt
+        |
+        o fa82175 create test2.txt
+        |
+        @ 127dd23 create test3.txt
+        "###);
+    }
+
+    {
+        git.branchless("undo", &["-y"])?;
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | |
+        | o 96d1c37 create test2.txt
+        | |
+        | o 70deb1e create test3.txt
+        |
+        @ 8f7aef5 create test4.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_cherry_pick_branch() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.run(&["config", "branchless.restack.preserveTimestamps", "true"])?;
+    git.run(&["checkout", "-b", "foo"])?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("cherry-pick", &["foo"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        [1/1] Committed as: 0770943 create test1.txt
+        branchless: running command: <git-executable> checkout 07709435a8f6d1566e0091896d130c78acd429dd --
+        branchless: running command: <git-executable> checkout master --
+        O f777ecc create initial.txt
+        |\
+        : o 62fc20d (foo) create test1.txt
+        :
+        @ 0770943 (> master) create test1.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_cherry_pick_conflict() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file_with_contents("test1", 1, "foo\n")?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file_with_contents("test1", 2, "bar\n")?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "cherry-pick",
+            &[&test1_oid.to_string()],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        This operation would cause a merge conflict:
+        - (1 conflicting file) 2a0e648 create test1.txt
+        To resolve merge conflicts, run: git cherry-pick 2a0e648e0bfc9f06de580d0ed9d5ce16f28d26f7
+        "###);
+    }
+
+    Ok(())
+}
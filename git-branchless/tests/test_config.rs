@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use lib::core::config::find_config_key_schema;
+use lib::testing::{GitRunOptions, make_git};
+use regex::Regex;
+
+#[test]
+fn test_config_set() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("config", &["set", "branchless.advance.auto", "yes"])?;
+        insta::assert_snapshot!(stdout, @"Set branchless.advance.auto to: true");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless(
+            "config",
+            &["set", "branchless.hint.abandonedThrottle", "5m"],
+        )?;
+        insta::assert_snapshot!(stdout, @"Set branchless.hint.abandonedThrottle to: 300");
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["config", "branchless.hint.abandonedThrottle"])?;
+        insta::assert_snapshot!(stdout, @"300");
+    }
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("config", &["set", "branchless.theme", "minimal"])?;
+        insta::assert_snapshot!(stdout, @"Set branchless.theme to: minimal");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless(
+            "config",
+            &["set", "branchless.smartlog.obsoleteMarker", "~"],
+        )?;
+        insta::assert_snapshot!(stdout, @"Set branchless.smartlog.obsoleteMarker to: ~");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_config_set_invalid() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "config",
+            &["set", "branchless.advnce.auto", "true"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @r###"
+        Unknown config key: branchless.advnce.auto
+        To see the known config keys, run: git branchless config list
+        "###);
+    }
+
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "config",
+            &["set", "branchless.smartlog.maxCommits", "lots"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"Invalid value for config key branchless.smartlog.maxCommits: expected an integer, but got: lots");
+    }
+
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "config",
+            &["set", "branchless.smartlog.defaultRevset", "draft("],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @r###"
+        Invalid value for config key branchless.smartlog.defaultRevset: parse error: Unrecognized EOF found at 6
+        Expected one of a commit/branch/tag, a string literal, "(", ")", "..", ":" or "::"
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run_with_options(
+            &[
+                "config",
+                "--get-regexp",
+                "^branchless\\.(advnce|smartlog)\\.",
+            ],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_config_list() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.branchless("config", &["set", "branchless.smartlog.maxCommits", "10"])?;
+    git.run(&["config", "branchless.advnce.auto", "true"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("config", &["list"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless.advance.auto = false (default)
+        branchless.commitDescriptors.branches = true (default)
+        branchless.commitDescriptors.differentialRevision = true (default)
+        branchless.commitDescriptors.forgeStatus = true (default)
+        branchless.commitDescriptors.relativeTime = false (local)
+        branchless.commitDescriptors.testStatus = false (default)
+        branchless.core.mainBranch = master (local)
+        branchless.core.publicHeadBehavior = allow (default)
+        branchless.hide.confirmUnique = false (default)
+        branchless.hint.abandonedThrottle = 0 (default)
+        branchless.hint.addSkippedFiles = true (default)
+        branchless.hint.advanceChildCommits = true (default)
+        branchless.hint.cleanCachedTestResults = true (default)
+        branchless.hint.conflictMarkers = false (default)
+        branchless.hint.moveImplicitHeadArgument = true (default)
+        branchless.hint.restackWarnAbandoned = true (default)
+        branchless.hint.smartlogFixAbandoned = true (default)
+        branchless.hint.testShowVerbose = true (default)
+        branchless.navigation.autoSwitchBranches = true (default)
+        branchless.next.interactive = false (default)
+        branchless.rebase.autoStash = false (default)
+        branchless.rebase.committerDateIsAuthorDate = false (default)
+        branchless.rebase.useRerere = false (default)
+        branchless.record.untrackedFiles = disable (default)
+        branchless.restack.preserveTimestamps = true (local)
+        branchless.smartlog.childOrder = committerDate (default)
+        branchless.smartlog.defaultRevset = ((draft() | branches() | @) % main()) | branches() | @ (default)
+        branchless.smartlog.detectDuplicates = false (default)
+        branchless.smartlog.maxCommits = 10 (local)
+        branchless.smartlog.rewrittenAsText = rewritten as (default)
+        branchless.smartlog.showStashes = false (default)
+        branchless.test.strategy = working-copy (default)
+        branchless.theme = default (default)
+        branchless.undo.createSnapshots = true (default)
+        branchless.advnce.auto = true (local, unknown key)
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_config_get_effective() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    // Create a fake $HOME directory, so that we can set a global config value.
+    let fake_home_dir = git.repo_path.join("fake_home");
+    std::fs::create_dir(&fake_home_dir)?;
+    std::fs::write(
+        fake_home_dir.join(".gitconfig"),
+        "[branchless \"smartlog\"]\n\tmaxCommits = 5\n",
+    )?;
+    let git_run_options = GitRunOptions {
+        env: HashMap::from([(
+            "HOME".to_string(),
+            fake_home_dir.to_string_lossy().to_string(),
+        )]),
+        ..Default::default()
+    };
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "config",
+            &["get", "branchless.smartlog.maxCommits", "--effective"],
+            &git_run_options,
+        )?;
+        insta::assert_snapshot!(stdout, @"5 (global)");
+    }
+
+    git.run(&["config", "branchless.smartlog.maxCommits", "10"])?;
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "config",
+            &["get", "branchless.smartlog.maxCommits", "--effective"],
+            &git_run_options,
+        )?;
+        insta::assert_snapshot!(stdout, @"10 (local)");
+    }
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "config",
+            &["get", "branchless.smartlog.maxCommits"],
+            &git_run_options,
+        )?;
+        insta::assert_snapshot!(stdout, @"10");
+    }
+
+    git.write_file_txt("included", "[branchless \"advance\"]\n\tauto = true\n")?;
+    git.run(&["config", "include.path", "../included.txt"])?;
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "config",
+            &["get", "branchless.advance.auto", "--effective"],
+            &git_run_options,
+        )?;
+        insta::assert_snapshot!(stdout, @"true (local, via include)");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "config",
+            &["get", "branchless.next.interactive", "--effective"],
+            &git_run_options,
+        )?;
+        insta::assert_snapshot!(stdout, @"false (default)");
+    }
+
+    Ok(())
+}
+
+fn collect_source_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> eyre::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_source_files(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_config_schema_covers_source_keys() -> eyre::Result<()> {
+    let workspace_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("crate should be in a workspace");
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(workspace_dir)? {
+        let src_dir = entry?.path().join("src");
+        if src_dir.is_dir() {
+            collect_source_files(&src_dir, &mut files)?;
+        }
+    }
+    assert!(!files.is_empty());
+
+    let key_re = Regex::new(r#""(branchless\.[A-Za-z0-9_.*{}]+)""#)?;
+    let mut unknown_keys = Vec::new();
+    for path in files {
+        let contents = std::fs::read_to_string(&path)?;
+        for captures in key_re.captures_iter(&contents) {
+            let key = &captures[1];
+            if find_config_key_schema(key).is_none() {
+                unknown_keys.push(format!("{}: {key}", path.display()));
+            }
+        }
+    }
+    assert!(
+        unknown_keys.is_empty(),
+        "config keys missing from CONFIG_SCHEMA: {unknown_keys:#?}"
+    );
+
+    Ok(())
+}
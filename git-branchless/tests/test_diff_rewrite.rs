@@ -0,0 +1,57 @@
+use lib::testing::{GitRunOptions, make_git};
+
+#[test]
+fn test_diff_rewrite() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+    git.run(&["config", "diff.tool", "stub"])?;
+    git.run(&["config", "difftool.stub.cmd", "cat \"$LOCAL\" \"$REMOTE\""])?;
+    git.run(&["config", "difftool.prompt", "false"])?;
+
+    git.detach_head()?;
+    let old_oid = git.commit_file("test1", 1)?;
+    git.write_file_txt("test1", "updated contents\n")?;
+    git.branchless("amend", &[])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("diff-rewrite", &[&old_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> difftool 62fc20d2a290daea0d52bdc2ed2ad4be6491010e 885cc20abe22d825aefe968b82881774d35f0af9
+        test1 contents
+        updated contents
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_rewrite_not_rewritten() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "diff-rewrite",
+            &["HEAD"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @"Commit has not been rewritten: 62fc20d create test1.txt");
+    }
+
+    Ok(())
+}
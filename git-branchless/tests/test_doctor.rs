@@ -0,0 +1,72 @@
+use lib::testing::{GitRunOptions, make_git};
+
+#[test]
+fn test_doctor_deep_detects_drift() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+    git.run(&["branch", "foo"])?;
+    git.run(&["branch", "baz"])?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("doctor", &["--deep"])?;
+        insta::assert_snapshot!(stdout, @"No problems found.");
+    }
+
+    // Update references without running the hooks, so that the event log and
+    // commit graph don't observe the changes.
+    let (tree_oid, _stderr) = git.run(&["rev-parse", "HEAD^{tree}"])?;
+    let (new_oid, _stderr) = git.run(&[
+        "commit-tree",
+        tree_oid.trim(),
+        "-p",
+        "HEAD",
+        "-m",
+        "unobserved commit",
+    ])?;
+    git.run(&[
+        "-c",
+        "core.hooksPath=/dev/null",
+        "branch",
+        "bar",
+        new_oid.trim(),
+    ])?;
+    git.run(&[
+        "-c",
+        "core.hooksPath=/dev/null",
+        "branch",
+        "-f",
+        "foo",
+        "HEAD",
+    ])?;
+    git.run(&["-c", "core.hooksPath=/dev/null", "branch", "-D", "baz"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "doctor",
+            &["--deep"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Found 1 referenced commit missing from the commit graph: 813efc58b84504684335fb431e38bd96db55b3e6
+        Resynced the commit graph.
+        Branch bar points to 813efc58b84504684335fb431e38bd96db55b3e6, but it isn't in the event log
+        Branch baz was deleted, but the event log has it at 62fc20d2a290daea0d52bdc2ed2ad4be6491010e
+        Branch foo points to 96d1c37a3d4363611c49f7e52186e189a04c531f, but the event log has it at 62fc20d2a290daea0d52bdc2ed2ad4be6491010e
+        Found 3 differences between the event log and the repository. Operations such as `git undo` may not behave as expected.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("doctor", &[])?;
+        insta::assert_snapshot!(stdout, @"No problems found.");
+    }
+
+    Ok(())
+}
@@ -46,7 +46,7 @@ fn test_git_v2_31_events() -> eyre::Result<()> {
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                2,
+                1,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -58,7 +58,7 @@ fn test_git_v2_31_events() -> eyre::Result<()> {
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                3,
+                2,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -70,7 +70,7 @@ fn test_git_v2_31_events() -> eyre::Result<()> {
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                3,
+                2,
             ),
             ref_name: ReferenceName(
                 "refs/heads/test1",
@@ -82,14 +82,14 @@ fn test_git_v2_31_events() -> eyre::Result<()> {
         CommitEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                4,
+                2,
             ),
             commit_oid: NonZeroOid(62fc20d2a290daea0d52bdc2ed2ad4be6491010e),
         },
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                5,
+                3,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -101,7 +101,7 @@ fn test_git_v2_31_events() -> eyre::Result<()> {
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                6,
+                3,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -113,7 +113,7 @@ fn test_git_v2_31_events() -> eyre::Result<()> {
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                7,
+                4,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -125,21 +125,21 @@ fn test_git_v2_31_events() -> eyre::Result<()> {
         CommitEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                8,
+                4,
             ),
             commit_oid: NonZeroOid(fe65c1fe15584744e649b2c79d4cf9b0d878f92e),
         },
         ObsoleteEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                9,
+                5,
             ),
             commit_oid: NonZeroOid(62fc20d2a290daea0d52bdc2ed2ad4be6491010e),
         },
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                9,
+                5,
             ),
             ref_name: ReferenceName(
                 "refs/heads/test1",
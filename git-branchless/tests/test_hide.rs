@@ -447,6 +447,63 @@ fn test_hide_recursive() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_hide_recursive_stack() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["branch", "foo"])?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless(
+            "hide",
+            &["-r", "--no-delete-branches", &test1_oid.to_string()],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Hid commit: 62fc20d create test1.txt
+        Hid commit: 70deb1e create test3.txt
+        To unhide these 2 commits, run: git undo
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |
+        x 62fc20d (manually hidden) create test1.txt
+        |
+        o 96d1c37 (foo) create test2.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("hide", &["-r", &test1_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Hid commit: 62fc20d create test1.txt
+        (It was already hidden, so this operation had no effect.)
+        Hid commit: 96d1c37 create test2.txt
+        branchless: processing 1 update: branch foo
+        Deleted 1 branch: foo
+        To unhide these 2 commits and restore 1 branch, run: git undo
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_smartlog_active_non_head_main_branch_commit() -> eyre::Result<()> {
     let GitWrapperWithRemoteRepo {
@@ -589,3 +646,170 @@ fn test_smartlog_show_only_branches() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_hide_range() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["branch", "feature"])?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test4", 4)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("hide", &[&format!("{test1_oid}..feature")])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Hid commit: 96d1c37 create test2.txt
+        Hid commit: 70deb1e create test3.txt
+        branchless: processing 1 update: branch feature
+        Deleted 1 branch: feature
+        To unhide these 2 commits and restore 1 branch, run: git undo
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        |
+        @ 8f7aef5 (> master) create test4.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_hide_range_excludes_public_commits() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, _stderr) = git.branchless("hide", &[&format!("(::{test2_oid})")])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Hid commit: 62fc20d create test1.txt
+        Hid commit: 96d1c37 create test2.txt
+        To unhide these 2 commits, run: git undo
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        @ 98b9119 (> master) create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_unhide_range() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    let test3_oid = git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+
+    let range = format!("{test1_oid}..{test3_oid}");
+    {
+        let (stdout, _stderr) = git.branchless("hide", &[&range])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Hid commit: 96d1c37 create test2.txt
+        Hid commit: 70deb1e create test3.txt
+        To unhide these 2 commits, run: git undo
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("unhide", &[&range])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Unhid commit: 96d1c37 create test2.txt
+        Unhid commit: 70deb1e create test3.txt
+        To hide these 2 commits, run: git undo
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc (> master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |
+        o 96d1c37 create test2.txt
+        |
+        o 70deb1e create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_hide_confirm_unique() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.run(&["config", "branchless.hide.confirmUnique", "true"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "hide",
+            &[&test1_oid.to_string()],
+            &GitRunOptions {
+                input: Some("n\n".to_string()),
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Warning: 1 commit will not be reachable from any remaining branch or HEAD:
+        - 62fc20d create test1.txt
+        Hide anyways? [yN] Aborting hide.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "hide",
+            &[&test1_oid.to_string()],
+            &GitRunOptions {
+                input: Some("y\n".to_string()),
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Warning: 1 commit will not be reachable from any remaining branch or HEAD:
+        - 62fc20d create test1.txt
+        Hide anyways? [yN] Hid commit: 62fc20d create test1.txt
+        To unhide this 1 commit, run: git undo
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @"@ f777ecc (> master) create initial.txt");
+    }
+
+    Ok(())
+}
@@ -52,6 +52,80 @@ fn test_abandoned_commit_message() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_abandoned_commit_message_throttled() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.run(&["config", "branchless.hint.abandonedThrottle", "1h"])?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "HEAD^"])?;
+
+    {
+        let (_stdout, stderr) = git.run(&["commit", "--amend", "-m", "amend test1"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 1 update: ref HEAD
+        branchless: processed commit: 9e8dbe9 amend test1
+        hint: to move child commits onto this commit, run: git advance
+        hint: disable this hint by running: git config --global branchless.hint.advanceChildCommits false
+        branchless: processing 1 rewritten commit
+        branchless: This operation abandoned 1 commit!
+        branchless: Consider running one of the following:
+        branchless:   - git restack: re-apply the abandoned commits/branches
+        branchless:     (this is most likely what you want to do)
+        branchless:   - git smartlog: assess the situation
+        branchless:   - git hide [<commit>...]: hide the commits from the smartlog
+        branchless:   - git undo: undo the operation
+        hint: disable this hint by running: git config --global branchless.hint.restackWarnAbandoned false
+        "###);
+    }
+
+    {
+        let (_stdout, stderr) = git.run(&["commit", "--amend", "-m", "amend test1 again"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 1 update: ref HEAD
+        branchless: processed commit: c1e22fd amend test1 again
+        hint: to move child commits onto this commit, run: git advance
+        hint: disable this hint by running: git config --global branchless.hint.advanceChildCommits false
+        branchless: processing 1 rewritten commit
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_abandoned_commit_message_invalid_throttle() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.run(&["config", "branchless.hint.abandonedThrottle", "-5m"])?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "HEAD^"])?;
+
+    {
+        let (_stdout, stderr) = git.run(&["commit", "--amend", "-m", "amend test1"])?;
+        assert!(
+            stderr.contains(
+                "Invalid value for config key branchless.hint.abandonedThrottle: expected a duration, but got: -5m"
+            ),
+            "{stderr}"
+        );
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_abandoned_branch_message() -> eyre::Result<()> {
     let git = make_git()?;
@@ -271,14 +345,14 @@ fn test_merge_commit_recorded() -> eyre::Result<()> {
         CommitEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                2,
+                1,
             ),
             commit_oid: NonZeroOid(62fc20d2a290daea0d52bdc2ed2ad4be6491010e),
         },
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                3,
+                2,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -290,7 +364,7 @@ fn test_merge_commit_recorded() -> eyre::Result<()> {
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                4,
+                2,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -302,7 +376,7 @@ fn test_merge_commit_recorded() -> eyre::Result<()> {
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                5,
+                3,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -314,14 +388,14 @@ fn test_merge_commit_recorded() -> eyre::Result<()> {
         CommitEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                6,
+                3,
             ),
             commit_oid: NonZeroOid(fe65c1fe15584744e649b2c79d4cf9b0d878f92e),
         },
         RefUpdateEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                8,
+                4,
             ),
             ref_name: ReferenceName(
                 "HEAD",
@@ -333,7 +407,7 @@ fn test_merge_commit_recorded() -> eyre::Result<()> {
         CommitEvent {
             timestamp: 0.0,
             event_tx_id: Id(
-                9,
+                4,
             ),
             commit_oid: NonZeroOid(91a5ccb4feefba38b0ffa4911c5c3f6c225f662e),
         },
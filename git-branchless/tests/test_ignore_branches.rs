@@ -1,4 +1,7 @@
-use lib::testing::make_git;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use lib::testing::{GitRunOptions, make_git};
 
 #[test]
 fn test_ignore_branches_exact() -> eyre::Result<()> {
@@ -120,3 +123,76 @@ fn test_ignore_branches_multiple_patterns() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_ignore_branches_older_than() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("file1", 1)?;
+
+    // Test commits are dated in 2020, so this branch is old.
+    git.run(&["checkout", "-b", "old-experiment"])?;
+    git.commit_file("file2", 2)?;
+    git.run(&["checkout", "master"])?;
+
+    // Commit with the current time, so that this branch is recent.
+    git.run(&["checkout", "-b", "recent-feature"])?;
+    git.write_file_txt("file3", "contents")?;
+    git.run(&["add", "."])?;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
+    git.run_with_options(
+        &["commit", "-m", "recent commit"],
+        &GitRunOptions {
+            env: HashMap::from([(
+                "GIT_COMMITTER_DATE".to_string(),
+                format!("@{} +0000", now.as_secs()),
+            )]),
+            ..Default::default()
+        },
+    )?;
+    git.run(&["checkout", "master"])?;
+
+    git.run(&[
+        "config",
+        "--add",
+        "branchless.core.ignoreBranches",
+        "older-than:30d",
+    ])?;
+
+    {
+        let stdout = git.smartlog()?;
+        assert!(!stdout.contains("old-experiment"));
+        assert!(stdout.contains("recent-feature"));
+        assert!(stdout.contains("(> master)"), "master should still appear");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_ignore_branches_older_than_invalid_duration() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("file1", 1)?;
+    git.run(&["checkout", "-b", "test"])?;
+    git.commit_file("file2", 2)?;
+    git.run(&["checkout", "master"])?;
+
+    // An invalid duration is skipped rather than ignoring every branch.
+    git.run(&[
+        "config",
+        "--add",
+        "branchless.core.ignoreBranches",
+        "older-than:soon",
+    ])?;
+
+    {
+        let stdout = git.smartlog()?;
+        assert!(stdout.contains("(test)"));
+        assert!(stdout.contains("(> master)"));
+    }
+
+    Ok(())
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use eyre::Context;
 use itertools::Itertools;
+use lib::core::eventlog::EventLogDb;
 use lib::git::GitVersion;
 use lib::testing::{
     GitInitOptions, GitRunOptions, GitWorktreeWrapper, make_git, make_git_worktree,
@@ -61,6 +62,85 @@ echo Hello, world
     Ok(())
 }
 
+#[test]
+fn test_install_hooks_force() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    // Simulate another tool overwriting the hook with a script that exits
+    // early, so that re-appending the git-branchless section isn't enough.
+    let hook_path = git.repo_path.join(".git").join("hooks").join("post-commit");
+    std::fs::write(
+        &hook_path,
+        "#!/bin/sh
+echo Hello, world
+exit 0
+",
+    )?;
+    git.branchless("install-hooks", &[])?;
+
+    {
+        let (_stdout, stderr) = git.run(&["commit", "--allow-empty", "-m", "test1"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 2 updates: branch master, ref HEAD
+        Hello, world
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("install-hooks", &["--force"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Reinstalling hooks: post-applypatch, post-checkout, post-commit, post-merge, post-rewrite, pre-auto-gc, reference-transaction
+        Backed up existing post-commit hook to: <repo-path>/.git/hooks/post-commit.branchless-backup
+        "###);
+    }
+
+    {
+        let hook_contents = std::fs::read_to_string(&hook_path)?;
+        insta::assert_snapshot!(hook_contents, @r###"
+        #!/bin/sh
+        ## START BRANCHLESS CONFIG
+
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-commit "$@"
+        ## END BRANCHLESS CONFIG
+        echo Hello, world
+        exit 0
+        "###);
+        let backup_contents =
+            std::fs::read_to_string(hook_path.with_file_name("post-commit.branchless-backup"))?;
+        insta::assert_snapshot!(backup_contents, @r###"
+        #!/bin/sh
+        echo Hello, world
+        exit 0
+        ## START BRANCHLESS CONFIG
+
+        BRANCHLESS_HOOK_PARENT_PID="$PPID" git branchless hook post-commit "$@"
+        ## END BRANCHLESS CONFIG
+        "###);
+    }
+
+    {
+        let (_stdout, stderr) = git.run(&["commit", "--allow-empty", "-m", "test2"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 2 updates: branch master, ref HEAD
+        branchless: processed commit: 5d975f4 test2
+        Hello, world
+        "###);
+    }
+
+    {
+        // Hooks which are already up-to-date aren't backed up again.
+        let (stdout, _stderr) = git.branchless("install-hooks", &["--force"])?;
+        insta::assert_snapshot!(stdout, @"Reinstalling hooks: post-applypatch, post-checkout, post-commit, post-merge, post-rewrite, pre-auto-gc, reference-transaction");
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_alias_installed() -> eyre::Result<()> {
     let git = make_git()?;
@@ -181,8 +261,6 @@ fn test_old_git_version_warning() -> eyre::Result<()> {
         let stdout = stdout.replace(version_str.trim(), "<git version output>");
         insta::assert_snapshot!(stdout, @r###"
         Created config file at <repo-path>/.git/branchless/config
-        Auto-detected your main branch as: master
-        If this is incorrect, run: git branchless init --main-branch <branch>
         Installing hooks: post-applypatch, post-checkout, post-commit, post-merge, post-rewrite, pre-auto-gc, reference-transaction
         Warning: the branchless workflow's `git undo` command requires Git
         v2.29 or later, but your Git version is: <git version output>
@@ -195,8 +273,8 @@ fn test_old_git_version_warning() -> eyre::Result<()> {
 
         This only applies to the `git undo` command. Other commands which are part of
         the branchless workflow will work properly.
-        Successfully installed git-branchless.
-        To uninstall, run: git branchless init --uninstall
+        git-branchless is already initialized.
+        To reinitialize it, run: git branchless init --force
         "###);
     }
 
@@ -233,6 +311,51 @@ fn test_init_basic() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_init_already_initialized() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+
+    let get_num_events = || -> eyre::Result<usize> {
+        let repo = git.get_repo()?;
+        let conn = repo.get_db_conn()?;
+        let event_log_db = EventLogDb::new(&conn)?;
+        Ok(event_log_db.get_events()?.len())
+    };
+    let num_events = get_num_events()?;
+    assert!(num_events > 0);
+
+    {
+        let (stdout, stderr) = git.branchless("init", &[])?;
+        insta::assert_snapshot!(stderr, @"");
+        insta::assert_snapshot!(stdout, @r###"
+        Created config file at <repo-path>/.git/branchless/config
+        Installing hooks: post-applypatch, post-checkout, post-commit, post-merge, post-rewrite, pre-auto-gc, reference-transaction
+        git-branchless is already initialized.
+        To reinitialize it, run: git branchless init --force
+        "###);
+    }
+    assert_eq!(get_num_events()?, num_events);
+
+    {
+        let (stdout, _stderr) = git.branchless("init", &["--force"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Created config file at <repo-path>/.git/branchless/config
+        Auto-detected your main branch as: master
+        If this is incorrect, run: git branchless init --main-branch <branch>
+        Installing hooks: post-applypatch, post-checkout, post-commit, post-merge, post-rewrite, pre-auto-gc, reference-transaction
+        Successfully installed git-branchless.
+        To uninstall, run: git branchless init --uninstall
+        "###);
+    }
+    assert_eq!(get_num_events()?, num_events);
+
+    Ok(())
+}
+
 #[cfg(unix)]
 #[test]
 fn test_init_prompt_for_main_branch() -> eyre::Result<()> {
@@ -317,9 +440,9 @@ fn test_main_branch_not_found_error_message() -> eyre::Result<()> {
 
        0: branchless::core::eventlog::from_event_log_db with effects=<Output fancy=false> repo=<Git repository at: "<repo-path>/.git/"> event_log_db=<EventLogDb path=Some("<repo-path>/.git/branchless/db.sqlite3")>
           at some/file/path.rs:123
-       1: git_branchless_smartlog::smartlog with effects=<Output fancy=false> git_run_info=<GitRunInfo path_to_git="<git-executable>" working_directory="<repo-path>" env=not shown> options=SmartlogOptions { event_id: None, revset: None, resolve_revset_options: ResolveRevsetOptions { show_hidden_commits: false }, reverse: false, exact: false }
+       1: git_branchless_smartlog::smartlog with effects=<Output fancy=false> git_run_info=<GitRunInfo path_to_git="<git-executable>" working_directory="<repo-path>" env=not shown> options=SmartlogOptions { event_id: None, revset: None, resolve_revset_options: ResolveRevsetOptions { show_hidden_commits: false }, reverse: false, exact: false, ancestors: None, exclude: [], relative_to: None, highlight: None, head: None, context: [], merges_only: false, no_merges: false, paths: [], verify: false, no_context: false, json_stream: false }
           at some/file/path.rs:123
-       2: git_branchless_smartlog::command_main with ctx=CommandContext { effects: <Output fancy=false>, git_run_info: <GitRunInfo path_to_git="<git-executable>" working_directory="<repo-path>" env=not shown> } args=SmartlogArgs { event_id: None, revset: None, reverse: false, exact: false, resolve_revset_options: ResolveRevsetOptions { show_hidden_commits: false } }
+       2: git_branchless_smartlog::command_main with ctx=CommandContext { effects: <Output fancy=false>, git_run_info: <GitRunInfo path_to_git="<git-executable>" working_directory="<repo-path>" env=not shown> } args=SmartlogArgs { event_id: None, revset: None, reverse: false, exact: false, ancestors: None, exclude: [], relative_to: None, highlight: None, head: None, context: [], merges_only: false, no_merges: false, paths: [], verify: false, no_context: false, json_stream: false, resolve_revset_options: ResolveRevsetOptions { show_hidden_commits: false } }
           at some/file/path.rs:123
 
     Suggestion:
@@ -357,8 +480,62 @@ fn test_init_uninstall() -> eyre::Result<()> {
         insta::assert_snapshot!(stderr, @"");
         insta::assert_snapshot!(stdout, @r###"
         Removing config file: <repo-path>/.git/branchless/config
+        Removing 2 branchless config keys from: <repo-path>/.git/config
+        Uninstalling hooks: post-applypatch, post-checkout, post-commit, post-merge, post-rewrite, pre-auto-gc, reference-transaction
+        "###);
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_init_uninstall_purge() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+
+    // A hook which was chained with the git-branchless hook should be kept.
+    let post_commit_path = git.repo_path.join(".git").join("hooks").join("post-commit");
+    let post_commit_contents = std::fs::read_to_string(&post_commit_path)?;
+    std::fs::write(
+        &post_commit_path,
+        format!("{post_commit_contents}echo 'Hello from the user hook'\n"),
+    )?;
+
+    {
+        let (stdout, stderr) = git.branchless("init", &["--uninstall", "--purge"])?;
+        insta::assert_snapshot!(stderr, @"");
+        insta::assert_snapshot!(stdout, @r###"
+        Removing config file: <repo-path>/.git/branchless/config
+        Removing 2 branchless config keys from: <repo-path>/.git/config
         Uninstalling hooks: post-applypatch, post-checkout, post-commit, post-merge, post-rewrite, pre-auto-gc, reference-transaction
+        Removing git-branchless data: <repo-path>/.git/branchless
+        "###);
+    }
+
+    {
+        let post_commit_contents = std::fs::read_to_string(&post_commit_path)?;
+        insta::assert_snapshot!(post_commit_contents, @r###"
+        #!/bin/sh
+        echo 'Hello from the user hook'
         "###);
+        assert!(
+            !git.repo_path
+                .join(".git")
+                .join("hooks")
+                .join("reference-transaction")
+                .exists()
+        );
+        assert!(!git.repo_path.join(".git").join("branchless").exists());
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["config", "--list", "--local"])?;
+        assert!(!stdout.contains("branchless"), "{stdout}");
+        let (stdout, _stderr) = git.run(&["for-each-ref", "refs/branchless/"])?;
+        insta::assert_snapshot!(stdout, @"");
     }
 
     Ok(())
@@ -657,17 +834,17 @@ fn test_install_man_pages() -> eyre::Result<()> {
     .SH NAME
     git\-branchless \- Branchless workflow for Git
     .SH SYNOPSIS
-    \fBgit\-branchless\fR [\fB\-C \fR] [\fB\-\-color\fR] [\fB\-h\fR|\fB\-\-help\fR] [\fB\-V\fR|\fB\-\-version\fR] <\fIsubcommands\fR>
+    \fBgit\-branchless\fR [\fB\-C \fR] [\fB\-\-color\fR] [\fB\-\-trace\fR] [\fB\-h\fR|\fB\-\-help\fR] [\fB\-V\fR|\fB\-\-version\fR] <\fIsubcommands\fR>
     .SH DESCRIPTION
     Branchless workflow for Git.
     .PP
     See the documentation at https://github.com/arxanas/git\-branchless/wiki.
     .SH OPTIONS
     .TP
-    \fB\-C\fR=\fIWORKING_DIRECTORY\fR
+    \fB\-C\fR \fI<WORKING_DIRECTORY>\fR
     Change to the given directory before executing the rest of the program. (The option is called `\-C` for symmetry with Git.)
     .TP
-    \fB\-\-color\fR=\fICOLOR\fR
+    \fB\-\-color\fR \fI<COLOR>\fR
     Flag to force enable or disable terminal colors
     .br
 
@@ -682,6 +859,9 @@ fn test_install_man_pages() -> eyre::Result<()> {
     never: Never display terminal colors
     .RE
     .TP
+    \fB\-\-trace\fR \fI<FILE>\fR
+    Write the tracing spans recorded while running the command to the given file as JSON, for profiling. This can also be set with the `GIT_BRANCHLESS_TRACE` environment variable
+    .TP
     \fB\-h\fR, \fB\-\-help\fR
     Print help (see a summary with \*(Aq\-h\*(Aq)
     .TP
@@ -689,15 +869,30 @@ fn test_install_man_pages() -> eyre::Result<()> {
     Print version
     .SH SUBCOMMANDS
     .TP
+    git\-branchless\-advance(1)
+    Move sibling commits onto the current commit
+    .TP
     git\-branchless\-amend(1)
     Amend the current HEAD commit
     .TP
     git\-branchless\-bug\-report(1)
     Gather information about recent operations to upload as part of a bug report
     .TP
+    git\-branchless\-cherry\-pick(1)
+    Apply the given commits on top of the current commit, in memory
+    .TP
+    git\-branchless\-config(1)
+    View and update git\-branchless configuration
+    .TP
+    git\-branchless\-diff\-rewrite(1)
+    Compare a commit with the commit it was rewritten into (for example, by a rebase or amend) using `git difftool`
+    .TP
     git\-branchless\-difftool(1)
     Use the partial commit selector UI as a Git\-compatible difftool; see git\-difftool(1) for more information on Git difftools
     .TP
+    git\-branchless\-doctor(1)
+    Check that git\-branchless\*(Aqs commit graph is consistent with the repository, resyncing it if necessary
+    .TP
     git\-branchless\-gc(1)
     Run internal garbage collection
     .TP
@@ -707,9 +902,15 @@ fn test_install_man_pages() -> eyre::Result<()> {
     git\-branchless\-init(1)
     Initialize the branchless workflow for this repository
     .TP
+    git\-branchless\-install\-hooks(1)
+    Install the git\-branchless hooks in the current repository. Use `\-\-force` to repair hooks which have been corrupted or overwritten
+    .TP
     git\-branchless\-install\-man\-pages(1)
     Install git\-branchless\*(Aqs man\-pages to the given path
     .TP
+    git\-branchless\-log(1)
+    Print the current stack as a list of commits, newest first
+    .TP
     git\-branchless\-move(1)
     Move a subtree of commits from one location to another
     .TP
@@ -719,9 +920,18 @@ fn test_install_man_pages() -> eyre::Result<()> {
     git\-branchless\-prev(1)
     Move to an earlier commit in the current stack
     .TP
+    git\-branchless\-prune\-worktrees(1)
+    Prune administrative entries for linked worktrees whose working directories no longer exist, along with any git\-branchless state associated with them
+    .TP
     git\-branchless\-query(1)
     Query the commit graph using the "revset" language and print matching commits
     .TP
+    git\-branchless\-reflog(1)
+    Show the history of operations recorded by git\-branchless, newest first. Unlike `git reflog`, internal reference updates are grouped by the operation which caused them
+    .TP
+    git\-branchless\-rename\-branch(1)
+    Rename a local branch. The rename is recorded as a single operation, so it can be reverted with `git undo`
+    .TP
     git\-branchless\-repair(1)
     Restore internal invariants by reconciling the internal operation log with the state of the Git repository
     .TP
@@ -740,6 +950,9 @@ fn test_install_man_pages() -> eyre::Result<()> {
     git\-branchless\-split(1)
     Split commits
     .TP
+    git\-branchless\-status(1)
+    Summarize the state of the commit graph: the number of draft and abandoned commits, and the current position
+    .TP
     git\-branchless\-submit(1)
     Push commits to a remote
     .TP
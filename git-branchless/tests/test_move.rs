@@ -1,5 +1,6 @@
+use lib::testing::pty::{PtyAction, run_in_pty};
 use lib::testing::{
-    GitInitOptions, GitRunOptions, GitWorktreeWrapper, GitWrapperWithRemoteRepo,
+    Git, GitInitOptions, GitRunOptions, GitWorktreeWrapper, GitWrapperWithRemoteRepo,
     extract_hint_command, make_git, make_git_with_remote_repo, make_git_worktree,
     remove_rebase_lines,
 };
@@ -75,6 +76,7 @@ fn test_move_stick() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(70deb1e28791d8e7dd5a1f0c871a51b91282562f),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(70deb1e28791d8e7dd5a1f0c871a51b91282562f),
@@ -84,6 +86,7 @@ fn test_move_stick() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(355e173bf9c5d2efac2e451da0cdad3fb82b869a),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(355e173bf9c5d2efac2e451da0cdad3fb82b869a),
@@ -210,6 +213,7 @@ fn test_move_insert_stick() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(70deb1e28791d8e7dd5a1f0c871a51b91282562f),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(70deb1e28791d8e7dd5a1f0c871a51b91282562f),
@@ -219,6 +223,7 @@ fn test_move_insert_stick() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(355e173bf9c5d2efac2e451da0cdad3fb82b869a),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(355e173bf9c5d2efac2e451da0cdad3fb82b869a),
@@ -228,6 +233,7 @@ fn test_move_insert_stick() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f),
@@ -1469,6 +1475,138 @@ fn test_move_insert_tree() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_move_after() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+
+    git.run(&["checkout", &test1_oid.to_string()])?;
+    git.commit_file("test3", 3)?;
+    let test4_oid = git.commit_file("test4", 4)?;
+
+    git.branchless(
+        "move",
+        &[
+            "-s",
+            &test4_oid.to_string(),
+            "--after",
+            &test1_oid.to_string(),
+        ],
+    )?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 62fc20d (master) create test1.txt
+        |
+        @ bf0d52a create test4.txt
+        |\
+        | o 44352d0 create test2.txt
+        |
+        o 0a4a701 create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_before() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+    let test3_oid = git.commit_file("test3", 3)?;
+    git.commit_file("test4", 4)?;
+    git.commit_file("test5", 5)?;
+
+    git.branchless("move", &["-x", "HEAD", "--before", &test3_oid.to_string()])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 62fc20d (master) create test1.txt
+        |
+        o 96d1c37 create test2.txt
+        |
+        @ d2e18e3 create test5.txt
+        |
+        o 8e521a1 create test3.txt
+        |
+        o e96ba35 create test4.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_after_before_moved_commit() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    let test3_oid = git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &[
+                "-s",
+                &test2_oid.to_string(),
+                "--after",
+                &test3_oid.to_string(),
+            ],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"The --after flag cannot be used with a commit which is itself being moved: 70deb1e28791d8e7dd5a1f0c871a51b91282562f");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &[
+                "-s",
+                &test2_oid.to_string(),
+                "--before",
+                &test3_oid.to_string(),
+            ],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"The --before flag cannot be used with a commit which is itself being moved: 70deb1e28791d8e7dd5a1f0c871a51b91282562f");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_move_exact_range_tree() -> eyre::Result<()> {
     let git = make_git()?;
@@ -2294,6 +2432,7 @@ fn test_move_with_source_not_in_smartlog() -> eyre::Result<()> {
                             commits_to_apply_oids: [
                                 NonZeroOid(70deb1e28791d8e7dd5a1f0c871a51b91282562f),
                             ],
+                            message: None,
                         },
                         DetectEmptyCommit {
                             commit_oid: NonZeroOid(70deb1e28791d8e7dd5a1f0c871a51b91282562f),
@@ -2303,6 +2442,7 @@ fn test_move_with_source_not_in_smartlog() -> eyre::Result<()> {
                             commits_to_apply_oids: [
                                 NonZeroOid(355e173bf9c5d2efac2e451da0cdad3fb82b869a),
                             ],
+                            message: None,
                         },
                         DetectEmptyCommit {
                             commit_oid: NonZeroOid(355e173bf9c5d2efac2e451da0cdad3fb82b869a),
@@ -2406,6 +2546,7 @@ fn test_move_merge_conflict() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(e85d25c772a05b5c73ea8ec43881c12bbf588848),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(e85d25c772a05b5c73ea8ec43881c12bbf588848),
@@ -2447,6 +2588,77 @@ fn test_move_merge_conflict() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_move_strategy_option() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    let base_oid = git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    let other_oid = git.commit_file_with_contents("conflict", 2, "conflict 1\n")?;
+    git.run(&["checkout", &base_oid.to_string()])?;
+    git.commit_file_with_contents("conflict", 2, "conflict 2\n")?;
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("move", &["-X", "ours", "--source", &other_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Skipped now-empty commit: 8567239 create conflict.txt
+        branchless: processing 1 rewritten commit
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["show", "HEAD:conflict.txt"])?;
+        insta::assert_snapshot!(stdout, @"conflict 2");
+    }
+
+    git.branchless("undo", &["--yes"])?;
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &[
+                "--on-disk",
+                "--strategy-option",
+                "theirs",
+                "--source",
+                &other_oid.to_string(),
+            ],
+        )?;
+        let stdout = remove_rebase_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> diff --quiet
+        Calling Git for on-disk rebase...
+        branchless: running command: <git-executable> rebase --continue
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 62fc20d (master) create test1.txt
+        |
+        @ 202143f create conflict.txt
+        |
+        o c7c2ea2 create conflict.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["show", "c7c2ea2:conflict.txt"])?;
+        insta::assert_snapshot!(stdout, @"conflict 1");
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_move_base() -> eyre::Result<()> {
     let git = make_git()?;
@@ -2480,6 +2692,7 @@ fn test_move_base() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f),
@@ -2489,6 +2702,7 @@ fn test_move_base() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(70deb1e28791d8e7dd5a1f0c871a51b91282562f),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(70deb1e28791d8e7dd5a1f0c871a51b91282562f),
@@ -2620,6 +2834,7 @@ fn test_move_checkout_new_head() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(fe65c1fe15584744e649b2c79d4cf9b0d878f92e),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(fe65c1fe15584744e649b2c79d4cf9b0d878f92e),
@@ -2696,6 +2911,7 @@ fn test_move_branch() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(98b9119d16974f372e76cb64a3b77c528fc0b18b),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(98b9119d16974f372e76cb64a3b77c528fc0b18b),
@@ -2779,73 +2995,248 @@ fn test_move_base_onto_head() -> eyre::Result<()> {
 }
 
 #[test]
-fn test_move_force_in_memory() -> eyre::Result<()> {
+fn test_move_dest_only_moves_current_stack() -> eyre::Result<()> {
     let git = make_git()?;
 
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
     git.init_repo()?;
 
-    git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
     git.commit_file("test2", 2)?;
-    git.run(&["checkout", "HEAD~"])?;
-
-    git.write_file_txt("test2", "conflicting contents")?;
-    git.run(&["add", "."])?;
-    git.run(&["commit", "-m", "conflicting test2"])?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
 
     {
-        let (stdout, stderr) = git.branchless_with_options(
-            "move",
-            &["-d", "master", "--in-memory"],
-            &GitRunOptions {
-                expected_exit_code: 1,
-                ..Default::default()
-            },
-        )?;
-        insta::assert_snapshot!(stderr, @"");
+        let (stdout, _stderr) = git.branchless("move", &["-d", "master"])?;
         insta::assert_snapshot!(stdout, @r###"
         Attempting rebase in-memory...
-        This operation would cause a merge conflict:
-        - (1 conflicting file) 081b474 conflicting test2
-        To resolve merge conflicts, retry this operation with the --merge option.
+        [1/2] Committed as: 4b9ce31 create test1.txt
+        [2/2] Committed as: 9f77bc5 create test2.txt
+        branchless: processing 2 rewritten commits
+        branchless: running command: <git-executable> checkout 4b9ce31bb58aed39e2329c3993ef97b0c4680e49 --
+        :
+        O 98b9119 (master) create test3.txt
+        |
+        @ 4b9ce31 create test1.txt
+        |
+        o 9f77bc5 create test2.txt
+        In-memory rebase succeeded.
         "###);
     }
 
     {
-        let (stdout, stderr) = git.branchless_with_options(
-            "move",
-            &["-d", "master", "--in-memory", "--merge"],
-            &GitRunOptions {
-                expected_exit_code: 2,
-                ..Default::default()
-            },
-        )?;
-        insta::assert_snapshot!(stderr, @r###"
-        error: the argument '--in-memory' cannot be used with '--merge'
-
-        Usage: git-branchless move --dest <DEST> --in-memory
-
-        For more information, try '--help'.
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 98b9119 (master) create test3.txt
+        |
+        @ 4b9ce31 create test1.txt
+        |
+        o 9f77bc5 create test2.txt
         "###);
-        insta::assert_snapshot!(stdout, @"");
     }
 
     Ok(())
 }
 
 #[test]
-fn test_move_force_in_memory_dry_run() -> eyre::Result<()> {
+fn test_move_explain() -> eyre::Result<()> {
     let git = make_git()?;
-
     git.init_repo()?;
 
+    git.detach_head()?;
     git.commit_file("test1", 1)?;
-    git.commit_file("test2", 2)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test4", 4)?;
+    git.run(&["checkout", "HEAD~"])?;
 
     {
-        git.run(&["checkout", "HEAD~"])?;
-        git.commit_file("test3", 3)?;
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &[
+                "--source",
+                &test2_oid.to_string(),
+                "--dest",
+                "master",
+                "--explain",
+            ],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Explanation of the rebase plan:
+        - Moving 96d1c37 create test2.txt onto 8f7aef5 create test4.txt, as requested
+        - Moving 70deb1e create test3.txt onto 96d1c37 create test2.txt, because an ancestor is being moved
+        Attempting rebase in-memory...
+        [1/2] Committed as: fa82175 create test2.txt
+        [2/2] Committed as: 127dd23 create test3.txt
+        branchless: processing 2 rewritten commits
+        In-memory rebase succeeded.
+        "###);
+    }
 
-        let starting_smartlog = git.smartlog()?;
+    Ok(())
+}
+
+#[test]
+fn test_move_regenerate_paths() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    let base_oid = git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    let lockfile_oid = git.commit_file_with_contents("deps/lockfile", 2, "lockfile 1\n")?;
+    git.commit_file_with_contents("conflict", 3, "conflict 1\n")?;
+    git.run(&["checkout", &base_oid.to_string()])?;
+    let lockfile_dest_oid = git.commit_file_with_contents("deps/lockfile", 2, "lockfile 2\n")?;
+    git.commit_file_with_contents("conflict", 3, "conflict 2\n")?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "move",
+            &[
+                "--exact",
+                &lockfile_oid.to_string(),
+                "--dest",
+                &lockfile_dest_oid.to_string(),
+                "--in-memory",
+            ],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        This operation would cause a merge conflict:
+        - (1 conflicting file) 00da5f6 create deps/lockfile.txt
+        To resolve merge conflicts, retry this operation with the --merge option.
+        "###);
+    }
+
+    git.run(&[
+        "config",
+        "branchless.rebase.regeneratePaths",
+        "lockfile.txt",
+    ])?;
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "move",
+            &["--source", &lockfile_oid.to_string(), "--in-memory"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/2] Committed as: f59a2ff create deps/lockfile.txt
+        This operation would cause a merge conflict:
+        - (1 conflicting file) 9f248bb create conflict.txt
+        To resolve merge conflicts, retry this operation with the --merge option.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &[
+                "--exact",
+                &lockfile_oid.to_string(),
+                "--dest",
+                &lockfile_dest_oid.to_string(),
+                "--in-memory",
+            ],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/2] Committed as: b9633e2 create deps/lockfile.txt
+        [2/2] Committed as: 5c309e9 create conflict.txt
+        branchless: processing 2 rewritten commits
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["show", "b9633e2:deps/lockfile.txt"])?;
+        insta::assert_snapshot!(stdout, @"lockfile 1");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_force_in_memory() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "HEAD~"])?;
+
+    git.write_file_txt("test2", "conflicting contents")?;
+    git.run(&["add", "."])?;
+    git.run(&["commit", "-m", "conflicting test2"])?;
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["-d", "master", "--in-memory"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"");
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        This operation would cause a merge conflict:
+        - (1 conflicting file) 081b474 conflicting test2
+        To resolve merge conflicts, retry this operation with the --merge option.
+        "###);
+    }
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["-d", "master", "--in-memory", "--merge"],
+            &GitRunOptions {
+                expected_exit_code: 2,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @r###"
+        error: the argument '--in-memory' cannot be used with '--merge'
+
+        Usage: git-branchless move --dest <DEST> --in-memory
+
+        For more information, try '--help'.
+        "###);
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_force_in_memory_dry_run() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+
+    {
+        git.run(&["checkout", "HEAD~"])?;
+        git.commit_file("test3", 3)?;
+
+        let starting_smartlog = git.smartlog()?;
         insta::assert_snapshot!(starting_smartlog, @r###"
             :
             O 62fc20d create test1.txt
@@ -3009,6 +3400,7 @@ fn test_move_in_memory_gc() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f),
@@ -3418,6 +3810,95 @@ fn test_move_no_reapply_upstream_commits() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_move_keep_empty() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.run(&["cherry-pick", &test1_oid.to_string()])?;
+    git.run(&["checkout", &test2_oid.to_string()])?;
+
+    // Without `--keep-empty`, the duplicate commit is dropped.
+    {
+        let git = git.duplicate_repo()?;
+        git.branchless(
+            "move",
+            &[
+                "--in-memory",
+                "--no-deduplicate-commits",
+                "-b",
+                "HEAD",
+                "-d",
+                "master",
+            ],
+        )?;
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 047b7ad (master) create test1.txt
+        |
+        @ fa46633 create test2.txt
+        "###);
+    }
+
+    // --in-memory
+    {
+        let git = git.duplicate_repo()?;
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &["--in-memory", "--keep-empty", "-b", "HEAD", "-d", "master"],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/2] Committed as: cfea32a create test1.txt
+        [2/2] Committed as: c47f017 create test2.txt
+        branchless: processing 2 rewritten commits
+        branchless: running command: <git-executable> checkout c47f01786c8bbe4ea84dee449c7b7e3c87fd4122 --
+        :
+        O 047b7ad (master) create test1.txt
+        |
+        o cfea32a create test1.txt
+        |
+        @ c47f017 create test2.txt
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    // --on-disk
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &["--on-disk", "--keep-empty", "-b", "HEAD", "-d", "master"],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> diff --quiet
+        Calling Git for on-disk rebase...
+        branchless: running command: <git-executable> rebase --continue
+        "###);
+
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 047b7ad (master) create test1.txt
+        |
+        o cfea32a create test1.txt
+        |
+        @ c47f017 create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_move_no_reapply_squashed_commits() -> eyre::Result<()> {
     let git = make_git()?;
@@ -3716,45 +4197,177 @@ fn test_move_with_unstaged_changes() -> eyre::Result<()> {
 }
 
 #[test]
-fn test_move_merge_commit() -> eyre::Result<()> {
+fn test_move_with_unstaged_changes_auto_stash() -> eyre::Result<()> {
     let git = make_git()?;
 
-    if !git.supports_reference_transactions()? {
-        return Ok(());
-    }
     git.init_repo()?;
+    git.run(&["config", "branchless.restack.preserveTimestamps", "true"])?;
+    git.run(&["config", "branchless.rebase.autoStash", "true"])?;
 
     git.commit_file("test1", 1)?;
-    git.run(&["checkout", "HEAD^"])?;
-    let test2_oid = git.commit_file("test2", 2)?;
-    git.run(&["checkout", "HEAD^"])?;
-    let test3_oid = git.commit_file("test3", 3)?;
-    git.run(&["merge", &test2_oid.to_string()])?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "HEAD~"])?;
+    git.commit_file("test3", 3)?;
+    git.write_file_txt("test3", "new contents")?;
 
-    git.run(&["checkout", &test3_oid.to_string()])?;
     {
-        let stdout = git.smartlog()?;
+        let git = git.duplicate_repo()?;
+        let (stdout, stderr) = git.branchless("move", &["--in-memory", "-d", "master"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: creating working copy snapshot
+        Previous HEAD position was 4838e49 create test3.txt
+        branchless: processing 1 update: ref HEAD
+        HEAD is now at 70deb1e create test3.txt
+        branchless: processing checkout
+        "###);
         insta::assert_snapshot!(stdout, @r###"
-        O f777ecc create initial.txt
-        |\
-        | o fe65c1f create test2.txt
-        | & (merge) 28790c7 Merge commit 'fe65c1fe15584744e649b2c79d4cf9b0d878f92e' into HEAD
-        |\
-        | @ 98b9119 create test3.txt
-        | |
-        | | & (merge) fe65c1f create test2.txt
-        | |/
-        | o 28790c7 Merge commit 'fe65c1fe15584744e649b2c79d4cf9b0d878f92e' into HEAD
+        branchless: running command: <git-executable> diff --quiet
+        Created autostash: b635af7 On (no branch): autostash
+        Attempting rebase in-memory...
+        [1/1] Committed as: 70deb1e create test3.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout 70deb1e28791d8e7dd5a1f0c871a51b91282562f --
+        :
+        O 96d1c37 (master) create test2.txt
         |
-        O 62fc20d (master) create test1.txt
+        @ 70deb1e create test3.txt
+        In-memory rebase succeeded.
+        Applied autostash.
+        "###);
+
+        let (stdout, _stderr) = git.run(&["diff"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        diff --git a/test3.txt b/test3.txt
+        index a474f4e..79d52c1 100644
+        --- a/test3.txt
+        +++ b/test3.txt
+        @@ -1 +1 @@
+        -test3 contents
+        +new contents
+        \ No newline at end of file
         "###);
     }
 
-    // --on-disk
     {
-        let git = git.duplicate_repo()?;
-
-        {
+        let (stdout, stderr) = git.branchless("move", &["--on-disk", "-d", "master"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 1 update: ref HEAD
+        branchless: processing 1 update: ref HEAD
+        Executing: git branchless hook-detect-empty-commit 4838e49b08954becdd17c0900c1179c2c654c627
+        Executing: git branchless hook-register-extra-post-rewrite-hook
+        branchless: processing 1 rewritten commit
+        Successfully rebased and updated detached HEAD.
+        "###);
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> diff --quiet
+        Created autostash: b635af7 On (no branch): autostash
+        branchless: running command: <git-executable> diff --quiet
+        Calling Git for on-disk rebase...
+        branchless: running command: <git-executable> rebase --continue
+        Applied autostash.
+        "###);
+
+        let (stdout, _stderr) = git.run(&["diff"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        diff --git a/test3.txt b/test3.txt
+        index a474f4e..79d52c1 100644
+        --- a/test3.txt
+        +++ b/test3.txt
+        @@ -1 +1 @@
+        -test3 contents
+        +new contents
+        \ No newline at end of file
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_auto_stash_rebase_error() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.run(&["config", "branchless.rebase.autoStash", "true"])?;
+
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "HEAD~"])?;
+    git.commit_file("test3", 3)?;
+    git.write_file_txt("test3", "new contents")?;
+
+    // Make it impossible to create the rebase state directory, so that the
+    // on-disk rebase fails after the changes have been stashed.
+    std::fs::write(git.repo_path.join(".git").join("rebase-merge"), "")?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "move",
+            &["--on-disk", "-d", "master"],
+            &GitRunOptions {
+                expected_exit_code: 101,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> diff --quiet
+        Created autostash: b635af7 On (no branch): autostash
+        branchless: running command: <git-executable> diff --quiet
+        Your changes are safe in the stash.
+        You can run "git stash pop" or "git stash drop" at any time.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["stash", "list"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        stash@{0}: autostash
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_merge_commit() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "HEAD^"])?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&["checkout", "HEAD^"])?;
+    let test3_oid = git.commit_file("test3", 3)?;
+    git.run(&["merge", &test2_oid.to_string()])?;
+
+    git.run(&["checkout", &test3_oid.to_string()])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | o fe65c1f create test2.txt
+        | & (merge) 28790c7 Merge commit 'fe65c1fe15584744e649b2c79d4cf9b0d878f92e' into HEAD
+        |\
+        | @ 98b9119 create test3.txt
+        | |
+        | | & (merge) fe65c1f create test2.txt
+        | |/
+        | o 28790c7 Merge commit 'fe65c1fe15584744e649b2c79d4cf9b0d878f92e' into HEAD
+        |
+        O 62fc20d (master) create test1.txt
+        "###);
+    }
+
+    // --on-disk
+    {
+        let git = git.duplicate_repo()?;
+
+        {
             let (stdout, stderr) = git.branchless(
                 "move",
                 &[
@@ -3811,6 +4424,7 @@ fn test_move_merge_commit() -> eyre::Result<()> {
                             commits_to_apply_oids: [
                                 NonZeroOid(fe65c1fe15584744e649b2c79d4cf9b0d878f92e),
                             ],
+                            message: None,
                         },
                         DetectEmptyCommit {
                             commit_oid: NonZeroOid(fe65c1fe15584744e649b2c79d4cf9b0d878f92e),
@@ -4194,6 +4808,7 @@ fn test_move_merge_commit_issue_912() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(50eef922b99bc8a8829a1ded374231f9a025d28c),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(50eef922b99bc8a8829a1ded374231f9a025d28c),
@@ -4206,6 +4821,7 @@ fn test_move_merge_commit_issue_912() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(00aa7adb4f38b8b1c04b062a1fdc897fcc6c471d),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(00aa7adb4f38b8b1c04b062a1fdc897fcc6c471d),
@@ -4223,6 +4839,7 @@ fn test_move_merge_commit_issue_912() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(af1a4cee7c63ea7eba381967223d17a6386e5a4c),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(af1a4cee7c63ea7eba381967223d17a6386e5a4c),
@@ -4248,6 +4865,7 @@ fn test_move_merge_commit_issue_912() -> eyre::Result<()> {
                         commits_to_apply_oids: [
                             NonZeroOid(7f5857ec34dab5bf7991da2512bf529789204413),
                         ],
+                        message: None,
                     },
                     DetectEmptyCommit {
                         commit_oid: NonZeroOid(7f5857ec34dab5bf7991da2512bf529789204413),
@@ -4918,6 +5536,60 @@ fn test_move_public_commit() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_move_signed_public_commit() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+
+    // Replace `HEAD` with a copy carrying a signature. The signature doesn't
+    // need to be valid, only present.
+    let signed_oid = {
+        let (commit_contents, _stderr) = git.run(&["cat-file", "commit", "HEAD"])?;
+        let (headers, message) = commit_contents
+            .split_once("\n\n")
+            .expect("commit should have a message");
+        let signed_commit_contents = format!(
+            "{headers}\ngpgsig -----BEGIN PGP SIGNATURE-----\n \n fake signature\n -----END PGP SIGNATURE-----\n\n{message}"
+        );
+        let (stdout, _stderr) = git.run_with_options(
+            &["hash-object", "-t", "commit", "-w", "--stdin"],
+            &GitRunOptions {
+                input: Some(signed_commit_contents),
+                ..Default::default()
+            },
+        )?;
+        stdout.trim().to_string()
+    };
+    git.run(&["reset", "--hard", &signed_oid])?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "move",
+            &["-x", ".^"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        You are trying to rewrite 2 public commits, such as: 9a5b213 create test2.txt
+        It is generally not advised to rewrite public commits, because your
+        collaborators will have difficulty merging your changes.
+        Retry with -f/--force-rewrite to proceed anyways.
+        Note: 1 of these commits is GPG-signed. Rewriting will drop the signatures.
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_move_delete_branch_config_entry() -> eyre::Result<()> {
     let git = make_git()?;
@@ -5329,7 +6001,7 @@ fn test_move_fixup_multiple_into_head() -> eyre::Result<()> {
                 "--in-memory",
                 "--fixup",
                 "-x",
-                &format!("{}+{}", test1_oid, test2_oid),
+                &format!("{test1_oid}+{test2_oid}"),
             ],
         )?;
         insta::assert_snapshot!(stdout, @r###"
@@ -5418,7 +6090,7 @@ fn test_move_fixup_multiple_into_ancestor_with_unmoved_head() -> eyre::Result<()
                 "--in-memory",
                 "--fixup",
                 "-x",
-                &format!("{}+{}", test2_oid, test3_oid),
+                &format!("{test2_oid}+{test3_oid}"),
                 "-d",
                 &test1_oid.to_string(),
             ],
@@ -5598,7 +6270,7 @@ fn test_move_fixup_multiple_disconnected_into_ancestor() -> eyre::Result<()> {
                 "--in-memory",
                 "--fixup",
                 "-x",
-                &format!("{}+{}", test3_oid, test5_oid),
+                &format!("{test3_oid}+{test5_oid}"),
                 "-d",
                 "test",
             ],
@@ -5794,7 +6466,7 @@ fn test_move_fixup_multiple_disconnected_into_head() -> eyre::Result<()> {
                 "--in-memory",
                 "--fixup",
                 "-x",
-                &format!("{}+{}", test3_oid, test5_oid),
+                &format!("{test3_oid}+{test5_oid}"),
             ],
         )?;
         insta::assert_snapshot!(stdout, @r###"
@@ -6206,7 +6878,7 @@ fn test_move_fixup_tree() -> eyre::Result<()> {
                 "--in-memory",
                 "--fixup",
                 "-x",
-                &format!("{}+{}", test2_oid, test6_oid),
+                &format!("{test2_oid}+{test6_oid}"),
                 "-d",
                 "test",
             ],
@@ -6329,6 +7001,102 @@ fn test_move_fixup_added_files() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_move_worktree() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["-s", "62fc20d", "-d", "master", "--worktree", "."],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"The --worktree path already exists and is not empty: .");
+    }
+
+    let worktree_parent = make_git()?;
+    let worktree_path = worktree_parent.repo_path.join("experiment");
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &[
+                "-s",
+                "62fc20d",
+                "-d",
+                "master",
+                "--worktree",
+                worktree_path.to_str().unwrap(),
+            ],
+        )?;
+        let stdout = stdout.replace(
+            worktree_parent.repo_path.to_str().unwrap(),
+            "<worktree-parent>",
+        );
+        insta::assert_snapshot!(stdout, @r###"
+        hint: you can omit the --dest flag in this case, as it defaults to HEAD
+        hint: disable this hint by running: git config --global branchless.hint.moveImplicitHeadArgument false
+        Attempting rebase in-memory...
+        [1/2] Committed as: 4b9ce31 create test1.txt
+        [2/2] Committed as: 9f77bc5 create test2.txt
+        branchless: processing 2 rewritten commits
+        branchless: running command: <git-executable> checkout master --
+        :
+        @ 98b9119 (> master) create test3.txt
+        |
+        o 4b9ce31 create test1.txt
+        |
+        o 9f77bc5 create test2.txt
+        In-memory rebase succeeded.
+        branchless: running command: <git-executable> worktree add --detach <worktree-parent>/experiment 9f77bc5fa2a01bc869e783c338a757c3b31e32ef
+        HEAD is now at 9f77bc5 create test2.txt
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        @ 98b9119 (> master) create test3.txt
+        |
+        o 4b9ce31 create test1.txt
+        |
+        o 9f77bc5 create test2.txt
+        "###);
+    }
+
+    let worktree = Git {
+        repo_path: worktree_path,
+        ..(*git).clone()
+    };
+    {
+        let stdout = worktree.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 98b9119 (master) create test3.txt
+        |
+        o 4b9ce31 create test1.txt
+        |
+        @ 9f77bc5 create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_worktree_rebase_in_memory() -> eyre::Result<()> {
     let git = make_git()?;
@@ -6410,3 +7178,991 @@ fn test_worktree_rebase_in_memory() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_move_in_place() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    let test3_oid = git.commit_file("test3", 3)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        @ 62fc20d create test1.txt
+        |
+        o 96d1c37 create test2.txt
+        |
+        o 70deb1e create test3.txt
+        "###);
+    }
+
+    // Use the current time for the rewritten commits, so that they get new OIDs.
+    git.run(&["config", "branchless.restack.preserveTimestamps", "false"])?;
+    git.branchless("move", &["-s", &test2_oid.to_string(), "--in-place"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("query", &["--raw", "descendants(@) - @"])?;
+        let new_oids = stdout.lines().collect::<Vec<_>>();
+        assert_eq!(new_oids.len(), 2, "{stdout}");
+        assert!(!stdout.contains(&test2_oid.to_string()), "{stdout}");
+        assert!(!stdout.contains(&test3_oid.to_string()), "{stdout}");
+
+        let (parent_oid, _stderr) = git.run(&["rev-parse", &format!("{}^", new_oids[0])])?;
+        assert_eq!(parent_oid.trim(), test1_oid.to_string());
+        let (parent_oid, _stderr) = git.run(&["rev-parse", &format!("{}^", new_oids[1])])?;
+        assert_eq!(parent_oid.trim(), new_oids[0]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_public_head_behavior() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+
+    git.run(&["config", "branchless.core.publicHeadBehavior", "skip"])?;
+    {
+        let (stdout, _stderr) = git.branchless("move", &["-d", &test1_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @"Skipping move: HEAD is at public commit f777ecc create initial.txt");
+    }
+
+    git.run(&[
+        "config",
+        "branchless.core.publicHeadBehavior",
+        "requireForce",
+    ])?;
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "move",
+            &["-d", &test1_oid.to_string()],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Refusing to move while HEAD is at public commit f777ecc create initial.txt
+        To proceed anyways, run: git move -f
+        "###);
+    }
+
+    // Explicitly-provided sources aren't affected.
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &["-x", &test1_oid.to_string(), "-d", "master", "--dry-run"],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        hint: you can omit the --dest flag in this case, as it defaults to HEAD
+        hint: disable this hint by running: git config --global branchless.hint.moveImplicitHeadArgument false
+        Attempting rebase in-memory...
+        [1/1] Committed as: 62fc20d create test1.txt
+        In-memory rebase would succeed.
+        (This was a dry-run; no commits were moved. Re-run without --dry-run to actually move commits.)
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_committer_date_is_author_date() -> eyre::Result<()> {
+    fn run_move(extra_args: &[&str]) -> eyre::Result<String> {
+        let git = make_git()?;
+        git.init_repo()?;
+        git.run(&["config", "branchless.restack.preserveTimestamps", "false"])?;
+        git.run(&[
+            "config",
+            "branchless.rebase.committerDateIsAuthorDate",
+            "true",
+        ])?;
+
+        git.detach_head()?;
+        let test1_oid = git.commit_file("test1", 1)?.to_string();
+        git.run(&["checkout", "master"])?;
+        git.commit_file("test2", 2)?;
+
+        let mut args = vec!["-x", &test1_oid, "-d", "master"];
+        args.extend(extra_args);
+        git.branchless("move", &args)?;
+
+        let (new_oid, _stderr) = git.branchless("query", &["--raw", "draft()"])?;
+        let (stdout, _stderr) = git.run(&["log", "-1", "--format=%H %at %ct", new_oid.trim()])?;
+        Ok(stdout)
+    }
+
+    let in_memory = run_move(&["--in-memory"])?;
+    // Make sure that the current time has changed before the next rewrite.
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let on_disk = run_move(&["--on-disk"])?;
+    assert_eq!(in_memory, on_disk);
+    insta::assert_snapshot!(in_memory, @"07709435a8f6d1566e0091896d130c78acd429dd 1603978496 1603978496");
+
+    Ok(())
+}
+
+#[test]
+fn test_move_reset_author() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.write_file_txt("test1", "test1 contents")?;
+    git.run(&["add", "."])?;
+    git.run(&[
+        "commit",
+        "-m",
+        "create test1.txt",
+        "--author",
+        "Teammate <teammate@example.com>",
+    ])?;
+    let test1_oid = git.get_repo()?.get_head_info()?.oid.unwrap();
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+
+    // Without `--reset-author`, the original author is kept.
+    {
+        let git = git.duplicate_repo()?;
+        git.branchless("move", &["-d", "master"])?;
+        let (stdout, _stderr) = git.run(&["log", "-1", "--format=%an <%ae>", "HEAD"])?;
+        insta::assert_snapshot!(stdout, @"Teammate <teammate@example.com>");
+    }
+
+    // Resetting authors isn't supported for on-disk rebases.
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["--reset-author", "--on-disk", "-d", "master"],
+            &GitRunOptions {
+                expected_exit_code: 2,
+                ..Default::default()
+            },
+        )?;
+        assert!(stderr.contains("cannot be used with"), "stderr: {stderr}");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("move", &["--reset-author", "-d", "master"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Committed as: 3b51dac create test1.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout 3b51dacb634d85cade49408c6f0b0385c1724048 --
+        :
+        O fe65c1f (master) create test2.txt
+        |
+        @ 3b51dac create test1.txt
+        In-memory rebase succeeded.
+        "###);
+
+        let (stdout, _stderr) = git.run(&["log", "-1", "--format=%an <%ae>", "HEAD"])?;
+        insta::assert_snapshot!(stdout, @"Testy McTestface <test@example.com>");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_onto_index() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["-s", &test1_oid.to_string(), "--onto-index"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"There are no staged changes to move onto.");
+    }
+
+    git.write_file_txt("test2", "staged contents\n")?;
+    git.run(&["add", "test2.txt"])?;
+    git.write_file_txt("test3", "unstaged contents\n")?;
+
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["--onto-index"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"The --onto-index flag requires the commits to move to be provided with --source, --base, or --exact.");
+    }
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("move", &["-s", &test1_oid.to_string(), "--onto-index"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Committed as: bcdbd02 create test1.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout master --
+        A	test2.txt
+        @ f777ecc (> master) create initial.txt
+        |
+        o 8e4beab Staged changes
+        |
+        o bcdbd02 create test1.txt
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["status", "--short"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        A  test2.txt
+        ?? test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_source_stdin() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (_stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["--source-stdin", "-d", "master"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                input: Some("\n".to_string()),
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"No commits to move were provided on stdin.");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "move",
+            &["--source-stdin", "-d", "master"],
+            &GitRunOptions {
+                input: Some(format!("{test1_oid}\n{test2_oid}\n")),
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        hint: you can omit the --dest flag in this case, as it defaults to HEAD
+        hint: disable this hint by running: git config --global branchless.hint.moveImplicitHeadArgument false
+        Attempting rebase in-memory...
+        [1/2] Committed as: 4b9ce31 create test1.txt
+        [2/2] Committed as: 200e547 create test2.txt
+        branchless: processing 2 rewritten commits
+        branchless: running command: <git-executable> checkout master --
+        :
+        @ 98b9119 (> master) create test3.txt
+        |\
+        | o 4b9ce31 create test1.txt
+        |
+        o 200e547 create test2.txt
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn test_move_interactive() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test3", 3)?;
+
+    run_in_pty(
+        &git,
+        "move",
+        &["-x", &test2_oid.to_string(), "--interactive"],
+        &[
+            PtyAction::WaitUntilContains("> "),
+            PtyAction::Write("test1"),
+            PtyAction::WaitUntilContains("> 62fc20d"),
+            PtyAction::Write("\r"),
+        ],
+    )?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | |
+        | o 96d1c37 create test2.txt
+        |
+        @ 98b9119 create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_interactive_not_a_terminal() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["-x", &test1_oid.to_string(), "--interactive"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"The --interactive flag requires standard input to be a terminal. Pass the destination with --dest instead.");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        |
+        @ fe65c1f create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_dest_previous() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    let test3_oid = git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["-s", &test3_oid.to_string(), "-d", "-"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"No previous location was recorded for commit 98b9119d16974f372e76cb64a3b77c528fc0b18b, as it has not been moved.");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    git.branchless(
+        "move",
+        &["-s", &test2_oid.to_string(), "-d", &test3_oid.to_string()],
+    )?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        |
+        @ 98b9119 create test3.txt
+        |
+        o 200e547 create test2.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &["-s", "draft() & paths.changed(test2.txt)", "-d", "-"],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Committed as: 96d1c37 create test2.txt
+        branchless: processing 1 rewritten commit
+        In-memory rebase succeeded.
+        "###);
+    }
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | |
+        | o 96d1c37 create test2.txt
+        |
+        @ 98b9119 create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_main_branch() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test2", 2)?;
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["-x", "master", "-d", &test1_oid.to_string()],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @r###"
+        Refusing to move the main branch master, since its tip fe65c1fe15584744e649b2c79d4cf9b0d878f92e is among the commits to move.
+        To move commits on top of the main branch instead, run: git move -s <commit> -d master
+        To move the main branch anyways, run the same command with -f
+        "###);
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_collapse() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test4", 4)?;
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("move", &["-s", &test1_oid.to_string(), "--collapse"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Committed as: 4e3cefc create test1.txt
+        branchless: processing 3 rewritten commits
+        branchless: running command: <git-executable> checkout master --
+        :
+        @ 8f7aef5 (> master) create test4.txt
+        |
+        o 4e3cefc create test1.txt
+        In-memory rebase succeeded.
+        "###);
+    }
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        @ 8f7aef5 (> master) create test4.txt
+        |
+        o 4e3cefc create test1.txt
+        "###);
+    }
+    {
+        let (collapsed_oid, _stderr) = git.branchless("query", &["--raw", "draft()"])?;
+        let (stdout, _stderr) =
+            git.run(&["show", "--stat", "--format=%B", collapsed_oid.trim()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        create test1.txt
+
+        create test2.txt
+
+        create test3.txt
+
+
+         test1.txt | 1 +
+         test2.txt | 1 +
+         test3.txt | 1 +
+         3 files changed, 3 insertions(+)
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_collapse_non_linear() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &["-s", &test1_oid.to_string(), "-d", "master", "--collapse"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"The --collapse flag can only be used to move a single linear range of commits.");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_timing() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &["-s", &test1_oid.to_string(), "--in-memory", "--timing"],
+        )?;
+        let timing_re = regex::Regex::new(r" \([0-9]+\.[0-9]{2}s\)$")?;
+        let stdout = stdout
+            .lines()
+            .map(|line| timing_re.replace(line, " (<time>)"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/2] Committed as: 4b9ce31 create test1.txt (<time>)
+        [2/2] Committed as: 9f77bc5 create test2.txt (<time>)
+        branchless: processing 2 rewritten commits
+        branchless: running command: <git-executable> checkout master --
+        :
+        @ 98b9119 (> master) create test3.txt
+        |
+        o 4b9ce31 create test1.txt
+        |
+        o 9f77bc5 create test2.txt
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_intermediate_branches() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["branch", "middle"])?;
+    git.commit_file("test3", 3)?;
+    git.run(&["branch", "tip"])?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test4", 4)?;
+
+    for args in [
+        vec!["-s", &test1_oid.to_string()],
+        vec!["-s", &test1_oid.to_string(), "--on-disk"],
+    ] {
+        let git = git.duplicate_repo()?;
+        git.branchless("move", &args)?;
+
+        let stdout = git.smartlog()?;
+        insta::allow_duplicates! {
+            insta::assert_snapshot!(stdout, @r###"
+            :
+            @ 8f7aef5 (> master) create test4.txt
+            |
+            o 6c398da create test1.txt
+            |
+            o d166405 (middle) create test2.txt
+            |
+            o e2e80c7 (tip) create test3.txt
+            "###);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_head_on_rewritten_commit() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+    git.run(&["commit", "--amend", "-m", "amended test1"])?;
+    let (amended_oid, _stderr) = git.run(&["rev-parse", "HEAD"])?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+
+    for args in [
+        vec!["-x", &test2_oid.to_string(), "-d", amended_oid.trim()],
+        vec![
+            "-x",
+            &test2_oid.to_string(),
+            "-d",
+            amended_oid.trim(),
+            "--on-disk",
+        ],
+    ] {
+        let git = git.duplicate_repo()?;
+        git.branchless("move", &args)?;
+
+        let stdout = git.smartlog()?;
+        insta::allow_duplicates! {
+            insta::assert_snapshot!(stdout, @r###"
+            O f777ecc (master) create initial.txt
+            |
+            @ ae94dc2 amended test1
+            |
+            o 51452b5 create test2.txt
+            "###);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_no_deduplicate() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.run(&["cherry-pick", &test1_oid.to_string()])?;
+    git.run(&["revert", "--no-edit", "HEAD"])?;
+
+    // The cherry-picked commit has the same patch ID, so the commit is dropped,
+    // even though its changes were reverted.
+    {
+        let git = git.duplicate_repo()?;
+        let (stdout, _stderr) = git.branchless("move", &["-x", &test1_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Skipped commit (was already applied upstream): 62fc20d create test1.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout master --
+        :
+        @ 30dab5a (> master) Revert "create test1.txt"
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) =
+            git.branchless("move", &["--no-deduplicate", "-x", &test1_oid.to_string()])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Committed as: 76c2a83 create test1.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout master --
+        :
+        @ 30dab5a (> master) Revert "create test1.txt"
+        |
+        o 76c2a83 create test1.txt
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_use_rerere() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.commit_file_with_contents("conflict", 2, "master contents\n")?;
+    git.run(&["checkout", "HEAD~"])?;
+    let conflict_oid = git.commit_file_with_contents("conflict", 2, "feature contents\n")?;
+
+    // Record a resolution for the conflict with `git rerere`.
+    git.run(&["config", "rerere.enabled", "true"])?;
+    git.run_with_options(
+        &["rebase", "master"],
+        &GitRunOptions {
+            expected_exit_code: 1,
+            ..Default::default()
+        },
+    )?;
+    git.write_file_txt("conflict", "resolved contents\n")?;
+    git.run(&["rerere"])?;
+    git.run(&["rebase", "--abort"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "move",
+            &[
+                "--exact",
+                &conflict_oid.to_string(),
+                "--dest",
+                "master",
+                "--in-memory",
+            ],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        This operation would cause a merge conflict:
+        - (1 conflicting file) 14a1558 create conflict.txt
+        To resolve merge conflicts, retry this operation with the --merge option.
+        "###);
+    }
+
+    git.run(&["config", "branchless.rebase.useRerere", "true"])?;
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &[
+                "--exact",
+                &conflict_oid.to_string(),
+                "--dest",
+                "master",
+                "--in-memory",
+            ],
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Committed as: cdc976a create conflict.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout cdc976aa8c9bceeae7c45d112a003798ba7fb859 --
+        :
+        O 912c70e (master) create conflict.txt
+        |
+        @ cdc976a create conflict.txt
+        In-memory rebase succeeded.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["show", "HEAD:conflict.txt"])?;
+        insta::assert_snapshot!(stdout, @"resolved contents");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_move_exec_on_conflict() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    let base_oid = git.commit_file("test1", 1)?;
+    git.detach_head()?;
+    let other_oid = git.commit_file_with_contents("conflict", 2, "conflict 1\n")?;
+    git.run(&["checkout", &base_oid.to_string()])?;
+    git.commit_file_with_contents("conflict", 2, "conflict 2\n")?;
+
+    // A resolver which fails leaves the rebase in progress.
+    {
+        let git = git.duplicate_repo()?;
+        let (stdout, stderr) = git.branchless_with_options(
+            "move",
+            &[
+                "--exec-on-conflict",
+                "false",
+                "--source",
+                &other_oid.to_string(),
+            ],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        let stdout = remove_rebase_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        Failed to merge in-memory, trying again on-disk...
+        branchless: running command: <git-executable> diff --quiet
+        Calling Git for on-disk rebase...
+        branchless: running command: <git-executable> rebase --continue
+        CONFLICT (add/add): Merge conflict in conflict.txt
+        Running conflict resolver: false
+        "###);
+        assert!(
+            stderr.contains("Conflict resolver failed with exit code 1. Resolve the conflicts manually and run: git rebase --continue"),
+            "{stderr}"
+        );
+    }
+
+    // A resolver which is killed by a signal is reported separately from one
+    // which exits with an error.
+    {
+        let git = git.duplicate_repo()?;
+        let (_stdout, stderr) = git.branchless_with_options(
+            "move",
+            &[
+                "--exec-on-conflict",
+                "kill -KILL $$",
+                "--source",
+                &other_oid.to_string(),
+            ],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        assert!(
+            stderr.contains("Conflict resolver was terminated by a signal. Resolve the conflicts manually and run: git rebase --continue"),
+            "{stderr}"
+        );
+    }
+
+    // A resolver which succeeds without resolving the conflicts also leaves
+    // the rebase in progress.
+    {
+        let git = git.duplicate_repo()?;
+        let (_stdout, stderr) = git.branchless_with_options(
+            "move",
+            &[
+                "--exec-on-conflict",
+                "true",
+                "--source",
+                &other_oid.to_string(),
+            ],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        assert!(
+            stderr.contains("Conflict resolver did not resolve all conflicts. Resolve the remaining conflicts manually and run: git rebase --continue"),
+            "{stderr}"
+        );
+    }
+
+    std::fs::write(
+        git.repo_path.join(".git").join("resolve-conflicts.sh"),
+        "for path in \"$@\"; do\n  echo resolved > \"$path\"\n  git add \"$path\"\ndone\n",
+    )?;
+    {
+        let (stdout, _stderr) = git.branchless(
+            "move",
+            &[
+                "--exec-on-conflict",
+                "sh .git/resolve-conflicts.sh",
+                "--source",
+                &other_oid.to_string(),
+            ],
+        )?;
+        let stdout = remove_rebase_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        Failed to merge in-memory, trying again on-disk...
+        branchless: running command: <git-executable> diff --quiet
+        Calling Git for on-disk rebase...
+        branchless: running command: <git-executable> rebase --continue
+        CONFLICT (add/add): Merge conflict in conflict.txt
+        Running conflict resolver: sh .git/resolve-conflicts.sh
+        branchless: running command: <git-executable> rebase --continue
+        [detached HEAD a136512] create conflict.txt
+         1 file changed, 1 insertion(+), 1 deletion(-)
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 62fc20d (master) create test1.txt
+        |
+        @ 202143f create conflict.txt
+        |
+        o a136512 create conflict.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["show", "a136512:conflict.txt"])?;
+        insta::assert_snapshot!(stdout, @"resolved");
+    }
+
+    Ok(())
+}
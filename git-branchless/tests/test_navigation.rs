@@ -422,6 +422,99 @@ fn test_navigation_traverse_all_the_way() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_navigation_traverse_all_the_way_ambiguous() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "master"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "next",
+            &["--all"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Found multiple possible child commits to go to after traversing 1 child:
+          - 96d1c37 create test2.txt (oldest)
+          - 4838e49 create test3.txt (newest)
+        (Pass --oldest (-o), --newest (-n), or --interactive (-i) to select between ambiguous commits)
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("next", &["--all", "--newest"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> checkout 4838e49b08954becdd17c0900c1179c2c654c627 --
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |\
+        | o 96d1c37 create test2.txt
+        |
+        @ 4838e49 create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn test_navigation_traverse_all_the_way_interactive() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+    git.commit_file("test3", 3)?;
+    git.commit_file("test4", 4)?;
+    git.run(&["checkout", "master"])?;
+
+    run_in_pty(
+        &git,
+        "next",
+        &["--all"],
+        &[
+            PtyAction::WaitUntilContains("> "),
+            PtyAction::Write("test3"),
+            PtyAction::WaitUntilContains("> test3"),
+            PtyAction::WaitUntilContains("> 4838e49"),
+            PtyAction::Write(CARRIAGE_RETURN),
+        ],
+    )?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 62fc20d create test1.txt
+        |\
+        | o 96d1c37 create test2.txt
+        |
+        o 4838e49 create test3.txt
+        |
+        @ a248207 create test4.txt
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_navigation_traverse_branches() -> eyre::Result<()> {
     let git = make_git()?;
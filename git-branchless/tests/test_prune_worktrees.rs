@@ -0,0 +1,48 @@
+use lib::testing::{make_git, make_git_worktree};
+
+#[test]
+fn test_prune_worktrees() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+
+    let temp_dir_path = {
+        let worktree_wrapper = make_git_worktree(&git, "new-worktree")?;
+        worktree_wrapper.temp_dir.path().to_path_buf()
+    };
+    let replace_temp_dir = |stdout: String| -> String {
+        stdout.replace(temp_dir_path.to_str().unwrap(), "<worktree-parent>")
+    };
+
+    {
+        let (stdout, _stderr) = git.branchless("prune-worktrees", &["--dry-run"])?;
+        let stdout = replace_temp_dir(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        Would prune 1 stale worktree:
+        - new-worktree (<worktree-parent>/new-worktree)
+        (This was a dry-run; run without --dry-run to apply changes.)
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("prune-worktrees", &[])?;
+        let stdout = replace_temp_dir(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        Pruning 1 stale worktree:
+        - new-worktree (<worktree-parent>/new-worktree)
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["worktree", "list", "--porcelain"])?;
+        assert!(!stdout.contains("new-worktree"), "{stdout}");
+        assert!(!git.repo_path.join(".git/worktrees/new-worktree").exists());
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("prune-worktrees", &[])?;
+        insta::assert_snapshot!(stdout, @"No stale worktrees found.");
+    }
+
+    Ok(())
+}
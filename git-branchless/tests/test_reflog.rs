@@ -0,0 +1,51 @@
+use lib::testing::make_git;
+
+#[test]
+fn test_reflog() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.branchless("hide", &["HEAD"])?;
+    git.branchless("move", &["-x", "HEAD", "-d", "master"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("reflog", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Transaction 5: move
+            Rewrote 1 commit
+            Took 1 working copy snapshot
+            Updated ref HEAD
+        Transaction 4: hide
+            Hid 1 commit
+        Transaction 3: git commit
+            Committed 1 commit
+            Updated ref HEAD
+        Transaction 2: git commit
+            Committed 1 commit
+            Updated ref HEAD
+        Transaction 1: git checkout
+            Updated ref HEAD
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("reflog", &["-n", "2"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Transaction 5: move
+            Rewrote 1 commit
+            Took 1 working copy snapshot
+            Updated ref HEAD
+        Transaction 4: hide
+            Hid 1 commit
+        "###);
+    }
+
+    Ok(())
+}
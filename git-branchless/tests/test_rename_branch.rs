@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use lib::core::forge_status_cache::{ForgeStatus, cache_forge_statuses};
+use lib::testing::{GitRunOptions, make_git, trim_lines};
+
+#[test]
+fn test_rename_branch() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.run(&["checkout", "-b", "foo"])?;
+    git.commit_file("test1", 1)?;
+
+    {
+        let repo = git.get_repo()?;
+        let conn = repo.get_db_conn()?;
+        cache_forge_statuses(
+            &conn,
+            &HashMap::from([(
+                "foo".to_string(),
+                ForgeStatus {
+                    identifier: "#123".to_string(),
+                    state: "open".to_string(),
+                },
+            )]),
+        )?;
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("rename-branch", &["foo", "bar"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: processing 3 updates: branch bar, branch foo, ref HEAD
+        Renamed branch foo to bar.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        @ 62fc20d (> bar) [#123 open] create test1.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("undo", &["--yes"])?;
+        let stdout = trim_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        Will apply these actions:
+        1. Check out from 62fc20d create test1.txt
+                       to 62fc20d create test1.txt
+        2. Delete branch bar at 62fc20d create test1.txt
+
+        3. Create branch foo at 62fc20d create test1.txt
+
+        branchless: running command: <git-executable> checkout 62fc20d2a290daea0d52bdc2ed2ad4be6491010e --detach --
+        O f777ecc (master) create initial.txt
+        |
+        @ 62fc20d (foo) create test1.txt
+        Applied 3 inverse events.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        @ 62fc20d (foo) create test1.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_rename_branch_errors() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+    git.run(&["branch", "foo"])?;
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "rename-branch",
+            &["nonexistent", "bar"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"Branch not found: nonexistent");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    {
+        let (stdout, stderr) = git.branchless_with_options(
+            "rename-branch",
+            &["foo", "master"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @"A branch named master already exists.");
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    Ok(())
+}
@@ -626,3 +626,111 @@ fn test_restack_worktree_bare_repo() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_restack_public_head_behavior() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+
+    git.run(&["config", "branchless.core.publicHeadBehavior", "skip"])?;
+    {
+        let (stdout, _stderr) = git.branchless("restack", &[])?;
+        insta::assert_snapshot!(stdout, @"Skipping restack: HEAD is at public commit 62fc20d create test1.txt");
+    }
+
+    git.run(&[
+        "config",
+        "branchless.core.publicHeadBehavior",
+        "requireForce",
+    ])?;
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "restack",
+            &[],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @r###"
+        Refusing to restack while HEAD is at public commit 62fc20d create test1.txt
+        To proceed anyways, run: git restack -f
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("restack", &["-f"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        No abandoned commits to restack.
+        No abandoned branches to restack.
+        :
+        @ 62fc20d (> master) create test1.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_restack_onto_main() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "HEAD^^"])?;
+    git.run(&["commit", "--amend", "-m", "amend test1.txt"])?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test4", 4)?;
+    git.run(&["checkout", "HEAD^"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        @ f777ecc create initial.txt
+        |\
+        | o 024c35c amend test1.txt
+        |\
+        | x 62fc20d (rewritten as 024c35ce) create test1.txt
+        | |
+        | o 96d1c37 create test2.txt
+        | |
+        | o 70deb1e create test3.txt
+        |
+        O 8f7aef5 (master) create test4.txt
+        hint: there is 1 abandoned commit in your commit graph
+        hint: to fix this, run: git restack
+        hint: disable this hint by running: git config --global branchless.hint.smartlogFixAbandoned false
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("restack", &["--onto-main"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/3] Committed as: 4424e23 amend test1.txt
+        [2/3] Committed as: d0a7e60 create test2.txt
+        [3/3] Committed as: 3347f77 create test3.txt
+        branchless: processing 3 rewritten commits
+        In-memory rebase succeeded.
+        Finished restacking commits.
+        No abandoned branches to restack.
+        @ f777ecc create initial.txt
+        |
+        O 8f7aef5 (master) create test4.txt
+        |
+        o 4424e23 amend test1.txt
+        |
+        o d0a7e60 create test2.txt
+        |
+        o 3347f77 create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
@@ -515,6 +515,45 @@ fn test_reword_fixup_ancestry_issue() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_reword_message_from() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_committer_date_is_author_date()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+    git.run(&["checkout", "-b", "test"])?;
+    git.write_file_txt("test1", "test1 contents\n")?;
+    git.run(&["add", "."])?;
+    git.run(&["commit", "-m", "message subject", "-m", "message body"])?;
+    git.commit_file("test2", 2)?;
+
+    git.branchless("reword", &["--message-from", "HEAD^"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        o 54f0dde message subject
+        |
+        @ 5a95b17 (> test) message subject
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["log", "-1", "--format=%B"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        message subject
+
+        message body
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_reword_merge_commit() -> eyre::Result<()> {
     let git = make_git()?;
@@ -0,0 +1,55 @@
+use lib::testing::make_git;
+
+#[test]
+fn test_status() -> eyre::Result<()> {
+    let git = make_git()?;
+    git.init_repo()?;
+    git.detach_head()?;
+    let test1_oid = git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", &test1_oid.to_string()])?;
+    git.run(&["commit", "--amend", "-m", "amended test1"])?;
+    git.run(&["branch", "branch-1"])?;
+    git.run(&["checkout", "branch-1"])?;
+
+    {
+        let (stdout, _stderr) = git.run(&["smartlog"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | @ ae94dc2 (> branch-1) amended test1
+        |
+        x 62fc20d (rewritten as ae94dc2a) create test1.txt
+        |
+        o 96d1c37 create test2.txt
+        |
+        o 70deb1e create test3.txt
+        hint: there is 1 abandoned commit in your commit graph
+        hint: to fix this, run: git restack
+        hint: disable this hint by running: git config --global branchless.hint.smartlogFixAbandoned false
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("status", &["--short"])?;
+        insta::assert_snapshot!(stdout, @"3 draft, 1 abandoned, on branch-1");
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("status", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Draft commits: 3
+        Abandoned commits: 1
+        On: branch-1
+        "###);
+    }
+
+    git.run(&["checkout", "--detach"])?;
+    {
+        let (stdout, _stderr) = git.branchless("status", &["--short"])?;
+        insta::assert_snapshot!(stdout, @"3 draft, 1 abandoned, on ae94dc2");
+    }
+
+    Ok(())
+}
@@ -111,6 +111,151 @@ fn test_sync_up_to_date() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_sync_stat() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    let test2_oid = git.commit_file("test2", 2)?;
+
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test3", 3)?;
+
+    git.run(&["checkout", "master"])?;
+    git.run(&["cherry-pick", &test2_oid.to_string()])?;
+    git.commit_file("test4", 4)?;
+
+    git.detach_head()?;
+    git.commit_file("test5", 5)?;
+    git.run(&["checkout", "master"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        | |
+        | o 96d1c37 create test2.txt
+        |\
+        : o 98b9119 create test3.txt
+        :
+        @ f7557bc (> master) create test4.txt
+        |
+        o 8bcc8f9 create test5.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("sync", &["--stat"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/2] Committed as: 30edf3d create test1.txt
+        [2/2] Skipped commit (was already applied upstream): 96d1c37 create test2.txt
+        branchless: processing 2 rewritten commits
+        branchless: running command: <git-executable> checkout master --
+        In-memory rebase succeeded.
+        Attempting rebase in-memory...
+        [1/1] Committed as: e6e1dac create test3.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout master --
+        In-memory rebase succeeded.
+        Synced 62fc20d create test1.txt
+        Synced 98b9119 create test3.txt
+        Not moving up-to-date stack at 8bcc8f9 create test5.txt
+        Moved 2 stacks, rebased 2 commits, dropped 1 duplicate commit, skipped 1 up-to-date stack.
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sync_continues_after_conflicting_stack() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file_with_contents("test2", 2, "stack contents\n")?;
+
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test3", 3)?;
+
+    git.run(&["checkout", "master"])?;
+    git.commit_file_with_contents("test2", 4, "master contents\n")?;
+
+    {
+        let (stdout, _stderr) = git.branchless("sync", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Committed as: e811968 create test1.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout master --
+        In-memory rebase succeeded.
+        Attempting rebase in-memory...
+        Attempting rebase in-memory...
+        [1/1] Committed as: 7d4be59 create test3.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout master --
+        In-memory rebase succeeded.
+        Synced 62fc20d create test1.txt
+        Synced 98b9119 create test3.txt
+        Merge conflict (1 file) for c23696b create test2.txt
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | o c23696b create test2.txt
+        |
+        @ 6d756ee (> master) create test2.txt
+        |\
+        | o e811968 create test1.txt
+        |
+        o 7d4be59 create test3.txt
+        "###);
+    }
+
+    {
+        // All of the synced stacks are recorded in the same transaction, so
+        // they are undone together.
+        git.branchless("undo", &["--yes"])?;
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        |\
+        | o c23696b create test2.txt
+        |\
+        | o 98b9119 create test3.txt
+        |
+        @ 6d756ee (master) create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_sync_pull() -> eyre::Result<()> {
     let GitWrapperWithRemoteRepo {
@@ -192,6 +337,106 @@ fn test_sync_pull() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_sync_pull_remote() -> eyre::Result<()> {
+    let GitWrapperWithRemoteRepo {
+        temp_dir,
+        original_repo,
+        cloned_repo,
+    } = make_git_with_remote_repo()?;
+    if !original_repo.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    original_repo.init_repo()?;
+    original_repo.commit_file("test1", 1)?;
+    original_repo.commit_file("test2", 2)?;
+
+    original_repo.clone_repo_into(&cloned_repo, &["--branch", "master"])?;
+    cloned_repo.init_repo_with_options(&GitInitOptions {
+        make_initial_commit: false,
+        ..Default::default()
+    })?;
+    cloned_repo.detach_head()?;
+
+    // Point the default remote at a copy which won't receive any new commits,
+    // and fetch from the original repository via a second remote.
+    let stale_repo_path = temp_dir.path().join("stale.git");
+    original_repo.run(&[
+        "clone",
+        "--bare",
+        original_repo.repo_path.to_str().unwrap(),
+        stale_repo_path.to_str().unwrap(),
+    ])?;
+    cloned_repo.run(&[
+        "remote",
+        "set-url",
+        "origin",
+        stale_repo_path.to_str().unwrap(),
+    ])?;
+    cloned_repo.run(&[
+        "remote",
+        "add",
+        "upstream",
+        original_repo.repo_path.to_str().unwrap(),
+    ])?;
+
+    original_repo.commit_file("test3", 3)?;
+    cloned_repo.commit_file("test4", 4)?;
+
+    {
+        let (stdout, _stderr) = cloned_repo.branchless("sync", &["-p"])?;
+        let stdout: String = remove_nondeterministic_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> fetch --all
+        Not updating branch master at 96d1c37 create test2.txt
+        Not moving up-to-date stack at f57e36f create test4.txt
+        "###);
+    }
+
+    {
+        let (stdout, stderr) = cloned_repo.branchless_with_options(
+            "sync",
+            &["--remote", "nonexistent"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stderr, @r###"
+        No remote named "nonexistent". Known remotes: origin, upstream
+        "###);
+        insta::assert_snapshot!(stdout, @"");
+    }
+
+    {
+        let (stdout, _stderr) = cloned_repo.branchless("sync", &["--remote", "upstream"])?;
+        let stdout: String = remove_nondeterministic_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> fetch upstream
+        Fast-forwarding branch master to 70deb1e create test3.txt
+        Attempting rebase in-memory...
+        [1/1] Committed as: 355e173 create test4.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout 355e173bf9c5d2efac2e451da0cdad3fb82b869a --
+        In-memory rebase succeeded.
+        Synced f57e36f create test4.txt
+        "###);
+    }
+
+    {
+        let stdout = cloned_repo.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 70deb1e (master) create test3.txt
+        |
+        @ 355e173 create test4.txt
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_sync_stack_from_commit() -> eyre::Result<()> {
     let git = make_git()?;
@@ -260,6 +505,59 @@ fn test_sync_stack_from_commit() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_sync_current_stack() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "-b", "foo"])?;
+    git.commit_file("test3", 3)?;
+    git.commit_file("test3-1", 3)?;
+    git.run(&["checkout", "-b", "bar", "master"])?;
+    git.commit_file("test4", 4)?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test5", 5)?;
+    git.run(&["checkout", "foo"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("sync", &["--current"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/2] Committed as: 8e521a1 create test3.txt
+        [2/2] Committed as: 11bfd99 create test3-1.txt
+        branchless: processing 1 update: branch foo
+        branchless: processing 2 rewritten commits
+        branchless: running command: <git-executable> checkout foo --
+        In-memory rebase succeeded.
+        Synced 70deb1e create test3.txt
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 96d1c37 create test2.txt
+        |\
+        | o f57e36f (bar) create test4.txt
+        |
+        O d2e18e3 (master) create test5.txt
+        |
+        o 8e521a1 create test3.txt
+        |
+        @ 11bfd99 (> foo) create test3-1.txt
+        "###);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_sync_divergent_main_branch() -> eyre::Result<()> {
     let GitWrapperWithRemoteRepo {
@@ -532,3 +830,162 @@ fn test_sync_checked_out_main_with_dirty_working_copy() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_sync_multiple_main_branches() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+    git.init_repo()?;
+    git.run(&["branch", "release"])?;
+    git.run(&["config", "branchless.core.mainBranch", "master"])?;
+    git.run(&["config", "--add", "branchless.core.mainBranch", "release"])?;
+
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+
+    git.run(&["checkout", "release"])?;
+    git.commit_file("test2", 2)?;
+    git.detach_head()?;
+    git.commit_file("test3", 3)?;
+
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test4", 4)?;
+    git.run(&["checkout", "release"])?;
+    git.commit_file("test5", 5)?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        |\
+        | O fe65c1f create test2.txt
+        | |\
+        | | o 0206717 create test3.txt
+        | |
+        | @ fb8baf9 (> release) create test5.txt
+        |
+        O 8f7aef5 (master) create test4.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("query", &["draft()"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        62fc20d create test1.txt
+        0206717 create test3.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("sync", &[])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Attempting rebase in-memory...
+        [1/1] Committed as: 6c398da create test1.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout release --
+        In-memory rebase succeeded.
+        Attempting rebase in-memory...
+        [1/1] Committed as: 8c7876c create test3.txt
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout release --
+        In-memory rebase succeeded.
+        Synced 62fc20d create test1.txt
+        Synced 0206717 create test3.txt
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | O 8f7aef5 (master) create test4.txt
+        | |
+        | o 6c398da create test1.txt
+        :
+        # 1 omitted commit
+        :
+        @ fb8baf9 (> release) create test5.txt
+        |
+        o 8c7876c create test3.txt
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sync_push() -> eyre::Result<()> {
+    let GitWrapperWithRemoteRepo {
+        temp_dir: _guard,
+        original_repo,
+        cloned_repo,
+    } = make_git_with_remote_repo()?;
+    if !original_repo.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    original_repo.init_repo()?;
+    original_repo.commit_file("test1", 1)?;
+    original_repo.commit_file("test2", 2)?;
+
+    original_repo.clone_repo_into(&cloned_repo, &["--branch", "master"])?;
+    cloned_repo.init_repo_with_options(&GitInitOptions {
+        make_initial_commit: false,
+        ..Default::default()
+    })?;
+    cloned_repo.run(&["checkout", "-b", "foo"])?;
+    cloned_repo.commit_file("test3", 3)?;
+    cloned_repo.run(&["checkout", "-b", "bar", "master"])?;
+    cloned_repo.commit_file("test4", 4)?;
+    cloned_repo.run(&["push", "--set-upstream", "origin", "foo", "bar"])?;
+
+    // `bar` is out of date with respect to its remote branch, but shouldn't be
+    // pushed since it won't be moved by the sync.
+    cloned_repo.run(&["commit", "--amend", "-m", "amend test4.txt"])?;
+    original_repo.commit_file("test5", 5)?;
+
+    {
+        let (stdout, _stderr) = cloned_repo.branchless("sync", &["--pull", "--push", "foo"])?;
+        let stdout: String = remove_nondeterministic_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        branchless: running command: <git-executable> fetch --all
+        Fast-forwarding branch master to d2e18e3 create test5.txt
+        Attempting rebase in-memory...
+        [1/1] Committed as: 8e521a1 create test3.txt
+        branchless: processing 1 update: branch foo
+        branchless: processing 1 rewritten commit
+        branchless: running command: <git-executable> checkout bar --
+        Your branch and 'origin/bar' have diverged,
+        and have 1 and 1 different commits each, respectively.
+          (use "git pull" to merge the remote branch into yours)
+        In-memory rebase succeeded.
+        Synced 70deb1e create test3.txt
+        branchless: running command: <git-executable> fetch origin refs/heads/foo
+        branchless: running command: <git-executable> push --force-with-lease origin foo
+        Pushed 1 branch: foo
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = original_repo.run(&["branch", "-v"])?;
+        insta::assert_snapshot!(stdout, @r###"
+          bar    f57e36f create test4.txt
+          foo    8e521a1 create test3.txt
+        * master d2e18e3 create test5.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = cloned_repo.branchless("sync", &["--push", "foo"])?;
+        let stdout: String = remove_nondeterministic_lines(stdout);
+        insta::assert_snapshot!(stdout, @"Not moving up-to-date stack at 8e521a1 create test3.txt");
+    }
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ use git_branchless_undo::tui::testing::{
 };
 use lib::core::dag::Dag;
 use lib::core::effects::Effects;
+use lib::core::eventlog::testing::{get_event_replayer_events, redact_event_timestamp};
 use lib::core::eventlog::{EventCursor, EventLogDb, EventReplayer};
 use lib::core::formatting::Glyphs;
 use lib::core::repo_ext::RepoExt;
@@ -167,9 +168,7 @@ fn test_undo_navigate() -> eyre::Result<()> {
         insta::assert_snapshot!(screen_to_string(&screenshot1), @r###"
         ┌───────────────────────────────────────────────────┤ Commit graph ├───────────────────────────────────────────────────┐
         │:                                                                                                                     │
-        │@ 96d1c37 (master) create test2.txt                                                                                   │
-        │                                                                                                                      │
-        │                                                                                                                      │
+        │@ 62fc20d (master) create test1.txt                                                                                   │
         │                                                                                                                      │
         │                                                                                                                      │
         │                                                                                                                      │
@@ -183,11 +182,13 @@ fn test_undo_navigate() -> eyre::Result<()> {
         │                                                                                                                      │
         └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
         ┌──────────────────────────────────────────────────────┤ Events ├──────────────────────────────────────────────────────┐
-        │Repo after transaction 3 (event 4). Press 'h' for help, 'q' to quit.                                                  │
-        │1. Check out from 62fc20d create test1.txt                                                                            │
-        │               to 96d1c37 create test2.txt                                                                            │
-        │2. Move branch master from 62fc20d create test1.txt                                                                   │
-        │                        to 96d1c37 create test2.txt                                                                   │
+        │Repo after transaction 1 (event 1). Press 'h' for help, 'q' to quit.                                                  │
+        │1. Check out from f777ecc create initial.txt                                                                          │
+        │               to 62fc20d create test1.txt                                                                            │
+        │2. Move branch master from f777ecc create initial.txt                                                                 │
+        │                        to 62fc20d create test1.txt                                                                   │
+        │3. Commit 62fc20d create test1.txt                                                                                    │
+        │                                                                                                                      │
         └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
         "###);
         insta::assert_snapshot!(screen_to_string(&screenshot2), @r###"
@@ -205,14 +206,14 @@ fn test_undo_navigate() -> eyre::Result<()> {
         │                                                                                                                      │
         │                                                                                                                      │
         │                                                                                                                      │
-        │                                                                                                                      │
-        │                                                                                                                      │
-        │                                                                                                                      │
-        │                                                                                                                      │
         └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
         ┌──────────────────────────────────────────────────────┤ Events ├──────────────────────────────────────────────────────┐
-        │Repo after transaction 4 (event 6). Press 'h' for help, 'q' to quit.                                                  │
-        │1. Commit 96d1c37 create test2.txt                                                                                    │
+        │Repo after transaction 2 (event 4). Press 'h' for help, 'q' to quit.                                                  │
+        │1. Check out from 62fc20d create test1.txt                                                                            │
+        │               to 96d1c37 create test2.txt                                                                            │
+        │2. Move branch master from 62fc20d create test1.txt                                                                   │
+        │                        to 96d1c37 create test2.txt                                                                   │
+        │3. Commit 96d1c37 create test2.txt                                                                                    │
         │                                                                                                                      │
         └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
         "###);
@@ -262,14 +263,14 @@ fn test_go_to_event() -> eyre::Result<()> {
     │                                                                                                                      │
     │                                                                                                                      │
     │                                                                                                                      │
-    │                                                                                                                      │
-    │                                                                                                                      │
-    │                                                                                                                      │
-    │                                                                                                                      │
     └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
     ┌──────────────────────────────────────────────────────┤ Events ├──────────────────────────────────────────────────────┐
-    │Repo after transaction 4 (event 6). Press 'h' for help, 'q' to quit.                                                  │
-    │1. Commit 96d1c37 create test2.txt                                                                                    │
+    │Repo after transaction 2 (event 4). Press 'h' for help, 'q' to quit.                                                  │
+    │1. Check out from 62fc20d create test1.txt                                                                            │
+    │               to 96d1c37 create test2.txt                                                                            │
+    │2. Move branch master from 62fc20d create test1.txt                                                                   │
+    │                        to 96d1c37 create test2.txt                                                                   │
+    │3. Commit 96d1c37 create test2.txt                                                                                    │
     │                                                                                                                      │
     └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
     "###);
@@ -387,7 +388,6 @@ fn test_undo_move_refs() -> eyre::Result<()> {
     let event_cursor = run_select_past_event(
         &git.get_repo()?,
         vec![
-            CursiveTestingEvent::Event('p'.into()),
             CursiveTestingEvent::Event('p'.into()),
             CursiveTestingEvent::Event(Key::Enter.into()),
             CursiveTestingEvent::Event('y'.into()),
@@ -476,7 +476,7 @@ fn test_historical_smartlog_visibility() -> eyre::Result<()> {
         │                                                                                                                      │
         └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
         ┌──────────────────────────────────────────────────────┤ Events ├──────────────────────────────────────────────────────┐
-        │Repo after transaction 5 (event 5). Press 'h' for help, 'q' to quit.                                                  │
+        │Repo after transaction 3 (event 5). Press 'h' for help, 'q' to quit.                                                  │
         │1. Hide commit 62fc20d create test1.txt                                                                               │
         │                                                                                                                      │
         └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
@@ -498,12 +498,12 @@ fn test_historical_smartlog_visibility() -> eyre::Result<()> {
         │                                                                                                                      │
         │                                                                                                                      │
         │                                                                                                                      │
-        │                                                                                                                      │
-        │                                                                                                                      │
         └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
         ┌──────────────────────────────────────────────────────┤ Events ├──────────────────────────────────────────────────────┐
-        │Repo after transaction 4 (event 4). Press 'h' for help, 'q' to quit.                                                  │
-        │1. Commit 62fc20d create test1.txt                                                                                    │
+        │Repo after transaction 2 (event 3). Press 'h' for help, 'q' to quit.                                                  │
+        │1. Check out from f777ecc create initial.txt                                                                          │
+        │               to 62fc20d create test1.txt                                                                            │
+        │2. Commit 62fc20d create test1.txt                                                                                    │
         │                                                                                                                      │
         └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
         "###);
@@ -707,7 +707,7 @@ fn test_git_bisect_produces_empty_event() -> eyre::Result<()> {
     │                                                                                                                      │
     └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
     ┌──────────────────────────────────────────────────────┤ Events ├──────────────────────────────────────────────────────┐
-    │Repo after transaction 3 (event 4). Press 'h' for help, 'q' to quit.                                                  │
+    │Repo after transaction 2 (event 4). Press 'h' for help, 'q' to quit.                                                  │
     │1. Empty event for BISECT_HEAD                                                                                        │
     │   This may be an unsupported use-case; see https://github.com/arxanas/git-branchless/issues/57                       │
     └──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┘
@@ -916,10 +916,193 @@ fn test_undo_no_confirm() -> eyre::Result<()> {
         Will apply these actions:
         1. Hide commit 62fc20d create test1.txt
 
+        2. Move branch master from 62fc20d create test1.txt
+                                to f777ecc create initial.txt
+        3. Check out from 62fc20d create test1.txt
+                       to f777ecc create initial.txt
+        branchless: running command: <git-executable> checkout master --detach --
+        @ f777ecc (master) create initial.txt
+        Applied 3 inverse events.
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_undo_redo() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.branchless("wrap", &["--", "commit", "--amend", "-m", "bad message"])?;
+
+    {
+        let (stdout, _stderr) = git.branchless("undo", &["--yes"])?;
+        let stdout = trim_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        Will apply these actions:
+        1. Rewrite commit 9ed8f9a bad message
+                      as 96d1c37 create test2.txt
+        2. Hide commit 9ed8f9a bad message
+
+        3. Move branch master from 9ed8f9a bad message
+                                to 96d1c37 create test2.txt
+        4. Check out from 9ed8f9a bad message
+                       to 96d1c37 create test2.txt
+        branchless: running command: <git-executable> checkout master --detach --
+        :
+        @ 96d1c37 (master) create test2.txt
+        Applied 4 inverse events.
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("undo", &["--redo", "--yes"])?;
+        let stdout = trim_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        Will apply these actions:
+        1. Check out from 96d1c37 create test2.txt
+                       to 9ed8f9a bad message
+        2. Move branch master from 96d1c37 create test2.txt
+                                to 9ed8f9a bad message
+        3. Commit 9ed8f9a bad message
+
+        4. Rewrite commit 96d1c37 create test2.txt
+                      as 9ed8f9a bad message
+        branchless: running command: <git-executable> checkout master --detach --
+        :
+        @ 9ed8f9a (master) bad message
+        Reapplied 4 events.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        @ 9ed8f9a (master) bad message
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("undo", &["--redo", "--yes"])?;
+        insta::assert_snapshot!(stdout, @"No undo to redo, exiting.");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_undo_refs_only() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "HEAD^"])?;
+    git.write_file_txt("test1", "modified contents")?;
+
+    {
+        let (stdout, _stderr) = git.branchless("undo", &["--yes", "--refs-only"])?;
+        let stdout = trim_lines(stdout);
+        insta::assert_snapshot!(stdout, @r###"
+        Will apply these actions:
+        1. Check out from 62fc20d create test1.txt
+                       to 96d1c37 create test2.txt
+        Warning: the working copy and index were left as-is, and now differ from HEAD.
         Applied 1 inverse event.
         "###);
     }
 
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        @ 96d1c37 (master) create test2.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.run(&["status", "--short"])?;
+        insta::assert_snapshot!(stdout, @r###"
+         M test1.txt
+        D  test2.txt
+        "###);
+    }
+
+    assert_eq!(
+        std::fs::read_to_string(git.repo_path.join("test1.txt"))?,
+        "modified contents"
+    );
+    assert!(!git.repo_path.join("test2.txt").exists());
+
+    {
+        let effects = Effects::new_suppress_for_test(Glyphs::text());
+        let repo = git.get_repo()?;
+        let conn = repo.get_db_conn()?;
+        let event_log_db = EventLogDb::new(&conn)?;
+        let event_replayer = EventReplayer::from_event_log_db(&effects, &repo, &event_log_db)?;
+        let last_event = get_event_replayer_events(&event_replayer)
+            .last()
+            .cloned()
+            .map(redact_event_timestamp);
+        insta::assert_debug_snapshot!(last_event, @r###"
+        Some(
+            RefUpdateEvent {
+                timestamp: 0.0,
+                event_tx_id: Id(
+                    4,
+                ),
+                ref_name: ReferenceName(
+                    "HEAD",
+                ),
+                old_oid: 62fc20d2a290daea0d52bdc2ed2ad4be6491010e,
+                new_oid: 96d1c37a3d4363611c49f7e52186e189a04c531f,
+                message: None,
+            },
+        )
+        "###);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_undo_redo_after_new_operation() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.commit_file("test1", 1)?;
+    git.commit_file("test2", 2)?;
+    git.branchless("undo", &["--yes"])?;
+    git.commit_file("test3", 3)?;
+
+    {
+        let (stdout, _stderr) = git.branchless_with_options(
+            "undo",
+            &["--redo", "--yes"],
+            &GitRunOptions {
+                expected_exit_code: 1,
+                ..Default::default()
+            },
+        )?;
+        insta::assert_snapshot!(stdout, @"Cannot redo: other operations have happened since the last undo.");
+    }
+
     Ok(())
 }
 
@@ -1007,3 +1190,83 @@ fn test_undo_unseen_commit() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_undo_amend_with_auto_advance() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.run(&["checkout", "master"])?;
+    git.detach_head()?;
+    git.commit_file("test2", 2)?;
+    git.run(&["config", "branchless.advance.auto", "true"])?;
+
+    {
+        let (stdout, stderr) = git.run(&["commit", "--amend", "-m", "amended test2"])?;
+        insta::assert_snapshot!(stderr, @r###"
+        branchless: processing 1 update: ref HEAD
+        branchless: processed commit: b867646 amended test2
+        Attempting rebase in-memory...
+        [1/2] Committed as: c90edfb create test1.txt
+        [2/2] Skipped commit (was already applied upstream): fe65c1f create test2.txt
+        branchless: processing 2 rewritten commits
+        In-memory rebase succeeded.
+        branchless: processing 1 rewritten commit
+        "###);
+        insta::assert_snapshot!(stdout, @r###"
+        [detached HEAD b867646] amended test2
+         Date: Thu Oct 29 12:34:56 2020 -0200
+         1 file changed, 1 insertion(+)
+         create mode 100644 test2.txt
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |
+        @ b867646 amended test2
+        |
+        o c90edfb create test1.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("undo", &["--yes"])?;
+        let stdout = trim_lines(stdout);
+        // The rewrite events within the transaction aren't recorded in a
+        // deterministic order, so only check the outcome of the undo.
+        let (_actions, outcome) = stdout
+            .split_once("branchless: running command: ")
+            .expect("undo should check out the original commit");
+        insta::assert_snapshot!(outcome, @r###"
+        <git-executable> checkout fe65c1fe15584744e649b2c79d4cf9b0d878f92e --detach --
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        |
+        @ fe65c1f create test2.txt
+        Applied 5 inverse events.
+        "###);
+    }
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc (master) create initial.txt
+        |\
+        | o 62fc20d create test1.txt
+        |
+        @ fe65c1f create test2.txt
+        "###);
+    }
+
+    Ok(())
+}
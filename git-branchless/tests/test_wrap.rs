@@ -52,7 +52,7 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    2,
+                    1,
                 ),
                 ref_name: ReferenceName(
                     "HEAD",
@@ -64,7 +64,7 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    3,
+                    2,
                 ),
                 ref_name: ReferenceName(
                     "HEAD",
@@ -76,7 +76,7 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    3,
+                    2,
                 ),
                 ref_name: ReferenceName(
                     "refs/heads/foo",
@@ -88,14 +88,14 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             CommitEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    4,
+                    2,
                 ),
                 commit_oid: NonZeroOid(62fc20d2a290daea0d52bdc2ed2ad4be6491010e),
             },
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    5,
+                    3,
                 ),
                 ref_name: ReferenceName(
                     "HEAD",
@@ -107,7 +107,7 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    5,
+                    3,
                 ),
                 ref_name: ReferenceName(
                     "refs/heads/foo",
@@ -119,14 +119,14 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             CommitEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    6,
+                    3,
                 ),
                 commit_oid: NonZeroOid(96d1c37a3d4363611c49f7e52186e189a04c531f),
             },
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    7,
+                    4,
                 ),
                 ref_name: ReferenceName(
                     "HEAD",
@@ -138,7 +138,7 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    8,
+                    5,
                 ),
                 ref_name: ReferenceName(
                     "HEAD",
@@ -150,7 +150,7 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    8,
+                    5,
                 ),
                 ref_name: ReferenceName(
                     "HEAD",
@@ -162,7 +162,7 @@ fn test_wrap_rebase_in_transaction() -> eyre::Result<()> {
             RefUpdateEvent {
                 timestamp: 0.0,
                 event_tx_id: Id(
-                    8,
+                    5,
                 ),
                 ref_name: ReferenceName(
                     "refs/heads/master",
@@ -375,3 +375,94 @@ fn test_wrap_exit_code() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_wrap_undo_as_unit() -> eyre::Result<()> {
+    let git = make_git()?;
+
+    if !git.supports_reference_transactions()? || git.get_version()? < GitVersion(2, 38, 0) {
+        return Ok(());
+    }
+
+    git.init_repo()?;
+    git.detach_head()?;
+    git.commit_file("test1", 1)?;
+    git.run(&["branch", "foo"])?;
+    git.commit_file("test2", 2)?;
+    git.run(&["checkout", "-b", "bar"])?;
+    git.run(&["checkout", "master"])?;
+    git.commit_file("test3", 3)?;
+    git.run(&["checkout", "bar"])?;
+
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | o 62fc20d (foo) create test1.txt
+        | |
+        | @ 96d1c37 (> bar) create test2.txt
+        |
+        O 98b9119 (master) create test3.txt
+        "###);
+    }
+
+    git.branchless("wrap", &["--", "rebase", "--update-refs", "master"])?;
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        :
+        O 98b9119 (master) create test3.txt
+        |
+        o 64e0ba6 (foo) create test1.txt
+        |
+        @ 5b8b988 (> bar) create test2.txt
+        "###);
+    }
+
+    {
+        let (stdout, _stderr) = git.branchless("undo", &["--yes"])?;
+        insta::assert_snapshot!(stdout, @r###"
+        Will apply these actions:
+        1. Move branch foo from 64e0ba6 create test1.txt
+                             to 62fc20d create test1.txt
+        2. Rewrite commit 5b8b988 create test2.txt
+                      as 96d1c37 create test2.txt
+        3. Rewrite commit 64e0ba6 create test1.txt
+                      as 62fc20d create test1.txt
+        4. Move branch bar from 5b8b988 create test2.txt
+                             to 96d1c37 create test2.txt
+        5. Check out from 5b8b988 create test2.txt
+                       to 64e0ba6 create test1.txt
+        6. Check out from 64e0ba6 create test1.txt
+                       to 98b9119 create test3.txt
+        7. Check out from 98b9119 create test3.txt
+                       to 96d1c37 create test2.txt
+        8. Check out from 98b9119 create test3.txt
+                       to 96d1c37 create test2.txt
+        branchless: running command: <git-executable> checkout bar --detach --
+        O f777ecc create initial.txt
+        |\
+        | o 62fc20d (foo) create test1.txt
+        | |
+        | @ 96d1c37 (bar) create test2.txt
+        |
+        O 98b9119 (master) create test3.txt
+        Applied 8 inverse events.
+        "###);
+    }
+    {
+        let stdout = git.smartlog()?;
+        insta::assert_snapshot!(stdout, @r###"
+        O f777ecc create initial.txt
+        |\
+        | o 62fc20d (foo) create test1.txt
+        | |
+        | @ 96d1c37 (bar) create test2.txt
+        |
+        O 98b9119 (master) create test3.txt
+        "###);
+    }
+
+    Ok(())
+}